@@ -0,0 +1,493 @@
+//! cap-inspect: reads this process's Linux capability sets from procfs and
+//! emits one boundary object per recognized `CAP_*` bit.
+//!
+//! Unlike `emit-record`, which always resolves `--primary-capability-id`
+//! against the loaded catalog, this binary observes capabilities the kernel
+//! reports directly, so a bit that has no catalog entry yet still needs a
+//! record. Recognized caps use the catalog's id/category/layer triple;
+//! unrecognized ones fall back to a synthetic id and
+//! `CapabilityCategory::Other` so the gap is visible instead of silently
+//! dropped.
+//!
+//! Status per capability:
+//! - `success`  — set in the effective set (`CapEff`).
+//! - `partial`  — permitted (`CapPrm`) but not effective.
+//! - `denied`   — masked out by the bounding set (`CapBnd`), or simply absent.
+//!
+//! CLI:
+//! - `--run-mode MODE` — forwarded to `detect-stack` (default: `baseline`).
+//! - `--catalog PATH` / `--boundary PATH` — override the usual resolution.
+//! - `--proc-status PATH` — read capability masks from this file instead of
+//!   `/proc/self/status` (mainly for tests and non-Linux hosts).
+//! - `--help` — print usage.
+
+use anyhow::{Context, Result, bail};
+use fencerunner::{
+    BoundaryObject, BoundarySchema, CapabilityCategory, CapabilityContext, CapabilityId,
+    CapabilityIndex, CapabilityLayer, CapabilitySnapshot, OperationInfo, Payload, ProbeInfo,
+    ResultInfo, RunInfo, StackInfo, find_repo_root, resolve_boundary_schema_path,
+    resolve_catalog_path, resolve_helper_binary,
+};
+use serde_json::{Value, json};
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+const DEFAULT_PROC_STATUS_PATH: &str = "/proc/self/status";
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("{err:#}");
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<()> {
+    let cli = Cli::parse()?;
+    let repo_root = find_repo_root()?;
+
+    let catalog_path = resolve_catalog_path(&repo_root, cli.catalog_path.as_deref().map(Path::new));
+    let capability_index = CapabilityIndex::load(&catalog_path)
+        .with_context(|| format!("loading capability catalog from {}", catalog_path.display()))?;
+
+    let boundary_schema_path = resolve_boundary_schema_path(
+        &repo_root,
+        cli.boundary_schema_path.as_deref().map(Path::new),
+    )?;
+    let boundary_schema = BoundarySchema::load(&boundary_schema_path).with_context(|| {
+        format!(
+            "loading boundary schema from {}",
+            boundary_schema_path.display()
+        )
+    })?;
+
+    let detect_stack = resolve_helper_binary(&repo_root, "detect-stack")?;
+    let stack_raw = run_command_json(&detect_stack, &[&cli.run_mode])
+        .with_context(|| format!("Failed to execute {}", detect_stack.display()))?;
+    let stack: StackInfo = serde_json::from_value(stack_raw)
+        .context("detect-stack emitted JSON that does not match the current stack schema")?;
+
+    let proc_status_path = cli
+        .proc_status_path
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_PROC_STATUS_PATH));
+    let masks = read_capability_masks(&proc_status_path).with_context(|| {
+        format!(
+            "reading capability masks from {}",
+            proc_status_path.display()
+        )
+    })?;
+
+    let mut stdout = String::new();
+    for bit in observed_bits(&masks) {
+        let name = capability_name(bit).unwrap_or("CAP_UNKNOWN");
+        let observation = CapabilityObservation {
+            effective: masks.effective.contains(&bit),
+            permitted: masks.permitted.contains(&bit),
+            bounded: masks.bounding.contains(&bit),
+        };
+        let status = observation.status();
+        let snapshot = capability_snapshot(&capability_index, name);
+
+        let record = build_record(
+            &boundary_schema,
+            capability_index.key(),
+            &stack,
+            &cli.run_mode,
+            name,
+            status,
+            &snapshot,
+        );
+        let value = serde_json::to_value(&record)?;
+        boundary_schema
+            .validate(&value)
+            .with_context(|| format!("validating emitted record for {name}"))?;
+
+        stdout.push_str(&serde_json::to_string(&record)?);
+        stdout.push('\n');
+    }
+
+    print!("{stdout}");
+    Ok(())
+}
+
+fn build_record(
+    boundary_schema: &BoundarySchema,
+    catalog_key: &fencerunner::CatalogKey,
+    stack: &StackInfo,
+    run_mode: &str,
+    cap_name: &str,
+    status: &'static str,
+    snapshot: &CapabilitySnapshot,
+) -> BoundaryObject {
+    BoundaryObject {
+        schema_version: boundary_schema.schema_version().to_string(),
+        schema_key: boundary_schema.schema_key().map(str::to_string),
+        capabilities_schema_version: Some(snapshot_catalog_key(snapshot, catalog_key)),
+        stack: stack.clone(),
+        probe: ProbeInfo {
+            id: "cap-inspect".to_string(),
+            version: "1".to_string(),
+            primary_capability_id: snapshot.id.clone(),
+            secondary_capability_ids: Vec::new(),
+        },
+        run: RunInfo {
+            mode: run_mode.to_string(),
+            workspace_root: None,
+            command: "cap-inspect".to_string(),
+        },
+        operation: OperationInfo {
+            category: snapshot.category.as_str().to_string(),
+            verb: "inspect_linux_capability".to_string(),
+            target: cap_name.to_string(),
+            args: json!({}),
+        },
+        result: ResultInfo {
+            observed_result: status.to_string(),
+            raw_exit_code: None,
+            errno: None,
+            message: None,
+            error_detail: None,
+        },
+        payload: Payload {
+            stdout_snippet: None,
+            stderr_snippet: None,
+            raw: json!({ "capability": cap_name }),
+        },
+        capability_context: CapabilityContext {
+            primary: snapshot.clone(),
+            secondary: Vec::new(),
+            resolved_grant: None,
+        },
+    }
+}
+
+// `capabilities_schema_version` identifies the catalog a snapshot was resolved
+// from; synthetic (unmapped-cap) snapshots were never resolved from one, so
+// they get a sentinel key instead of pretending to match the loaded catalog.
+fn snapshot_catalog_key(
+    snapshot: &CapabilitySnapshot,
+    catalog_key: &fencerunner::CatalogKey,
+) -> fencerunner::CatalogKey {
+    match &snapshot.category {
+        CapabilityCategory::Other(_) => fencerunner::CatalogKey("unmapped_linux_caps".to_string()),
+        _ => catalog_key.clone(),
+    }
+}
+
+/// Resolve a catalog snapshot for `cap_name` if a mapping exists and the
+/// mapped id is present in the loaded catalog; otherwise synthesize an
+/// `Other`-categorized snapshot so the capability is still reported.
+fn capability_snapshot(index: &CapabilityIndex, cap_name: &str) -> CapabilitySnapshot {
+    if let Some(mapped_id) = mapped_capability_id(cap_name) {
+        let id = CapabilityId(mapped_id.to_string());
+        if let Some(capability) = index.capability(&id) {
+            return capability.snapshot();
+        }
+    }
+
+    CapabilitySnapshot {
+        id: CapabilityId(format!(
+            "cap_linux_unmapped_{}",
+            cap_name.to_ascii_lowercase()
+        )),
+        category: CapabilityCategory::Other(cap_name.to_string()),
+        layer: CapabilityLayer::OsSandbox,
+    }
+}
+
+/// Mapping from recognized `CAP_*` names to catalog capability ids.
+fn mapped_capability_id(cap_name: &str) -> Option<&'static str> {
+    CAP_CATALOG_MAP
+        .iter()
+        .find(|(name, _)| *name == cap_name)
+        .map(|(_, id)| *id)
+}
+
+const CAP_CATALOG_MAP: &[(&str, &str)] = &[
+    ("CAP_CHOWN", "cap_fs_change_ownership"),
+    ("CAP_DAC_OVERRIDE", "cap_fs_bypass_permission_checks"),
+    ("CAP_DAC_READ_SEARCH", "cap_fs_bypass_read_search"),
+    ("CAP_FOWNER", "cap_fs_bypass_owner_checks"),
+    ("CAP_KILL", "cap_proc_kill_other"),
+    ("CAP_SETGID", "cap_proc_setgid"),
+    ("CAP_SETUID", "cap_proc_setuid"),
+    ("CAP_NET_BIND_SERVICE", "cap_net_bind_privileged_port"),
+    ("CAP_NET_ADMIN", "cap_net_admin_interface"),
+    ("CAP_NET_RAW", "cap_net_raw_socket"),
+    ("CAP_SYS_CHROOT", "cap_fs_chroot_escape"),
+    ("CAP_SYS_PTRACE", "cap_proc_ptrace_attach"),
+    ("CAP_SYS_ADMIN", "cap_proc_sys_admin_mount"),
+    ("CAP_SYS_MODULE", "cap_sysctl_load_kernel_module"),
+];
+
+/// Bit position -> canonical `CAP_*` name, per `capabilities(7)`.
+const CAP_NAMES: &[(u8, &str)] = &[
+    (0, "CAP_CHOWN"),
+    (1, "CAP_DAC_OVERRIDE"),
+    (2, "CAP_DAC_READ_SEARCH"),
+    (3, "CAP_FOWNER"),
+    (4, "CAP_FSETID"),
+    (5, "CAP_KILL"),
+    (6, "CAP_SETGID"),
+    (7, "CAP_SETUID"),
+    (8, "CAP_SETPCAP"),
+    (9, "CAP_LINUX_IMMUTABLE"),
+    (10, "CAP_NET_BIND_SERVICE"),
+    (11, "CAP_NET_BROADCAST"),
+    (12, "CAP_NET_ADMIN"),
+    (13, "CAP_NET_RAW"),
+    (14, "CAP_IPC_LOCK"),
+    (15, "CAP_IPC_OWNER"),
+    (16, "CAP_SYS_MODULE"),
+    (17, "CAP_SYS_RAWIO"),
+    (18, "CAP_SYS_CHROOT"),
+    (19, "CAP_SYS_PTRACE"),
+    (20, "CAP_SYS_PACCT"),
+    (21, "CAP_SYS_ADMIN"),
+    (22, "CAP_SYS_BOOT"),
+    (23, "CAP_SYS_NICE"),
+    (24, "CAP_SYS_RESOURCE"),
+    (25, "CAP_SYS_TIME"),
+    (26, "CAP_SYS_TTY_CONFIG"),
+    (27, "CAP_MKNOD"),
+    (28, "CAP_LEASE"),
+    (29, "CAP_AUDIT_WRITE"),
+    (30, "CAP_AUDIT_CONTROL"),
+    (31, "CAP_SETFCAP"),
+    (32, "CAP_MAC_OVERRIDE"),
+    (33, "CAP_MAC_ADMIN"),
+    (34, "CAP_SYSLOG"),
+    (35, "CAP_WAKE_ALARM"),
+    (36, "CAP_BLOCK_SUSPEND"),
+    (37, "CAP_AUDIT_READ"),
+    (38, "CAP_PERFMON"),
+    (39, "CAP_BPF"),
+    (40, "CAP_CHECKPOINT_RESTORE"),
+];
+
+fn capability_name(bit: u8) -> Option<&'static str> {
+    CAP_NAMES
+        .iter()
+        .find(|(candidate, _)| *candidate == bit)
+        .map(|(_, name)| *name)
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct CapabilityMasks {
+    effective: BTreeSet<u8>,
+    permitted: BTreeSet<u8>,
+    bounding: BTreeSet<u8>,
+    inheritable: BTreeSet<u8>,
+    ambient: BTreeSet<u8>,
+}
+
+struct CapabilityObservation {
+    effective: bool,
+    permitted: bool,
+    bounded: bool,
+}
+
+impl CapabilityObservation {
+    fn status(&self) -> &'static str {
+        if self.effective {
+            "success"
+        } else if !self.bounded {
+            "denied"
+        } else if self.permitted {
+            "partial"
+        } else {
+            "denied"
+        }
+    }
+}
+
+/// Every bit set in any of the five masks, in ascending order, so each
+/// observed capability is reported exactly once.
+fn observed_bits(masks: &CapabilityMasks) -> BTreeSet<u8> {
+    let mut bits = BTreeSet::new();
+    bits.extend(&masks.effective);
+    bits.extend(&masks.permitted);
+    bits.extend(&masks.bounding);
+    bits.extend(&masks.inheritable);
+    bits.extend(&masks.ambient);
+    bits
+}
+
+fn read_capability_masks(path: &Path) -> Result<CapabilityMasks> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("reading proc status file {}", path.display()))?;
+    parse_capability_masks(&contents)
+}
+
+fn parse_capability_masks(contents: &str) -> Result<CapabilityMasks> {
+    let mut masks = CapabilityMasks::default();
+    for line in contents.lines() {
+        let Some((label, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match label.trim() {
+            "CapEff" => masks.effective = decode_mask(value)?,
+            "CapPrm" => masks.permitted = decode_mask(value)?,
+            "CapBnd" => masks.bounding = decode_mask(value)?,
+            "CapInh" => masks.inheritable = decode_mask(value)?,
+            "CapAmb" => masks.ambient = decode_mask(value)?,
+            _ => {}
+        }
+    }
+    Ok(masks)
+}
+
+fn decode_mask(hex: &str) -> Result<BTreeSet<u8>> {
+    let value = u64::from_str_radix(hex, 16)
+        .with_context(|| format!("parsing capability mask '{hex}' as hex"))?;
+    let mut bits = BTreeSet::new();
+    for bit in 0..64u8 {
+        if value & (1u64 << bit) != 0 {
+            bits.insert(bit);
+        }
+    }
+    Ok(bits)
+}
+
+fn run_command_json(path: &Path, args: &[&str]) -> Result<Value> {
+    let output = Command::new(path)
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("{} failed: {stderr}", path.display());
+    }
+    serde_json::from_slice(&output.stdout).context("Failed to parse command output as JSON")
+}
+
+struct Cli {
+    run_mode: String,
+    catalog_path: Option<String>,
+    boundary_schema_path: Option<String>,
+    proc_status_path: Option<PathBuf>,
+}
+
+impl Cli {
+    fn parse() -> Result<Self> {
+        let mut run_mode = "baseline".to_string();
+        let mut catalog_path = None;
+        let mut boundary_schema_path = None;
+        let mut proc_status_path = None;
+
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--run-mode" => run_mode = next_value(&mut args, "--run-mode")?,
+                "--catalog" => catalog_path = Some(next_value(&mut args, "--catalog")?),
+                "--boundary" => boundary_schema_path = Some(next_value(&mut args, "--boundary")?),
+                "--proc-status" => {
+                    proc_status_path = Some(PathBuf::from(next_value(&mut args, "--proc-status")?))
+                }
+                "--help" | "-h" => {
+                    print_usage();
+                    std::process::exit(0);
+                }
+                other => bail!("Unknown flag: {other}"),
+            }
+        }
+
+        Ok(Self {
+            run_mode,
+            catalog_path,
+            boundary_schema_path,
+            proc_status_path,
+        })
+    }
+}
+
+fn next_value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String> {
+    args.next()
+        .ok_or_else(|| anyhow::anyhow!("Missing value for {flag}"))
+}
+
+fn print_usage() {
+    eprintln!(
+        "Usage: cap-inspect [--run-mode MODE] [--catalog PATH] [--boundary PATH] [--proc-status PATH]\n\nReads this process's Linux capability sets and emits one boundary-object\nrecord per recognized CAP_* bit, tagged with CapabilityLayer::OsSandbox."
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_STATUS: &str = "Name:\tcap_inspect\nCapInh:\t0000000000000000\nCapPrm:\t0000000000003400\nCapEff:\t0000000000001000\nCapBnd:\t0000000000003c00\nCapAmb:\t0000000000000000\n";
+
+    #[test]
+    fn parse_capability_masks_reads_all_five_fields() {
+        let masks = parse_capability_masks(SAMPLE_STATUS).expect("parses");
+        assert!(masks.effective.contains(&12));
+        assert!(masks.permitted.contains(&12));
+        assert!(masks.permitted.contains(&13));
+        assert!(masks.bounding.contains(&10));
+        assert!(masks.inheritable.is_empty());
+        assert!(masks.ambient.is_empty());
+    }
+
+    #[test]
+    fn decode_mask_rejects_non_hex_input() {
+        assert!(decode_mask("not-hex").is_err());
+    }
+
+    #[test]
+    fn status_is_success_when_effective() {
+        let observation = CapabilityObservation {
+            effective: true,
+            permitted: true,
+            bounded: true,
+        };
+        assert_eq!(observation.status(), "success");
+    }
+
+    #[test]
+    fn status_is_partial_when_permitted_but_not_effective() {
+        let observation = CapabilityObservation {
+            effective: false,
+            permitted: true,
+            bounded: true,
+        };
+        assert_eq!(observation.status(), "partial");
+    }
+
+    #[test]
+    fn status_is_denied_when_masked_out_by_bounding_set() {
+        let observation = CapabilityObservation {
+            effective: false,
+            permitted: false,
+            bounded: false,
+        };
+        assert_eq!(observation.status(), "denied");
+    }
+
+    #[test]
+    fn observed_bits_unions_all_five_masks() {
+        let masks = parse_capability_masks(SAMPLE_STATUS).expect("parses");
+        let bits = observed_bits(&masks);
+        assert_eq!(bits, BTreeSet::from([10, 11, 12, 13]));
+    }
+
+    #[test]
+    fn capability_name_resolves_known_bits_and_none_for_unknown() {
+        assert_eq!(capability_name(12), Some("CAP_NET_ADMIN"));
+        assert_eq!(capability_name(63), None);
+    }
+
+    #[test]
+    fn mapped_capability_id_covers_recognized_caps_only() {
+        assert_eq!(
+            mapped_capability_id("CAP_NET_ADMIN"),
+            Some("cap_net_admin_interface")
+        );
+        assert_eq!(mapped_capability_id("CAP_SYS_BOOT"), None);
+    }
+}
@@ -1,13 +1,27 @@
 //! Collects host/sandbox metadata for inclusion in boundary objects.
 //!
-//! The binary is intentionally dependency-free and lightweight because probes
-//! invoke it for every record. It reflects the current run mode (from CLI or
-//! env), captures any sandbox override, and emits a JSON `StackInfo` snapshot.
+//! The binary stays lightweight because probes invoke it for every record: it
+//! reflects the current run mode (from CLI or env), captures any sandbox
+//! override plus the container image identity the `oci` execution backend
+//! exports, and emits a JSON `VersionInfo` snapshot. By default that snapshot
+//! only carries the cheap fields (no file I/O beyond `uname`); passing
+//! `--capabilities PATH` (or `FENCE_CATALOG_PATH`) additionally resolves the
+//! boundary/catalog `schema_version` consts and the sorted `primary_capability`
+//! ids available in this build, the way a `detect-stack --capabilities`
+//! handshake lets `probe-exec`/`probe-matrix` reject a mismatched
+//! harness/probe pairing before running anything.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use fencerunner::connectors::RunMode;
+use fencerunner::{
+    CANONICAL_BOUNDARY_SCHEMA_PATH, CANONICAL_CAPABILITY_CATALOG_SCHEMA_PATH,
+    load_catalog_from_path,
+};
 use serde::Serialize;
+use serde_json::Value;
 use std::env;
+use std::fs::File;
+use std::path::Path;
 use std::process::Command;
 
 fn main() {
@@ -18,19 +32,34 @@ fn main() {
 }
 
 fn run() -> Result<()> {
-    let cli_run_mode = parse_cli_run_mode();
-    let run_mode_raw = match cli_run_mode {
+    let cli = ParsedCli::parse();
+    let run_mode_raw = match cli.run_mode {
         Some(mode) => mode,
         None => env_non_empty_any(&["FENCE_RUN_MODE"]).unwrap_or_else(|| usage_and_exit()),
     };
 
-    let _run_mode = RunMode::try_from(run_mode_raw.as_str())?;
+    RunMode::try_from(run_mode_raw.as_str())?;
     let sandbox_mode = env_non_empty("FENCE_SANDBOX_MODE");
-    let os_info = detect_uname(&["-srm"]).unwrap_or_else(|| fallback_os_info());
+    let container_image = env_non_empty("FENCE_CONTAINER_IMAGE");
+    let os_info = detect_uname(&["-srm"]).unwrap_or_else(fallback_os_info);
 
-    let info = StackInfo {
+    let capabilities_path = cli
+        .capabilities_path
+        .or_else(|| env_non_empty("FENCE_CATALOG_PATH"));
+    let primary_capabilities = capabilities_path
+        .as_deref()
+        .map(load_primary_capability_ids)
+        .transpose()?;
+    let protocol = primary_capabilities.is_some().then(protocol_versions);
+
+    let info = VersionInfo {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        protocol,
+        run_mode: run_mode_raw,
         sandbox_mode,
+        container_image,
         os: os_info,
+        primary_capabilities,
     };
 
     println!("{}", serde_json::to_string(&info)?);
@@ -38,21 +67,89 @@ fn run() -> Result<()> {
 }
 
 #[derive(Serialize)]
-struct StackInfo {
+struct VersionInfo {
+    version: String,
+    /// `(boundary_schema_version, catalog_schema_version)`, resolved from the
+    /// canonical schema files' `schema_version` consts. Only populated when a
+    /// capabilities catalog was resolved, since orchestration only needs it
+    /// to negotiate against a specific probe/catalog pairing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    protocol: Option<(String, String)>,
+    run_mode: String,
     sandbox_mode: Option<String>,
+    container_image: Option<String>,
     os: String,
+    /// Sorted `Capability.id` values from the resolved catalog; absent when
+    /// no `--capabilities`/`FENCE_CATALOG_PATH` was given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    primary_capabilities: Option<Vec<String>>,
 }
 
-fn parse_cli_run_mode() -> Option<String> {
-    let mut args = env::args().skip(1);
-    let first = args.next()?;
-    if matches!(first.as_str(), "-h" | "--help") {
-        usage_and_exit();
-    }
-    if args.next().is_some() {
-        usage_and_exit();
+/// Resolves the boundary and capability-catalog `schema_version` consts from
+/// their canonical on-disk schema files, for [`VersionInfo::protocol`].
+fn protocol_versions() -> (String, String) {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let boundary = schema_version_from_file(&manifest_dir.join(CANONICAL_BOUNDARY_SCHEMA_PATH))
+        .unwrap_or_else(|| "unknown".to_string());
+    let catalog =
+        schema_version_from_file(&manifest_dir.join(CANONICAL_CAPABILITY_CATALOG_SCHEMA_PATH))
+            .unwrap_or_else(|| "unknown".to_string());
+    (boundary, catalog)
+}
+
+fn schema_version_from_file(path: &Path) -> Option<String> {
+    let file = File::open(path).ok()?;
+    let value: Value = serde_json::from_reader(file).ok()?;
+    value
+        .get("schema_version")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// Loads the catalog at `path` and projects each capability's id, sorted, for
+/// [`VersionInfo::primary_capabilities`].
+fn load_primary_capability_ids(path: &str) -> Result<Vec<String>> {
+    let catalog = load_catalog_from_path(Path::new(path))
+        .with_context(|| format!("loading capability catalog {path}"))?;
+    let mut ids: Vec<String> = catalog
+        .capabilities
+        .into_iter()
+        .map(|capability| capability.id.0)
+        .collect();
+    ids.sort();
+    Ok(ids)
+}
+
+#[derive(Default)]
+struct ParsedCli {
+    run_mode: Option<String>,
+    capabilities_path: Option<String>,
+}
+
+impl ParsedCli {
+    fn parse() -> Self {
+        let mut cli = Self::default();
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => usage_and_exit(),
+                "--capabilities" => {
+                    if cli.capabilities_path.is_some() {
+                        usage_and_exit();
+                    }
+                    cli.capabilities_path = Some(args.next().unwrap_or_else(|| usage_and_exit()));
+                }
+                _ if arg.starts_with("--") => usage_and_exit(),
+                _ => {
+                    if cli.run_mode.is_some() {
+                        usage_and_exit();
+                    }
+                    cli.run_mode = Some(arg);
+                }
+            }
+        }
+        cli
     }
-    Some(first)
 }
 
 fn detect_uname(args: &[&str]) -> Option<String> {
@@ -89,6 +186,6 @@ fn env_non_empty_any(names: &[&str]) -> Option<String> {
 }
 
 fn usage_and_exit() -> ! {
-    eprintln!("Usage: detect-stack [RUN_MODE]");
+    eprintln!("Usage: detect-stack [RUN_MODE] [--capabilities PATH]");
     std::process::exit(1);
 }
@@ -3,16 +3,20 @@
 //! This binary is the authoritative serializer for probe output. It validates
 //! capability IDs against the shipped catalog, shells out to `detect-stack` for
 //! host context, resolves workspace roots following the documented fallback
-//! order, and prints a single JSON record to stdout.
+//! order, and prints a single JSON record to stdout. `--annotate` (or
+//! `FENCE_ANNOTATIONS`) additionally prints a `fence:`-prefixed diagnostic
+//! line to stderr for non-success results, so CI problem matchers can surface
+//! capability regressions inline without parsing the JSON record.
 
 use anyhow::{Context, Result, anyhow, bail};
 use fencerunner::emit_support::{
-    JsonObjectBuilder, PayloadArgs, TextSource, normalize_secondary_ids, not_empty,
-    validate_capability_id, validate_status,
+    JsonObjectBuilder, PayloadArgs, TextSource, did_you_mean, normalize_secondary_ids, not_empty,
+    parse_defaults_file, prune_null_fields, validate_capability_id, validate_status,
 };
 use fencerunner::{
-    BoundarySchema, CapabilityId, CapabilityIndex, CapabilitySnapshot, StackInfo, find_repo_root,
-    resolve_boundary_schema_path, resolve_catalog_path, resolve_helper_binary, split_list,
+    BoundarySchema, CapabilityGrant, CapabilityId, CapabilityIndex, CapabilitySnapshot, StackInfo,
+    check_requested_capability, find_repo_root, resolve_boundary_schema_path, resolve_catalog_path,
+    resolve_helper_binary, resolve_probe, split_list, verify_attenuation_chain,
 };
 use serde_json::{Value, json};
 use std::env;
@@ -29,7 +33,61 @@ fn main() {
     }
 }
 
+/// Every flag `CliArgs::parse` recognizes, used to suggest fixes for typos.
+const KNOWN_FLAGS: &[&str] = &[
+    "--catalog",
+    "--boundary",
+    "--run-mode",
+    "--probe-name",
+    "--probe-id",
+    "--probe-version",
+    "--category",
+    "--verb",
+    "--target",
+    "--status",
+    "--errno",
+    "--message",
+    "--raw-exit-code",
+    "--error-detail",
+    "--payload-file",
+    "--payload-merge-file",
+    "--payload-stdout",
+    "--payload-stdout-file",
+    "--payload-stdout-binary",
+    "--payload-stderr",
+    "--payload-stderr-file",
+    "--payload-stderr-binary",
+    "--payload-raw",
+    "--payload-raw-file",
+    "--payload-raw-field",
+    "--payload-raw-field-json",
+    "--payload-raw-null",
+    "--payload-raw-list",
+    "--operation-args",
+    "--operation-args-file",
+    "--operation-arg",
+    "--operation-arg-json",
+    "--operation-arg-null",
+    "--operation-arg-list",
+    "--primary-capability-id",
+    "--secondary-capability-id",
+    "--command",
+    "--omit-empty",
+    "--compact",
+    "--signing-key-file",
+    "--signing-key-id",
+    "--defaults-file",
+    "--delegation-chain-file",
+    "--validate",
+    "--annotate",
+    "--help",
+];
+
 fn run() -> Result<()> {
+    if env::args_os().nth(1).as_deref() == Some(std::ffi::OsStr::new("version")) {
+        return run_version();
+    }
+
     let args = CliArgs::parse()?;
     let repo_root = find_repo_root()?;
 
@@ -43,6 +101,7 @@ fn run() -> Result<()> {
             capability_catalog_path.display()
         )
     })?;
+    warn_if_migrated(&capability_index, &capability_catalog_path);
     if capability_index.ids().next().is_none() {
         bail!(
             "No capability IDs found in {}",
@@ -96,6 +155,15 @@ fn run() -> Result<()> {
         .map(|cap| cap.snapshot())
         .collect();
 
+    let resolved_grant = match &args.delegation_chain_file {
+        Some(path) => Some(resolve_delegation_chain(
+            path,
+            &args.primary_capability_id,
+            &args.verb,
+        )?),
+        None => None,
+    };
+
     let boundary_schema_path = resolve_boundary_schema_path(
         &repo_root,
         args.boundary_schema_path.as_deref().map(Path::new),
@@ -113,7 +181,7 @@ fn run() -> Result<()> {
         )
     })?;
 
-    let record = json!({
+    let mut record = json!({
         "schema_version": boundary_schema.schema_version(),
         "schema_key": schema_key,
         "capabilities_schema_version": capabilities_schema_version,
@@ -140,14 +208,158 @@ fn run() -> Result<()> {
         "capability_context": {
             "primary": primary_capability_snapshot,
             "secondary": secondary_capability_snapshots,
+            "resolved_grant": resolved_grant,
         }
     });
 
+    if args.omit_empty {
+        // Required envelope fields are never null, so pruning unset optional
+        // fields cannot drop anything the boundary schema requires.
+        prune_null_fields(&mut record);
+    }
+
     boundary_schema.validate(&record)?;
+    if args.strict_validate {
+        validate_strict(&record)?;
+    }
+
+    if let Some(key_file) = &args.signing_key_file {
+        fencerunner::signing::sign_record(&mut record, key_file, args.signing_key_id.as_deref())
+            .with_context(|| format!("signing record with key {}", key_file.display()))?;
+    }
+
+    if args.annotate || env_flag_set("FENCE_ANNOTATIONS") {
+        emit_annotation(
+            &repo_root,
+            &args.probe_name,
+            &args.primary_capability_id,
+            &args.status,
+            args.message.as_deref(),
+            args.error_detail.as_deref(),
+        );
+    }
+
     println!("{}", serde_json::to_string(&record)?);
     Ok(())
 }
 
+/// Warn on stderr when `index` was loaded from a catalog declaring an older
+/// schema version than the one it was upcast to, so a team running against a
+/// stale-but-still-known catalog notices instead of silently trusting
+/// migrated data.
+fn warn_if_migrated(index: &CapabilityIndex, catalog_path: &Path) {
+    if let Some(from) = index.migrated_from() {
+        eprintln!(
+            "warning: capability catalog {} was migrated from schema_version '{}'",
+            catalog_path.display(),
+            from
+        );
+    }
+}
+
+fn env_flag_set(name: &str) -> bool {
+    env::var(name)
+        .map(|value| !value.is_empty())
+        .unwrap_or(false)
+}
+
+/// Print a single `fence:SEVERITY:FILE:LINE:CODE: MESSAGE` diagnostic line to
+/// stderr for a non-`success` result, in the regexp-friendly single-line
+/// format CI problem matchers expect, so a capability regression surfaces as
+/// an inline annotation instead of requiring a downstream JSON parse. Kept
+/// off stdout (and off by default) so existing JSON consumers of the
+/// boundary record on stdout are unaffected.
+fn emit_annotation(
+    repo_root: &Path,
+    probe_name: &str,
+    primary_capability_id: &CapabilityId,
+    status: &str,
+    message: Option<&str>,
+    error_detail: Option<&str>,
+) {
+    let severity = match status {
+        "error" => "error",
+        "denied" | "partial" => "warning",
+        _ => return,
+    };
+
+    let file = match resolve_probe(repo_root, probe_name) {
+        Ok(probe) => probe
+            .path
+            .strip_prefix(repo_root)
+            .map(|relative| relative.display().to_string())
+            .unwrap_or_else(|_| probe.path.display().to_string()),
+        Err(_) => probe_name.to_string(),
+    };
+    let message = message.or(error_detail).unwrap_or(status);
+
+    eprintln!(
+        "fence:{severity}:{file}:1:{}: {message}",
+        primary_capability_id.0
+    );
+}
+
+/// Print the effective compatibility surface as a single JSON object and exit.
+///
+/// Skips record emission entirely so harnesses can confirm a probe, the
+/// catalog, and the consumer agree on schema versions before running probes.
+fn run_version() -> Result<()> {
+    let mut args = env::args_os().skip(2);
+    let mut catalog_path = None;
+    let mut boundary_schema_path = None;
+    let mut run_mode = "baseline".to_string();
+
+    while let Some(arg_os) = args.next() {
+        let arg = os_to_string(arg_os);
+        match arg.as_str() {
+            "--catalog" => catalog_path = Some(next_value(&mut args, "--catalog")?),
+            "--boundary" => boundary_schema_path = Some(next_value(&mut args, "--boundary")?),
+            "--run-mode" => run_mode = next_value(&mut args, "--run-mode")?,
+            other => bail!("Unknown flag for version subcommand: {other}"),
+        }
+    }
+
+    let repo_root = find_repo_root()?;
+    let detect_stack = resolve_helper_binary(&repo_root, "detect-stack")?;
+
+    let capability_catalog_path =
+        resolve_catalog_path(&repo_root, catalog_path.as_deref().map(Path::new));
+    let capability_index = CapabilityIndex::load(&capability_catalog_path).with_context(|| {
+        format!(
+            "loading capability catalog from {}",
+            capability_catalog_path.display()
+        )
+    })?;
+    warn_if_migrated(&capability_index, &capability_catalog_path);
+
+    let boundary_schema_path = resolve_boundary_schema_path(
+        &repo_root,
+        boundary_schema_path.as_deref().map(Path::new),
+    )?;
+    let boundary_schema = BoundarySchema::load(&boundary_schema_path).with_context(|| {
+        format!(
+            "loading boundary schema from {}",
+            boundary_schema_path.display()
+        )
+    })?;
+
+    let stack_json = run_command_json(&detect_stack, &[&run_mode])
+        .with_context(|| format!("Failed to execute {}", detect_stack.display()))?;
+
+    let info = json!({
+        "schema_version": boundary_schema.schema_version(),
+        "schema_key": boundary_schema.schema_key(),
+        "capabilities_schema_version": capability_index.key(),
+        "catalog_path": capability_catalog_path,
+        "boundary_schema_path": boundary_schema_path,
+        "detect_stack_path": detect_stack,
+        "stack": stack_json,
+    });
+
+    println!("{}", serde_json::to_string(&info)?);
+    Ok(())
+}
+
 /// Parsed command-line arguments for a single record emission.
 ///
 /// Fields mirror the boundary-event envelope; most values are required because probes are
@@ -171,16 +383,32 @@ struct CliArgs {
     primary_capability_id: CapabilityId,
     secondary_capability_ids: Vec<CapabilityId>,
     command: String,
+    omit_empty: bool,
+    signing_key_file: Option<PathBuf>,
+    signing_key_id: Option<String>,
+    strict_validate: bool,
+    delegation_chain_file: Option<PathBuf>,
+    annotate: bool,
 }
 
 impl CliArgs {
     fn parse() -> Result<Self> {
-        let mut args = env::args_os().skip(1);
+        let all_args: Vec<OsString> = env::args_os().skip(1).collect();
         let mut config = PartialArgs::default();
 
+        if let Some(defaults_path) = find_defaults_file_flag(&all_args)? {
+            apply_defaults_file(&defaults_path, &mut config)?;
+        }
+
+        let mut args = all_args.into_iter();
+
         while let Some(arg_os) = args.next() {
             let arg = os_to_string(arg_os);
             match arg.as_str() {
+                "--defaults-file" => {
+                    // Already consumed above; skip its value here.
+                    next_value(&mut args, "--defaults-file")?;
+                }
                 "--catalog" => {
                     let value = next_value(&mut args, "--catalog")?;
                     config.catalog_path = Some(value);
@@ -215,6 +443,7 @@ impl CliArgs {
                     let value = PathBuf::from(next_value(&mut args, "--payload-file")?);
                     config.payload.set_payload_file(value)?;
                 }
+                "--payload-merge-file" => config.payload.enable_merge_with_file(),
                 "--payload-stdout" => {
                     let value = next_value(&mut args, "--payload-stdout")?;
                     config.payload.set_stdout(TextSource::Inline(value))?;
@@ -223,6 +452,10 @@ impl CliArgs {
                     let value = PathBuf::from(next_value(&mut args, "--payload-stdout-file")?);
                     config.payload.set_stdout(TextSource::File(value))?;
                 }
+                "--payload-stdout-binary" => {
+                    let value = PathBuf::from(next_value(&mut args, "--payload-stdout-binary")?);
+                    config.payload.set_stdout(TextSource::BinaryFile(value))?;
+                }
                 "--payload-stderr" => {
                     let value = next_value(&mut args, "--payload-stderr")?;
                     config.payload.set_stderr(TextSource::Inline(value))?;
@@ -231,6 +464,10 @@ impl CliArgs {
                     let value = PathBuf::from(next_value(&mut args, "--payload-stderr-file")?);
                     config.payload.set_stderr(TextSource::File(value))?;
                 }
+                "--payload-stderr-binary" => {
+                    let value = PathBuf::from(next_value(&mut args, "--payload-stderr-binary")?);
+                    config.payload.set_stderr(TextSource::BinaryFile(value))?;
+                }
                 "--payload-raw" => {
                     let value = next_value(&mut args, "--payload-raw")?;
                     config
@@ -310,12 +547,34 @@ impl CliArgs {
                     .secondary_capability_ids
                     .push(next_value(&mut args, "--secondary-capability-id")?),
                 "--command" => config.command = Some(next_value(&mut args, "--command")?),
+                "--omit-empty" | "--compact" => config.omit_empty = true,
+                "--signing-key-file" => {
+                    let value = next_value(&mut args, "--signing-key-file")?;
+                    config.signing_key_file = Some(PathBuf::from(value));
+                }
+                "--signing-key-id" => {
+                    config.signing_key_id = Some(next_value(&mut args, "--signing-key-id")?)
+                }
+                "--delegation-chain-file" => {
+                    let value = next_value(&mut args, "--delegation-chain-file")?;
+                    config.delegation_chain_file = Some(PathBuf::from(value));
+                }
+                "--annotate" => config.annotate = true,
+                "--validate" => {}
+                flag if flag.starts_with("--validate=") => {
+                    let mode = flag.trim_start_matches("--validate=");
+                    match mode {
+                        "strict" => config.strict_validate = true,
+                        other => bail!("Unknown --validate mode: {other} (expected 'strict')"),
+                    }
+                }
                 "--help" | "-h" => {
                     print_usage();
                     std::process::exit(1);
                 }
                 other => {
-                    eprintln!("Unknown flag: {other}");
+                    let hint = did_you_mean(other, KNOWN_FLAGS.iter().copied());
+                    eprintln!("Unknown flag: {other}.{hint}");
                     print_usage();
                     std::process::exit(1);
                 }
@@ -348,6 +607,12 @@ struct PartialArgs {
     primary_capability_id: Option<String>,
     secondary_capability_ids: Vec<String>,
     command: Option<String>,
+    omit_empty: bool,
+    signing_key_file: Option<PathBuf>,
+    signing_key_id: Option<String>,
+    strict_validate: bool,
+    delegation_chain_file: Option<PathBuf>,
+    annotate: bool,
 }
 
 impl PartialArgs {
@@ -371,6 +636,12 @@ impl PartialArgs {
             primary_capability_id,
             secondary_capability_ids,
             command,
+            omit_empty,
+            signing_key_file,
+            signing_key_id,
+            strict_validate,
+            delegation_chain_file,
+            annotate,
         } = self;
 
         Ok(CliArgs {
@@ -398,6 +669,12 @@ impl PartialArgs {
                 .map(|id| CapabilityId(id))
                 .collect(),
             command: Self::require("--command", command)?,
+            omit_empty,
+            signing_key_file,
+            signing_key_id,
+            strict_validate,
+            delegation_chain_file,
+            annotate,
         })
     }
 
@@ -406,6 +683,81 @@ impl PartialArgs {
     }
 }
 
+/// Scan the raw argument list for `--defaults-file PATH` without consuming it,
+/// so the defaults can be applied before the real flag-parsing pass.
+fn find_defaults_file_flag(args: &[OsString]) -> Result<Option<PathBuf>> {
+    for (idx, arg) in args.iter().enumerate() {
+        if arg == "--defaults-file" {
+            let value = args
+                .get(idx + 1)
+                .ok_or_else(|| anyhow!("Missing value for --defaults-file"))?;
+            return Ok(Some(PathBuf::from(value)));
+        }
+    }
+    Ok(None)
+}
+
+/// Seed `config` from a `KEY=VALUE` defaults file; explicit CLI flags parsed
+/// afterward still take precedence since they overwrite these values.
+fn apply_defaults_file(path: &Path, config: &mut PartialArgs) -> Result<()> {
+    let values = parse_defaults_file(path)
+        .with_context(|| format!("loading defaults file {}", path.display()))?;
+
+    let mut get = |key: &str| values.get(key).cloned();
+    if let Some(value) = get("CATALOG_PATH") {
+        config.catalog_path = Some(value);
+    }
+    if let Some(value) = get("BOUNDARY_SCHEMA_PATH") {
+        config.boundary_schema_path = Some(value);
+    }
+    if let Some(value) = get("RUN_MODE") {
+        config.run_mode = Some(value);
+    }
+    if let Some(value) = get("PROBE_NAME") {
+        config.probe_name = Some(value);
+    }
+    if let Some(value) = get("PROBE_VERSION") {
+        config.probe_version = Some(value);
+    }
+    if let Some(value) = get("CATEGORY") {
+        config.category = Some(value);
+    }
+    if let Some(value) = get("VERB") {
+        config.verb = Some(value);
+    }
+    if let Some(value) = get("TARGET") {
+        config.target = Some(value);
+    }
+    if let Some(value) = get("STATUS") {
+        config.status = Some(value);
+    }
+    if let Some(value) = get("ERRNO") {
+        config.errno = Some(value);
+    }
+    if let Some(value) = get("MESSAGE") {
+        config.message = Some(value);
+    }
+    if let Some(value) = get("RAW_EXIT_CODE") {
+        config.raw_exit_code = Some(parse_i64(value, "RAW_EXIT_CODE")?);
+    }
+    if let Some(value) = get("ERROR_DETAIL") {
+        config.error_detail = Some(value);
+    }
+    if let Some(value) = get("PRIMARY_CAPABILITY_ID") {
+        config.primary_capability_id = Some(value);
+    }
+    if let Some(value) = get("COMMAND") {
+        config.command = Some(value);
+    }
+    if let Some(value) = get("SIGNING_KEY_FILE") {
+        config.signing_key_file = Some(PathBuf::from(value));
+    }
+    if let Some(value) = get("SIGNING_KEY_ID") {
+        config.signing_key_id = Some(value);
+    }
+    Ok(())
+}
+
 fn next_value(args: &mut impl Iterator<Item = OsString>, flag: &str) -> Result<String> {
     args.next()
         .map(os_to_string)
@@ -462,6 +814,62 @@ fn run_command_json(path: &Path, args: &[&str]) -> Result<Value> {
     serde_json::from_slice(&output.stdout).context("Failed to parse command output as JSON")
 }
 
+/// Top-level keys the boundary-object schema declares; `--validate=strict`
+/// flags anything outside this set instead of letting it pass silently.
+const KNOWN_RECORD_KEYS: &[&str] = &[
+    "schema_version",
+    "schema_key",
+    "capabilities_schema_version",
+    "stack",
+    "probe",
+    "run",
+    "operation",
+    "result",
+    "payload",
+    "capability_context",
+];
+
+/// Extra checks layered on top of the schema validation that always runs:
+/// unknown top-level keys, and the `^[A-Za-z0-9_.-]+$` constraint on
+/// `capabilities_schema_version` that the schema test suite otherwise checks
+/// by hand.
+fn validate_strict(record: &Value) -> Result<()> {
+    let object = record
+        .as_object()
+        .ok_or_else(|| anyhow!("boundary record is not a JSON object"))?;
+
+    let mut errors = Vec::new();
+    for key in object.keys() {
+        if !KNOWN_RECORD_KEYS.contains(&key.as_str()) {
+            errors.push(format!("unknown top-level key '{key}'"));
+        }
+    }
+
+    if let Some(cap_schema) = object
+        .get("capabilities_schema_version")
+        .and_then(Value::as_str)
+    {
+        if cap_schema.is_empty()
+            || !cap_schema
+                .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+        {
+            errors.push(format!(
+                "capabilities_schema_version must match ^[A-Za-z0-9_.-]+$, got {cap_schema}"
+            ));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        bail!(
+            "strict boundary record validation failed:\n{}",
+            errors.join("\n")
+        );
+    }
+}
+
 fn resolve_secondary_capabilities<'a>(
     capabilities: &'a CapabilityIndex,
     ids: &[CapabilityId],
@@ -476,6 +884,24 @@ fn resolve_secondary_capabilities<'a>(
     Ok(caps)
 }
 
+/// Load a `--delegation-chain-file`, verify it only narrows root-to-leaf, and
+/// confirm the requested capability/operation fits inside the resolved leaf.
+fn resolve_delegation_chain(
+    path: &Path,
+    primary_capability_id: &CapabilityId,
+    operation: &str,
+) -> Result<CapabilityGrant> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("reading delegation chain file {}", path.display()))?;
+    let chain: Vec<CapabilityGrant> = serde_json::from_str(&contents)
+        .with_context(|| format!("parsing delegation chain file {}", path.display()))?;
+    let leaf = verify_attenuation_chain(&chain)
+        .with_context(|| format!("verifying delegation chain file {}", path.display()))?;
+    let leaf = check_requested_capability(leaf, primary_capability_id, operation)
+        .with_context(|| format!("checking delegation chain file {}", path.display()))?;
+    Ok(leaf.clone())
+}
+
 fn resolve_workspace_root() -> Result<Option<String>> {
     if let Ok(env_root) = env::var("FENCE_WORKSPACE_ROOT") {
         if !env_root.is_empty() {
@@ -530,7 +956,7 @@ fn print_usage() {
 }
 
 fn usage() -> &'static str {
-    "Usage: emit-record --run-mode MODE --probe-name NAME --probe-version VERSION \
+    "Usage: emit-record version [--catalog PATH] [--boundary PATH] [--run-mode MODE]\n  Prints the effective schema/catalog compatibility surface as JSON and exits.\n\nUsage: emit-record [--defaults-file PATH] --run-mode MODE --probe-name NAME --probe-version VERSION \
   --primary-capability-id CAP_ID --command COMMAND \
-  --category CATEGORY --verb VERB --target TARGET --status STATUS [options]\n\nOptions:\n  --errno ERRNO\n  --message MESSAGE\n  --raw-exit-code CODE\n  --error-detail TEXT\n  --secondary-capability-id CAP_ID   # repeat for multiple entries\n  --payload-file PATH (JSON object)\n  --payload-stdout TEXT | --payload-stdout-file PATH\n  --payload-stderr TEXT | --payload-stderr-file PATH\n  --payload-raw JSON_OBJECT | --payload-raw-file PATH\n  --payload-raw-field KEY VALUE\n  --payload-raw-field-json KEY JSON_VALUE\n  --payload-raw-null KEY\n  --payload-raw-list KEY \"a,b,c\"\n  --operation-args JSON_OBJECT | --operation-args-file PATH\n  --operation-arg KEY VALUE\n  --operation-arg-json KEY JSON_VALUE\n  --operation-arg-null KEY\n  --operation-arg-list KEY \"a,b,c\"\n"
+  --category CATEGORY --verb VERB --target TARGET --status STATUS [options]\n\nOptions:\n  --errno ERRNO\n  --message MESSAGE\n  --raw-exit-code CODE\n  --error-detail TEXT\n  --secondary-capability-id CAP_ID   # repeat for multiple entries\n  --payload-file PATH (JSON object)\n  --payload-merge-file                 # layer inline payload flags onto --payload-file instead of rejecting the combination\n  --payload-stdout TEXT | --payload-stdout-file PATH | --payload-stdout-binary PATH\n  --payload-stderr TEXT | --payload-stderr-file PATH | --payload-stderr-binary PATH\n  --payload-raw JSON_OBJECT | --payload-raw-file PATH\n  --payload-raw-field KEY VALUE\n  --payload-raw-field-json KEY JSON_VALUE\n  --payload-raw-null KEY\n  --payload-raw-list KEY \"a,b,c\"\n  --operation-args JSON_OBJECT | --operation-args-file PATH\n  --operation-arg KEY VALUE\n  --operation-arg-json KEY JSON_VALUE\n  --operation-arg-null KEY\n  --operation-arg-list KEY \"a,b,c\"\n  --omit-empty | --compact           # prune null fields from the emitted record\n  --signing-key-file PATH            # sign the record with a raw 32-byte ed25519 seed\n  --signing-key-id ID                 # key identifier recorded alongside the signature\n  --defaults-file PATH                # KEY=VALUE defaults; explicit flags still win\n  --delegation-chain-file PATH        # JSON array of CapabilityGrant, root first; verified and\n                                       # recorded as capability_context.resolved_grant\n  --validate[=strict]                 # schema validation always runs; =strict also rejects\n                                       # unknown top-level keys and malformed capabilities_schema_version\n  --annotate                          # also print a fence:SEVERITY:FILE:LINE:CODE: MESSAGE\n                                       # diagnostic to stderr for denied/partial/error results\n                                       # (or set FENCE_ANNOTATIONS to any non-empty value)\n"
 }
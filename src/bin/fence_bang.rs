@@ -4,8 +4,19 @@
 //! (or honors `PROBES`/`PROBES_RAW`), selects modes (`MODES` or defaults based
 //! on Codex availability), executes each probe via `fence-run`, and prints each
 //! emitted JSON object on its own line.
+//!
+//! Probes run concurrently across worker threads, gated by a GNU Make
+//! jobserver (see [`codex_fence::jobserver`]): when invoked from inside a
+//! `make -jN` recipe, `fence-bang` inherits that jobserver via `MAKEFLAGS` and
+//! never runs more probes at once than `make` granted it; otherwise it spins
+//! up a standalone jobserver sized by `--jobs`/`FENCE_JOBS`/available
+//! parallelism. The jobserver's auth token is forwarded into each spawned
+//! `fence-run` child's `MAKEFLAGS`, so a probe that itself shells out to `make`
+//! cooperates with the same pool instead of oversubscribing it.
 
-use anyhow::{Context, Result, bail};
+use anyhow::{Context, Result, anyhow, bail};
+use codex_fence::jobserver::{self, JobServer};
+use codex_fence::runtime::{CommandLogSpan, Verbosity};
 use codex_fence::{
     Probe, codex_present, find_repo_root, list_probes, resolve_helper_binary, resolve_probe,
     split_list,
@@ -14,8 +25,11 @@ use serde_json::Value;
 use std::{
     collections::BTreeSet,
     env,
+    ffi::OsString,
     path::Path,
     process::{Command, Stdio},
+    sync::Mutex,
+    thread,
 };
 
 fn main() {
@@ -26,16 +40,61 @@ fn main() {
 }
 
 fn run() -> Result<()> {
+    let cli = Cli::parse()?;
     let repo_root = find_repo_root()?;
     let probes = resolve_probes(&repo_root)?;
     let modes = resolve_modes()?;
+    let jobs = jobserver::from_environment(cli.jobs)?;
+    let verbosity = Verbosity::resolve(cli.verbose);
+
+    let tasks: Vec<(Probe, String)> = modes
+        .iter()
+        .flat_map(|mode| {
+            probes
+                .iter()
+                .cloned()
+                .map(move |probe| (probe, mode.clone()))
+        })
+        .collect();
+
+    let stdout_lock: Mutex<()> = Mutex::new(());
+    let errors: Mutex<Vec<String>> = Mutex::new(Vec::new());
 
-    for mode in modes {
-        for probe in &probes {
-            run_probe(&repo_root, probe, &mode)?;
+    thread::scope(|scope| {
+        for (probe, mode) in &tasks {
+            scope.spawn(|| {
+                let slot = match jobs.acquire() {
+                    Ok(slot) => slot,
+                    Err(err) => {
+                        errors
+                            .lock()
+                            .expect("errors mutex poisoned")
+                            .push(format!("failed to acquire jobserver slot: {err:#}"));
+                        return;
+                    }
+                };
+                let result = run_probe(&repo_root, probe, mode, &jobs, verbosity, &stdout_lock);
+                drop(slot);
+                if let Err(err) = result {
+                    errors
+                        .lock()
+                        .expect("errors mutex poisoned")
+                        .push(format!("{err:#}"));
+                }
+            });
         }
+    });
+
+    let errors = errors.into_inner().expect("errors mutex poisoned");
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        bail!(
+            "{} probe(s) failed; see stderr for details:\n{}",
+            errors.len(),
+            errors.join("\n")
+        )
     }
-    Ok(())
 }
 
 fn resolve_modes() -> Result<Vec<String>> {
@@ -93,16 +152,36 @@ fn resolve_probes(repo_root: &Path) -> Result<Vec<Probe>> {
     Ok(probes)
 }
 
-fn run_probe(repo_root: &Path, probe: &Probe, mode: &str) -> Result<()> {
+fn run_probe(
+    repo_root: &Path,
+    probe: &Probe,
+    mode: &str,
+    jobs: &JobServer,
+    verbosity: Verbosity,
+    stdout_lock: &Mutex<()>,
+) -> Result<()> {
     let runner = resolve_helper_binary(repo_root, "fence-run")?;
+    let makeflags = inject_makeflags(jobs.auth_arg());
+    let args = vec![OsString::from(mode), probe.path.as_os_str().to_os_string()];
+    let fence_env = vec![(OsString::from("MAKEFLAGS"), OsString::from(&makeflags))];
+    let log_span = CommandLogSpan::start(
+        verbosity,
+        runner.as_os_str(),
+        &args,
+        repo_root,
+        mode,
+        &fence_env,
+    );
     let output = Command::new(&runner)
         .arg(mode)
         .arg(&probe.path)
         .current_dir(repo_root)
+        .env("MAKEFLAGS", makeflags)
         .stdout(Stdio::piped())
         .stderr(Stdio::inherit())
         .output()
         .with_context(|| format!("Failed to execute {}", runner.display()))?;
+    log_span.finish(output.status.code(), &output.stdout, &[]);
 
     if !output.status.success() {
         // Gracefully skip codex modes when the host blocks sandbox application.
@@ -116,6 +195,16 @@ fn run_probe(repo_root: &Path, probe: &Probe, mode: &str) -> Result<()> {
             );
             return Ok(());
         }
+        // fence-netns (the `isolated` mode launcher) exits 72 when it can't
+        // build the namespace sandbox, mirroring how codex modes are skipped
+        // above rather than failing the whole matrix.
+        if mode == "isolated" && output.status.code() == Some(72) {
+            eprintln!(
+                "fence-bang: skipping mode {mode} for probe {}: namespace isolation unavailable",
+                probe.id
+            );
+            return Ok(());
+        }
         let code = output.status.code().unwrap_or(-1);
         bail!(
             "Probe {} in mode {} returned non-zero exit code {code}",
@@ -124,13 +213,92 @@ fn run_probe(repo_root: &Path, probe: &Probe, mode: &str) -> Result<()> {
         );
     }
 
-    let json_value: Value = serde_json::from_slice(&output.stdout).with_context(|| {
+    // Tracing (see codex_fence::execution_trace) appends a second NDJSON line
+    // after the boundary object; only the first non-empty line is the
+    // boundary object itself, so parse that one and pass the rest through
+    // verbatim rather than assuming the helper's stdout is a single value.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines().filter(|line| !line.trim().is_empty());
+    let boundary_line = lines
+        .next()
+        .with_context(|| format!("Probe {} in mode {} produced no output", probe.id, mode))?;
+    let json_value: Value = serde_json::from_str(boundary_line).with_context(|| {
         format!(
             "Failed to parse boundary object for probe {} in mode {}",
             probe.id, mode
         )
     })?;
     let compact = serde_json::to_string(&json_value)?;
+    let trailing: Vec<&str> = lines.collect();
+
+    let _guard = stdout_lock.lock().expect("stdout mutex poisoned");
     println!("{compact}");
+    for line in trailing {
+        println!("{line}");
+    }
     Ok(())
 }
+
+/// Fold a jobserver `--jobserver-auth=R,W` token into this process's own
+/// `MAKEFLAGS`, replacing any inherited auth token rather than appending a
+/// second one (a child honoring the old token would talk to a pipe that's no
+/// longer attached to this jobserver's pool).
+fn inject_makeflags(auth_arg: String) -> String {
+    let existing = env::var("MAKEFLAGS").unwrap_or_default();
+    let mut tokens: Vec<&str> = existing
+        .split_whitespace()
+        .filter(|token| {
+            !token.starts_with("--jobserver-auth=") && !token.starts_with("--jobserver-fds=")
+        })
+        .collect();
+    tokens.push(&auth_arg);
+    tokens.join(" ")
+}
+
+struct Cli {
+    jobs: Option<usize>,
+    verbose: u32,
+}
+
+impl Cli {
+    fn parse() -> Result<Self> {
+        let mut args = env::args_os();
+        let _program = args.next();
+        let mut jobs = None;
+        let mut verbose = 0;
+
+        while let Some(arg) = args.next() {
+            let arg_str = arg
+                .to_str()
+                .ok_or_else(|| anyhow!("invalid UTF-8 in argument"))?;
+            match arg_str {
+                "--jobs" => jobs = Some(next_jobs(&mut args)?),
+                "-v" | "--verbose" => verbose += 1,
+                "-vv" => verbose += 2,
+                "--help" | "-h" => usage(0),
+                other => bail!("unknown argument: {other}"),
+            }
+        }
+
+        Ok(Self { jobs, verbose })
+    }
+}
+
+fn next_jobs(args: &mut env::ArgsOs) -> Result<usize> {
+    let value = args
+        .next()
+        .ok_or_else(|| anyhow!("--jobs requires a value"))?;
+    let value = value
+        .into_string()
+        .map_err(|_| anyhow!("--jobs must be valid UTF-8"))?;
+    value
+        .parse()
+        .with_context(|| format!("--jobs must be a positive integer, got '{value}'"))
+}
+
+fn usage(code: i32) -> ! {
+    eprintln!(
+        "Usage: fence-bang [--jobs N] [-v|-vv]\n\nOptions:\n  --jobs N   Standalone jobserver parallelism when none is inherited via MAKEFLAGS\n             (or set FENCE_JOBS; defaults to available parallelism).\n  -v, --verbose  Log each fence-run invocation's program/argv/cwd/mode/env before\n             spawning and its elapsed time/exit code after (repeatable; -vv or a\n             second -v also prints captured stdout).\n  --help     Show this help text.\n\nHonors MODES/PROBES/PROBES_RAW the same way `make matrix` does.\nFENCE_LOG sets command-log verbosity (0/1/2 or quiet/verbose/debug) independent of -v."
+    );
+    std::process::exit(code);
+}
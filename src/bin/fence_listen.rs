@@ -1,15 +1,40 @@
 //! Plain-text listener that turns cfbo-v1 NDJSON into a readable summary.
 //!
-//! This binary intentionally stays text-only so it can sit in pipelines like
-//! `codex-fence --bang | codex-fence --listen`. It leans on the shared
-//! boundary reader so it understands the exact cfbo-v1 schema without rolling
-//! bespoke parsers.
+//! This binary intentionally stays text-only by default so it can sit in
+//! pipelines like `codex-fence --bang | codex-fence --listen`. It leans on
+//! the shared boundary reader so it understands the exact cfbo-v1 schema
+//! without rolling bespoke parsers. `--format json|junit|tap` swaps the
+//! human summary for a machine-readable report of the same records, for
+//! callers that want to feed this into CI instead of a terminal.
+//!
+//! The default text format renders one record at a time as it arrives off
+//! `reader` (see [`render_records_streaming`]) rather than buffering the
+//! whole stream, so a long-lived `--bang | --listen` pipeline shows progress
+//! instead of going silent until EOF; the running [`ListenStats`] are
+//! flushed as a summary footer once the stream ends. `--follow` additionally
+//! re-prints that summary block every `--follow-every` records or
+//! `--follow-interval` seconds, whichever comes first, so a pipe that stays
+//! open for a long time still gets periodic progress without waiting for
+//! EOF.
+//!
+//! When a record's probe declares an `expected_result` (see
+//! [`codex_fence::ProbeMetadata`]), the listener joins the observed result
+//! against that expectation and classifies the record as [`Assertion::Pass`]
+//! or [`Assertion::Fail`]; a probe with no declared expectation stays
+//! [`Assertion::Unasserted`] and never affects the exit code. `run()` exits
+//! non-zero if any record is classified `Fail`, the way compiletest matches
+//! expected outcomes against actual ones, so this binary doubles as a
+//! regression gate instead of only a human-readable transcript.
 
-use anyhow::{Result, anyhow, bail};
-use codex_fence::{BoundaryObject, BoundaryReadError, read_boundary_objects};
+use anyhow::{Context, Result, anyhow, bail};
+use codex_fence::junit::{JunitCase, JunitOutcome, JunitSuite, render_junit_xml};
+use codex_fence::reporter::format_counts;
+use codex_fence::{BoundaryObject, BoundaryReadError, ProbeMetadata, read_boundary_objects};
 use std::collections::{BTreeMap, BTreeSet};
+use std::env;
 use std::fmt;
-use std::io::{self, BufRead, BufReader, IsTerminal};
+use std::io::{self, BufRead, BufReader, IsTerminal, Write as _};
+use std::time::{Duration, Instant};
 
 fn main() {
     if let Err(err) = run() {
@@ -19,6 +44,7 @@ fn main() {
 }
 
 fn run() -> Result<()> {
+    let cli = Cli::parse()?;
     let stdin = io::stdin();
     if stdin.is_terminal() {
         bail!(
@@ -26,23 +52,234 @@ fn run() -> Result<()> {
         );
     }
 
+    let expectations = load_expectations();
     let reader = BufReader::new(stdin.lock());
+    if cli.format == OutputFormat::Text && cli.follow.is_some() {
+        let fail_count = run_follow(
+            reader,
+            cli.follow.expect("checked is_some"),
+            &expectations,
+            &cli.filter,
+            cli.group_by,
+        )
+        .map_err(|err| anyhow!(err))?;
+        return fail_on_assertions(fail_count);
+    }
+
     let mut output = String::new();
-    render_listen_output(reader, &mut output).map_err(|err| match err {
+    let fail_count = render_listen_output(
+        reader,
+        &mut output,
+        cli.format,
+        &expectations,
+        &cli.filter,
+        cli.group_by,
+    )
+    .map_err(|err| match err {
         ListenError::Boundary(inner) => anyhow!(inner),
         ListenError::Write(inner) => anyhow!(inner),
     })?;
     print!("{}", output);
+    fail_on_assertions(fail_count)
+}
+
+fn fail_on_assertions(fail_count: usize) -> Result<()> {
+    if fail_count > 0 {
+        bail!("{fail_count} record(s) failed their probe's expected_result assertion");
+    }
     Ok(())
 }
 
-/// Read NDJSON from `reader`, summarize, and render into the provided writer.
+/// Best-effort load of every probe's [`ProbeMetadata`] under the current
+/// repository's `probes/` tree, keyed by probe id. Returns an empty map
+/// (rather than an error) when the repo root or probe scripts can't be
+/// resolved, since a record whose probe isn't found this way should read as
+/// unasserted, not abort the listener.
+fn load_expectations() -> Expectations {
+    let Ok(repo_root) = codex_fence::find_repo_root() else {
+        return Expectations::new();
+    };
+    let Ok(probes) = codex_fence::list_probes(&repo_root) else {
+        return Expectations::new();
+    };
+    probes
+        .into_iter()
+        .filter_map(|probe| {
+            ProbeMetadata::from_script(&probe.path)
+                .ok()
+                .map(|metadata| (probe.id, metadata))
+        })
+        .collect()
+}
+
+type Expectations = BTreeMap<String, ProbeMetadata>;
+
+/// Whether a record's observed result matched, mismatched, or was never
+/// checked against its probe's declared `expected_result`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Assertion {
+    Pass,
+    Fail,
+    Unasserted,
+}
+
+impl Assertion {
+    fn marker(self) -> &'static str {
+        match self {
+            Assertion::Pass => "PASS",
+            Assertion::Fail => "FAIL",
+            Assertion::Unasserted => "    ",
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Assertion::Pass => "pass",
+            Assertion::Fail => "fail",
+            Assertion::Unasserted => "unasserted",
+        }
+    }
+}
+
+/// Classify `record` against `expectations`: `Unasserted` when the probe
+/// isn't known or declares no expectation for this run mode, `Pass`/`Fail`
+/// otherwise depending on whether the observed result matches.
+fn classify(expectations: &Expectations, record: &BoundaryObject) -> Assertion {
+    let Some(expected) = expectations
+        .get(&record.probe.id)
+        .and_then(|metadata| metadata.expected_result_for_mode(&record.run.mode))
+    else {
+        return Assertion::Unasserted;
+    };
+    if expected == record.result.observed_result {
+        Assertion::Pass
+    } else {
+        Assertion::Fail
+    }
+}
+
+/// Drive the streaming text renderer straight against stdout, flushing after
+/// each record and re-printing the running summary every
+/// [`FollowConfig::every_records`] records or [`FollowConfig::every_interval`]
+/// elapsed, whichever comes first. Returns the number of records classified
+/// `Fail` against `expectations`.
+fn run_follow(
+    mut reader: impl BufRead,
+    follow: FollowConfig,
+    expectations: &Expectations,
+    filter: &RecordFilter,
+    group_by: GroupBy,
+) -> Result<usize, ListenError> {
+    let mut stdout = io::stdout();
+    let mut stats = ListenStats::new(group_by);
+    let mut line_buf = String::new();
+    let mut line_number = 0usize;
+    let mut idx = 0usize;
+    let mut last_summary_at = Instant::now();
+
+    loop {
+        line_buf.clear();
+        let bytes = reader
+            .read_line(&mut line_buf)
+            .map_err(BoundaryReadError::Io)
+            .map_err(ListenError::Boundary)?;
+        if bytes == 0 {
+            break;
+        }
+        line_number += 1;
+        let trimmed = line_buf.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let record: BoundaryObject = serde_json::from_str(trimmed).map_err(|error| {
+            ListenError::Boundary(BoundaryReadError::Parse {
+                line: line_number,
+                error,
+            })
+        })?;
+        if !filter.matches(&record) {
+            continue;
+        }
+
+        idx += 1;
+        let assertion = classify(expectations, &record);
+        stats.update(&record, assertion);
+        write_flushed(&mut stdout, |buf| {
+            render_record(idx, &record, assertion, buf)
+        })?;
+
+        if idx % follow.every_records == 0 || last_summary_at.elapsed() >= follow.every_interval {
+            write_flushed(&mut stdout, |buf| render_summary(&stats, buf))?;
+            last_summary_at = Instant::now();
+        }
+    }
+
+    write_flushed(&mut stdout, |buf| render_summary(&stats, buf))?;
+    Ok(stats.fail)
+}
+
+/// Render into a scratch [`String`] via `render`, then write it straight to
+/// `out` and flush, so each record/summary becomes visible immediately
+/// instead of waiting behind stdout's own buffering.
+fn write_flushed(
+    out: &mut impl io::Write,
+    render: impl FnOnce(&mut String) -> fmt::Result,
+) -> Result<(), ListenError> {
+    let mut buf = String::new();
+    render(&mut buf).map_err(ListenError::Write)?;
+    out.write_all(buf.as_bytes())
+        .and_then(|_| out.flush())
+        .map_err(|err| ListenError::Boundary(BoundaryReadError::Io(err)))
+}
+
+/// Read NDJSON from `reader`, summarize, and render into the provided writer
+/// in the requested `format`. The `Text` format streams: each record renders
+/// as soon as its line parses, and [`ListenStats`] are printed as a footer
+/// once the stream ends (see [`render_records_streaming`]). The other
+/// formats are single documents, so they still read the whole stream first.
+/// Returns the number of records classified `Fail` against `expectations`
+/// regardless of format, so `run()` can gate on it uniformly.
 pub fn render_listen_output<R: BufRead, W: fmt::Write>(
     reader: R,
     writer: &mut W,
-) -> Result<(), ListenError> {
-    let records = read_boundary_objects(reader).map_err(ListenError::Boundary)?;
-    render_records(&records, writer).map_err(ListenError::Write)
+    format: OutputFormat,
+    expectations: &Expectations,
+    filter: &RecordFilter,
+    group_by: GroupBy,
+) -> Result<usize, ListenError> {
+    match format {
+        OutputFormat::Text => {
+            render_records_streaming(reader, writer, expectations, filter, group_by)
+        }
+        OutputFormat::Json => {
+            let records = read_boundary_objects(reader).map_err(ListenError::Boundary)?;
+            let records = filter_records(records, filter);
+            render_json_report(&records, expectations, writer).map_err(ListenError::Write)?;
+            Ok(summarize_records(&records, expectations, group_by).fail)
+        }
+        OutputFormat::Junit => {
+            let records = read_boundary_objects(reader).map_err(ListenError::Boundary)?;
+            let records = filter_records(records, filter);
+            render_junit_report(&records, writer).map_err(ListenError::Write)?;
+            Ok(summarize_records(&records, expectations, group_by).fail)
+        }
+        OutputFormat::Tap => {
+            let records = read_boundary_objects(reader).map_err(ListenError::Boundary)?;
+            let records = filter_records(records, filter);
+            render_tap_report(&records, writer).map_err(ListenError::Write)?;
+            Ok(summarize_records(&records, expectations, group_by).fail)
+        }
+    }
+}
+
+/// Drop every record `filter` doesn't select, the way Deno's test runner's
+/// `--filter` narrows a run instead of only hiding output after the fact;
+/// both the summary counts and the per-record dump see only the selection.
+fn filter_records(records: Vec<BoundaryObject>, filter: &RecordFilter) -> Vec<BoundaryObject> {
+    if filter.is_empty() {
+        return records;
+    }
+    records.into_iter().filter(|r| filter.matches(r)).collect()
 }
 
 #[derive(Debug, Default)]
@@ -51,36 +288,115 @@ struct ListenStats {
     distinct_probes: usize,
     results: BTreeMap<String, usize>,
     modes: BTreeMap<String, usize>,
+    seen_probes: BTreeSet<String>,
+    pass: usize,
+    fail: usize,
+    unasserted: usize,
+    group_by: GroupBy,
+    /// Result counts tallied per [`GroupBy`] key (e.g. one `format_counts`
+    /// breakdown per `CapabilityCategory`); empty when `group_by` is
+    /// [`GroupBy::None`].
+    grouped: BTreeMap<String, BTreeMap<String, usize>>,
 }
 
-fn summarize_records(records: &[BoundaryObject]) -> ListenStats {
-    let mut stats = ListenStats::default();
-    stats.total_records = records.len();
-    stats.distinct_probes = records
-        .iter()
-        .map(|record| record.probe.id.as_str())
-        .collect::<BTreeSet<_>>()
-        .len();
+impl ListenStats {
+    fn new(group_by: GroupBy) -> Self {
+        Self {
+            group_by,
+            ..Self::default()
+        }
+    }
 
-    for record in records {
-        *stats
+    /// Fold one more record into the running counters. Kept separate from
+    /// [`summarize_records`] so a streaming reader can update stats per
+    /// record without holding the whole stream in memory.
+    fn update(&mut self, record: &BoundaryObject, assertion: Assertion) {
+        self.total_records += 1;
+        self.seen_probes.insert(record.probe.id.clone());
+        self.distinct_probes = self.seen_probes.len();
+        *self
             .results
             .entry(record.result.observed_result.clone())
             .or_insert(0) += 1;
-        *stats.modes.entry(record.run.mode.clone()).or_insert(0) += 1;
+        *self.modes.entry(record.run.mode.clone()).or_insert(0) += 1;
+        match assertion {
+            Assertion::Pass => self.pass += 1,
+            Assertion::Fail => self.fail += 1,
+            Assertion::Unasserted => self.unasserted += 1,
+        }
+        if let Some(key) = self.group_by.key_for(record) {
+            *self
+                .grouped
+                .entry(key)
+                .or_default()
+                .entry(record.result.observed_result.clone())
+                .or_insert(0) += 1;
+        }
     }
+}
 
+fn summarize_records(
+    records: &[BoundaryObject],
+    expectations: &Expectations,
+    group_by: GroupBy,
+) -> ListenStats {
+    let mut stats = ListenStats::new(group_by);
+    for record in records {
+        let assertion = classify(expectations, record);
+        stats.update(record, assertion);
+    }
     stats
 }
 
-fn render_records(records: &[BoundaryObject], writer: &mut impl fmt::Write) -> fmt::Result {
-    let stats = summarize_records(records);
-    render_summary(&stats, writer)?;
-    writeln!(writer)?;
-    for (idx, record) in records.iter().enumerate() {
-        render_record(idx + 1, record, writer)?;
+/// Render `reader`'s NDJSON one line at a time: each record prints via
+/// [`render_record`] as soon as it parses, and the running [`ListenStats`]
+/// print as a summary footer once the stream ends. This is what lets a
+/// long-lived `--bang | --listen` pipe show progress instead of going silent
+/// until EOF. Returns the number of records classified `Fail`.
+fn render_records_streaming<R: BufRead>(
+    mut reader: R,
+    writer: &mut impl fmt::Write,
+    expectations: &Expectations,
+    filter: &RecordFilter,
+    group_by: GroupBy,
+) -> Result<usize, ListenError> {
+    let mut stats = ListenStats::new(group_by);
+    let mut line_buf = String::new();
+    let mut line_number = 0usize;
+    let mut idx = 0usize;
+
+    loop {
+        line_buf.clear();
+        let bytes = reader
+            .read_line(&mut line_buf)
+            .map_err(BoundaryReadError::Io)
+            .map_err(ListenError::Boundary)?;
+        if bytes == 0 {
+            break;
+        }
+        line_number += 1;
+        let trimmed = line_buf.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let record: BoundaryObject = serde_json::from_str(trimmed).map_err(|error| {
+            ListenError::Boundary(BoundaryReadError::Parse {
+                line: line_number,
+                error,
+            })
+        })?;
+        if !filter.matches(&record) {
+            continue;
+        }
+
+        idx += 1;
+        let assertion = classify(expectations, &record);
+        stats.update(&record, assertion);
+        render_record(idx, &record, assertion, writer).map_err(ListenError::Write)?;
     }
-    Ok(())
+
+    render_summary(&stats, writer).map_err(ListenError::Write)?;
+    Ok(stats.fail)
 }
 
 fn render_summary(stats: &ListenStats, writer: &mut impl fmt::Write) -> fmt::Result {
@@ -98,14 +414,34 @@ fn render_summary(stats: &ListenStats, writer: &mut impl fmt::Write) -> fmt::Res
         "modes          : {}",
         format_counts(&stats.modes, "none")
     )?;
+    writeln!(
+        writer,
+        "assertions     : pass={} fail={} unasserted={}",
+        stats.pass, stats.fail, stats.unasserted
+    )?;
+    if !stats.grouped.is_empty() {
+        writeln!(writer, "by {}:", stats.group_by.label())?;
+        for (key, counts) in &stats.grouped {
+            writeln!(writer, "  {:<16} {}", key, format_counts(counts, "none"))?;
+        }
+    }
     Ok(())
 }
 
-fn render_record(idx: usize, record: &BoundaryObject, writer: &mut impl fmt::Write) -> fmt::Result {
+fn render_record(
+    idx: usize,
+    record: &BoundaryObject,
+    assertion: Assertion,
+    writer: &mut impl fmt::Write,
+) -> fmt::Result {
     writeln!(
         writer,
-        "[#{}] {:<7} mode={} probe={}",
-        idx, record.result.observed_result, record.run.mode, record.probe.id
+        "[#{}] {} {:<7} mode={} probe={}",
+        idx,
+        assertion.marker(),
+        record.result.observed_result,
+        record.run.mode,
+        record.probe.id
     )?;
     let capability = &record.capability_context.primary;
     writeln!(
@@ -176,14 +512,403 @@ fn truncate_line(line: &str) -> String {
     shortened
 }
 
-fn format_counts(map: &BTreeMap<String, usize>, empty_label: &str) -> String {
-    if map.is_empty() {
-        return empty_label.to_string();
+/// Render `records` as a single machine-readable JSON object: the same
+/// counts [`render_summary`] reports in text form (`total_records`,
+/// `distinct_probes`, `results`, `modes`, `pass`/`fail`/`unasserted`) plus a
+/// compact `records` array (index, result, mode, probe, capability,
+/// operation, assertion, and trimmed message/snippets). The fail count that
+/// gates the exit code is computed by the caller, not returned here.
+fn render_json_report(
+    records: &[BoundaryObject],
+    expectations: &Expectations,
+    writer: &mut impl fmt::Write,
+) -> fmt::Result {
+    let stats = summarize_records(records, expectations, GroupBy::None);
+
+    let record_entries: Vec<serde_json::Value> = records
+        .iter()
+        .enumerate()
+        .map(|(idx, record)| {
+            serde_json::json!({
+                "index": idx + 1,
+                "result": record.result.observed_result,
+                "mode": record.run.mode,
+                "probe": record.probe.id,
+                "capability": {
+                    "id": record.capability_context.primary.id.0,
+                    "category": record.capability_context.primary.category.as_str(),
+                    "layer": record.capability_context.primary.layer.as_str(),
+                },
+                "operation": {
+                    "verb": record.operation.verb,
+                    "target": record.operation.target,
+                },
+                "assertion": classify(expectations, record).as_str(),
+                "message": record
+                    .result
+                    .message
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|msg| !msg.is_empty()),
+                "stdout_snippet": trimmed_snippet(record.payload.stdout_snippet.as_deref()),
+                "stderr_snippet": trimmed_snippet(record.payload.stderr_snippet.as_deref()),
+            })
+        })
+        .collect();
+
+    let report = serde_json::json!({
+        "total_records": stats.total_records,
+        "distinct_probes": stats.distinct_probes,
+        "results": stats.results,
+        "modes": stats.modes,
+        "assertions": {
+            "pass": stats.pass,
+            "fail": stats.fail,
+            "unasserted": stats.unasserted,
+        },
+        "records": record_entries,
+    });
+
+    let rendered = serde_json::to_string_pretty(&report).map_err(|_| fmt::Error)?;
+    writeln!(writer, "{rendered}")
+}
+
+/// Like [`write_snippet`]'s trimming, but returning an owned trimmed string
+/// for JSON embedding (or `None` for missing/blank input) instead of writing
+/// it to a text buffer directly.
+fn trimmed_snippet(snippet: Option<&str>) -> Option<String> {
+    let trimmed = snippet?.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    let mut lines = trimmed.lines();
+    let mut kept: Vec<String> = Vec::new();
+    for _ in 0..MAX_SNIPPET_LINES {
+        match lines.next() {
+            Some(line) => kept.push(truncate_line(line)),
+            None => break,
+        }
+    }
+    if lines.next().is_some() {
+        kept.push("…".to_string());
+    }
+    Some(kept.join("\n"))
+}
+
+/// Render `records` as a JUnit `<testsuites>` document: one `<testcase>` per
+/// record, named after the probe id with the primary capability id as its
+/// classname. `error` becomes a `<error>` element (the probe didn't complete
+/// cleanly); `denied`/`partial` become a `<failure>` element (it ran, but the
+/// boundary didn't hold); `success` is a bare passing `<testcase>`.
+fn render_junit_report(records: &[BoundaryObject], writer: &mut impl fmt::Write) -> fmt::Result {
+    let suite = JunitSuite {
+        name: "fence-listen".to_string(),
+        cases: records.iter().map(junit_case_for_record).collect(),
+    };
+    write!(writer, "{}", render_junit_xml(&[suite]))
+}
+
+fn junit_case_for_record(record: &BoundaryObject) -> JunitCase {
+    let message = record
+        .result
+        .message
+        .clone()
+        .filter(|msg| !msg.trim().is_empty())
+        .unwrap_or_else(|| format!("observed_result={}", record.result.observed_result));
+    let outcome = match record.result.observed_result.as_str() {
+        "success" => JunitOutcome::Pass,
+        "error" => JunitOutcome::Error(message),
+        _ => JunitOutcome::Failure(message),
+    };
+    JunitCase {
+        probe_id: record.probe.id.clone(),
+        classname: Some(record.capability_context.primary.id.0.clone()),
+        outcome,
+    }
+}
+
+/// Render `records` as a TAP (Test Anything Protocol) stream: a `1..N` plan
+/// line followed by one `ok`/`not ok` line per record, each annotated with
+/// the probe id and, for a non-success result, a YAML diagnostic block
+/// carrying the observed result and message.
+fn render_tap_report(records: &[BoundaryObject], writer: &mut impl fmt::Write) -> fmt::Result {
+    writeln!(writer, "1..{}", records.len())?;
+    for (idx, record) in records.iter().enumerate() {
+        let number = idx + 1;
+        if record.result.observed_result == "success" {
+            writeln!(writer, "ok {number} - {}", record.probe.id)?;
+            continue;
+        }
+        writeln!(
+            writer,
+            "not ok {number} - {} ({})",
+            record.probe.id, record.result.observed_result
+        )?;
+        writeln!(writer, "  ---")?;
+        writeln!(writer, "  result: {}", record.result.observed_result)?;
+        if let Some(message) = record
+            .result
+            .message
+            .as_deref()
+            .map(str::trim)
+            .filter(|msg| !msg.is_empty())
+        {
+            writeln!(writer, "  message: {message}")?;
+        }
+        writeln!(writer, "  ...")?;
+    }
+    Ok(())
+}
+
+/// Output format selected by `--format`: the default human-readable text
+/// summary, or a machine-readable `json`/`junit`/`tap` report of the same
+/// records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Junit,
+    Tap,
+}
+
+impl OutputFormat {
+    fn parse(value: &str) -> Result<Self> {
+        match value {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "junit" => Ok(OutputFormat::Junit),
+            "tap" => Ok(OutputFormat::Tap),
+            other => bail!("unknown format '{other}' (expected text, json, junit, or tap)"),
+        }
+    }
+}
+
+/// `--follow` tuning: how often [`run_follow`] re-prints the running summary
+/// while the stream stays open, whichever of the two comes first.
+#[derive(Debug, Clone, Copy)]
+struct FollowConfig {
+    every_records: usize,
+    every_interval: Duration,
+}
+
+impl Default for FollowConfig {
+    fn default() -> Self {
+        Self {
+            every_records: 20,
+            every_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Record-selection criteria applied before both the summary counts and the
+/// per-record dump (`--filter-result`, `--filter-mode`, `--filter-probe`,
+/// `--filter-capability`), the way Deno's test runner's `--filter` narrows a
+/// run instead of only hiding output after the fact.
+#[derive(Debug, Clone, Default)]
+struct RecordFilter {
+    results: Option<BTreeSet<String>>,
+    mode: Option<String>,
+    probe_glob: Option<String>,
+    capability: Option<String>,
+}
+
+impl RecordFilter {
+    fn is_empty(&self) -> bool {
+        self.results.is_none()
+            && self.mode.is_none()
+            && self.probe_glob.is_none()
+            && self.capability.is_none()
+    }
+
+    fn matches(&self, record: &BoundaryObject) -> bool {
+        if let Some(results) = &self.results {
+            if !results.contains(&record.result.observed_result) {
+                return false;
+            }
+        }
+        if let Some(mode) = &self.mode {
+            if mode != &record.run.mode {
+                return false;
+            }
+        }
+        if let Some(pattern) = &self.probe_glob {
+            if !glob_match(pattern, &record.probe.id) {
+                return false;
+            }
+        }
+        if let Some(capability) = &self.capability {
+            if capability != &record.capability_context.primary.id.0 {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Minimal glob matcher supporting `*` (any run of characters, including
+/// none); every other character must match literally. Good enough for
+/// probe-id filtering without pulling in a dependency for one wildcard.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    fn match_from(pattern: &[u8], text: &[u8]) -> bool {
+        match pattern.first() {
+            None => text.is_empty(),
+            Some(b'*') => (0..=text.len()).any(|i| match_from(&pattern[1..], &text[i..])),
+            Some(&ch) => !text.is_empty() && text[0] == ch && match_from(&pattern[1..], &text[1..]),
+        }
+    }
+    match_from(pattern.as_bytes(), text.as_bytes())
+}
+
+/// How [`ListenStats`] breaks its result counts down beyond the flat
+/// `results`/`modes` totals: per [`codex_fence::CapabilityCategory`], per
+/// [`codex_fence::CapabilityLayer`], or per run mode, selected via `--group-by`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum GroupBy {
+    #[default]
+    None,
+    CapabilityCategory,
+    CapabilityLayer,
+    Mode,
+}
+
+impl GroupBy {
+    fn parse(value: &str) -> Result<Self> {
+        match value {
+            "capability-category" => Ok(GroupBy::CapabilityCategory),
+            "capability-layer" => Ok(GroupBy::CapabilityLayer),
+            "mode" => Ok(GroupBy::Mode),
+            other => bail!(
+                "unknown --group-by value '{other}' (expected capability-category, capability-layer, or mode)"
+            ),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            GroupBy::None => "none",
+            GroupBy::CapabilityCategory => "capability-category",
+            GroupBy::CapabilityLayer => "capability-layer",
+            GroupBy::Mode => "mode",
+        }
+    }
+
+    fn key_for(self, record: &BoundaryObject) -> Option<String> {
+        match self {
+            GroupBy::None => None,
+            GroupBy::CapabilityCategory => Some(
+                record
+                    .capability_context
+                    .primary
+                    .category
+                    .as_str()
+                    .to_string(),
+            ),
+            GroupBy::CapabilityLayer => {
+                Some(record.capability_context.primary.layer.as_str().to_string())
+            }
+            GroupBy::Mode => Some(record.run.mode.clone()),
+        }
+    }
+}
+
+struct Cli {
+    format: OutputFormat,
+    follow: Option<FollowConfig>,
+    filter: RecordFilter,
+    group_by: GroupBy,
+}
+
+impl Cli {
+    fn parse() -> Result<Self> {
+        let mut args = env::args_os();
+        let _program = args.next();
+        let mut format = OutputFormat::Text;
+        let mut follow: Option<FollowConfig> = None;
+        let mut filter = RecordFilter::default();
+        let mut group_by = GroupBy::None;
+
+        while let Some(arg) = args.next() {
+            let arg_str = arg
+                .to_str()
+                .ok_or_else(|| anyhow!("invalid UTF-8 in argument"))?;
+            match arg_str {
+                "--format" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--format requires a value"))?;
+                    let value = value
+                        .into_string()
+                        .map_err(|_| anyhow!("--format must be valid UTF-8"))?;
+                    format = OutputFormat::parse(&value)?;
+                }
+                "--follow" => {
+                    follow.get_or_insert_with(FollowConfig::default);
+                }
+                "--follow-every" => {
+                    let config = follow.get_or_insert_with(FollowConfig::default);
+                    config.every_records = next_usize(&mut args, "--follow-every")?;
+                }
+                "--follow-interval" => {
+                    let config = follow.get_or_insert_with(FollowConfig::default);
+                    config.every_interval =
+                        Duration::from_secs(next_usize(&mut args, "--follow-interval")? as u64);
+                }
+                "--filter-result" => {
+                    let value = next_value(&mut args, "--filter-result")?;
+                    let values: BTreeSet<String> = value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|value| !value.is_empty())
+                        .map(str::to_string)
+                        .collect();
+                    if values.is_empty() {
+                        bail!("--filter-result requires at least one value");
+                    }
+                    filter.results = Some(values);
+                }
+                "--filter-mode" => filter.mode = Some(next_value(&mut args, "--filter-mode")?),
+                "--filter-probe" => {
+                    filter.probe_glob = Some(next_value(&mut args, "--filter-probe")?)
+                }
+                "--filter-capability" => {
+                    filter.capability = Some(next_value(&mut args, "--filter-capability")?)
+                }
+                "--group-by" => group_by = GroupBy::parse(&next_value(&mut args, "--group-by")?)?,
+                "--help" | "-h" => usage(0),
+                other => bail!("unknown argument: {other}"),
+            };
+        }
+
+        Ok(Self {
+            format,
+            follow,
+            filter,
+            group_by,
+        })
     }
-    map.iter()
-        .map(|(key, value)| format!("{}={}", key, value))
-        .collect::<Vec<_>>()
-        .join(", ")
+}
+
+fn next_value(args: &mut env::ArgsOs, flag: &str) -> Result<String> {
+    let value = args
+        .next()
+        .ok_or_else(|| anyhow!("{flag} requires a value"))?;
+    value
+        .into_string()
+        .map_err(|_| anyhow!("{flag} must be valid UTF-8"))
+}
+
+fn next_usize(args: &mut env::ArgsOs, flag: &str) -> Result<usize> {
+    let value = next_value(args, flag)?;
+    value
+        .parse()
+        .with_context(|| format!("{flag} must be a positive integer, got '{value}'"))
+}
+
+fn usage(code: i32) -> ! {
+    eprintln!(
+        "Usage: codex-fence --listen [--format text|json|junit|tap] [--follow [--follow-every N] [--follow-interval SECS]]\n                     [--filter-result R1,R2] [--filter-mode MODE] [--filter-probe GLOB]\n                     [--filter-capability ID] [--group-by capability-category|capability-layer|mode]\n\nReads cfbo-v1 NDJSON from stdin.\n\nWhen a record's probe declares an expected_result (see probes/AGENTS.md), each\nrecord is classified pass/fail/unasserted against it; the process exits\nnon-zero if any record fails, so this binary doubles as a regression gate.\n\nOptions:\n  --format FORMAT        Select the output shape (default text).\n                         json  emits a single object with total_records, distinct_probes,\n                               results, modes, assertions (pass/fail/unasserted), and a\n                               compact records array (index, result, mode, probe,\n                               capability, operation, assertion, and trimmed message/\n                               snippets).\n                         junit emits a <testsuites> document, one <testcase> per record\n                               (probe id as name, capability id as classname); error\n                               becomes <error>, denied/partial become <failure>.\n                         tap   emits a `1..N` plan plus one ok/not ok line per record.\n  --follow               With --format text, keep reading (tailing an unclosed pipe) and\n                         re-print the running summary every --follow-every records or\n                         --follow-interval seconds, whichever comes first, instead of only\n                         printing it once at EOF.\n  --follow-every N       Records between summary reprints under --follow (default 20).\n  --follow-interval SECS Seconds between summary reprints under --follow (default 5).\n  --filter-result R1,R2  Keep only records whose observed_result is one of the given\n                         comma-separated values (e.g. denied,error).\n  --filter-mode MODE     Keep only records whose run mode equals MODE.\n  --filter-probe GLOB    Keep only records whose probe id matches GLOB (`*` wildcard).\n  --filter-capability ID Keep only records whose primary capability id equals ID.\n  --group-by DIMENSION   Break the results/assertions summary down by\n                         capability-category, capability-layer, or mode, in addition to\n                         the flat totals.\n  --help                 Show this help text."
+    );
+    std::process::exit(code);
 }
 
 #[derive(Debug)]
@@ -209,7 +934,15 @@ mod tests {
     fn renders_summary_and_records_for_golden_snippet() {
         let reader = golden_snippet_reader();
         let mut output = String::new();
-        render_listen_output(reader, &mut output).expect("render should succeed");
+        render_listen_output(
+            reader,
+            &mut output,
+            OutputFormat::Text,
+            &Expectations::new(),
+            &RecordFilter::default(),
+            GroupBy::None,
+        )
+        .expect("render should succeed");
 
         assert!(output.contains("total records  : 10"));
         assert!(
@@ -244,7 +977,15 @@ mod tests {
         let cursor = Cursor::new(Vec::<u8>::new());
         let reader = BufReader::new(cursor);
         let mut output = String::new();
-        render_listen_output(reader, &mut output).expect("empty input should succeed");
+        render_listen_output(
+            reader,
+            &mut output,
+            OutputFormat::Text,
+            &Expectations::new(),
+            &RecordFilter::default(),
+            GroupBy::None,
+        )
+        .expect("empty input should succeed");
         assert!(output.contains("total records  : 0"));
 
         let mut record = minimal_record();
@@ -254,12 +995,220 @@ mod tests {
         render_listen_output(
             BufReader::new(Cursor::new(ndjson.into_bytes())),
             &mut buffer,
+            OutputFormat::Text,
+            &Expectations::new(),
+            &RecordFilter::default(),
+            GroupBy::None,
         )
         .unwrap();
         assert!(buffer.contains("[#1]"));
         assert!(buffer.contains(&record.probe.id));
     }
 
+    #[test]
+    fn renders_text_records_before_the_summary_footer() {
+        let record = minimal_record();
+        let ndjson = serde_json::to_string(&record).unwrap();
+        let mut output = String::new();
+        render_listen_output(
+            BufReader::new(Cursor::new(ndjson.into_bytes())),
+            &mut output,
+            OutputFormat::Text,
+            &Expectations::new(),
+            &RecordFilter::default(),
+            GroupBy::None,
+        )
+        .unwrap();
+
+        let record_pos = output.find("[#1]").expect("record rendered");
+        let summary_pos = output
+            .find("codex-fence listen summary")
+            .expect("summary rendered");
+        assert!(
+            record_pos < summary_pos,
+            "expected streamed record before the summary footer"
+        );
+    }
+
+    #[test]
+    fn renders_json_report_with_record_fields_and_stats() {
+        let record = minimal_record();
+        let ndjson = serde_json::to_string(&record).unwrap();
+        let mut output = String::new();
+        render_listen_output(
+            BufReader::new(Cursor::new(ndjson.into_bytes())),
+            &mut output,
+            OutputFormat::Json,
+            &Expectations::new(),
+            &RecordFilter::default(),
+            GroupBy::None,
+        )
+        .unwrap();
+
+        let report: serde_json::Value = serde_json::from_str(&output).expect("valid JSON output");
+        assert_eq!(report["total_records"], 1);
+        assert_eq!(report["records"][0]["probe"], "sample_probe");
+        assert_eq!(report["records"][0]["capability"]["id"], "cap_sample");
+        assert_eq!(report["records"][0]["stdout_snippet"], "hello");
+    }
+
+    #[test]
+    fn renders_junit_report_with_error_and_failure_distinction() {
+        let mut denied = minimal_record();
+        denied.probe.id = "denied_probe".to_string();
+        denied.result.observed_result = "denied".to_string();
+        let mut errored = minimal_record();
+        errored.probe.id = "errored_probe".to_string();
+        errored.result.observed_result = "error".to_string();
+        let ndjson = [&denied, &errored]
+            .iter()
+            .map(|record| serde_json::to_string(record).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let mut output = String::new();
+        render_listen_output(
+            BufReader::new(Cursor::new(ndjson.into_bytes())),
+            &mut output,
+            OutputFormat::Junit,
+            &Expectations::new(),
+            &RecordFilter::default(),
+            GroupBy::None,
+        )
+        .unwrap();
+
+        assert!(output.contains("tests=\"2\" failures=\"1\" errors=\"1\""));
+        assert!(output.contains("name=\"denied_probe\" classname=\"cap_sample\""));
+        assert!(output.contains("<failure"));
+        assert!(output.contains("<error"));
+    }
+
+    #[test]
+    fn renders_tap_report_with_plan_and_not_ok_lines() {
+        let mut record = minimal_record();
+        record.result.observed_result = "denied".to_string();
+        let ndjson = serde_json::to_string(&record).unwrap();
+
+        let mut output = String::new();
+        render_listen_output(
+            BufReader::new(Cursor::new(ndjson.into_bytes())),
+            &mut output,
+            OutputFormat::Tap,
+            &Expectations::new(),
+            &RecordFilter::default(),
+            GroupBy::None,
+        )
+        .unwrap();
+
+        assert!(output.starts_with("1..1\n"));
+        assert!(output.contains("not ok 1 - sample_probe (denied)"));
+    }
+
+    #[test]
+    fn classify_is_unasserted_when_probe_has_no_expected_result() {
+        let record = minimal_record();
+        assert_eq!(
+            classify(&Expectations::new(), &record),
+            Assertion::Unasserted
+        );
+    }
+
+    #[test]
+    fn classify_matches_expected_result_against_observed() {
+        let mut record = minimal_record();
+        record.result.observed_result = "denied".to_string();
+        let mut expectations = Expectations::new();
+        expectations.insert(
+            record.probe.id.clone(),
+            metadata_with_expected(Some("denied"), BTreeMap::new()),
+        );
+        assert_eq!(classify(&expectations, &record), Assertion::Pass);
+
+        record.result.observed_result = "success".to_string();
+        assert_eq!(classify(&expectations, &record), Assertion::Fail);
+    }
+
+    #[test]
+    fn classify_prefers_per_mode_expectation_over_default() {
+        let mut record = minimal_record();
+        record.run.mode = "hardened".to_string();
+        record.result.observed_result = "denied".to_string();
+        let mut by_mode = BTreeMap::new();
+        by_mode.insert("hardened".to_string(), "denied".to_string());
+        let mut expectations = Expectations::new();
+        expectations.insert(
+            record.probe.id.clone(),
+            metadata_with_expected(Some("success"), by_mode),
+        );
+        assert_eq!(classify(&expectations, &record), Assertion::Pass);
+    }
+
+    #[test]
+    fn render_listen_output_reports_fail_count_for_mismatched_expectation() {
+        let mut record = minimal_record();
+        record.result.observed_result = "denied".to_string();
+        let ndjson = serde_json::to_string(&record).unwrap();
+        let mut expectations = Expectations::new();
+        expectations.insert(
+            record.probe.id.clone(),
+            metadata_with_expected(Some("success"), BTreeMap::new()),
+        );
+
+        let mut output = String::new();
+        let fail_count = render_listen_output(
+            BufReader::new(Cursor::new(ndjson.into_bytes())),
+            &mut output,
+            OutputFormat::Text,
+            &expectations,
+            &RecordFilter::default(),
+            GroupBy::None,
+        )
+        .unwrap();
+
+        assert_eq!(fail_count, 1);
+        assert!(output.contains("[#1] FAIL"));
+        assert!(output.contains("assertions     : pass=0 fail=1 unasserted=0"));
+    }
+
+    #[test]
+    fn render_listen_output_leaves_unasserted_probes_out_of_fail_count() {
+        let record = minimal_record();
+        let ndjson = serde_json::to_string(&record).unwrap();
+
+        let mut output = String::new();
+        let fail_count = render_listen_output(
+            BufReader::new(Cursor::new(ndjson.into_bytes())),
+            &mut output,
+            OutputFormat::Text,
+            &Expectations::new(),
+            &RecordFilter::default(),
+            GroupBy::None,
+        )
+        .unwrap();
+
+        assert_eq!(fail_count, 0);
+        assert!(output.contains("assertions     : pass=0 fail=0 unasserted=1"));
+    }
+
+    fn metadata_with_expected(
+        expected: Option<&str>,
+        expected_by_mode: BTreeMap<String, String>,
+    ) -> ProbeMetadata {
+        ProbeMetadata {
+            script: PathBuf::from("probe.sh"),
+            probe_name: None,
+            probe_version: None,
+            primary_capability: None,
+            secondary_capabilities: Vec::new(),
+            problem_matchers: Vec::new(),
+            platform_cfg: None,
+            expected_result: expected.map(str::to_string),
+            expected_result_by_mode: expected_by_mode,
+            has_dynamic_capability_reference: false,
+            diagnostics: Vec::new(),
+        }
+    }
+
     fn golden_snippet_reader() -> BufReader<File> {
         let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
             .join("tests/mocks/cfbo-golden-snippet.ndjson");
@@ -267,6 +1216,108 @@ mod tests {
         BufReader::new(file)
     }
 
+    #[test]
+    fn glob_match_supports_a_single_wildcard_anywhere_in_the_pattern() {
+        assert!(glob_match("fs_*", "fs_git_like_name_write"));
+        assert!(glob_match("*_write", "fs_git_like_name_write"));
+        assert!(glob_match("*", "anything"));
+        assert!(glob_match("fs_exact", "fs_exact"));
+        assert!(!glob_match("fs_exact", "fs_exact_not"));
+        assert!(!glob_match("net_*", "fs_git_like_name_write"));
+    }
+
+    #[test]
+    fn record_filter_matches_checks_every_criterion() {
+        let record = minimal_record();
+
+        let mut filter = RecordFilter::default();
+        assert!(filter.is_empty());
+        assert!(filter.matches(&record));
+
+        filter.results = Some(BTreeSet::from(["denied".to_string()]));
+        assert!(!filter.matches(&record));
+        filter.results = Some(BTreeSet::from(["success".to_string()]));
+        assert!(filter.matches(&record));
+        filter.results = None;
+
+        filter.mode = Some("hardened".to_string());
+        assert!(!filter.matches(&record));
+        filter.mode = Some("baseline".to_string());
+        assert!(filter.matches(&record));
+        filter.mode = None;
+
+        filter.probe_glob = Some("other_*".to_string());
+        assert!(!filter.matches(&record));
+        filter.probe_glob = Some("sample_*".to_string());
+        assert!(filter.matches(&record));
+        filter.probe_glob = None;
+
+        filter.capability = Some("cap_other".to_string());
+        assert!(!filter.matches(&record));
+        filter.capability = Some("cap_sample".to_string());
+        assert!(filter.matches(&record));
+    }
+
+    #[test]
+    fn render_listen_output_excludes_records_the_filter_rejects() {
+        let mut kept = minimal_record();
+        kept.probe.id = "kept_probe".to_string();
+        let mut dropped = minimal_record();
+        dropped.probe.id = "dropped_probe".to_string();
+        dropped.result.observed_result = "denied".to_string();
+        let ndjson = [&kept, &dropped]
+            .iter()
+            .map(|record| serde_json::to_string(record).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let filter = RecordFilter {
+            results: Some(BTreeSet::from(["success".to_string()])),
+            ..RecordFilter::default()
+        };
+        let mut output = String::new();
+        render_listen_output(
+            BufReader::new(Cursor::new(ndjson.into_bytes())),
+            &mut output,
+            OutputFormat::Text,
+            &Expectations::new(),
+            &filter,
+            GroupBy::None,
+        )
+        .unwrap();
+
+        assert!(output.contains("total records  : 1"));
+        assert!(output.contains("kept_probe"));
+        assert!(!output.contains("dropped_probe"));
+    }
+
+    #[test]
+    fn group_by_parses_known_values_and_rejects_others() {
+        assert_eq!(
+            GroupBy::parse("capability-category").unwrap(),
+            GroupBy::CapabilityCategory
+        );
+        assert_eq!(
+            GroupBy::parse("capability-layer").unwrap(),
+            GroupBy::CapabilityLayer
+        );
+        assert_eq!(GroupBy::parse("mode").unwrap(), GroupBy::Mode);
+        assert!(GroupBy::parse("probe").is_err());
+    }
+
+    #[test]
+    fn render_summary_emits_a_grouped_breakdown_when_group_by_is_set() {
+        let mut stats = ListenStats::new(GroupBy::Mode);
+        let record = minimal_record();
+        stats.update(&record, Assertion::Unasserted);
+        let mut output = String::new();
+        render_summary(&stats, &mut output).unwrap();
+
+        assert!(output.contains("by mode:"));
+        assert!(output.contains("baseline"));
+        assert!(output.contains("success=1"));
+    }
+
     fn minimal_record() -> BoundaryObject {
         BoundaryObject {
             schema_version: "cfbo-v1".to_string(),
@@ -277,6 +1328,7 @@ mod tests {
                 codex_cli_version: Some("codex-cli test".to_string()),
                 codex_profile: None,
                 sandbox_mode: Some("baseline".to_string()),
+                container_image: None,
                 os: "Darwin".to_string(),
             },
             probe: codex_fence::ProbeInfo {
@@ -315,6 +1367,7 @@ mod tests {
                     layer: CapabilityLayer::OsSandbox,
                 },
                 secondary: Vec::new(),
+                resolved_grant: None,
             },
         }
     }
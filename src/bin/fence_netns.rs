@@ -0,0 +1,254 @@
+//! Small in-crate launcher for `isolated` run mode: `codex_fence::connectors`
+//! re-invokes this binary in place of the probe itself for `RunMode::Isolated`,
+//! and it builds a fresh, unprivileged Linux namespace sandbox around the
+//! probe before handing off control via `execv`.
+//!
+//! Unlike `probe-exec`'s `namespace` backend (which shells out to the
+//! `unshare` util-linux binary), this launcher calls `unshare(2)` directly so
+//! it can also write the `uid_map`/`gid_map` pair an unprivileged user
+//! namespace needs before any mount setup, and sets up the mount namespace
+//! itself rather than delegating to a one-line shell script.
+//!
+//! Usage: `fence-netns PROBE_PATH`
+//!
+//! Honors the same `FENCE_WORKSPACE_ROOT`/`TMPDIR` environment `probe-exec`
+//! already exports; both are bind-mounted (read-write) into the new mount
+//! namespace before the probe is launched. Any namespace setup failure exits
+//! with [`NAMESPACE_SETUP_FAILURE_CODE`] instead of a generic error so
+//! `fence-bang` can skip `isolated` the same way it already skips an
+//! unavailable codex sandbox.
+
+use anyhow::{Context, Result, anyhow, bail};
+use std::env;
+use std::ffi::CString;
+use std::path::{Path, PathBuf};
+
+/// Distinct from codex-sandbox's skip code (71, see `fence-bang`) so the two
+/// "this mode isn't usable on this host" signals stay distinguishable in
+/// stderr/exit-code triage.
+const NAMESPACE_SETUP_FAILURE_CODE: i32 = 72;
+
+fn main() {
+    match run() {
+        Ok(()) => unreachable!("run() either execs the probe or exits the process directly"),
+        Err(err) => {
+            eprintln!("fence-netns: {err:#}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run() -> Result<()> {
+    let probe_path = parse_args()?;
+    imp::launch(&probe_path)
+}
+
+fn parse_args() -> Result<PathBuf> {
+    let mut args = env::args_os().skip(1);
+    let probe_path = args
+        .next()
+        .ok_or_else(|| anyhow!("Usage: fence-netns PROBE_PATH"))?;
+    Ok(PathBuf::from(probe_path))
+}
+
+fn path_to_cstring(path: &Path) -> Result<CString> {
+    use std::os::unix::ffi::OsStrExt;
+    CString::new(path.as_os_str().as_bytes())
+        .with_context(|| format!("path contains an interior NUL: {}", path.display()))
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use super::{NAMESPACE_SETUP_FAILURE_CODE, path_to_cstring};
+    use anyhow::{Context, Result, bail};
+    use std::env;
+    use std::ffi::CString;
+    use std::fs;
+    use std::io;
+    use std::path::{Path, PathBuf};
+    use std::ptr;
+
+    /// Build the namespace sandbox and exec `probe_path` inside it, or exit
+    /// with [`NAMESPACE_SETUP_FAILURE_CODE`] if any required setup step fails.
+    pub fn launch(probe_path: &Path) -> Result<()> {
+        let uid = unsafe { libc::getuid() };
+        let gid = unsafe { libc::getgid() };
+
+        let flags =
+            libc::CLONE_NEWUSER | libc::CLONE_NEWNS | libc::CLONE_NEWPID | libc::CLONE_NEWNET;
+        if unsafe { libc::unshare(flags) } != 0 {
+            fail_setup(&format!(
+                "unshare(CLONE_NEWUSER|CLONE_NEWNS|CLONE_NEWPID|CLONE_NEWNET) failed: {}",
+                io::Error::last_os_error()
+            ));
+        }
+
+        if let Err(err) = write_id_maps(uid, gid) {
+            fail_setup(&format!("{err:#}"));
+        }
+
+        // CLONE_NEWPID only takes effect for children spawned after unshare,
+        // so the process that sees itself as PID 1 in the new namespace has
+        // to be a fork, not this process.
+        match unsafe { libc::fork() } {
+            -1 => {
+                fail_setup(&format!("fork failed: {}", io::Error::last_os_error()));
+                unreachable!()
+            }
+            0 => child_main(probe_path),
+            child_pid => parent_main(child_pid),
+        }
+    }
+
+    fn fail_setup(message: &str) -> ! {
+        eprintln!("fence-netns: {message}");
+        std::process::exit(NAMESPACE_SETUP_FAILURE_CODE);
+    }
+
+    /// Disable `setgroups` and map the caller's uid/gid to root inside the new
+    /// user namespace -- the standard unprivileged-userns dance, and in that
+    /// order (`gid_map` is rejected while `setgroups` is still `allow`).
+    fn write_id_maps(uid: u32, gid: u32) -> Result<()> {
+        fs::write("/proc/self/setgroups", "deny").context("writing /proc/self/setgroups")?;
+        fs::write("/proc/self/uid_map", format!("0 {uid} 1\n"))
+            .context("writing /proc/self/uid_map")?;
+        fs::write("/proc/self/gid_map", format!("0 {gid} 1\n"))
+            .context("writing /proc/self/gid_map")?;
+        Ok(())
+    }
+
+    fn parent_main(child_pid: libc::pid_t) -> Result<()> {
+        let mut status: libc::c_int = 0;
+        loop {
+            let rc = unsafe { libc::waitpid(child_pid, &mut status, 0) };
+            if rc == child_pid {
+                break;
+            }
+            if rc < 0 && io::Error::last_os_error().kind() != io::ErrorKind::Interrupted {
+                fail_setup(&format!(
+                    "waitpid on namespace child failed: {}",
+                    io::Error::last_os_error()
+                ));
+            }
+        }
+
+        if libc::WIFEXITED(status) {
+            std::process::exit(libc::WEXITSTATUS(status));
+        }
+        eprintln!("fence-netns: probe terminated by signal inside namespace");
+        std::process::exit(1);
+    }
+
+    /// Runs as PID 1 of the new namespaces: finishes mount setup, then execs
+    /// the probe. Never returns on success.
+    fn child_main(probe_path: &Path) -> ! {
+        if let Err(err) = setup_mounts(probe_path) {
+            fail_setup(&format!("{err:#}"));
+        }
+        if let Err(err) = exec_probe(probe_path) {
+            fail_setup(&format!("exec failed: {err:#}"));
+        }
+        unreachable!("exec_probe only returns on failure, already handled above")
+    }
+
+    fn setup_mounts(probe_path: &Path) -> Result<()> {
+        mount_raw(
+            Path::new("none"),
+            Path::new("/"),
+            None,
+            libc::MS_REC | libc::MS_PRIVATE,
+        )
+        .context("marking / as MS_REC|MS_PRIVATE")?;
+
+        if let Some(workspace_root) = env::var_os("FENCE_WORKSPACE_ROOT") {
+            if !workspace_root.is_empty() {
+                let root = PathBuf::from(workspace_root);
+                mount_raw(&root, &root, None, libc::MS_BIND)
+                    .with_context(|| format!("bind-mounting workspace root {}", root.display()))?;
+            }
+        }
+
+        if let Some(tmpdir) = env::var_os("TMPDIR") {
+            if !tmpdir.is_empty() {
+                let tmpdir = PathBuf::from(tmpdir);
+                mount_raw(&tmpdir, &tmpdir, None, libc::MS_BIND).with_context(|| {
+                    format!("bind-mounting workspace tmpdir {}", tmpdir.display())
+                })?;
+            }
+        }
+
+        mount_raw(Path::new("proc"), Path::new("/proc"), Some("proc"), 0)
+            .context("mounting a fresh /proc")?;
+
+        // Best-effort: a probe that can't be remounted read-only (e.g. its
+        // filesystem doesn't support per-mount MS_RDONLY) still runs; this
+        // only hardens the common case.
+        if let Err(err) = mount_raw(probe_path, probe_path, None, libc::MS_BIND) {
+            eprintln!("fence-netns: warning: could not bind-mount probe path read-only: {err:#}");
+        } else if let Err(err) = mount_raw(
+            probe_path,
+            probe_path,
+            None,
+            libc::MS_BIND | libc::MS_REMOUNT | libc::MS_RDONLY,
+        ) {
+            eprintln!("fence-netns: warning: could not remount probe path read-only: {err:#}");
+        }
+
+        Ok(())
+    }
+
+    fn mount_raw(
+        source: &Path,
+        target: &Path,
+        fstype: Option<&str>,
+        flags: libc::c_ulong,
+    ) -> Result<()> {
+        let source_c = path_to_cstring(source)?;
+        let target_c = path_to_cstring(target)?;
+        let fstype_c = fstype.map(CString::new).transpose()?;
+
+        let rc = unsafe {
+            libc::mount(
+                source_c.as_ptr(),
+                target_c.as_ptr(),
+                fstype_c.as_ref().map_or(ptr::null(), |c| c.as_ptr()),
+                flags,
+                ptr::null(),
+            )
+        };
+        if rc != 0 {
+            bail!(
+                "mount({} -> {}) failed: {}",
+                source.display(),
+                target.display(),
+                io::Error::last_os_error()
+            );
+        }
+        Ok(())
+    }
+
+    fn exec_probe(probe_path: &Path) -> Result<()> {
+        let program = path_to_cstring(probe_path)?;
+        let argv = [program.as_ptr(), ptr::null()];
+        unsafe {
+            libc::execv(program.as_ptr(), argv.as_ptr());
+        }
+        bail!(
+            "execv({}) failed: {}",
+            probe_path.display(),
+            io::Error::last_os_error()
+        )
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    use super::NAMESPACE_SETUP_FAILURE_CODE;
+    use anyhow::Result;
+    use std::path::Path;
+
+    pub fn launch(_probe_path: &Path) -> Result<()> {
+        eprintln!("fence-netns: isolated run mode requires Linux namespaces");
+        std::process::exit(NAMESPACE_SETUP_FAILURE_CODE);
+    }
+}
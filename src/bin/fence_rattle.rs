@@ -3,16 +3,39 @@
 //! The CLI selects a subset of probes by capability id or explicit probe id,
 //! fans out across the requested modes, and shells out to `fence-bang` so the
 //! existing execution pipeline (fence-run → emit-record) remains untouched.
+//! `--jobs N` shards a large selection across up to N concurrent `fence-bang`
+//! children instead of handing the whole list to one (see [`run_shards`]).
+//!
+//! A discovered `fence.toml` (walked upward from the cwd to the repo root,
+//! first match wins) can supply `[defaults]` for `modes`/`repeat`/`jobs` and
+//! `[alias]` entries mapping a short name to a capability id or a list of
+//! probe ids, mirroring how Cargo resolves command aliases from config (see
+//! [`load_fence_config`]). Precedence is CLI flag > environment variable >
+//! `fence.toml` > built-in default.
+//!
+//! Default modes are not just "codex present or not": [`detect_mode_availability`]
+//! exercises each candidate mode once (cached for the process) via `fence-run
+//! probe-mode`, the same trivial sandbox-launch check `fence-run` itself uses
+//! to preflight a real probe, so a host that has `codex` installed but can't
+//! actually apply its sandbox still gets a sensible default. `--probe-modes`
+//! prints that detection table without running anything.
 
 use anyhow::{Context, Result, anyhow, bail};
+use codex_fence::emit_support::did_you_mean;
 use codex_fence::{
-    CapabilityId, CapabilityIndex, Probe, ProbeMetadata, codex_present, find_repo_root,
-    list_probes, resolve_helper_binary, resolve_probe,
+    CapabilityId, CapabilityIndex, Probe, ProbeMetadata, find_repo_root, list_probes,
+    resolve_helper_binary, resolve_probe, split_list,
 };
-use std::collections::BTreeSet;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
 use std::env;
-use std::path::Path;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
 
 fn main() {
     if let Err(err) = run() {
@@ -24,15 +47,32 @@ fn main() {
 fn run() -> Result<()> {
     let cli = Cli::parse()?;
     let repo_root = find_repo_root()?;
-    let modes = resolve_modes(&cli.modes)?;
-    let plan = resolve_selection(&repo_root, &cli.selection)?;
+
+    if cli.probe_modes {
+        print_probe_modes(&repo_root);
+        return Ok(());
+    }
+
+    let config = load_fence_config(&repo_root)?;
+    let modes = resolve_modes(&repo_root, &cli.modes, config.as_ref())?;
+    let repeat = resolve_repeat(cli.repeat, config.as_ref())?;
+    let selection = cli
+        .selection
+        .ok_or_else(|| anyhow!("--cap, --probe, or --alias is required for --rattle"))?;
+    let plan = resolve_selection(&repo_root, &selection, config.as_ref())?;
 
     if cli.list_only {
-        print_dry_run(&plan, &modes, cli.repeat);
+        print_dry_run(&plan, &modes, repeat);
         return Ok(());
     }
 
-    run_matrix(&repo_root, &plan.probes, &modes, cli.repeat)
+    run_matrix(
+        &repo_root,
+        &plan.probes,
+        &modes,
+        repeat,
+        resolve_jobs(cli.jobs, config.as_ref())?,
+    )
 }
 
 fn print_dry_run(plan: &SelectionPlan, modes: &[String], repeat: u32) {
@@ -51,53 +91,331 @@ fn print_dry_run(plan: &SelectionPlan, modes: &[String], repeat: u32) {
     }
 }
 
-fn run_matrix(repo_root: &Path, probes: &[Probe], modes: &[String], repeat: u32) -> Result<()> {
+/// `--probe-modes`: print [`detect_mode_availability`]'s table without
+/// resolving a selection or running anything, so a user can see what their
+/// host supports before committing to a long `--cap`/`--probe` run.
+fn print_probe_modes(repo_root: &Path) {
+    println!("codex-fence rattle mode detection");
+    for availability in detect_mode_availability(repo_root) {
+        match &availability.reason {
+            Some(reason) if !availability.runnable => {
+                println!("  {:<13} unavailable: {reason}", availability.mode)
+            }
+            _ => println!("  {:<13} available", availability.mode),
+        }
+    }
+}
+
+/// Whether a candidate mode actually launches on this host, per
+/// [`detect_mode_availability`].
+struct ModeAvailability {
+    mode: String,
+    runnable: bool,
+    /// Populated when `runnable` is false: the reason `fence-run probe-mode`
+    /// gave for the launch failing.
+    reason: Option<String>,
+}
+
+/// Exercises every candidate mode once via `fence-run probe-mode MODE` (see
+/// `fence_run.rs`'s `probe_codex_mode_launch`) — the same trivial `mktemp -d`
+/// sandbox-launch check `fence-run` uses to preflight a real probe, run here
+/// standalone with no probe resolved — and caches the result for the rest of
+/// the process, the way tooling queries a compiler for its supported `cfg`s
+/// once rather than per compilation unit. `baseline` never needs a sandbox,
+/// so it's reported runnable without shelling out.
+fn detect_mode_availability(repo_root: &Path) -> &'static [ModeAvailability] {
+    static CACHE: OnceLock<Vec<ModeAvailability>> = OnceLock::new();
+    CACHE.get_or_init(|| {
+        ["baseline", "codex-sandbox", "codex-full"]
+            .iter()
+            .map(|mode| probe_mode_availability(repo_root, mode))
+            .collect()
+    })
+}
+
+fn probe_mode_availability(repo_root: &Path, mode: &str) -> ModeAvailability {
+    if mode == "baseline" {
+        return ModeAvailability {
+            mode: mode.to_string(),
+            runnable: true,
+            reason: None,
+        };
+    }
+
+    let attempt = resolve_helper_binary(repo_root, "fence-run").and_then(|helper| {
+        Command::new(&helper)
+            .arg("probe-mode")
+            .arg(mode)
+            .current_dir(repo_root)
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .output()
+            .with_context(|| format!("failed to execute {}", helper.display()))
+    });
+
+    match attempt {
+        Ok(output) if output.status.success() => ModeAvailability {
+            mode: mode.to_string(),
+            runnable: true,
+            reason: None,
+        },
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            let reason = if stderr.is_empty() {
+                format!(
+                    "fence-run probe-mode exited with code {:?}",
+                    output.status.code()
+                )
+            } else {
+                stderr
+            };
+            ModeAvailability {
+                mode: mode.to_string(),
+                runnable: false,
+                reason: Some(reason),
+            }
+        }
+        Err(err) => ModeAvailability {
+            mode: mode.to_string(),
+            runnable: false,
+            reason: Some(format!("{err:#}")),
+        },
+    }
+}
+
+/// Resolve `--jobs`, mirroring `probe-matrix`'s `resolve_jobs`: the CLI flag
+/// wins, then the `JOBS` env var, then `[defaults] jobs` from `fence.toml`,
+/// otherwise the host's available parallelism.
+fn resolve_jobs(cli_jobs: Option<usize>, config: Option<&FenceConfigFile>) -> Result<usize> {
+    if let Some(jobs) = cli_jobs {
+        if jobs == 0 {
+            bail!("--jobs must be at least 1");
+        }
+        return Ok(jobs);
+    }
+
+    if let Ok(raw) = env::var("JOBS") {
+        let jobs: usize = raw
+            .trim()
+            .parse()
+            .with_context(|| format!("invalid JOBS value: {raw}"))?;
+        if jobs == 0 {
+            bail!("JOBS must be at least 1");
+        }
+        return Ok(jobs);
+    }
+
+    if let Some(jobs) = config.and_then(|config| config.defaults.jobs) {
+        if jobs == 0 {
+            bail!("fence.toml [defaults] jobs must be at least 1");
+        }
+        return Ok(jobs);
+    }
+
+    Ok(thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1))
+}
+
+/// Resolve `--repeat`, following the same CLI > env (`REPEAT`) > `fence.toml`
+/// `[defaults] repeat` > built-in default (1) precedence as [`resolve_jobs`].
+fn resolve_repeat(cli_repeat: Option<u32>, config: Option<&FenceConfigFile>) -> Result<u32> {
+    if let Some(repeat) = cli_repeat {
+        if repeat == 0 {
+            bail!("--repeat must be >= 1");
+        }
+        return Ok(repeat);
+    }
+
+    if let Ok(raw) = env::var("REPEAT") {
+        let repeat: u32 = raw
+            .trim()
+            .parse()
+            .with_context(|| format!("invalid REPEAT value: {raw}"))?;
+        if repeat == 0 {
+            bail!("REPEAT must be >= 1");
+        }
+        return Ok(repeat);
+    }
+
+    if let Some(repeat) = config.and_then(|config| config.defaults.repeat) {
+        if repeat == 0 {
+            bail!("fence.toml [defaults] repeat must be at least 1");
+        }
+        return Ok(repeat);
+    }
+
+    Ok(1)
+}
+
+/// Splits `probes` into up to `jobs` disjoint, contiguous shards, each
+/// pre-joined into the same comma-separated `PROBES` value a single-shard
+/// run would have used, so `--jobs N` fans a large selection out across N
+/// concurrent `fence-bang` children instead of handing the whole list to one.
+fn shard_probe_ids(probes: &[Probe], jobs: usize) -> Vec<String> {
+    let shard_count = jobs.min(probes.len()).max(1);
+    let shard_size = (probes.len() + shard_count - 1) / shard_count;
+    probes
+        .chunks(shard_size.max(1))
+        .map(|chunk| {
+            chunk
+                .iter()
+                .map(|probe| probe.id.as_str())
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+        .collect()
+}
+
+/// Run every probe shard (see [`shard_probe_ids`]) once per `repeat`
+/// attempt. Each attempt pushes all shards onto a shared work queue and
+/// spawns up to `jobs` worker threads pulling from it, each running one
+/// `fence-bang` child to completion before grabbing the next shard, so a
+/// large `--cap` selection that used to run inside a single serial helper
+/// invocation now fans out across concurrent children.
+fn run_matrix(
+    repo_root: &Path,
+    probes: &[Probe],
+    modes: &[String],
+    repeat: u32,
+    jobs: usize,
+) -> Result<()> {
     if probes.is_empty() {
         bail!("no probes resolved for rattle run");
     }
     let helper = resolve_helper_binary(repo_root, "fence-bang")?;
-    let probes_arg = probes
-        .iter()
-        .map(|probe| probe.id.as_str())
-        .collect::<Vec<_>>()
-        .join(",");
+    let shards = shard_probe_ids(probes, jobs);
     let modes_arg = modes.join(" ");
 
     for attempt in 0..repeat {
-        let mut cmd = Command::new(&helper);
-        cmd.current_dir(repo_root)
-            .stdout(Stdio::inherit())
-            .stderr(Stdio::inherit())
-            .env("PROBES", &probes_arg)
-            .env("MODES", &modes_arg);
-        if env::var_os("CODEX_FENCE_ROOT").is_none() {
-            cmd.env("CODEX_FENCE_ROOT", repo_root);
-        }
+        run_shards(
+            repo_root, &helper, &shards, &modes_arg, jobs, attempt, repeat,
+        )?;
+    }
 
-        let status = cmd
-            .status()
-            .with_context(|| format!("failed to execute {}", helper.display()))?;
-        if !status.success() {
-            let prefix = if repeat > 1 {
-                format!("repeat {} failed", attempt + 1)
-            } else {
-                "rattle run failed".to_string()
-            };
-            if let Some(code) = status.code() {
-                bail!("{prefix} with exit code {code}");
-            }
-            bail!("{prefix}: helper terminated by signal");
+    Ok(())
+}
+
+/// One `repeat` attempt's worth of sharded `fence-bang` invocations, run
+/// concurrently across up to `jobs` worker threads pulling from a shared
+/// cursor over `shards` (mirroring `probe-matrix`'s `run_matrix` worker-pool
+/// shape), aggregating failures so one bad shard doesn't hide the others.
+fn run_shards(
+    repo_root: &Path,
+    helper: &Path,
+    shards: &[String],
+    modes_arg: &str,
+    jobs: usize,
+    attempt: u32,
+    repeat: u32,
+) -> Result<()> {
+    let cursor = AtomicUsize::new(0);
+    let errors: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    let stdout_lock: Mutex<()> = Mutex::new(());
+
+    let worker_count = jobs.min(shards.len().max(1));
+    thread::scope(|scope| {
+        for _ in 0..worker_count {
+            scope.spawn(|| loop {
+                let index = cursor.fetch_add(1, Ordering::SeqCst);
+                let Some(probes_arg) = shards.get(index) else {
+                    break;
+                };
+                if let Err(err) = run_shard(repo_root, helper, probes_arg, modes_arg, &stdout_lock)
+                {
+                    let prefix = if repeat > 1 {
+                        format!("repeat {} shard {}", attempt + 1, index + 1)
+                    } else {
+                        format!("shard {}", index + 1)
+                    };
+                    errors
+                        .lock()
+                        .expect("errors mutex poisoned")
+                        .push(format!("{prefix} failed: {err:#}"));
+                }
+            });
         }
+    });
+
+    let errors = errors.into_inner().expect("errors mutex poisoned");
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        bail!(
+            "{} shard(s) failed; see stderr for details:\n{}",
+            errors.len(),
+            errors.join("\n")
+        )
+    }
+}
+
+/// Run one `fence-bang` child over `probes_arg`, capturing its output and
+/// flushing it atomically under `stdout_lock` once the child exits, so
+/// concurrent shards never interleave partial NDJSON lines on our stdout.
+fn run_shard(
+    repo_root: &Path,
+    helper: &Path,
+    probes_arg: &str,
+    modes_arg: &str,
+    stdout_lock: &Mutex<()>,
+) -> Result<()> {
+    let mut cmd = Command::new(helper);
+    cmd.current_dir(repo_root)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .env("PROBES", probes_arg)
+        .env("MODES", modes_arg);
+    if env::var_os("CODEX_FENCE_ROOT").is_none() {
+        cmd.env("CODEX_FENCE_ROOT", repo_root);
     }
 
+    let output = cmd
+        .output()
+        .with_context(|| format!("failed to execute {}", helper.display()))?;
+
+    {
+        let _guard = stdout_lock.lock().expect("stdout mutex poisoned");
+        io::stdout()
+            .write_all(&output.stdout)
+            .context("forwarding fence-bang stdout")?;
+        io::stderr()
+            .write_all(&output.stderr)
+            .context("forwarding fence-bang stderr")?;
+    }
+
+    if !output.status.success() {
+        if let Some(code) = output.status.code() {
+            bail!("fence-bang exited with code {code}");
+        }
+        bail!("fence-bang terminated by signal");
+    }
     Ok(())
 }
 
-fn resolve_modes(requested: &[String]) -> Result<Vec<String>> {
-    let modes = if requested.is_empty() {
-        default_modes()
+/// Resolve the execution modes, following CLI > env (`MODES`) > `fence.toml`
+/// `[defaults] modes` > [`default_modes`] precedence. A mode that reaches
+/// this function by explicit request (CLI, env, or `fence.toml`, as opposed
+/// to falling back to [`default_modes`]) is checked against
+/// [`detect_mode_availability`] and rejected here, with the detected reason,
+/// rather than failing deep inside the real matrix.
+fn resolve_modes(
+    repo_root: &Path,
+    requested: &[String],
+    config: Option<&FenceConfigFile>,
+) -> Result<Vec<String>> {
+    let (modes, explicit) = if !requested.is_empty() {
+        (requested.to_vec(), true)
+    } else if let Ok(raw) = env::var("MODES") {
+        let parsed = split_list(&raw);
+        if parsed.is_empty() {
+            (default_modes(repo_root), false)
+        } else {
+            (parsed, true)
+        }
+    } else if let Some(modes) = config.and_then(|config| config.defaults.modes.clone()) {
+        (modes, true)
     } else {
-        requested.to_vec()
+        (default_modes(repo_root), false)
     };
 
     if modes.is_empty() {
@@ -111,34 +429,170 @@ fn resolve_modes(requested: &[String]) -> Result<Vec<String>> {
         bail!("unsupported mode requested: {invalid}");
     }
 
+    if explicit {
+        let availability = detect_mode_availability(repo_root);
+        for mode in &modes {
+            if let Some(unrunnable) = availability
+                .iter()
+                .find(|candidate| &candidate.mode == mode && !candidate.runnable)
+            {
+                let reason = unrunnable
+                    .reason
+                    .as_deref()
+                    .unwrap_or("mode is not runnable on this host");
+                bail!("{mode} unavailable: {reason}");
+            }
+        }
+    }
+
     Ok(modes)
 }
 
-fn default_modes() -> Vec<String> {
-    if codex_present() {
-        vec![
-            "baseline".to_string(),
-            "codex-sandbox".to_string(),
-            "codex-full".to_string(),
-        ]
-    } else {
-        vec!["baseline".to_string()]
-    }
+/// Only the modes [`detect_mode_availability`] actually observed launching on
+/// this host, in `baseline`, `codex-sandbox`, `codex-full` order. Replaces the
+/// old `codex_present()`-only check, which planned `codex-sandbox`/`codex-full`
+/// whenever the `codex` binary existed even if its sandbox couldn't apply.
+fn default_modes(repo_root: &Path) -> Vec<String> {
+    detect_mode_availability(repo_root)
+        .iter()
+        .filter(|availability| availability.runnable)
+        .map(|availability| availability.mode.clone())
+        .collect()
 }
 
-fn resolve_selection(repo_root: &Path, selection: &Selection) -> Result<SelectionPlan> {
+fn resolve_selection(
+    repo_root: &Path,
+    selection: &Selection,
+    config: Option<&FenceConfigFile>,
+) -> Result<SelectionPlan> {
     match selection {
         Selection::Capability(id) => resolve_capability_selection(repo_root, id),
         Selection::Probes(ids) => resolve_probe_selection(repo_root, ids),
+        Selection::Alias(name) => resolve_alias_selection(repo_root, name, config),
+    }
+}
+
+/// Resolve a `--alias NAME` selection against `[alias]` entries from a
+/// discovered `fence.toml`, expanding to a capability or explicit probe-id
+/// selection *before* those ids ever reach [`resolve_probe`], the same way
+/// `probe-target`'s `--set` expands before `resolve_probe`.
+fn resolve_alias_selection(
+    repo_root: &Path,
+    name: &str,
+    config: Option<&FenceConfigFile>,
+) -> Result<SelectionPlan> {
+    let aliases = config.map(|config| &config.alias);
+    let Some(entry) = aliases.and_then(|aliases| aliases.get(name)) else {
+        let hint = did_you_mean(
+            name,
+            aliases
+                .map(|aliases| aliases.keys().map(String::as_str).collect::<Vec<_>>())
+                .unwrap_or_default()
+                .into_iter(),
+        );
+        bail!("unknown alias '{name}' (no [alias] entry in fence.toml){hint}");
+    };
+
+    match entry {
+        AliasEntry::Capability(cap_id) => {
+            let id = CapabilityId(cap_id.clone());
+            resolve_capability_selection(repo_root, &id)
+        }
+        AliasEntry::Probes(probe_ids) => resolve_probe_selection(repo_root, probe_ids),
+    }
+}
+
+/// Discover and load `fence.toml`, walking upward from the cwd to
+/// `repo_root` (inclusive); the first match wins. A missing file anywhere
+/// along the walk is not an error, but a malformed one is, and any `[alias]`
+/// entry that shadows a real capability or probe id is rejected up front so
+/// it can't silently mask the real id at selection time.
+fn load_fence_config(repo_root: &Path) -> Result<Option<FenceConfigFile>> {
+    let Some((path, config)) = discover_fence_config(repo_root)? else {
+        return Ok(None);
+    };
+    validate_aliases(repo_root, &path, &config)?;
+    Ok(Some(config))
+}
+
+fn discover_fence_config(repo_root: &Path) -> Result<Option<(PathBuf, FenceConfigFile)>> {
+    let start =
+        env::current_dir().context("resolving current directory for fence.toml discovery")?;
+    let mut dir = start.as_path();
+    loop {
+        let candidate = dir.join("fence.toml");
+        if candidate.is_file() {
+            let contents = fs::read_to_string(&candidate)
+                .with_context(|| format!("reading {}", candidate.display()))?;
+            let config: FenceConfigFile = toml::from_str(&contents)
+                .with_context(|| format!("parsing {}", candidate.display()))?;
+            return Ok(Some((candidate, config)));
+        }
+        if dir == repo_root {
+            return Ok(None);
+        }
+        match dir.parent() {
+            Some(parent) => dir = parent,
+            None => return Ok(None),
+        }
+    }
+}
+
+fn validate_aliases(repo_root: &Path, path: &Path, config: &FenceConfigFile) -> Result<()> {
+    if config.alias.is_empty() {
+        return Ok(());
+    }
+
+    let catalog_path = repo_root.join("schema").join("capabilities.json");
+    let index = CapabilityIndex::load(&catalog_path)?;
+    let known_probes = list_probes(repo_root)?;
+
+    for name in config.alias.keys() {
+        if index.capability(&CapabilityId(name.clone())).is_some() {
+            bail!(
+                "{}: [alias] entry '{name}' shadows an existing capability id of the same name",
+                path.display()
+            );
+        }
+        if known_probes.iter().any(|probe| probe.id == name.as_str()) {
+            bail!(
+                "{}: [alias] entry '{name}' shadows an existing probe id of the same name",
+                path.display()
+            );
+        }
     }
+    Ok(())
+}
+
+#[derive(Deserialize, Default)]
+struct FenceConfigFile {
+    #[serde(default)]
+    defaults: DefaultsSection,
+    #[serde(default)]
+    alias: BTreeMap<String, AliasEntry>,
+}
+
+#[derive(Deserialize, Default)]
+struct DefaultsSection {
+    modes: Option<Vec<String>>,
+    repeat: Option<u32>,
+    jobs: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AliasEntry {
+    Capability(String),
+    Probes(Vec<String>),
 }
 
 fn resolve_capability_selection(repo_root: &Path, id: &CapabilityId) -> Result<SelectionPlan> {
     let catalog_path = repo_root.join("schema").join("capabilities.json");
     let index = CapabilityIndex::load(&catalog_path)?;
     if index.capability(id).is_none() {
+        let hint = did_you_mean(&id.0, index.ids().map(|id| id.0.as_str()));
         bail!(
-            "unknown capability '{}' (not present in bundled catalog)",
+            "unknown capability '{}' (not present in bundled catalog){hint}",
             id.0
         );
     }
@@ -179,7 +633,11 @@ fn resolve_probe_selection(repo_root: &Path, requested: &[String]) -> Result<Sel
     let mut probes = Vec::new();
     let mut seen = BTreeSet::new();
     for raw in requested {
-        let resolved = resolve_probe(repo_root, raw)?;
+        let resolved = resolve_probe(repo_root, raw).map_err(|err| {
+            let known = list_probes(repo_root).unwrap_or_default();
+            let hint = did_you_mean(raw, known.iter().map(|probe| probe.id.as_str()));
+            anyhow!("{err:#}{hint}")
+        })?;
         if seen.insert(resolved.id.clone()) {
             probes.push(resolved);
         }
@@ -205,13 +663,18 @@ enum SelectionDescription {
 enum Selection {
     Capability(CapabilityId),
     Probes(Vec<String>),
+    Alias(String),
 }
 
 struct Cli {
-    selection: Selection,
+    /// `None` only when `--probe-modes` was given without a selection; every
+    /// other path through `Cli::parse` either resolves a selection or bails.
+    selection: Option<Selection>,
     modes: Vec<String>,
-    repeat: u32,
+    repeat: Option<u32>,
+    jobs: Option<usize>,
     list_only: bool,
+    probe_modes: bool,
 }
 
 impl Cli {
@@ -221,9 +684,12 @@ impl Cli {
 
         let mut cap: Option<String> = None;
         let mut probes: Vec<String> = Vec::new();
+        let mut alias: Option<String> = None;
         let mut modes: Vec<String> = Vec::new();
-        let mut repeat: u32 = 1;
+        let mut repeat: Option<u32> = None;
+        let mut jobs: Option<usize> = None;
         let mut list_only = false;
+        let mut probe_modes = false;
 
         while let Some(arg) = args.next() {
             let arg_str = arg
@@ -241,18 +707,28 @@ impl Cli {
                     let value = next_value("--probe", &mut args)?;
                     probes.push(normalize_token(value, "--probe")?);
                 }
+                "--alias" => {
+                    let value = next_value("--alias", &mut args)?;
+                    if alias.is_some() {
+                        bail!("--alias may only be specified once");
+                    }
+                    alias = Some(normalize_token(value, "--alias")?);
+                }
                 "--mode" => {
                     let value = next_value("--mode", &mut args)?;
                     modes.push(normalize_token(value, "--mode")?);
                 }
                 "--repeat" => {
                     let value = next_value("--repeat", &mut args)?;
-                    repeat = value.parse().context("--repeat must be >= 1")?;
-                    if repeat == 0 {
+                    let parsed: u32 = value.parse().context("--repeat must be >= 1")?;
+                    if parsed == 0 {
                         bail!("--repeat must be >= 1");
                     }
+                    repeat = Some(parsed);
                 }
+                "--jobs" => jobs = Some(next_jobs(&mut args)?),
                 "--list-only" => list_only = true,
+                "--probe-modes" => probe_modes = true,
                 "--help" | "-h" => usage(0),
                 other => {
                     bail!("unknown argument: {other}");
@@ -260,14 +736,16 @@ impl Cli {
             }
         }
 
-        let selection = match (cap, probes.is_empty()) {
-            (Some(cap_id), true) => Selection::Capability(CapabilityId(cap_id)),
-            (None, false) => Selection::Probes(probes),
-            (Some(_), false) => {
-                bail!("Specify exactly one of --cap or --probe");
+        let selection = match (cap, probes.is_empty(), alias) {
+            (Some(cap_id), true, None) => Some(Selection::Capability(CapabilityId(cap_id))),
+            (None, false, None) => Some(Selection::Probes(probes)),
+            (None, true, Some(name)) => Some(Selection::Alias(name)),
+            (None, true, None) if probe_modes => None,
+            (None, true, None) => {
+                bail!("--cap, --probe, or --alias is required for --rattle");
             }
-            (None, true) => {
-                bail!("--cap or --probe is required for --rattle");
+            _ => {
+                bail!("Specify exactly one of --cap, --probe, or --alias");
             }
         };
 
@@ -275,7 +753,9 @@ impl Cli {
             selection,
             modes,
             repeat,
+            jobs,
             list_only,
+            probe_modes,
         })
     }
 }
@@ -289,6 +769,13 @@ fn next_value(flag: &str, args: &mut env::ArgsOs) -> Result<String> {
         .map_err(|_| anyhow!("{flag} value must be valid UTF-8"))
 }
 
+fn next_jobs(args: &mut env::ArgsOs) -> Result<usize> {
+    let value = next_value("--jobs", args)?;
+    value
+        .parse()
+        .with_context(|| format!("--jobs must be a positive integer, got '{value}'"))
+}
+
 fn normalize_token(raw: String, flag: &str) -> Result<String> {
     let trimmed = raw.trim();
     if trimmed.is_empty() {
@@ -299,7 +786,7 @@ fn normalize_token(raw: String, flag: &str) -> Result<String> {
 
 fn usage(code: i32) -> ! {
     eprintln!(
-        "Usage: fence-rattle (--cap <capability-id> | --probe <probe-id>) [options]\n\nOptions:\n      --cap <id>        Run every probe whose primary capability matches <id>.\n      --probe <id>      Run a specific probe (repeatable).\n      --mode <mode>     Restrict modes (baseline, codex-sandbox, codex-full).\n      --repeat <n>      Rerun the selection n times (default: 1).\n      --list-only       Print the plan without executing probes.\n      --help            Show this help text.\n"
+        "Usage: fence-rattle (--cap <capability-id> | --probe <probe-id> | --alias <name>) [options]\n       fence-rattle --probe-modes\n\nOptions:\n      --cap <id>        Run every probe whose primary capability matches <id>.\n      --probe <id>      Run a specific probe (repeatable).\n      --alias <name>    Run a [alias] entry (capability id or probe list) from\n                        a discovered fence.toml.\n      --mode <mode>     Restrict modes (baseline, codex-sandbox, codex-full).\n                        Requesting a mode that can't actually launch on this\n                        host fails immediately with the detected reason.\n      --repeat <n>      Rerun the selection n times (default: 1, or\n                        [defaults] repeat from fence.toml).\n      --jobs <n>        Fan the selection out across n concurrent fence-bang\n                        children instead of one (default: available parallelism,\n                        or [defaults] jobs from fence.toml).\n      --list-only       Print the plan without executing probes.\n      --probe-modes     Print which modes actually launch on this host (see\n                        fence-run probe-mode) and exit; no selection required.\n      --help            Show this help text.\n\nCLI flags win over the JOBS/MODES/REPEAT environment variables, which win\nover a discovered fence.toml's [defaults], which win over built-in defaults.\nDefault modes are whichever of baseline/codex-sandbox/codex-full are detected\nas runnable, not just whichever the codex binary is installed.\n"
     );
     std::process::exit(code);
 }
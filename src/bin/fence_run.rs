@@ -5,14 +5,31 @@
 //! - export `FENCE_*` environment expected by probe scripts and `emit-record`
 //! - wrap Codex sandbox/full invocations when requested
 //! - honor workspace overrides without silently falling back to host defaults
+//! - check the probe's emitted boundary object against any `# fence-expect`
+//!   header directives declared for the active mode (see
+//!   [`parse_probe_expectations`]), compiletest-style, and fail loudly on a
+//!   mismatch instead of requiring a bespoke Rust test per probe
 
-use anyhow::{Context, Result, bail};
-use codex_fence::{codex_present, find_repo_root, resolve_probe};
+use anyhow::{Context, Result, anyhow, bail};
+use codex_fence::connectors;
+use codex_fence::fence_run_support::{
+    ContainmentPolicy, ContainmentViolation, WorkspaceOverride, WorkspacePlan, canonicalize_path,
+    reject_containment_violation, workspace_plan_from_override, workspace_tmpdir_plan,
+};
+use codex_fence::runtime::{CommandLogSpan, Verbosity};
+use codex_fence::{
+    BoundaryObject, Probe, TraceOp, capture_direct, classify_result, codex_present,
+    find_repo_root, parse_trace_ops_ndjson, read_boundary_objects, resolve_probe,
+};
+use regex::Regex;
+use serde::Serialize;
 use serde_json::json;
+use std::collections::BTreeMap;
 use std::env;
 use std::env::VarError;
 use std::ffi::OsString;
 use std::fs;
+use std::io::{self, BufReader, Write};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use tempfile::NamedTempFile;
@@ -25,19 +42,49 @@ fn main() {
 }
 
 fn run() -> Result<()> {
-    let args = CliArgs::parse()?;
+    match Invocation::parse()? {
+        Invocation::Single(args) => run_single(args),
+        Invocation::Matrix(args) => run_matrix(args),
+        Invocation::ProbeMode(mode) => run_probe_mode(&mode),
+    }
+}
+
+fn run_single(args: CliArgs) -> Result<()> {
     let repo_root = find_repo_root()?;
     let workspace_root = canonicalize_path(&repo_root);
-    let workspace_plan = determine_workspace_plan(&workspace_root, args.workspace_override)?;
+    let containment_policy = ContainmentPolicy::repo_root(&workspace_root);
+    let workspace_plan =
+        determine_workspace_plan(&workspace_root, args.workspace_override, &containment_policy)?;
+    reject_containment_violation("workspace root", workspace_plan.containment_error.as_ref())?;
     let resolved_probe = resolve_probe(&workspace_root, &args.probe_name)?;
     ensure_probe_executable(&resolved_probe.path)?;
-    let workspace_tmpdir = workspace_tmpdir(&workspace_root);
+    if !check_fence_supported(&resolved_probe.path)? {
+        let predicate = extract_probe_var(&resolved_probe.path, "fence_supported")
+            .expect("fence_supported predicate present when check_fence_supported returns false");
+        emit_skip_record(&repo_root, &resolved_probe.path, &args.run_mode, &predicate)?;
+        return Ok(());
+    }
+    let workspace_tmpdir_plan_result =
+        workspace_tmpdir_plan(&workspace_plan, &workspace_root, &containment_policy);
+    reject_containment_violation(
+        "workspace tmpdir",
+        workspace_tmpdir_plan_result.containment_error.as_ref(),
+    )?;
+    let workspace_tmpdir = workspace_tmpdir_plan_result.path;
+    let expectations = parse_probe_expectations(&resolved_probe.path)?;
+    let verbosity = Verbosity::resolve(args.verbose);
 
     let sandbox_mode = sandbox_mode_for_mode(&args.run_mode)?;
     let platform = detect_platform().unwrap_or_else(|| env::consts::OS.to_string());
-    let command_spec = build_command_spec(&args.run_mode, &platform, &resolved_probe.path)?;
+    let command_spec = build_command_spec(
+        &args.run_mode,
+        &platform,
+        &resolved_probe.path,
+        &workspace_root,
+        workspace_tmpdir.as_deref(),
+    )?;
 
-    if codex_mode(&args.run_mode) {
+    if preflight_mode(&args.run_mode) {
         if let Some(tmpdir) = workspace_tmpdir.as_ref() {
             if run_codex_preflight(
                 &repo_root,
@@ -45,39 +92,470 @@ fn run() -> Result<()> {
                 &platform,
                 tmpdir,
                 &resolved_probe.path,
-            )? {
+            )?
+            .is_some()
+            {
                 // Preflight emitted a denial record; skip running the probe.
                 return Ok(());
             }
         }
     }
 
-    run_command(
-        command_spec,
-        &args.run_mode,
-        &sandbox_mode,
-        &workspace_plan,
-        workspace_tmpdir.as_deref(),
+    let manifest_path = workspace_tmpdir
+        .as_deref()
+        .map(|tmpdir| {
+            let manifest = build_run_manifest(
+                &resolved_probe,
+                &args.run_mode,
+                &sandbox_mode,
+                &workspace_plan,
+                &platform,
+                &command_spec,
+            );
+            write_run_manifest(tmpdir, &resolved_probe.id, &manifest)
+        })
+        .transpose()?;
+
+    let start = std::time::Instant::now();
+    let stdout_bytes = if args.trace {
+        let tmpdir = workspace_tmpdir
+            .as_deref()
+            .ok_or_else(|| anyhow!("--trace requires a workspace tmpdir (see --workspace-root)"))?;
+        let (execution, trace) = execute_probe_traced(
+            command_spec,
+            &args.run_mode,
+            &sandbox_mode,
+            &workspace_plan,
+            workspace_tmpdir.as_deref(),
+            &expectations,
+            verbosity,
+            &resolved_probe.id,
+        )?;
+        for op in sink_trace_ops(tmpdir, &resolved_probe.id, &trace)? {
+            emit_trace_record(&repo_root, &resolved_probe.path, &args.run_mode, &op)?;
+        }
+        if let Some(path) = manifest_path.as_deref() {
+            record_run_completion(path, &execution.status, start.elapsed().as_millis())?;
+        }
+        stdout_or_exit(execution)?
+    } else {
+        run_command(
+            command_spec,
+            &args.run_mode,
+            &sandbox_mode,
+            &workspace_plan,
+            workspace_tmpdir.as_deref(),
+            &expectations,
+            verbosity,
+            manifest_path.as_deref(),
+            start,
+        )?
+    };
+
+    if let Some(snapshot_path) = &args.snapshot_path {
+        run_snapshot_check(snapshot_path, args.bless, &stdout_bytes)?;
+    }
+    Ok(())
+}
+
+/// Modes iterated by `fence-run matrix`, modeled after compiletest's fixed
+/// `mode` enum rather than today's single-mode invocation. `container` runs
+/// the probe inside an actually-isolated container (dropped capabilities,
+/// read-only root, no network) instead of simulating the boundary, so its
+/// deltas against `baseline` prove the sandbox enforces what it claims to.
+const MATRIX_MODES: &[&str] = &["baseline", "codex-sandbox", "codex-full", "container"];
+
+fn run_matrix(args: MatrixArgs) -> Result<()> {
+    let repo_root = find_repo_root()?;
+    let workspace_root = canonicalize_path(&repo_root);
+    let containment_policy = ContainmentPolicy::repo_root(&workspace_root);
+    let workspace_plan =
+        determine_workspace_plan(&workspace_root, args.workspace_override, &containment_policy)?;
+    reject_containment_violation("workspace root", workspace_plan.containment_error.as_ref())?;
+    let resolved_probe = resolve_probe(&workspace_root, &args.probe_name)?;
+    ensure_probe_executable(&resolved_probe.path)?;
+    let workspace_tmpdir_plan_result =
+        workspace_tmpdir_plan(&workspace_plan, &workspace_root, &containment_policy);
+    reject_containment_violation(
+        "workspace tmpdir",
+        workspace_tmpdir_plan_result.containment_error.as_ref(),
     )?;
+    let workspace_tmpdir = workspace_tmpdir_plan_result.path;
+    let expectations = parse_probe_expectations(&resolved_probe.path)?;
+    let platform = detect_platform().unwrap_or_else(|| env::consts::OS.to_string());
+    let verbosity = Verbosity::resolve(args.verbose);
+
+    let outcomes: Vec<MatrixModeOutcome> = MATRIX_MODES
+        .iter()
+        .map(|mode| {
+            run_matrix_mode(
+                &repo_root,
+                &workspace_plan,
+                workspace_tmpdir.as_deref(),
+                &resolved_probe.path,
+                &platform,
+                mode,
+                &expectations,
+                verbosity,
+            )
+        })
+        .collect();
+
+    render_matrix_report(&resolved_probe.id, &outcomes);
     Ok(())
 }
 
+/// `fence-run probe-mode MODE`: reports whether `mode`'s sandbox actually
+/// launches on this host, with no probe resolved and no boundary object
+/// emitted. `baseline` needs no sandbox and is always available; the codex
+/// modes are exercised with the same trivial `mktemp -d` invocation
+/// [`run_codex_preflight`] uses to gate a real probe run (see
+/// [`probe_codex_mode_launch`]). Consumers like `fence-rattle`'s mode
+/// detection pass run this once per mode and cache the result rather than
+/// discovering a launch failure mid-matrix.
+fn run_probe_mode(mode: &str) -> Result<()> {
+    match mode {
+        "baseline" => {
+            println!("available");
+            Ok(())
+        }
+        "codex-sandbox" | "codex-full" => {
+            probe_codex_mode_launch(mode)?;
+            println!("available");
+            Ok(())
+        }
+        other => bail!("Unknown mode: {other}"),
+    }
+}
+
+/// Standalone version of [`run_codex_preflight`]'s launch check: same argv,
+/// built via [`codex_preflight_args`], but run against a scratch directory
+/// under the system temp dir instead of a workspace tmpdir, since no probe or
+/// workspace is involved in a mode-availability check.
+fn probe_codex_mode_launch(mode: &str) -> Result<()> {
+    ensure_codex_available()?;
+    let platform = detect_platform().unwrap_or_else(|| env::consts::OS.to_string());
+    let target_platform = platform_target(&platform)?;
+    let target = env::temp_dir().join(format!("fence-probe-mode-{mode}-{}", std::process::id()));
+    let args = codex_preflight_args(mode, target_platform, &target)?;
+
+    let output = Command::new("codex")
+        .args(&args)
+        .output()
+        .context("codex mode probe failed to spawn")?;
+
+    if output.status.success() {
+        let _ = fs::remove_dir(&target);
+        return Ok(());
+    }
+
+    let _ = fs::remove_dir(&target);
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    if stderr.is_empty() {
+        bail!(
+            "{mode} unavailable: codex sandbox setup exited {:?}",
+            output.status.code()
+        );
+    }
+    bail!("{mode} unavailable: {stderr}");
+}
+
+/// Result of attempting one [`MATRIX_MODES`] entry. `error` is populated
+/// instead of the other fields when the attempt itself failed (e.g. codex
+/// unavailable, probe crashed) rather than the probe reporting a capability
+/// result.
+struct MatrixModeOutcome {
+    mode: String,
+    observed_result: Option<String>,
+    errno: Option<String>,
+    message: Option<String>,
+    capability_id: Option<String>,
+    error: Option<String>,
+}
+
+/// Run one mode of the matrix, converting any failure into an outcome with
+/// only `error` populated so a single bad mode can't abort the others.
+fn run_matrix_mode(
+    repo_root: &Path,
+    workspace_plan: &WorkspacePlan,
+    workspace_tmpdir: Option<&Path>,
+    probe_path: &Path,
+    platform: &str,
+    mode: &str,
+    expectations: &ProbeExpectations,
+    verbosity: Verbosity,
+) -> MatrixModeOutcome {
+    let attempt = (|| -> Result<serde_json::Value> {
+        if !check_fence_supported(probe_path)? {
+            let predicate = extract_probe_var(probe_path, "fence_supported").expect(
+                "fence_supported predicate present when check_fence_supported returns false",
+            );
+            return emit_skip_record(repo_root, probe_path, mode, &predicate);
+        }
+
+        let sandbox_mode = sandbox_mode_for_mode(mode)?;
+        let command_spec =
+            build_command_spec(mode, platform, probe_path, repo_root, workspace_tmpdir)?;
+
+        if preflight_mode(mode) {
+            if let Some(tmpdir) = workspace_tmpdir {
+                if let Some(denial) =
+                    run_codex_preflight(repo_root, mode, platform, tmpdir, probe_path)?
+                {
+                    return Ok(denial);
+                }
+            }
+        }
+
+        let execution = execute_probe(
+            command_spec,
+            mode,
+            &sandbox_mode,
+            workspace_plan,
+            workspace_tmpdir,
+            expectations,
+            verbosity,
+        )?;
+        last_json_line(&execution.stdout).context("no JSON boundary object found on probe stdout")
+    })();
+
+    match attempt {
+        Ok(record) => matrix_outcome_from_value(mode, &record),
+        Err(err) => MatrixModeOutcome {
+            mode: mode.to_string(),
+            observed_result: None,
+            errno: None,
+            message: None,
+            capability_id: None,
+            error: Some(err.to_string()),
+        },
+    }
+}
+
+/// Build a [`MatrixModeOutcome`] from a boundary object's JSON rendering.
+fn matrix_outcome_from_value(mode: &str, record: &serde_json::Value) -> MatrixModeOutcome {
+    let pointer_str = |pointer: &str| {
+        record
+            .pointer(pointer)
+            .and_then(serde_json::Value::as_str)
+            .map(str::to_string)
+    };
+
+    MatrixModeOutcome {
+        mode: mode.to_string(),
+        observed_result: pointer_str("/result/observed_result"),
+        errno: pointer_str("/result/errno"),
+        message: pointer_str("/result/message"),
+        capability_id: pointer_str("/capability_context/primary/id"),
+        error: None,
+    }
+}
+
+/// A capability whose observed result differs between the baseline mode and
+/// some other mode in the matrix.
+struct CapabilityDelta {
+    capability_id: String,
+    baseline_result: String,
+    mode: String,
+    mode_result: String,
+}
+
+/// Compare each non-baseline outcome's `observed_result` against baseline's,
+/// emitting a delta only when both are present and differ.
+fn capability_deltas(outcomes: &[MatrixModeOutcome]) -> Vec<CapabilityDelta> {
+    let Some(baseline) = outcomes.iter().find(|outcome| outcome.mode == "baseline") else {
+        return Vec::new();
+    };
+    let Some(baseline_result) = baseline.observed_result.as_ref() else {
+        return Vec::new();
+    };
+
+    outcomes
+        .iter()
+        .filter(|outcome| outcome.mode != "baseline")
+        .filter_map(|outcome| {
+            let mode_result = outcome.observed_result.as_ref()?;
+            if mode_result == baseline_result {
+                return None;
+            }
+            Some(CapabilityDelta {
+                capability_id: outcome
+                    .capability_id
+                    .clone()
+                    .or_else(|| baseline.capability_id.clone())
+                    .unwrap_or_else(|| "unknown".to_string()),
+                baseline_result: baseline_result.clone(),
+                mode: outcome.mode.clone(),
+                mode_result: mode_result.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Print a human-readable mode table and deltas-vs-baseline section, followed
+/// by a machine-readable JSON rendering of the same data.
+fn render_matrix_report(probe_id: &str, outcomes: &[MatrixModeOutcome]) {
+    let deltas = capability_deltas(outcomes);
+
+    println!("Matrix report for probe '{probe_id}':");
+    println!(
+        "{:<14} {:<10} {:<8} {}",
+        "MODE", "RESULT", "ERRNO", "MESSAGE"
+    );
+    for outcome in outcomes {
+        if let Some(error) = &outcome.error {
+            println!("{:<14} {:<10} {:<8} {}", outcome.mode, "error", "-", error);
+            continue;
+        }
+        println!(
+            "{:<14} {:<10} {:<8} {}",
+            outcome.mode,
+            outcome.observed_result.as_deref().unwrap_or("-"),
+            outcome.errno.as_deref().unwrap_or("-"),
+            outcome.message.as_deref().unwrap_or("-")
+        );
+    }
+
+    println!("\nDeltas vs baseline:");
+    if deltas.is_empty() {
+        println!("  (none)");
+    } else {
+        for delta in &deltas {
+            println!(
+                "  {}: baseline={} {}={}",
+                delta.capability_id, delta.baseline_result, delta.mode, delta.mode_result
+            );
+        }
+    }
+
+    let report = build_matrix_report_json(probe_id, outcomes, &deltas);
+    println!(
+        "\n{}",
+        serde_json::to_string_pretty(&report).unwrap_or_else(|_| report.to_string())
+    );
+}
+
+fn build_matrix_report_json(
+    probe_id: &str,
+    outcomes: &[MatrixModeOutcome],
+    deltas: &[CapabilityDelta],
+) -> serde_json::Value {
+    json!({
+        "probe_id": probe_id,
+        "records": outcomes.iter().map(|outcome| json!({
+            "mode": outcome.mode,
+            "observed_result": outcome.observed_result,
+            "errno": outcome.errno,
+            "message": outcome.message,
+            "capability_id": outcome.capability_id,
+            "error": outcome.error,
+        })).collect::<Vec<_>>(),
+        "capability_deltas": deltas.iter().map(|delta| json!({
+            "capability_id": delta.capability_id,
+            "baseline_result": delta.baseline_result,
+            "mode": delta.mode,
+            "mode_result": delta.mode_result,
+        })).collect::<Vec<_>>(),
+    })
+}
+
 struct CliArgs {
     workspace_override: Option<WorkspaceOverride>,
     run_mode: String,
     probe_name: String,
+    snapshot_path: Option<PathBuf>,
+    bless: bool,
+    verbose: u32,
+    trace: bool,
+}
+
+/// Arguments for `fence-run matrix PROBE_NAME`.
+struct MatrixArgs {
+    workspace_override: Option<WorkspaceOverride>,
+    probe_name: String,
+    verbose: u32,
 }
 
-#[derive(Clone)]
-/// How the workspace root should be exported to the probe.
-enum WorkspaceOverride {
-    UsePath(OsString),
-    SkipExport,
+/// Either a classic single-mode invocation, a `matrix` comparison run, or a
+/// standalone `probe-mode` availability check.
+enum Invocation {
+    Single(CliArgs),
+    Matrix(MatrixArgs),
+    ProbeMode(String),
 }
 
-/// Finalized workspace export plan after considering CLI/env overrides.
-struct WorkspacePlan {
-    export_value: Option<OsString>,
+impl Invocation {
+    fn parse() -> Result<Self> {
+        Self::parse_from(env::args().skip(1))
+    }
+
+    fn parse_from(args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut args_iter = args.peekable();
+        let mut workspace_override = None;
+        let mut verbose = 0;
+
+        // Consume leading flags that apply to either invocation shape before
+        // deciding whether the first positional is `matrix`.
+        while let Some(arg) = args_iter.peek().cloned() {
+            if arg.starts_with("--workspace-root=") {
+                let value = arg.split_once('=').map(|(_, v)| v).unwrap_or("");
+                workspace_override = Some(parse_workspace_override(value));
+                args_iter.next();
+                continue;
+            }
+            match arg.as_str() {
+                "--workspace-root" => {
+                    args_iter.next();
+                    let value = args_iter.next().unwrap_or_else(|| {
+                        eprintln!("Missing path for --workspace-root");
+                        usage();
+                    });
+                    workspace_override = Some(parse_workspace_override(&value));
+                }
+                "-v" | "--verbose" => {
+                    args_iter.next();
+                    verbose += 1;
+                }
+                "-vv" => {
+                    args_iter.next();
+                    verbose += 2;
+                }
+                "-h" | "--help" => {
+                    args_iter.next();
+                    usage();
+                }
+                _ => break,
+            }
+        }
+
+        if args_iter.peek().map(String::as_str) == Some("matrix") {
+            args_iter.next();
+            let probe_name = args_iter.next().unwrap_or_else(|| usage());
+            if args_iter.next().is_some() {
+                usage();
+            }
+            return Ok(Self::Matrix(MatrixArgs {
+                workspace_override,
+                probe_name,
+                verbose,
+            }));
+        }
+
+        if args_iter.peek().map(String::as_str) == Some("probe-mode") {
+            args_iter.next();
+            let mode = args_iter.next().unwrap_or_else(|| usage());
+            if args_iter.next().is_some() {
+                usage();
+            }
+            return Ok(Self::ProbeMode(mode));
+        }
+
+        Ok(Self::Single(CliArgs::parse_remaining(
+            workspace_override,
+            verbose,
+            args_iter,
+        )?))
+    }
 }
 
 /// Program and arguments used to execute the probe for a given mode.
@@ -87,9 +565,17 @@ struct CommandSpec {
 }
 
 impl CliArgs {
-    fn parse() -> Result<Self> {
-        let mut args_iter = env::args().skip(1);
-        let mut workspace_override = None;
+    /// Parses the remainder of the command line for the classic single-mode
+    /// invocation, given a `workspace_override` already collected by
+    /// [`Invocation::parse`] from any leading flags shared with `matrix`.
+    fn parse_remaining(
+        mut workspace_override: Option<WorkspaceOverride>,
+        mut verbose: u32,
+        mut args_iter: impl Iterator<Item = String>,
+    ) -> Result<Self> {
+        let mut snapshot_path = None;
+        let mut bless = false;
+        let mut trace = false;
         let mut positionals = Vec::new();
 
         while let Some(arg) = args_iter.next() {
@@ -107,6 +593,17 @@ impl CliArgs {
                     });
                     workspace_override = Some(parse_workspace_override(&value));
                 }
+                "--snapshot" => {
+                    let value = args_iter.next().unwrap_or_else(|| {
+                        eprintln!("Missing path for --snapshot");
+                        usage();
+                    });
+                    snapshot_path = Some(PathBuf::from(value));
+                }
+                "--bless" => bless = true,
+                "--trace" => trace = true,
+                "-v" | "--verbose" => verbose += 1,
+                "-vv" => verbose += 2,
                 "-h" | "--help" => usage(),
                 _ if arg.starts_with("--") => {
                     eprintln!("Unknown option: {arg}");
@@ -123,18 +620,26 @@ impl CliArgs {
         if positionals.len() != 2 {
             usage();
         }
+        if bless && snapshot_path.is_none() {
+            eprintln!("--bless requires --snapshot PATH");
+            usage();
+        }
 
         Ok(Self {
             workspace_override,
             run_mode: positionals[0].clone(),
             probe_name: positionals[1].clone(),
+            snapshot_path,
+            bless,
+            verbose,
+            trace,
         })
     }
 }
 
 fn usage() -> ! {
     eprintln!(
-        "Usage: fence-run [--workspace-root PATH] MODE PROBE_NAME\n\nOverrides:\n  --workspace-root PATH   Export PATH via FENCE_WORKSPACE_ROOT (defaults to repo root).\n                          Pass an empty string to defer to emit-record's git/pwd fallback.\n\nEnvironment:\n  FENCE_WORKSPACE_ROOT    When set, takes precedence over the default repo root export."
+        "Usage: fence-run [--workspace-root PATH] [--snapshot PATH [--bless]] [--trace] [-v|-vv] MODE PROBE_NAME\n       fence-run [--workspace-root PATH] [-v|-vv] matrix PROBE_NAME\n       fence-run probe-mode MODE\n\nOverrides:\n  --workspace-root PATH   Export PATH via FENCE_WORKSPACE_ROOT (defaults to repo root).\n                          Pass an empty string to defer to emit-record's git/pwd fallback.\n  --snapshot PATH         Compare the emitted boundary object (after redacting volatile\n                          fields like workspace_root) against a golden file, printing a\n                          unified diff and exiting nonzero on drift. Golden string values\n                          may contain a `[..]` wildcard matching any substring.\n  --bless                 With --snapshot, write the redacted output as the new golden\n                          file instead of comparing against it.\n  --trace                 Capture a syscall-level provenance trace of every filesystem and\n                          network operation the probe performs, emitting one `trace`-category\n                          record per operation via emit-record alongside the probe's boundary\n                          object. Requires a workspace tmpdir (see --workspace-root).\n  -v, --verbose           Log the resolved command, argv, cwd, run mode, and fence env\n                          before spawning the probe, plus elapsed time/exit code after\n                          (repeatable; -vv or a second -v also prints captured output).\n\nmatrix PROBE_NAME runs PROBE_NAME once per mode in baseline, codex-sandbox,\ncodex-full, container order, reporting a per-mode table plus any capability results that\ndiverge from baseline instead of exiting nonzero on the first mode that does.\n\nprobe-mode MODE checks whether MODE's sandbox actually launches on this host\n(no probe involved) and prints \"available\" or fails with a precise reason;\nused by fence-rattle's mode detection pass.\n\nEnvironment:\n  FENCE_WORKSPACE_ROOT    When set, takes precedence over the default repo root export.\n  FENCE_LOG               Set command-log verbosity (0/1/2 or quiet/verbose/debug)\n                          independent of -v; the more detailed of the two wins."
     );
     std::process::exit(1);
 }
@@ -150,11 +655,12 @@ fn parse_workspace_override(value: &str) -> WorkspaceOverride {
 fn determine_workspace_plan(
     default_root: &Path,
     cli_override: Option<WorkspaceOverride>,
+    policy: &ContainmentPolicy,
 ) -> Result<WorkspacePlan> {
     // CLI override wins; otherwise honor FENCE_WORKSPACE_ROOT if set, and only
     // then fall back to the repo root.
     if let Some(override_value) = cli_override {
-        return Ok(workspace_plan_from_override(override_value));
+        return Ok(workspace_plan_from_override(override_value, policy));
     }
 
     let env_override = match env::var_os("FENCE_WORKSPACE_ROOT") {
@@ -164,43 +670,16 @@ fn determine_workspace_plan(
     };
 
     if let Some(value) = env_override {
-        return Ok(workspace_plan_from_override(value));
-    }
-
-    Ok(WorkspacePlan {
-        export_value: Some(default_root.as_os_str().to_os_string()),
-    })
-}
-
-fn workspace_plan_from_override(value: WorkspaceOverride) -> WorkspacePlan {
-    match value {
-        WorkspaceOverride::SkipExport => WorkspacePlan { export_value: None },
-        WorkspaceOverride::UsePath(path) => WorkspacePlan {
-            export_value: Some(canonicalize_os_string(&path)),
-        },
+        return Ok(workspace_plan_from_override(value, policy));
     }
-}
-
-fn canonicalize_path(path: &Path) -> PathBuf {
-    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
-}
-
-fn canonicalize_os_string(value: &OsString) -> OsString {
-    let candidate = PathBuf::from(value);
-    fs::canonicalize(&candidate)
-        .unwrap_or(candidate)
-        .into_os_string()
-}
 
-/// Prefer a workspace-scoped tmp dir so probes land temp files inside the
-/// allowed tree even when system defaults are blocked.
-fn workspace_tmpdir(workspace_root: &Path) -> Option<PathBuf> {
-    let candidate = workspace_root.join("tmp");
-    if fs::create_dir_all(&candidate).is_ok() {
-        Some(canonicalize_path(&candidate))
-    } else {
-        None
-    }
+    // The default root is the containment policy's own repo root, trusted by
+    // construction, so it goes through `TrustedPath` rather than `UsePath`'s
+    // redundant self-check.
+    Ok(workspace_plan_from_override(
+        WorkspaceOverride::TrustedPath(default_root.as_os_str().to_os_string()),
+        policy,
+    ))
 }
 
 fn ensure_probe_executable(path: &Path) -> Result<()> {
@@ -219,6 +698,13 @@ fn codex_mode(run_mode: &str) -> bool {
     matches!(run_mode, "codex-sandbox" | "codex-full")
 }
 
+/// Modes that should attempt [`run_codex_preflight`] before spawning the
+/// probe: the codex modes, plus `container`, whose preflight checks for an
+/// unreachable daemon or a read-only mount instead of a sandbox denial.
+fn preflight_mode(run_mode: &str) -> bool {
+    codex_mode(run_mode) || run_mode == "container"
+}
+
 fn has_execute_bit(metadata: &fs::Metadata) -> bool {
     #[cfg(unix)]
     {
@@ -247,11 +733,20 @@ fn sandbox_mode_for_mode(run_mode: &str) -> Result<OsString> {
         "codex-full" => Ok(OsString::from(
             env_value.unwrap_or_else(|| "danger-full-access".to_string()),
         )),
+        "container" => Ok(OsString::from(
+            env_value.unwrap_or_else(|| "container-readonly".to_string()),
+        )),
         other => bail!("Unknown mode: {other}"),
     }
 }
 
-fn build_command_spec(run_mode: &str, platform: &str, probe_path: &Path) -> Result<CommandSpec> {
+fn build_command_spec(
+    run_mode: &str,
+    platform: &str,
+    probe_path: &Path,
+    workspace_root: &Path,
+    workspace_tmpdir: Option<&Path>,
+) -> Result<CommandSpec> {
     let probe_arg = probe_path.as_os_str().to_os_string();
     match run_mode {
         "baseline" => Ok(CommandSpec {
@@ -286,6 +781,20 @@ fn build_command_spec(run_mode: &str, platform: &str, probe_path: &Path) -> Resu
                 ],
             })
         }
+        "container" => {
+            let plan = connectors::plan_for_mode(
+                run_mode,
+                platform,
+                probe_path,
+                workspace_root,
+                workspace_tmpdir,
+                None,
+            )?;
+            Ok(CommandSpec {
+                program: plan.command.program,
+                args: plan.command.args,
+            })
+        }
         other => bail!("Unknown mode: {other}"),
     }
 }
@@ -298,6 +807,39 @@ fn platform_target(platform: &str) -> Result<&'static str> {
     }
 }
 
+/// Builds the `codex sandbox ... -- /usr/bin/mktemp -d TARGET` argv used to
+/// exercise whether `run_mode`'s sandbox actually applies, shared by
+/// [`run_codex_preflight`] (which runs it before a real probe and emits a
+/// denial record on failure) and [`probe_codex_mode_launch`] (which runs it
+/// standalone for mode-availability detection).
+fn codex_preflight_args(
+    run_mode: &str,
+    platform_target: &str,
+    target: &Path,
+) -> Result<Vec<OsString>> {
+    let mut args: Vec<OsString> = Vec::new();
+    match run_mode {
+        "codex-sandbox" => {
+            args.push(OsString::from("sandbox"));
+            args.push(OsString::from(platform_target));
+            args.push(OsString::from("--full-auto"));
+        }
+        "codex-full" => {
+            args.push(OsString::from("--dangerously-bypass-approvals-and-sandbox"));
+            args.push(OsString::from("sandbox"));
+            args.push(OsString::from(platform_target));
+        }
+        other => bail!("Unsupported mode for codex preflight: {other}"),
+    }
+    args.push(OsString::from("--"));
+    args.push(OsString::from("/usr/bin/mktemp"));
+    args.push(OsString::from("-d"));
+    args.push(OsString::from(
+        target.as_os_str().to_string_lossy().to_string(),
+    ));
+    Ok(args)
+}
+
 fn ensure_codex_available() -> Result<()> {
     if codex_present() {
         return Ok(());
@@ -318,90 +860,598 @@ fn detect_platform() -> Option<String> {
         return None;
     }
     let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    if value.is_empty() { None } else { Some(value) }
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
 }
 
-fn run_command(
+/// Outcome of a single probe invocation, before the caller decides whether a
+/// nonzero exit should abort the process (single-mode) or simply be folded
+/// into a per-mode outcome (matrix).
+struct ProbeExecution {
+    stdout: Vec<u8>,
+    status: std::process::ExitStatus,
+}
+
+fn execute_probe(
     spec: CommandSpec,
     run_mode: &str,
     sandbox_mode: &OsString,
     workspace_plan: &WorkspacePlan,
     workspace_tmpdir: Option<&Path>,
-) -> Result<()> {
+    expectations: &ProbeExpectations,
+    verbosity: Verbosity,
+) -> Result<ProbeExecution> {
     let mut command = Command::new(&spec.program);
     for arg in &spec.args {
         command.arg(arg);
     }
-    command.env("FENCE_RUN_MODE", run_mode);
-    command.env("FENCE_SANDBOX_MODE", sandbox_mode);
+    let mut fence_env: Vec<(OsString, OsString)> = vec![
+        (OsString::from("FENCE_RUN_MODE"), OsString::from(run_mode)),
+        (OsString::from("FENCE_SANDBOX_MODE"), sandbox_mode.clone()),
+    ];
     if let Some(value) = workspace_plan.export_value.as_ref() {
-        command.env("FENCE_WORKSPACE_ROOT", value);
+        fence_env.push((OsString::from("FENCE_WORKSPACE_ROOT"), value.clone()));
     }
     if let Some(tmpdir) = workspace_tmpdir {
-        command.env("TMPDIR", tmpdir);
+        fence_env.push((OsString::from("TMPDIR"), tmpdir.as_os_str().to_os_string()));
     }
+    for (key, value) in &fence_env {
+        command.env(key, value);
+    }
+    // Piped (rather than inherited) so the emitted boundary object can be
+    // checked against `# fence-expect` directives before being forwarded
+    // byte-for-byte to our own stdout, preserving today's contract with
+    // downstream consumers like fence-bang.
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let cwd = env::current_dir().unwrap_or_default();
+    let log_span = CommandLogSpan::start(
+        verbosity,
+        &spec.program,
+        &spec.args,
+        &cwd,
+        run_mode,
+        &fence_env,
+    );
 
-    let status = command
-        .status()
+    let output = command
+        .output()
         .with_context(|| format!("Failed to execute {}", spec.program.to_string_lossy()))?;
-    if !status.success() {
-        if let Some(code) = status.code() {
-            std::process::exit(code);
-        } else {
-            bail!("Probe terminated by signal");
-        }
-    }
-    Ok(())
-}
+    log_span.finish(output.status.code(), &output.stdout, &output.stderr);
 
-fn classify_preflight_error(stderr: &str) -> (&'static str, Option<&'static str>, String) {
-    let lower = stderr.to_ascii_lowercase();
-    if lower.contains("operation not permitted") {
-        ("denied", Some("EPERM"), "codex sandbox preflight denied (operation not permitted)".to_string())
-    } else if lower.contains("permission denied") {
-        ("denied", Some("EACCES"), "codex sandbox preflight denied (permission denied)".to_string())
-    } else {
-        ("error", None, "codex sandbox preflight failed".to_string())
-    }
-}
+    io::stdout()
+        .write_all(&output.stdout)
+        .context("forwarding probe stdout")?;
+    io::stderr()
+        .write_all(&output.stderr)
+        .context("forwarding probe stderr")?;
 
-fn extract_probe_var(path: &Path, var: &str) -> Option<String> {
-    let contents = fs::read_to_string(path).ok()?;
-    for line in contents.lines() {
-        let trimmed = line.trim_start();
-        if !trimmed.starts_with(var) {
-            continue;
-        }
-        if let Some(rest) = trimmed.splitn(2, '=').nth(1) {
-            let val = rest
-                .split('#')
-                .next()
-                .unwrap_or("")
-                .trim()
-                .trim_matches(|c| c == '"' || c == '\'');
-            if !val.is_empty() {
-                return Some(val.to_string());
-            }
+    if let Ok(records) = read_boundary_objects(BufReader::new(output.stdout.as_slice())) {
+        if let Some(record) = records.last() {
+            check_expectations(expectations, run_mode, record, &output.stderr)?;
         }
     }
-    None
-}
 
-fn write_temp_payload(value: &serde_json::Value) -> Result<PathBuf> {
-    let mut file = NamedTempFile::new().context("create payload temp file")?;
-    serde_json::to_writer(&mut file, value)?;
-    let (_file, path) = file.keep().context("persist payload temp file")?;
-    Ok(path)
+    Ok(ProbeExecution {
+        stdout: output.stdout,
+        status: output.status,
+    })
 }
 
-fn emit_preflight_record(
-    repo_root: &Path,
-    probe_path: &Path,
+/// Like [`execute_probe`], but runs the probe under [`capture_direct`]'s
+/// ptrace-based tracer instead of a plain [`Command`] so `--trace` can
+/// recover the syscall-level provenance record alongside the usual
+/// stdout/expectations handling.
+fn execute_probe_traced(
+    spec: CommandSpec,
     run_mode: &str,
-    target_path: &Path,
-    exit_code: i32,
-    stderr: &str,
+    sandbox_mode: &OsString,
+    workspace_plan: &WorkspacePlan,
+    workspace_tmpdir: Option<&Path>,
+    expectations: &ProbeExpectations,
+    verbosity: Verbosity,
+    probe_id: &str,
+) -> Result<(ProbeExecution, codex_fence::ExecutionTrace)> {
+    let mut fence_env: Vec<(OsString, OsString)> = vec![
+        (OsString::from("FENCE_RUN_MODE"), OsString::from(run_mode)),
+        (OsString::from("FENCE_SANDBOX_MODE"), sandbox_mode.clone()),
+    ];
+    if let Some(value) = workspace_plan.export_value.as_ref() {
+        fence_env.push((OsString::from("FENCE_WORKSPACE_ROOT"), value.clone()));
+    }
+    if let Some(tmpdir) = workspace_tmpdir {
+        fence_env.push((OsString::from("TMPDIR"), tmpdir.as_os_str().to_os_string()));
+    }
+
+    let cwd = env::current_dir().unwrap_or_default();
+    let log_span = CommandLogSpan::start(
+        verbosity,
+        &spec.program,
+        &spec.args,
+        &cwd,
+        run_mode,
+        &fence_env,
+    );
+
+    let (output, trace) = capture_direct(
+        &spec.program,
+        &spec.args,
+        &fence_env,
+        &cwd,
+        probe_id,
+        run_mode,
+    )?;
+    log_span.finish(output.status.code(), &output.stdout, &output.stderr);
+
+    io::stdout()
+        .write_all(&output.stdout)
+        .context("forwarding probe stdout")?;
+    io::stderr()
+        .write_all(&output.stderr)
+        .context("forwarding probe stderr")?;
+
+    if let Ok(records) = read_boundary_objects(BufReader::new(output.stdout.as_slice())) {
+        if let Some(record) = records.last() {
+            check_expectations(expectations, run_mode, record, &output.stderr)?;
+        }
+    }
+
+    Ok((
+        ProbeExecution {
+            stdout: output.stdout,
+            status: output.status,
+        },
+        trace,
+    ))
+}
+
+fn run_command(
+    spec: CommandSpec,
+    run_mode: &str,
+    sandbox_mode: &OsString,
+    workspace_plan: &WorkspacePlan,
+    workspace_tmpdir: Option<&Path>,
+    expectations: &ProbeExpectations,
+    verbosity: Verbosity,
+    manifest_path: Option<&Path>,
+    start: std::time::Instant,
+) -> Result<Vec<u8>> {
+    let execution = execute_probe(
+        spec,
+        run_mode,
+        sandbox_mode,
+        workspace_plan,
+        workspace_tmpdir,
+        expectations,
+        verbosity,
+    )?;
+    if let Some(path) = manifest_path {
+        record_run_completion(path, &execution.status, start.elapsed().as_millis())?;
+    }
+    stdout_or_exit(execution)
+}
+
+/// Exits the process with the probe's own exit code on failure, otherwise
+/// returns its captured stdout. Shared by [`run_command`] and the `--trace`
+/// path in [`run_single`], which both need to preserve a probe's nonzero
+/// exit status as `fence-run`'s own exit status.
+fn stdout_or_exit(execution: ProbeExecution) -> Result<Vec<u8>> {
+    if !execution.status.success() {
+        if let Some(code) = execution.status.code() {
+            std::process::exit(code);
+        } else {
+            bail!("Probe terminated by signal");
+        }
+    }
+    Ok(execution.stdout)
+}
+
+/// Declarative per-mode contract for a probe, parsed from `# fence-expect`
+/// header comments in its script. Modes with no directive default to "any"
+/// (unchecked), matching rustc compiletest's header-directive model.
+#[derive(Debug, Default)]
+struct ProbeExpectations {
+    per_mode: BTreeMap<String, String>,
+    message_pattern: Option<Regex>,
+}
+
+/// Parse `# fence-expect MODE=RESULT` and `# fence-expect-message REGEX`
+/// header comments out of `probe_path`. A probe with no directives is left
+/// fully permissive (every mode defaults to "any").
+fn parse_probe_expectations(probe_path: &Path) -> Result<ProbeExpectations> {
+    let contents = fs::read_to_string(probe_path)
+        .with_context(|| format!("reading probe script {}", probe_path.display()))?;
+    let mut expectations = ProbeExpectations::default();
+
+    for line in contents.lines() {
+        let Some(comment) = line.trim_start().strip_prefix('#') else {
+            continue;
+        };
+        let comment = comment.trim_start();
+
+        if let Some(pattern) = comment.strip_prefix("fence-expect-message") {
+            let pattern = pattern.trim();
+            if pattern.is_empty() {
+                bail!(
+                    "malformed fence-expect-message directive '{line}' (expected a regex pattern)"
+                );
+            }
+            expectations.message_pattern =
+                Some(Regex::new(pattern).with_context(|| {
+                    format!("invalid fence-expect-message pattern '{pattern}'")
+                })?);
+            continue;
+        }
+
+        if let Some(rest) = comment.strip_prefix("fence-expect") {
+            let rest = rest.trim();
+            let (mode, result) = rest.split_once('=').with_context(|| {
+                format!("malformed fence-expect directive '{line}' (expected 'MODE=RESULT')")
+            })?;
+            let mode = mode.trim();
+            let result = result.trim();
+            if mode.is_empty() || result.is_empty() {
+                bail!("malformed fence-expect directive '{line}' (expected 'MODE=RESULT')");
+            }
+            expectations
+                .per_mode
+                .insert(mode.to_string(), result.to_string());
+        }
+    }
+
+    Ok(expectations)
+}
+
+/// Compare `record` (the boundary object the probe just emitted) against
+/// `expectations` for `run_mode`. A mode with no `# fence-expect` directive
+/// defaults to "any" and is never checked. On mismatch, returns a readable
+/// diagnostic so the caller can surface it and exit nonzero.
+fn check_expectations(
+    expectations: &ProbeExpectations,
+    run_mode: &str,
+    record: &BoundaryObject,
+    stderr: &[u8],
 ) -> Result<()> {
+    if let Some(expected_result) = expectations.per_mode.get(run_mode) {
+        if &record.result.observed_result != expected_result {
+            return Err(anyhow!(
+                "fence-expect mismatch for probe '{}' in mode '{run_mode}': expected result '{expected_result}', observed '{}'",
+                record.probe.id,
+                record.result.observed_result
+            ));
+        }
+    }
+
+    if let Some(pattern) = &expectations.message_pattern {
+        let stderr_text = String::from_utf8_lossy(stderr);
+        let message = record.result.message.as_deref().unwrap_or("");
+        if !pattern.is_match(message) && !pattern.is_match(&stderr_text) {
+            return Err(anyhow!(
+                "fence-expect-message mismatch for probe '{}' in mode '{run_mode}': pattern '{}' matched neither result.message ('{message}') nor stderr",
+                record.probe.id,
+                pattern.as_str()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// JSON pointers redacted before a snapshot is compared or blessed, mirroring
+/// cargo-test-support's `compare.rs` normalization pass. Pointers that don't
+/// resolve against the current schema (e.g. a field not yet emitted) are
+/// simply no-ops rather than errors.
+const SNAPSHOT_REDACT_POINTERS: &[&str] = &["/result/duration_ms", "/run/workspace_root"];
+
+/// Sentinel written in place of a redacted field.
+const SNAPSHOT_REDACTED: &str = "[redacted]";
+
+/// Lines of context kept on either side of a hunk in the rendered diff.
+const DIFF_CONTEXT: usize = 3;
+
+/// Extract the final line of `stdout` that parses as JSON, i.e. the boundary
+/// object a probe just emitted (probes may also print unrelated diagnostic
+/// lines before it).
+fn last_json_line(stdout: &[u8]) -> Option<serde_json::Value> {
+    let text = String::from_utf8_lossy(stdout);
+    text.lines()
+        .rev()
+        .find_map(|line| serde_json::from_str::<serde_json::Value>(line.trim()).ok())
+}
+
+/// Redact volatile fields from `value` by JSON pointer, then scrub any
+/// remaining string that looks like an absolute temp path or a timestamp.
+fn redact_snapshot_value(mut value: serde_json::Value) -> serde_json::Value {
+    for pointer in SNAPSHOT_REDACT_POINTERS {
+        if let Some(slot) = value.pointer_mut(pointer) {
+            *slot = serde_json::Value::String(SNAPSHOT_REDACTED.to_string());
+        }
+    }
+    redact_volatile_strings(&mut value);
+    value
+}
+
+fn redact_volatile_strings(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::String(text) => {
+            if looks_like_temp_path(text) || looks_like_timestamp(text) {
+                *text = SNAPSHOT_REDACTED.to_string();
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                redact_volatile_strings(item);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for (_, item) in map.iter_mut() {
+                redact_volatile_strings(item);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn looks_like_temp_path(text: &str) -> bool {
+    text.starts_with("/tmp/") || text.starts_with("/var/folders/") || text.contains("/T/tmp")
+}
+
+fn looks_like_timestamp(text: &str) -> bool {
+    Regex::new(r"^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}")
+        .map(|re| re.is_match(text))
+        .unwrap_or(false)
+}
+
+/// Compare a golden line against an actual line, treating a `[..]` placeholder
+/// in `golden` as a wildcard matching any substring (cargo-test-support's
+/// `compare.rs` convention).
+fn line_matches(golden: &str, actual: &str) -> bool {
+    if !golden.contains("[..]") {
+        return golden == actual;
+    }
+
+    let segments: Vec<&str> = golden.split("[..]").collect();
+    let mut cursor = 0;
+    for (index, segment) in segments.iter().enumerate() {
+        if segment.is_empty() {
+            continue;
+        }
+        if index == 0 {
+            if !actual[cursor..].starts_with(segment) {
+                return false;
+            }
+            cursor += segment.len();
+        } else if index == segments.len() - 1 {
+            return actual[cursor..].ends_with(segment);
+        } else {
+            match actual[cursor..].find(segment) {
+                Some(found) => cursor += found + segment.len(),
+                None => return false,
+            }
+        }
+    }
+    true
+}
+
+/// Compare a golden snapshot against the actual (already-redacted) rendering,
+/// line by line, honoring `[..]` wildcards.
+fn snapshot_matches(golden: &str, actual: &str) -> bool {
+    let golden_lines: Vec<&str> = golden.lines().collect();
+    let actual_lines: Vec<&str> = actual.lines().collect();
+    if golden_lines.len() != actual_lines.len() {
+        return false;
+    }
+    golden_lines
+        .iter()
+        .zip(actual_lines.iter())
+        .all(|(g, a)| line_matches(g, a))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiffOp {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Line-level diff between `golden` and `actual` via a classic LCS table; no
+/// diff crate is available in this tree, so this is a small hand-rolled
+/// O(n*m) dynamic-programming implementation.
+fn diff_lines<'a>(golden: &[&'a str], actual: &[&'a str]) -> Vec<(DiffOp, &'a str)> {
+    let (n, m) = (golden.len(), actual.len());
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if golden[i] == actual[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if golden[i] == actual[j] {
+            ops.push((DiffOp::Equal, golden[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            ops.push((DiffOp::Delete, golden[i]));
+            i += 1;
+        } else {
+            ops.push((DiffOp::Insert, actual[j]));
+            j += 1;
+        }
+    }
+    while i < n {
+        ops.push((DiffOp::Delete, golden[i]));
+        i += 1;
+    }
+    while j < m {
+        ops.push((DiffOp::Insert, actual[j]));
+        j += 1;
+    }
+    ops
+}
+
+/// Render a unified diff (context + `-`/`+` markers) of `golden` vs `actual`,
+/// in the spirit of cargo-test-support's `diff.rs`. Exact-match only; the
+/// `[..]` wildcard only governs pass/fail, not the diagnostic display.
+fn render_unified_diff(golden: &str, actual: &str) -> String {
+    let golden_lines: Vec<&str> = golden.lines().collect();
+    let actual_lines: Vec<&str> = actual.lines().collect();
+    let ops = diff_lines(&golden_lines, &actual_lines);
+
+    let interesting: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, (op, _))| *op != DiffOp::Equal)
+        .map(|(index, _)| index)
+        .collect();
+    if interesting.is_empty() {
+        return String::new();
+    }
+
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for index in interesting {
+        let start = index.saturating_sub(DIFF_CONTEXT);
+        let end = (index + DIFF_CONTEXT + 1).min(ops.len());
+        match ranges.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    let mut rendered = String::new();
+    for (start, end) in ranges {
+        rendered.push_str(&format!("@@ line {} @@\n", start + 1));
+        for (op, line) in &ops[start..end] {
+            let prefix = match op {
+                DiffOp::Equal => ' ',
+                DiffOp::Delete => '-',
+                DiffOp::Insert => '+',
+            };
+            rendered.push(prefix);
+            rendered.push_str(line);
+            rendered.push('\n');
+        }
+    }
+    rendered
+}
+
+/// Compare (or bless) the redacted boundary object against a golden file.
+///
+/// With `bless`, the redacted rendering overwrites `snapshot_path`. Otherwise
+/// the golden file must already exist; a mismatch prints a unified diff to
+/// stderr and returns an error so `main` exits nonzero.
+fn run_snapshot_check(snapshot_path: &Path, bless: bool, stdout_bytes: &[u8]) -> Result<()> {
+    let record =
+        last_json_line(stdout_bytes).context("no JSON boundary object found on probe stdout")?;
+    let redacted = redact_snapshot_value(record);
+    let mut rendered = serde_json::to_string_pretty(&redacted).context("rendering snapshot")?;
+    rendered.push('\n');
+
+    if bless {
+        fs::write(snapshot_path, &rendered)
+            .with_context(|| format!("writing snapshot {}", snapshot_path.display()))?;
+        eprintln!("Blessed snapshot at {}", snapshot_path.display());
+        return Ok(());
+    }
+
+    let golden = fs::read_to_string(snapshot_path).with_context(|| {
+        format!(
+            "reading snapshot {} (run with --bless to create it)",
+            snapshot_path.display()
+        )
+    })?;
+
+    if snapshot_matches(&golden, &rendered) {
+        return Ok(());
+    }
+
+    let diff = render_unified_diff(&golden, &rendered);
+    eprintln!("snapshot mismatch for {}:\n{diff}", snapshot_path.display());
+    bail!(
+        "boundary object does not match snapshot {}",
+        snapshot_path.display()
+    );
+}
+
+fn classify_preflight_error(
+    stderr: &str,
+    kind: &str,
+) -> (&'static str, Option<&'static str>, String) {
+    let lower = stderr.to_ascii_lowercase();
+    if lower.contains("operation not permitted") {
+        (
+            "denied",
+            Some("EPERM"),
+            format!("{kind} preflight denied (operation not permitted)"),
+        )
+    } else if lower.contains("permission denied") {
+        (
+            "denied",
+            Some("EACCES"),
+            format!("{kind} preflight denied (permission denied)"),
+        )
+    } else {
+        ("error", None, format!("{kind} preflight failed"))
+    }
+}
+
+fn extract_probe_var(path: &Path, var: &str) -> Option<String> {
+    let contents = fs::read_to_string(path).ok()?;
+    for line in contents.lines() {
+        let trimmed = line.trim_start();
+        if !trimmed.starts_with(var) {
+            continue;
+        }
+        if let Some(rest) = trimmed.splitn(2, '=').nth(1) {
+            let val = rest
+                .split('#')
+                .next()
+                .unwrap_or("")
+                .trim()
+                .trim_matches(|c| c == '"' || c == '\'');
+            if !val.is_empty() {
+                return Some(val.to_string());
+            }
+        }
+    }
+    None
+}
+
+/// Evaluates a probe's optional `fence_supported = "cfg(...)"` platform
+/// predicate (extracted the same way as `primary_capability_id`) against the
+/// host's [`connectors::host_cfg_map`] using the same evaluator
+/// `connectors::plan_for_mode` already relies on for mode gating. A missing
+/// predicate means "run everywhere"; a malformed expression surfaces as an
+/// error rather than a silent skip.
+fn check_fence_supported(probe_path: &Path) -> Result<bool> {
+    let Some(predicate) = extract_probe_var(probe_path, "fence_supported") else {
+        return Ok(true);
+    };
+    connectors::eval_cfg_predicate(&predicate, &connectors::host_cfg_map())
+        .with_context(|| format!("invalid fence_supported predicate: {predicate}"))
+}
+
+/// Emits a `status = "skipped"` record through `emit-record` (reusing the
+/// `emit_preflight_record` plumbing under a `skip` category) when a probe's
+/// `fence_supported` predicate doesn't match this host, so the run matrix
+/// shows an explicit "skipped on this platform" row instead of a spurious
+/// error.
+fn emit_skip_record(
+    repo_root: &Path,
+    probe_path: &Path,
+    run_mode: &str,
+    predicate: &str,
+) -> Result<serde_json::Value> {
     let emit_record = codex_fence::resolve_helper_binary(repo_root, "emit-record")?;
     let probe_file = probe_path
         .file_name()
@@ -410,21 +1460,244 @@ fn emit_preflight_record(
     let probe_id = probe_file.trim_end_matches(".sh");
     let primary_capability = extract_probe_var(probe_path, "primary_capability_id")
         .unwrap_or_else(|| "cap_fs_read_workspace_tree".to_string());
-    let probe_version = extract_probe_var(probe_path, "probe_version").unwrap_or_else(|| "1".to_string());
-    let (status, errno, message) = classify_preflight_error(stderr);
+    let probe_version =
+        extract_probe_var(probe_path, "probe_version").unwrap_or_else(|| "1".to_string());
+    let message =
+        format!("skipped: fence_supported predicate {predicate} does not match this host");
 
-    let command_str = format!(
-        "codex {} mktemp -d {}",
-        run_mode,
-        target_path.to_string_lossy()
+    let payload = json!({
+        "stdout_snippet": "",
+        "stderr_snippet": "",
+        "raw": {
+            "fence_supported": predicate,
+        }
+    });
+    let operation_args = json!({
+        "skip": true,
+        "fence_supported": predicate,
+        "run_mode": run_mode
+    });
+    let payload_file = write_temp_payload(&payload)?;
+
+    let mut cmd = Command::new(&emit_record);
+    cmd.arg("--run-mode")
+        .arg(run_mode)
+        .arg("--probe-name")
+        .arg(probe_id)
+        .arg("--probe-version")
+        .arg(probe_version)
+        .arg("--primary-capability-id")
+        .arg(primary_capability)
+        .arg("--command")
+        .arg(format!("platform-check {predicate}"))
+        .arg("--category")
+        .arg("skip")
+        .arg("--verb")
+        .arg("platform-check")
+        .arg("--target")
+        .arg(predicate)
+        .arg("--status")
+        .arg("skipped")
+        .arg("--message")
+        .arg(&message)
+        .arg("--raw-exit-code")
+        .arg("0")
+        .arg("--operation-args")
+        .arg(operation_args.to_string())
+        .arg("--payload-file")
+        .arg(payload_file)
+        .arg("--errno")
+        .arg("");
+
+    let output = cmd.output().context("failed to emit skip record")?;
+    io::stdout()
+        .write_all(&output.stdout)
+        .context("forwarding skip record stdout")?;
+    if !output.status.success() {
+        bail!(
+            "emit-record failed for skip record (exit {:?})",
+            output.status.code()
+        );
+    }
+
+    serde_json::from_slice(&output.stdout).context("parsing emitted skip record as JSON")
+}
+
+fn write_temp_payload(value: &serde_json::Value) -> Result<PathBuf> {
+    let mut file = NamedTempFile::new().context("create payload temp file")?;
+    serde_json::to_writer(&mut file, value)?;
+    let (_file, path) = file.keep().context("persist payload temp file")?;
+    Ok(path)
+}
+
+/// Diffable provenance header for a single `fence-run` invocation, written
+/// into `workspace_tmpdir` before the probe is spawned and patched with the
+/// exit/duration fields once it finishes, so downstream tooling has a
+/// complete description of the run context to correlate against the
+/// per-operation records `emit-record` produces along the way.
+#[derive(Debug, Serialize)]
+struct RunManifest {
+    probe_path: String,
+    probe_version: String,
+    primary_capability_id: String,
+    run_mode: String,
+    sandbox_mode: String,
+    workspace_export: Option<String>,
+    platform: String,
+    arch: String,
+    command_program: String,
+    command_args: Vec<String>,
+    codex_present: bool,
+    fence_env: BTreeMap<String, String>,
+}
+
+/// Assembles a [`RunManifest`] from the inputs already resolved by the time
+/// `run_single` is about to spawn the probe. Mirrors the `FENCE_*` env that
+/// [`execute_probe`]/[`execute_probe_traced`] build independently, so the
+/// recorded env matches what the child actually receives.
+fn build_run_manifest(
+    resolved_probe: &Probe,
+    run_mode: &str,
+    sandbox_mode: &OsString,
+    workspace_plan: &WorkspacePlan,
+    platform: &str,
+    command_spec: &CommandSpec,
+) -> RunManifest {
+    let primary_capability = extract_probe_var(&resolved_probe.path, "primary_capability_id")
+        .unwrap_or_else(|| "cap_fs_read_workspace_tree".to_string());
+    let probe_version =
+        extract_probe_var(&resolved_probe.path, "probe_version").unwrap_or_else(|| "1".to_string());
+
+    let mut fence_env = BTreeMap::new();
+    fence_env.insert("FENCE_RUN_MODE".to_string(), run_mode.to_string());
+    fence_env.insert(
+        "FENCE_SANDBOX_MODE".to_string(),
+        sandbox_mode.to_string_lossy().to_string(),
     );
+    if let Some(value) = workspace_plan.export_value.as_ref() {
+        fence_env.insert(
+            "FENCE_WORKSPACE_ROOT".to_string(),
+            value.to_string_lossy().to_string(),
+        );
+    }
+
+    RunManifest {
+        probe_path: resolved_probe.path.to_string_lossy().to_string(),
+        probe_version,
+        primary_capability_id: primary_capability,
+        run_mode: run_mode.to_string(),
+        sandbox_mode: sandbox_mode.to_string_lossy().to_string(),
+        workspace_export: workspace_plan
+            .export_value
+            .as_ref()
+            .map(|v| v.to_string_lossy().to_string()),
+        platform: platform.to_string(),
+        arch: env::consts::ARCH.to_string(),
+        command_program: command_spec.program.to_string_lossy().to_string(),
+        command_args: command_spec
+            .args
+            .iter()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect(),
+        codex_present: codex_present(),
+        fence_env,
+    }
+}
+
+/// Writes `manifest` into `workspace_tmpdir`, named after the probe id and
+/// pid the same way [`sink_trace_ops`] names its sink file, so the two
+/// per-run artifacts sort together.
+fn write_run_manifest(
+    workspace_tmpdir: &Path,
+    probe_id: &str,
+    manifest: &RunManifest,
+) -> Result<PathBuf> {
+    let path = workspace_tmpdir.join(format!(
+        "fence-run-manifest-{probe_id}-{}.json",
+        std::process::id()
+    ));
+    let file = fs::File::create(&path)
+        .with_context(|| format!("creating run manifest {}", path.display()))?;
+    serde_json::to_writer_pretty(file, manifest).context("serializing run manifest")?;
+    Ok(path)
+}
+
+/// Patches the on-disk run manifest with the probe's exit disposition and
+/// wall-clock duration. Called whether the probe succeeded or not, including
+/// just before [`stdout_or_exit`] forwards a nonzero exit code as our own, so
+/// the manifest is never left without a completion record.
+fn record_run_completion(
+    manifest_path: &Path,
+    status: &std::process::ExitStatus,
+    duration_ms: u128,
+) -> Result<()> {
+    let raw = fs::read_to_string(manifest_path)
+        .with_context(|| format!("reading run manifest {}", manifest_path.display()))?;
+    let mut value: serde_json::Value =
+        serde_json::from_str(&raw).context("parsing run manifest as JSON")?;
+    if let serde_json::Value::Object(map) = &mut value {
+        map.insert("exit_code".to_string(), json!(status.code()));
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::ExitStatusExt;
+            map.insert("signal".to_string(), json!(status.signal()));
+        }
+        map.insert("duration_ms".to_string(), json!(duration_ms));
+    }
+    let file = fs::File::create(manifest_path)
+        .with_context(|| format!("rewriting run manifest {}", manifest_path.display()))?;
+    serde_json::to_writer_pretty(file, &value).context("serializing updated run manifest")
+}
+
+fn emit_preflight_record(
+    repo_root: &Path,
+    probe_path: &Path,
+    run_mode: &str,
+    target_path: &Path,
+    exit_code: i32,
+    stderr: &str,
+) -> Result<serde_json::Value> {
+    let emit_record = codex_fence::resolve_helper_binary(repo_root, "emit-record")?;
+    let probe_file = probe_path
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown");
+    let probe_id = probe_file.trim_end_matches(".sh");
+    let primary_capability = extract_probe_var(probe_path, "primary_capability_id")
+        .unwrap_or_else(|| "cap_fs_read_workspace_tree".to_string());
+    let probe_version =
+        extract_probe_var(probe_path, "probe_version").unwrap_or_else(|| "1".to_string());
+    let (status, errno, message) = classify_preflight_error(
+        stderr,
+        if run_mode == "container" {
+            "container"
+        } else {
+            "codex sandbox"
+        },
+    );
+
+    let (command_str, preflight_kind) = if run_mode == "container" {
+        (
+            format!("container mktemp -d {}", target_path.to_string_lossy()),
+            "container_tmp",
+        )
+    } else {
+        (
+            format!(
+                "codex {} mktemp -d {}",
+                run_mode,
+                target_path.to_string_lossy()
+            ),
+            "codex_tmp",
+        )
+    };
 
     let payload = json!({
         "stdout_snippet": "",
         "stderr_snippet": stderr,
         "raw": {
             "preflight_target": target_path.to_string_lossy(),
-            "preflight_kind": "codex_tmp",
+            "preflight_kind": preflight_kind,
             "stderr": stderr,
             "exit_code": exit_code
         }
@@ -472,12 +1745,130 @@ fn emit_preflight_record(
         cmd.arg("--errno").arg("");
     }
 
-    let status_out = cmd.status().context("failed to emit preflight record")?;
-    if !status_out.success() {
-        bail!("emit-record failed for preflight (exit {:?})", status_out.code());
+    let output = cmd.output().context("failed to emit preflight record")?;
+    io::stdout()
+        .write_all(&output.stdout)
+        .context("forwarding preflight record stdout")?;
+    if !output.status.success() {
+        bail!(
+            "emit-record failed for preflight (exit {:?})",
+            output.status.code()
+        );
     }
 
-    Ok(())
+    serde_json::from_slice(&output.stdout).context("parsing emitted preflight record as JSON")
+}
+
+/// Writes `trace.ops` as NDJSON into a file under `workspace_tmpdir` (the
+/// `--trace` sink must live inside the probe's allowed workspace tree, same
+/// as any other temp file it writes) and re-parses it back with
+/// [`parse_trace_ops_ndjson`], which tolerates a truncated trailing record.
+/// `capture_direct` already hands back `trace.ops` in memory, but emitting
+/// through a file and re-reading it is what actually exercises that
+/// tolerant-parsing contract rather than merely asserting it in the abstract.
+fn sink_trace_ops(
+    workspace_tmpdir: &Path,
+    probe_id: &str,
+    trace: &codex_fence::ExecutionTrace,
+) -> Result<Vec<TraceOp>> {
+    let sink_path = workspace_tmpdir.join(format!(
+        "fence-trace-{probe_id}-{}.ndjson",
+        std::process::id()
+    ));
+    let mut file = fs::File::create(&sink_path)
+        .with_context(|| format!("creating trace sink {}", sink_path.display()))?;
+    for op in &trace.ops {
+        serde_json::to_writer(&mut file, op).context("serializing trace op")?;
+        file.write_all(b"\n").context("writing trace sink")?;
+    }
+    let raw = fs::read_to_string(&sink_path)
+        .with_context(|| format!("reading trace sink {}", sink_path.display()))?;
+    Ok(parse_trace_ops_ndjson(&raw))
+}
+
+/// Emits one `emit-record` call per traced operation, classifying its status
+/// the same way [`classify_preflight_error`] classifies a preflight failure,
+/// just from the syscall's raw return value via [`classify_result`] instead
+/// of matching on stderr text.
+fn emit_trace_record(
+    repo_root: &Path,
+    probe_path: &Path,
+    run_mode: &str,
+    op: &TraceOp,
+) -> Result<serde_json::Value> {
+    let emit_record = codex_fence::resolve_helper_binary(repo_root, "emit-record")?;
+    let probe_file = probe_path
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown");
+    let probe_id = probe_file.trim_end_matches(".sh");
+    let primary_capability = extract_probe_var(probe_path, "primary_capability_id")
+        .unwrap_or_else(|| "cap_fs_read_workspace_tree".to_string());
+    let probe_version =
+        extract_probe_var(probe_path, "probe_version").unwrap_or_else(|| "1".to_string());
+
+    let target = op.target();
+    let (status, errno) = match op.result() {
+        Some(result) => classify_result(result),
+        None => ("success", None),
+    };
+    let message = format!("traced {} {}", op.verb(), target);
+    let raw_exit_code = op.result().unwrap_or(0);
+
+    let payload = json!({
+        "stdout_snippet": "",
+        "stderr_snippet": "",
+        "raw": op,
+    });
+    let operation_args = json!({
+        "trace": true,
+        "target": target,
+        "run_mode": run_mode
+    });
+    let payload_file = write_temp_payload(&payload)?;
+
+    let mut cmd = Command::new(&emit_record);
+    cmd.arg("--run-mode")
+        .arg(run_mode)
+        .arg("--probe-name")
+        .arg(probe_id)
+        .arg("--probe-version")
+        .arg(probe_version)
+        .arg("--primary-capability-id")
+        .arg(primary_capability)
+        .arg("--command")
+        .arg(format!("trace {} {}", op.verb(), target))
+        .arg("--category")
+        .arg("trace")
+        .arg("--verb")
+        .arg(op.verb())
+        .arg("--target")
+        .arg(&target)
+        .arg("--status")
+        .arg(status)
+        .arg("--message")
+        .arg(&message)
+        .arg("--raw-exit-code")
+        .arg(raw_exit_code.to_string())
+        .arg("--operation-args")
+        .arg(operation_args.to_string())
+        .arg("--payload-file")
+        .arg(payload_file)
+        .arg("--errno")
+        .arg(errno.unwrap_or_default());
+
+    let output = cmd.output().context("failed to emit trace record")?;
+    io::stdout()
+        .write_all(&output.stdout)
+        .context("forwarding trace record stdout")?;
+    if !output.status.success() {
+        bail!(
+            "emit-record failed for trace op (exit {:?})",
+            output.status.code()
+        );
+    }
+
+    serde_json::from_slice(&output.stdout).context("parsing emitted trace record as JSON")
 }
 
 fn run_codex_preflight(
@@ -486,54 +1877,204 @@ fn run_codex_preflight(
     platform: &str,
     workspace_tmpdir: &Path,
     probe_path: &Path,
-) -> Result<bool> {
+) -> Result<Option<serde_json::Value>> {
+    if run_mode == "container" {
+        return run_container_preflight(repo_root, workspace_tmpdir, probe_path);
+    }
+
     // Detect hosts that block codex sandbox writes before invoking the probe.
     // When blocked, emit a boundary object describing the denial so matrix runs
     // keep producing output for the affected mode.
+    if !codex_mode(run_mode) {
+        return Ok(None);
+    }
     ensure_codex_available()?;
     let target = workspace_tmpdir.join("codex-preflight.XXXXXX");
-    let platform_target = platform_target(platform)?;
-
-    let mut args: Vec<OsString> = Vec::new();
-    match run_mode {
-        "codex-sandbox" => {
-            args.push(OsString::from("sandbox"));
-            args.push(OsString::from(platform_target));
-            args.push(OsString::from("--full-auto"));
-        }
-        "codex-full" => {
-            args.push(OsString::from("--dangerously-bypass-approvals-and-sandbox"));
-            args.push(OsString::from("sandbox"));
-            args.push(OsString::from(platform_target));
-        }
-        _ => return Ok(false),
-    }
-    args.push(OsString::from("--"));
-    args.push(OsString::from("/usr/bin/mktemp"));
-    args.push(OsString::from("-d"));
-    args.push(OsString::from(
-        target.as_os_str().to_string_lossy().to_string(),
-    ));
+    let target_platform = platform_target(platform)?;
+    let args = codex_preflight_args(run_mode, target_platform, &target)?;
 
     let mut cmd = Command::new("codex");
     cmd.args(&args);
     let output = cmd.output().context("codex preflight failed to spawn")?;
 
     if output.status.success() {
-        return Ok(false);
+        return Ok(None);
     }
 
     let stderr = String::from_utf8_lossy(&output.stderr).to_string();
     let code = output.status.code().unwrap_or(-1);
-    emit_preflight_record(repo_root, probe_path, run_mode, &target, code, &stderr)?;
-    Ok(true)
+    let record = emit_preflight_record(repo_root, probe_path, run_mode, &target, code, &stderr)?;
+    Ok(Some(record))
+}
+
+/// Parallel to [`run_codex_preflight`]'s codex-sandbox check: runs `mktemp -d`
+/// inside a throwaway container using the same runtime/image/isolation flags
+/// `container` mode itself uses, so an unreachable daemon or a read-only
+/// mount is caught before the probe runs, and the matrix still produces a
+/// denial record for this mode instead of a bare connector error.
+fn run_container_preflight(
+    repo_root: &Path,
+    workspace_tmpdir: &Path,
+    probe_path: &Path,
+) -> Result<Option<serde_json::Value>> {
+    let target = workspace_tmpdir.join("container-preflight.XXXXXX");
+    let Some(spec) = connectors::container_preflight_command(workspace_tmpdir, &target) else {
+        return Ok(None);
+    };
+
+    let mut cmd = Command::new(&spec.program);
+    cmd.args(&spec.args);
+    let output = cmd
+        .output()
+        .context("container preflight failed to spawn")?;
+
+    if output.status.success() {
+        return Ok(None);
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    let code = output.status.code().unwrap_or(-1);
+    let record = emit_preflight_record(repo_root, probe_path, "container", &target, code, &stderr)?;
+    Ok(Some(record))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use codex_fence::{
+        CapabilityCategory, CapabilityContext, CapabilityId, CapabilityLayer, CapabilitySnapshot,
+    };
     use std::sync::atomic::{AtomicUsize, Ordering};
 
+    #[test]
+    fn parse_probe_expectations_reads_per_mode_and_message_directives() {
+        let workspace = TempWorkspace::new();
+        let script = workspace.root.join("probe.sh");
+        fs::write(
+            &script,
+            "#!/usr/bin/env bash\n# fence-expect baseline=success\n# fence-expect codex-sandbox=denied\n# fence-expect-message ^blocked\nexit 0\n",
+        )
+        .unwrap();
+
+        let expectations = parse_probe_expectations(&script).unwrap();
+        assert_eq!(
+            expectations.per_mode.get("baseline").map(String::as_str),
+            Some("success")
+        );
+        assert_eq!(
+            expectations
+                .per_mode
+                .get("codex-sandbox")
+                .map(String::as_str),
+            Some("denied")
+        );
+        assert!(expectations.per_mode.get("codex-full").is_none());
+        assert!(expectations
+            .message_pattern
+            .as_ref()
+            .unwrap()
+            .is_match("blocked by sandbox"));
+    }
+
+    #[test]
+    fn parse_probe_expectations_rejects_malformed_directive() {
+        let workspace = TempWorkspace::new();
+        let script = workspace.root.join("probe.sh");
+        fs::write(
+            &script,
+            "#!/usr/bin/env bash\n# fence-expect baseline\nexit 0\n",
+        )
+        .unwrap();
+
+        assert!(parse_probe_expectations(&script).is_err());
+    }
+
+    #[test]
+    fn check_expectations_flags_mismatched_result() {
+        let mut expectations = ProbeExpectations::default();
+        expectations
+            .per_mode
+            .insert("baseline".to_string(), "denied".to_string());
+        let record = minimal_record();
+
+        let err = check_expectations(&expectations, "baseline", &record, b"").unwrap_err();
+        assert!(err.to_string().contains("expected result 'denied'"));
+        assert!(err.to_string().contains("observed 'success'"));
+    }
+
+    #[test]
+    fn check_expectations_passes_modes_with_no_directive() {
+        let expectations = ProbeExpectations::default();
+        let record = minimal_record();
+        assert!(check_expectations(&expectations, "baseline", &record, b"").is_ok());
+    }
+
+    #[test]
+    fn check_expectations_matches_message_pattern_against_stderr() {
+        let mut expectations = ProbeExpectations::default();
+        expectations.message_pattern = Some(Regex::new("^blocked").unwrap());
+        let mut record = minimal_record();
+        record.result.message = None;
+
+        assert!(
+            check_expectations(&expectations, "baseline", &record, b"blocked: no access").is_ok()
+        );
+        assert!(
+            check_expectations(&expectations, "baseline", &record, b"unrelated failure").is_err()
+        );
+    }
+
+    fn minimal_record() -> BoundaryObject {
+        BoundaryObject {
+            schema_version: "cfbo-v1".to_string(),
+            schema_key: None,
+            capabilities_schema_version: None,
+            stack: codex_fence::StackInfo {
+                sandbox_mode: Some("baseline".to_string()),
+                container_image: None,
+                os: "Darwin".to_string(),
+            },
+            probe: codex_fence::ProbeInfo {
+                id: "sample_probe".to_string(),
+                version: "1".to_string(),
+                primary_capability_id: CapabilityId("cap_sample".to_string()),
+                secondary_capability_ids: Vec::new(),
+            },
+            run: codex_fence::RunInfo {
+                mode: "baseline".to_string(),
+                workspace_root: Some("/tmp".to_string()),
+                command: "echo sample".to_string(),
+            },
+            operation: codex_fence::OperationInfo {
+                category: "fs".to_string(),
+                verb: "read".to_string(),
+                target: "/tmp/sample".to_string(),
+                args: serde_json::json!({}),
+            },
+            result: codex_fence::ResultInfo {
+                observed_result: "success".to_string(),
+                raw_exit_code: Some(0),
+                errno: None,
+                message: Some("sample message".to_string()),
+                error_detail: None,
+            },
+            payload: codex_fence::Payload {
+                stdout_snippet: Some("hello".to_string()),
+                stderr_snippet: None,
+                raw: serde_json::json!({}),
+            },
+            capability_context: CapabilityContext {
+                primary: CapabilitySnapshot {
+                    id: CapabilityId("cap_sample".to_string()),
+                    category: CapabilityCategory::Filesystem,
+                    layer: CapabilityLayer::OsSandbox,
+                },
+                secondary: Vec::new(),
+                resolved_grant: None,
+            },
+        }
+    }
+
     #[test]
     fn resolve_probe_prefers_probes_dir() {
         let workspace = TempWorkspace::new();
@@ -554,29 +2095,53 @@ mod tests {
 
     #[test]
     fn workspace_override_skip_export() {
-        let plan = workspace_plan_from_override(WorkspaceOverride::SkipExport);
+        let workspace = TempWorkspace::new();
+        let policy = ContainmentPolicy::repo_root(&workspace.root);
+        let plan = workspace_plan_from_override(WorkspaceOverride::SkipExport, &policy);
         assert!(plan.export_value.is_none());
+        assert!(plan.containment_error.is_none());
     }
 
     #[test]
     fn workspace_override_canonicalizes_path() {
         let workspace = TempWorkspace::new();
-        let plan = workspace_plan_from_override(WorkspaceOverride::UsePath(
-            workspace.root.join("probes").into_os_string(),
-        ));
-        assert!(
-            plan.export_value
-                .unwrap()
-                .to_string_lossy()
-                .contains("probes")
+        fs::create_dir_all(workspace.root.join("probes")).unwrap();
+        let policy = ContainmentPolicy::repo_root(&workspace.root);
+        let plan = workspace_plan_from_override(
+            WorkspaceOverride::UsePath(workspace.root.join("probes").into_os_string()),
+            &policy,
         );
+        assert!(plan
+            .export_value
+            .unwrap()
+            .to_string_lossy()
+            .contains("probes"));
+    }
+
+    #[test]
+    fn workspace_override_rejects_escaping_path() {
+        let workspace = TempWorkspace::new();
+        let outside = TempWorkspace::new();
+        let policy = ContainmentPolicy::repo_root(&workspace.root);
+        let plan = workspace_plan_from_override(
+            WorkspaceOverride::UsePath(outside.root.as_os_str().to_os_string()),
+            &policy,
+        );
+        assert!(plan.export_value.is_none());
+        assert!(plan.containment_error.is_some());
     }
 
     #[test]
     fn workspace_tmpdir_prefers_workspace_tree() {
         let workspace = TempWorkspace::new();
         let canonical_root = canonicalize_path(&workspace.root);
-        let tmpdir = workspace_tmpdir(&canonical_root).expect("tmpdir");
+        let policy = ContainmentPolicy::repo_root(&canonical_root);
+        let plan = WorkspacePlan {
+            export_value: Some(canonical_root.as_os_str().to_os_string()),
+            containment_error: None,
+        };
+        let tmpdir_plan = workspace_tmpdir_plan(&plan, &canonical_root, &policy);
+        let tmpdir = tmpdir_plan.path.expect("tmpdir");
         assert!(tmpdir.starts_with(&canonical_root));
         assert!(tmpdir.ends_with("tmp"));
         assert!(tmpdir.is_dir());
@@ -585,7 +2150,7 @@ mod tests {
     #[test]
     fn classify_preflight_recognizes_permission_denied() {
         let (status, errno, message) =
-            classify_preflight_error("mktemp: Operation not permitted\n");
+            classify_preflight_error("mktemp: Operation not permitted\n", "codex sandbox");
         assert_eq!(status, "denied");
         assert_eq!(errno, Some("EPERM"));
         assert!(message.contains("preflight"));
@@ -593,11 +2158,155 @@ mod tests {
 
     #[test]
     fn classify_preflight_defaults_to_error() {
-        let (status, errno, _) = classify_preflight_error("unexpected failure");
+        let (status, errno, _) = classify_preflight_error("unexpected failure", "container");
         assert_eq!(status, "error");
         assert!(errno.is_none());
     }
 
+    #[test]
+    fn redact_snapshot_value_masks_workspace_root_and_volatile_strings() {
+        let mut record = serde_json::to_value(minimal_record()).unwrap();
+        record["payload"]["raw"] = serde_json::json!({
+            "temp_path": "/tmp/codex-fence-test-1-2",
+            "timestamp": "2026-07-26T12:00:00Z",
+            "stable": "unchanged",
+        });
+
+        let redacted = redact_snapshot_value(record);
+        assert_eq!(redacted["run"]["workspace_root"], SNAPSHOT_REDACTED);
+        assert_eq!(redacted["payload"]["raw"]["temp_path"], SNAPSHOT_REDACTED);
+        assert_eq!(redacted["payload"]["raw"]["timestamp"], SNAPSHOT_REDACTED);
+        assert_eq!(redacted["payload"]["raw"]["stable"], "unchanged");
+    }
+
+    #[test]
+    fn line_matches_honors_wildcard_placeholder() {
+        assert!(line_matches(
+            "  \"target\": \"[..]\",",
+            "  \"target\": \"/tmp/abc\","
+        ));
+        assert!(line_matches("prefix[..]suffix", "prefix-anything-suffix"));
+        assert!(!line_matches("prefix[..]suffix", "nope"));
+        assert!(!line_matches("exact", "different"));
+    }
+
+    #[test]
+    fn snapshot_matches_requires_equal_line_counts() {
+        assert!(!snapshot_matches("a\nb\n", "a\n"));
+        assert!(snapshot_matches("a\n[..]\n", "a\nanything\n"));
+    }
+
+    #[test]
+    fn render_unified_diff_reports_changed_lines() {
+        let diff = render_unified_diff("a\nb\nc\n", "a\nX\nc\n");
+        assert!(diff.contains("-b"));
+        assert!(diff.contains("+X"));
+        assert!(diff.contains(" a"));
+    }
+
+    #[test]
+    fn run_snapshot_check_blesses_then_compares() {
+        let workspace = TempWorkspace::new();
+        let snapshot_path = workspace.root.join("probe.snapshot.json");
+        let record = minimal_record();
+        let stdout = format!("{}\n", serde_json::to_string(&record).unwrap());
+
+        run_snapshot_check(&snapshot_path, true, stdout.as_bytes()).unwrap();
+        assert!(snapshot_path.is_file());
+
+        run_snapshot_check(&snapshot_path, false, stdout.as_bytes()).unwrap();
+
+        let mut changed_record = record;
+        changed_record.result.observed_result = "denied".to_string();
+        let changed_stdout = format!("{}\n", serde_json::to_string(&changed_record).unwrap());
+        assert!(run_snapshot_check(&snapshot_path, false, changed_stdout.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn invocation_parse_from_recognizes_matrix_subcommand() {
+        let args = ["matrix", "example"].into_iter().map(String::from);
+        let invocation = Invocation::parse_from(args).unwrap();
+        match invocation {
+            Invocation::Matrix(args) => assert_eq!(args.probe_name, "example"),
+            Invocation::Single(_) | Invocation::ProbeMode(_) => {
+                panic!("expected a matrix invocation")
+            }
+        }
+    }
+
+    #[test]
+    fn invocation_parse_from_keeps_single_mode_invocation() {
+        let args = ["baseline", "example"].into_iter().map(String::from);
+        let invocation = Invocation::parse_from(args).unwrap();
+        match invocation {
+            Invocation::Single(args) => {
+                assert_eq!(args.run_mode, "baseline");
+                assert_eq!(args.probe_name, "example");
+            }
+            Invocation::Matrix(_) | Invocation::ProbeMode(_) => {
+                panic!("expected a single-mode invocation")
+            }
+        }
+    }
+
+    #[test]
+    fn invocation_parse_from_honors_workspace_root_before_matrix() {
+        let args = ["--workspace-root", "/tmp", "matrix", "example"]
+            .into_iter()
+            .map(String::from);
+        let invocation = Invocation::parse_from(args).unwrap();
+        match invocation {
+            Invocation::Matrix(args) => assert!(args.workspace_override.is_some()),
+            Invocation::Single(_) | Invocation::ProbeMode(_) => {
+                panic!("expected a matrix invocation")
+            }
+        }
+    }
+
+    fn outcome(mode: &str, observed_result: Option<&str>) -> MatrixModeOutcome {
+        MatrixModeOutcome {
+            mode: mode.to_string(),
+            observed_result: observed_result.map(str::to_string),
+            errno: None,
+            message: None,
+            capability_id: Some("cap_sample".to_string()),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn capability_deltas_reports_modes_that_diverge_from_baseline() {
+        let outcomes = vec![
+            outcome("baseline", Some("success")),
+            outcome("codex-sandbox", Some("denied")),
+            outcome("codex-full", Some("success")),
+        ];
+
+        let deltas = capability_deltas(&outcomes);
+        assert_eq!(deltas.len(), 1);
+        assert_eq!(deltas[0].mode, "codex-sandbox");
+        assert_eq!(deltas[0].baseline_result, "success");
+        assert_eq!(deltas[0].mode_result, "denied");
+    }
+
+    #[test]
+    fn capability_deltas_omits_modes_with_missing_results() {
+        let outcomes = vec![
+            outcome("baseline", Some("success")),
+            outcome("codex-sandbox", None),
+        ];
+        assert!(capability_deltas(&outcomes).is_empty());
+    }
+
+    #[test]
+    fn matrix_outcome_from_value_extracts_expected_fields() {
+        let record = serde_json::to_value(minimal_record()).unwrap();
+        let outcome = matrix_outcome_from_value("baseline", &record);
+        assert_eq!(outcome.observed_result.as_deref(), Some("success"));
+        assert_eq!(outcome.capability_id.as_deref(), Some("cap_sample"));
+        assert!(outcome.error.is_none());
+    }
+
     struct TempWorkspace {
         root: PathBuf,
     }
@@ -2,12 +2,19 @@
 //!
 //! Invokes `tools/validate_contract_gate.sh` from the detected repo root and
 //! proxies its exit status so CI and local workflows can rely on a single Rust
-//! binary instead of the shell shim.
+//! binary instead of the shell shim. `--report junit --output PATH` captures
+//! the script's `[PASS]`/`[FAIL] <probe id>: <message>` lines and renders
+//! them as a JUnit `<testsuite>` via [`codex_fence::junit`] instead of only
+//! leaving a human transcript on stdout, so CI can ingest per-probe results
+//! directly.
 
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Context, Result, anyhow, bail};
 use codex_fence::find_repo_root;
+use codex_fence::junit::{JunitCase, JunitOutcome, JunitSuite, render_junit_xml};
 use std::env;
-use std::process::{Command, Stdio};
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
 
 fn main() {
     if let Err(err) = run() {
@@ -18,10 +25,39 @@ fn main() {
 
 fn run() -> Result<()> {
     let repo_root = find_repo_root()?;
-    let mut args: Vec<String> = env::args().skip(1).collect();
-    let has_probe_flag = args.iter().any(|arg| arg == "--probe");
-    let mut script_args: Vec<String> = Vec::new();
+    let raw_args: Vec<String> = env::args().skip(1).collect();
+    let has_probe_flag = raw_args.iter().any(|arg| arg == "--probe");
+
+    let mut report_junit = false;
+    let mut output_path: Option<String> = None;
+    let mut args: Vec<String> = Vec::new();
+    let mut iter = raw_args.into_iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--report" => {
+                let format = iter
+                    .next()
+                    .ok_or_else(|| anyhow!("--report requires a value (expected 'junit')"))?;
+                if format != "junit" {
+                    bail!("unknown report format '{format}' (expected 'junit')");
+                }
+                report_junit = true;
+            }
+            "--output" => {
+                let path = iter
+                    .next()
+                    .ok_or_else(|| anyhow!("--output requires a PATH"))?;
+                output_path = Some(path);
+            }
+            other => args.push(other.to_string()),
+        }
+    }
+
+    if report_junit && output_path.is_none() {
+        bail!("--report junit requires --output PATH");
+    }
 
+    let mut script_args: Vec<String> = Vec::new();
     if !args.is_empty() && !args[0].starts_with('-') {
         if has_probe_flag {
             return Err(anyhow!(
@@ -35,6 +71,29 @@ fn run() -> Result<()> {
 
     script_args.extend(args.into_iter());
     let script = repo_root.join("tools/validate_contract_gate.sh");
+
+    if let Some(output_path) = output_path {
+        let output = run_script_captured(&repo_root, &script, &script_args)?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        print!("{stdout}");
+        eprint!("{stderr}");
+
+        let suite = parse_gate_transcript(&stdout, &stderr);
+        let xml = render_junit_xml(&[suite]);
+        fs::write(&output_path, xml)
+            .with_context(|| format!("writing JUnit report to {output_path}"))?;
+
+        match output.status.code() {
+            Some(0) => return Ok(()),
+            Some(code) => std::process::exit(code),
+            None => {
+                eprintln!("static probe contract terminated by signal");
+                std::process::exit(1);
+            }
+        }
+    }
+
     let mut cmd = Command::new(&script);
     cmd.current_dir(&repo_root)
         .env("FENCE_TEST_FORCE_SCRIPT", "1")
@@ -55,3 +114,57 @@ fn run() -> Result<()> {
         }
     }
 }
+
+fn run_script_captured(repo_root: &Path, script: &Path, script_args: &[String]) -> Result<Output> {
+    let mut cmd = Command::new(script);
+    cmd.current_dir(repo_root)
+        .env("FENCE_TEST_FORCE_SCRIPT", "1")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    cmd.args(script_args);
+    cmd.output()
+        .with_context(|| format!("Failed to execute {}", script.display()))
+}
+
+/// Parse `[PASS] <probe id>` / `[FAIL] <probe id>: <message>` lines from the
+/// static contract gate's combined transcript into one [`JunitSuite`] of
+/// per-probe [`JunitCase`]s. Falls back to a single synthetic case wrapping
+/// the raw transcript when no such lines are found, so a script crash still
+/// produces a report instead of an empty one.
+fn parse_gate_transcript(stdout: &str, stderr: &str) -> JunitSuite {
+    let mut cases = Vec::new();
+    for line in stdout.lines() {
+        if let Some(rest) = line.strip_prefix("[PASS] ") {
+            cases.push(JunitCase {
+                probe_id: rest.trim().to_string(),
+                classname: None,
+                outcome: JunitOutcome::Pass,
+            });
+        } else if let Some(rest) = line.strip_prefix("[FAIL] ") {
+            let (probe_id, message) = rest.split_once(':').unwrap_or((rest, ""));
+            cases.push(JunitCase {
+                probe_id: probe_id.trim().to_string(),
+                classname: None,
+                outcome: JunitOutcome::Failure(message.trim().to_string()),
+            });
+        }
+    }
+
+    if cases.is_empty() {
+        cases.push(JunitCase {
+            probe_id: "static-contract-gate".to_string(),
+            classname: None,
+            outcome: if stderr.trim().is_empty() {
+                JunitOutcome::Pass
+            } else {
+                JunitOutcome::Failure(stderr.trim().to_string())
+            },
+        });
+    }
+
+    JunitSuite {
+        name: "static-contract".to_string(),
+        cases,
+    }
+}
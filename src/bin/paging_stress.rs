@@ -6,30 +6,57 @@
 //! - 1: invalid arguments
 //! - 2: internal error (allocation or runtime failure)
 //! - 3: self-enforced timeout reached
+//! - 4: `--expect-major-faults` was supplied but the observed major-fault
+//!   delta fell short of it
 //!
 //! CLI:
 //! - `--megabytes <N>` — total allocation size in MiB (default: 8).
 //! - `--passes <N>` — number of full sweeps to perform (default: 1).
-//! - `--pattern <sequential|random>` — page access order (default: sequential).
+//! - `--pattern <sequential|random|working-set>` — page access order
+//!   (default: sequential).
 //! - `--max-seconds <N>` — optional self-enforced timeout.
+//! - `--threads <N>` — split the allocation into N contiguous shards, each
+//!   swept concurrently by its own thread (default: 1). Not supported with
+//!   `--pattern working-set`.
+//! - `--evict-fraction <0.0..=1.0>` — with `--pattern working-set`, the
+//!   fraction of the mapped range to `madvise(MADV_DONTNEED)` between passes
+//!   so it faults back in on the next sweep (default: 1.0).
+//! - `--expect-major-faults <N>` — with `--pattern working-set`, fail with
+//!   exit code 4 if the `ru_majflt` delta over the run is below `N`.
 //! - `--help` — print usage.
 //!
 //! Probes invoke this helper with explicit arguments and interpret only the
 //! exit code so the probe contract (single JSON record, no stdout) stays
-//! intact.
+//! intact. With `--threads > 1`, the shared deadline is enforced cooperatively:
+//! the first worker to observe it stops itself and flips a shared
+//! [`AtomicBool`] the other workers check at the same cadence, so the whole
+//! run still honors `--max-seconds` as a single wall-clock budget.
+//!
+//! `--pattern sequential`/`random` only ever touch a resident `Vec`, so after
+//! the first pass the kernel keeps the whole range resident and no real
+//! paging occurs. `--pattern working-set` maps its range with `mmap` instead
+//! and evicts part of it between passes, so probes asserting on genuine
+//! paging (not just cache warming) have a mode that actually produces major
+//! faults. It is unix-only; selecting it on other targets is an invalid
+//! argument.
 
 use std::io::{self, Write};
 use std::process::ExitCode;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
 use std::time::{Duration, Instant};
 
 const DEFAULT_MEGABYTES: usize = 8;
 const DEFAULT_PASSES: u64 = 1;
+const DEFAULT_THREADS: usize = 1;
+const DEFAULT_EVICT_FRACTION: f64 = 1.0;
 const DEADLINE_CHECK_INTERVAL: usize = 256;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum Pattern {
     Sequential,
     Random,
+    WorkingSet,
 }
 
 #[derive(Clone, Debug)]
@@ -38,6 +65,9 @@ struct Config {
     passes: u64,
     pattern: Pattern,
     max_seconds: Option<u64>,
+    threads: usize,
+    evict_fraction: Option<f64>,
+    expect_major_faults: Option<u64>,
 }
 
 enum ParseOutcome {
@@ -48,6 +78,7 @@ enum ParseOutcome {
 enum RunError {
     Timeout { elapsed: Duration, limit: Duration },
     Internal(String),
+    FaultExpectationNotMet { expected: u64, observed: u64 },
 }
 
 fn main() -> ExitCode {
@@ -73,6 +104,12 @@ fn main() -> ExitCode {
                 eprintln!("paging-stress: internal error: {msg}");
                 ExitCode::from(2)
             }
+            Err(RunError::FaultExpectationNotMet { expected, observed }) => {
+                eprintln!(
+                    "paging-stress: expected at least {expected} major faults, observed {observed}"
+                );
+                ExitCode::from(4)
+            }
         },
         Err(message) => {
             eprintln!("paging-stress: {message}");
@@ -91,6 +128,9 @@ where
     let mut passes = DEFAULT_PASSES;
     let mut pattern = Pattern::Sequential;
     let mut max_seconds = None;
+    let mut threads = DEFAULT_THREADS;
+    let mut evict_fraction = None;
+    let mut expect_major_faults = None;
 
     let mut iter = args.peekable();
     while let Some(arg) = iter.next() {
@@ -115,9 +155,15 @@ where
                 pattern = match raw.as_str() {
                     "sequential" => Pattern::Sequential,
                     "random" => Pattern::Random,
+                    "working-set" => {
+                        if cfg!(not(unix)) {
+                            return Err("--pattern working-set requires a unix target".to_string());
+                        }
+                        Pattern::WorkingSet
+                    }
                     _ => {
                         return Err(format!(
-                            "unsupported --pattern {raw}; expected sequential or random"
+                            "unsupported --pattern {raw}; expected sequential, random, or working-set"
                         ));
                     }
                 };
@@ -132,6 +178,24 @@ where
                 }
                 max_seconds = Some(value);
             }
+            "--threads" => {
+                let raw = iter
+                    .next()
+                    .ok_or_else(|| "--threads requires a value".to_string())?;
+                threads = parse_positive_usize(&raw, "--threads")?;
+            }
+            "--evict-fraction" => {
+                let raw = iter
+                    .next()
+                    .ok_or_else(|| "--evict-fraction requires a value".to_string())?;
+                evict_fraction = Some(parse_unit_fraction(&raw, "--evict-fraction")?);
+            }
+            "--expect-major-faults" => {
+                let raw = iter
+                    .next()
+                    .ok_or_else(|| "--expect-major-faults requires a value".to_string())?;
+                expect_major_faults = Some(parse_u64(&raw, "--expect-major-faults")?);
+            }
             other if other.starts_with('-') => {
                 return Err(format!("unrecognized flag {other}"));
             }
@@ -147,6 +211,17 @@ where
     if passes == 0 {
         return Err("--passes must be greater than zero".to_string());
     }
+    if pattern == Pattern::WorkingSet && threads != DEFAULT_THREADS {
+        return Err("--threads is not supported with --pattern working-set".to_string());
+    }
+    if pattern != Pattern::WorkingSet {
+        if evict_fraction.is_some() {
+            return Err("--evict-fraction only applies to --pattern working-set".to_string());
+        }
+        if expect_major_faults.is_some() {
+            return Err("--expect-major-faults only applies to --pattern working-set".to_string());
+        }
+    }
 
     let total_bytes = megabytes
         .checked_mul(1024 * 1024)
@@ -157,10 +232,17 @@ where
         passes,
         pattern,
         max_seconds,
+        threads,
+        evict_fraction,
+        expect_major_faults,
     }))
 }
 
 fn run_workload(config: &Config) -> Result<(), RunError> {
+    if config.pattern == Pattern::WorkingSet {
+        return run_working_set(config);
+    }
+
     let page_size = page_size();
     if page_size == 0 {
         return Err(RunError::Internal(
@@ -168,7 +250,7 @@ fn run_workload(config: &Config) -> Result<(), RunError> {
         ));
     }
 
-    let page_count = page_count(config.total_bytes, page_size);
+    let total_page_count = page_count(config.total_bytes, page_size);
     let deadline = config.max_seconds.map(Duration::from_secs);
 
     let mut buffer = Vec::new();
@@ -178,24 +260,67 @@ fn run_workload(config: &Config) -> Result<(), RunError> {
     buffer.resize(config.total_bytes, 0u8);
 
     let start = Instant::now();
-    match config.pattern {
-        Pattern::Sequential => sequential_sweep(
-            &mut buffer,
-            page_size,
-            page_count,
-            config.passes,
-            start,
-            deadline,
-        ),
-        Pattern::Random => random_sweep(
-            &mut buffer,
-            page_size,
-            page_count,
-            config.passes,
-            start,
-            deadline,
-        ),
-    }
+    let stop = AtomicBool::new(false);
+    let threads = config.threads.max(1);
+    let shard_pages = page_count(total_page_count.max(1), threads);
+    let shard_bytes = (shard_pages * page_size).max(1);
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = buffer
+            .chunks_mut(shard_bytes)
+            .enumerate()
+            .map(|(shard_index, shard)| {
+                let stop = &stop;
+                scope.spawn(move || {
+                    let shard_page_count = page_count(shard.len(), page_size);
+                    let shard_seed = (shard_index as u64 + 1).wrapping_mul(0x2545_F491_4F6C_DD1D);
+                    match config.pattern {
+                        Pattern::Sequential => sequential_sweep(
+                            shard,
+                            page_size,
+                            shard_page_count,
+                            config.passes,
+                            start,
+                            deadline,
+                            stop,
+                        ),
+                        Pattern::Random => random_sweep(
+                            shard,
+                            page_size,
+                            shard_page_count,
+                            config.passes,
+                            start,
+                            deadline,
+                            stop,
+                            shard_seed,
+                        ),
+                        Pattern::WorkingSet => {
+                            unreachable!("working-set runs through run_working_set")
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        let mut first_err = None;
+        for handle in handles {
+            let result = handle.join().unwrap_or_else(|_| {
+                Err(RunError::Internal(
+                    "paging-stress worker thread panicked".to_string(),
+                ))
+            });
+            if let Err(err) = result {
+                if first_err.is_none() {
+                    first_err = Some(err);
+                }
+            }
+        }
+
+        match first_err {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    })
 }
 
 fn sequential_sweep(
@@ -205,12 +330,13 @@ fn sequential_sweep(
     passes: u64,
     start: Instant,
     deadline: Option<Duration>,
+    stop: &AtomicBool,
 ) -> Result<(), RunError> {
     for _ in 0..passes {
-        check_deadline(start, deadline)?;
+        check_deadline(start, deadline, stop)?;
         for idx in 0..page_count {
             if idx % DEADLINE_CHECK_INTERVAL == 0 {
-                check_deadline(start, deadline)?;
+                check_deadline(start, deadline, stop)?;
             }
             touch_page(buffer, idx, page_size);
         }
@@ -225,6 +351,8 @@ fn random_sweep(
     passes: u64,
     start: Instant,
     deadline: Option<Duration>,
+    stop: &AtomicBool,
+    shard_seed: u64,
 ) -> Result<(), RunError> {
     if page_count == 0 {
         return Ok(());
@@ -232,12 +360,12 @@ fn random_sweep(
 
     let mut indices: Vec<usize> = (0..page_count).collect();
     for pass in 0..passes {
-        check_deadline(start, deadline)?;
-        let seed = (pass + 1) ^ (page_count as u64).wrapping_mul(0x9E3779B97F4A7C15);
+        check_deadline(start, deadline, stop)?;
+        let seed = (pass + 1) ^ (page_count as u64).wrapping_mul(0x9E3779B97F4A7C15) ^ shard_seed;
         shuffle_indices(&mut indices, seed);
         for (idx, &page) in indices.iter().enumerate() {
             if idx % DEADLINE_CHECK_INTERVAL == 0 {
-                check_deadline(start, deadline)?;
+                check_deadline(start, deadline, stop)?;
             }
             touch_page(buffer, page, page_size);
         }
@@ -246,6 +374,134 @@ fn random_sweep(
     Ok(())
 }
 
+#[cfg(unix)]
+fn run_working_set(config: &Config) -> Result<(), RunError> {
+    let page_size = page_size();
+    if page_size == 0 {
+        return Err(RunError::Internal(
+            "system page size reported as zero".to_string(),
+        ));
+    }
+
+    let page_count = page_count(config.total_bytes, page_size);
+    let deadline = config.max_seconds.map(Duration::from_secs);
+    let evict_fraction = config.evict_fraction.unwrap_or(DEFAULT_EVICT_FRACTION);
+    let evict_pages = ((page_count as f64) * evict_fraction).round() as usize;
+    let evict_pages = evict_pages.min(page_count);
+
+    let before = read_rusage()?;
+    let mut map = AnonMap::new(config.total_bytes)?;
+
+    let start = Instant::now();
+    let stop = AtomicBool::new(false);
+    for pass in 0..config.passes {
+        check_deadline(start, deadline, &stop)?;
+        let buffer = map.as_mut_slice();
+        for idx in 0..page_count {
+            if idx % DEADLINE_CHECK_INTERVAL == 0 {
+                check_deadline(start, deadline, &stop)?;
+            }
+            touch_page(buffer, idx, page_size);
+        }
+        if pass + 1 < config.passes && evict_pages > 0 {
+            map.evict(evict_pages, page_size)?;
+        }
+    }
+
+    drop(map);
+    let after = read_rusage()?;
+
+    if let Some(expected) = config.expect_major_faults {
+        let observed = (after.ru_majflt - before.ru_majflt).max(0) as u64;
+        if observed < expected {
+            return Err(RunError::FaultExpectationNotMet { expected, observed });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn run_working_set(_config: &Config) -> Result<(), RunError> {
+    Err(RunError::Internal(
+        "--pattern working-set requires a unix target".to_string(),
+    ))
+}
+
+#[cfg(unix)]
+struct AnonMap {
+    ptr: *mut u8,
+    len: usize,
+}
+
+#[cfg(unix)]
+impl AnonMap {
+    fn new(len: usize) -> Result<Self, RunError> {
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(RunError::Internal(format!(
+                "mmap failed: {}",
+                io::Error::last_os_error()
+            )));
+        }
+        Ok(Self {
+            ptr: ptr as *mut u8,
+            len,
+        })
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+
+    fn evict(&mut self, pages: usize, page_size: usize) -> Result<(), RunError> {
+        let bytes = (pages * page_size).min(self.len);
+        if bytes == 0 {
+            return Ok(());
+        }
+        let rc =
+            unsafe { libc::madvise(self.ptr as *mut libc::c_void, bytes, libc::MADV_DONTNEED) };
+        if rc != 0 {
+            return Err(RunError::Internal(format!(
+                "madvise(MADV_DONTNEED) failed: {}",
+                io::Error::last_os_error()
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+impl Drop for AnonMap {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.ptr as *mut libc::c_void, self.len);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn read_rusage() -> Result<libc::rusage, RunError> {
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    let rc = unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) };
+    if rc != 0 {
+        return Err(RunError::Internal(format!(
+            "getrusage failed: {}",
+            io::Error::last_os_error()
+        )));
+    }
+    Ok(usage)
+}
+
 fn shuffle_indices(indices: &mut [usize], seed: u64) {
     let mut rng = XorShift64::new(seed);
     for i in (1..indices.len()).rev() {
@@ -261,10 +517,19 @@ fn touch_page(buffer: &mut [u8], page: usize, page_size: usize) {
     }
 }
 
-fn check_deadline(start: Instant, deadline: Option<Duration>) -> Result<(), RunError> {
+fn check_deadline(
+    start: Instant,
+    deadline: Option<Duration>,
+    stop: &AtomicBool,
+) -> Result<(), RunError> {
     if let Some(limit) = deadline {
+        if stop.load(Ordering::Relaxed) {
+            let elapsed = start.elapsed();
+            return Err(RunError::Timeout { elapsed, limit });
+        }
         let elapsed = start.elapsed();
         if elapsed >= limit {
+            stop.store(true, Ordering::Relaxed);
             return Err(RunError::Timeout { elapsed, limit });
         }
     }
@@ -310,13 +575,28 @@ fn parse_positive_usize(raw: &str, flag: &str) -> Result<usize, String> {
         })
 }
 
+fn parse_u64(raw: &str, flag: &str) -> Result<u64, String> {
+    raw.parse::<u64>()
+        .map_err(|_| format!("{flag} expects a non-negative integer, got '{raw}'"))
+}
+
+fn parse_unit_fraction(raw: &str, flag: &str) -> Result<f64, String> {
+    let value = raw
+        .parse::<f64>()
+        .map_err(|_| format!("{flag} expects a number, got '{raw}'"))?;
+    if !(0.0..=1.0).contains(&value) {
+        return Err(format!("{flag} must be between 0.0 and 1.0"));
+    }
+    Ok(value)
+}
+
 fn print_usage(program: &str, mut target: impl Write) {
     let _ = writeln!(
         target,
         "\
-Usage: {program} [--megabytes N] [--passes N] [--pattern sequential|random] [--max-seconds N]\n\n\
-Options:\n  --megabytes N   Number of MiB to allocate (default {DEFAULT_MEGABYTES}).\n  --passes N      How many full sweeps to perform (default {DEFAULT_PASSES}).\n  --pattern MODE  Access pattern: sequential or random (default sequential).\n  --max-seconds N Abort after N seconds (self-imposed timeout).\n  --help          Show this message.\n\n\
-Exit codes:\n  0 success, 1 invalid arguments, 2 internal error, 3 timeout."
+Usage: {program} [--megabytes N] [--passes N] [--pattern sequential|random|working-set] [--max-seconds N] [--threads N] [--evict-fraction F] [--expect-major-faults N]\n\n\
+Options:\n  --megabytes N          Number of MiB to allocate (default {DEFAULT_MEGABYTES}).\n  --passes N             How many full sweeps to perform (default {DEFAULT_PASSES}).\n  --pattern MODE         Access pattern: sequential, random, or working-set\n                         (default sequential; working-set is unix-only).\n  --max-seconds N        Abort after N seconds (self-imposed timeout).\n  --threads N            Split the allocation into N shards, each swept by\n                         its own thread, sharing one deadline (default\n                         {DEFAULT_THREADS}). Not supported with working-set.\n  --evict-fraction F     With --pattern working-set, fraction of the range\n                         (0.0..=1.0) to evict between passes (default\n                         {DEFAULT_EVICT_FRACTION}).\n  --expect-major-faults N  With --pattern working-set, fail with exit code 4\n                         if the observed ru_majflt delta is below N.\n  --help                 Show this message.\n\n\
+Exit codes:\n  0 success, 1 invalid arguments, 2 internal error, 3 timeout,\n  4 major-fault expectation not met."
     );
 }
 
@@ -3,8 +3,10 @@
 //! This helper mirrors the behavior expected by probe scripts on both macOS and
 //! Linux, avoiding reliance on platform-specific coreutils implementations.
 
-use anyhow::{Result, bail};
+use anyhow::{Result, anyhow, bail};
+use std::cmp::Reverse;
 use std::env;
+use std::ffi::OsString;
 use std::fs;
 use std::path::{Component, Path, PathBuf};
 
@@ -16,17 +18,24 @@ fn main() {
 }
 
 fn run() -> Result<()> {
-    match parse_args()? {
-        Command::RealPath(path) => {
-            if let Some(resolved) = resolve_realpath(&path) {
-                println!("{}", resolved.display());
+    let (command, remaps) = parse_args()?;
+    match command {
+        Command::RealPath {
+            path,
+            allow_missing,
+        } => {
+            if allow_missing {
+                let resolved = resolve_realpath_allow_missing(&path);
+                println!("{}", apply_remap(&resolved, &remaps).display());
+            } else if let Some(resolved) = resolve_realpath(&path) {
+                println!("{}", apply_remap(&resolved, &remaps).display());
             } else {
                 println!();
             }
         }
         Command::RelativePath { target, base } => {
             let relative = compute_relpath(&target, &base);
-            println!("{}", relative.display());
+            println!("{}", apply_remap(&relative, &remaps).display());
         }
         Command::Help => {
             print_usage();
@@ -36,52 +45,117 @@ fn run() -> Result<()> {
 }
 
 enum Command {
-    RealPath(PathBuf),
+    RealPath { path: PathBuf, allow_missing: bool },
     RelativePath { target: PathBuf, base: PathBuf },
     Help,
 }
 
-fn parse_args() -> Result<Command> {
+/// One `--remap FROM=TO` rule: rewrite a resolved path's leading `FROM`
+/// component sequence to `TO`, e.g. stripping an absolute checkout location
+/// before probe results are stored or diffed across machines.
+type RemapRule = (PathBuf, PathBuf);
+
+fn parse_args() -> Result<(Command, Vec<RemapRule>)> {
     let mut args = env::args_os();
     let _program = args.next();
 
-    let Some(subcommand) = args.next() else {
+    let mut remaps = Vec::new();
+    let mut allow_missing = false;
+    let mut rest: Vec<OsString> = Vec::new();
+    while let Some(arg) = args.next() {
+        if arg.to_str() == Some("--remap") {
+            let value = args
+                .next()
+                .ok_or_else(|| anyhow!("--remap expects FROM=TO"))?;
+            remaps.push(parse_remap(&value)?);
+        } else if arg.to_str() == Some("--missing") {
+            allow_missing = true;
+        } else {
+            rest.push(arg);
+        }
+    }
+
+    let mut rest = rest.into_iter();
+    let Some(subcommand) = rest.next() else {
         bail!(usage());
     };
 
-    match subcommand.to_str() {
+    let command = match subcommand.to_str() {
         Some("realpath") => {
-            let Some(target) = args.next() else {
+            let Some(target) = rest.next() else {
                 bail!("realpath expects exactly one argument");
             };
-            if args.next().is_some() {
+            if rest.next().is_some() {
                 bail!("realpath expects exactly one argument");
             }
-            Ok(Command::RealPath(PathBuf::from(target)))
+            Command::RealPath {
+                path: PathBuf::from(target),
+                allow_missing,
+            }
         }
         Some("relpath") => {
-            let Some(target) = args.next() else {
+            let Some(target) = rest.next() else {
                 bail!("relpath expects a target path and a base path");
             };
-            let Some(base) = args.next() else {
+            let Some(base) = rest.next() else {
                 bail!("relpath expects a target path and a base path");
             };
-            if args.next().is_some() {
+            if rest.next().is_some() {
                 bail!("relpath expects only a target path and a base path");
             }
-            Ok(Command::RelativePath {
+            Command::RelativePath {
                 target: PathBuf::from(target),
                 base: PathBuf::from(base),
-            })
+            }
         }
-        Some("--help") | Some("-h") => Ok(Command::Help),
+        Some("--help") | Some("-h") => Command::Help,
         Some(other) => bail!("Unknown subcommand: {other}"),
         None => bail!("Subcommand must be valid Unicode"),
+    };
+
+    Ok((command, remaps))
+}
+
+fn parse_remap(value: &OsString) -> Result<RemapRule> {
+    let text = value
+        .to_str()
+        .ok_or_else(|| anyhow!("--remap value must be valid Unicode"))?;
+    let (from, to) = text
+        .split_once('=')
+        .ok_or_else(|| anyhow!("--remap expects FROM=TO, got '{text}'"))?;
+    if from.is_empty() || to.is_empty() {
+        bail!("--remap expects non-empty FROM and TO, got '{text}'");
+    }
+    Ok((PathBuf::from(from), PathBuf::from(to)))
+}
+
+/// Rewrites the first (longest-`from`-prefix-first) rule in `rules` whose
+/// `from` component sequence is a leading prefix of `path`, replacing that
+/// span with `to`'s components. `/a/b=>X` is tried before `/a=>Y` so the more
+/// specific rule wins. Leaves `path` untouched if no rule matches.
+fn apply_remap(path: &Path, rules: &[RemapRule]) -> PathBuf {
+    let path_components: Vec<_> = path.components().collect();
+    let mut ordered: Vec<&RemapRule> = rules.iter().collect();
+    ordered.sort_by_key(|(from, _)| Reverse(from.components().count()));
+
+    for (from, to) in ordered {
+        let from_components: Vec<_> = from.components().collect();
+        if path_components.len() >= from_components.len()
+            && path_components[..from_components.len()] == from_components[..]
+        {
+            let mut remapped = to.clone();
+            for component in &path_components[from_components.len()..] {
+                remapped.push(component.as_os_str());
+            }
+            return remapped;
+        }
     }
+
+    path.to_path_buf()
 }
 
 fn usage() -> &'static str {
-    "Usage: portable-path <realpath|relpath> [args]\n\nCommands:\n  realpath <path>          Resolve <path> to a canonical absolute path.\n  relpath <path> <base>    Emit the relative path from <base> to <path>.\n"
+    "Usage: portable-path [--remap FROM=TO]... [--missing] <realpath|relpath> [args]\n\nCommands:\n  realpath <path>          Resolve <path> to a canonical absolute path.\n  relpath <path> <base>    Emit the relative path from <base> to <path>.\n\nOptions:\n  --remap FROM=TO          Rewrite a resolved path's leading FROM component\n                           sequence to TO (repeatable). The longest matching\n                           FROM wins; only the first match is applied.\n  --missing                With realpath, tolerate a target (or parent) that\n                           doesn't exist yet: resolve the longest existing\n                           ancestor and lexically normalize the rest instead\n                           of printing an empty line.\n"
 }
 
 fn print_usage() {
@@ -92,6 +166,59 @@ fn resolve_realpath(path: &Path) -> Option<PathBuf> {
     fs::canonicalize(path).ok()
 }
 
+/// Like `resolve_realpath`, but tolerates a target (or parent) that doesn't
+/// exist yet: resolves the longest existing ancestor with
+/// `fs::canonicalize` (fully resolving any symlinks in that prefix), then
+/// re-appends the remaining components after normalizing away `.`/`..`
+/// lexically. Always returns an absolute path, joining against
+/// `current_dir()` first when `path` is relative.
+fn resolve_realpath_allow_missing(path: &Path) -> PathBuf {
+    let joined = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        env::current_dir()
+            .map(|cwd| cwd.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    };
+    let normalized = normalize_lexical(&joined);
+
+    let mut existing = normalized;
+    let mut tail: Vec<std::ffi::OsString> = Vec::new();
+    while !existing.exists() {
+        match existing.file_name() {
+            Some(name) => {
+                tail.push(name.to_os_string());
+                existing.pop();
+            }
+            None => break,
+        }
+    }
+    tail.reverse();
+
+    let mut resolved = fs::canonicalize(&existing).unwrap_or(existing);
+    for name in tail {
+        resolved.push(name);
+    }
+    resolved
+}
+
+/// Collapses `.` and resolves `..` against prior components without
+/// touching the filesystem. A `..` that would escape the root is dropped
+/// instead, clamping at root rather than producing an escaping path.
+fn normalize_lexical(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized
+}
+
 fn absolute_path(path: &Path) -> PathBuf {
     let candidate = if path.is_absolute() {
         path.to_path_buf()
@@ -171,4 +298,49 @@ mod tests {
         let rel = compute_relpath(target, base);
         assert_eq!(rel, PathBuf::from("."));
     }
+
+    #[test]
+    fn remap_prefers_longest_matching_prefix() {
+        let rules = vec![
+            (PathBuf::from("/a"), PathBuf::from("Y")),
+            (PathBuf::from("/a/b"), PathBuf::from("X")),
+        ];
+        let remapped = apply_remap(Path::new("/a/b/c"), &rules);
+        assert_eq!(remapped, PathBuf::from("X/c"));
+    }
+
+    #[test]
+    fn remap_leaves_unmatched_path_untouched() {
+        let rules = vec![(PathBuf::from("/checkout"), PathBuf::from("WORKSPACE"))];
+        let remapped = apply_remap(Path::new("/other/path"), &rules);
+        assert_eq!(remapped, PathBuf::from("/other/path"));
+    }
+
+    #[test]
+    fn parse_remap_rejects_missing_separator() {
+        let err = parse_remap(&OsString::from("no-equals-sign")).expect_err("should reject");
+        assert!(err.to_string().contains("FROM=TO"));
+    }
+
+    #[test]
+    fn normalize_lexical_clamps_parent_dir_at_root() {
+        let normalized = normalize_lexical(Path::new("/a/../../b"));
+        assert_eq!(normalized, PathBuf::from("/b"));
+    }
+
+    #[test]
+    fn realpath_allow_missing_resolves_existing_ancestor_and_appends_tail() {
+        let dir =
+            std::env::temp_dir().join(format!("portable-path-missing-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("create fixture dir");
+        let canonical_dir = fs::canonicalize(&dir).expect("canonicalize fixture dir");
+
+        let target = dir.join("not/created/yet.txt");
+        let resolved = resolve_realpath_allow_missing(&target);
+
+        assert_eq!(resolved, canonical_dir.join("not/created/yet.txt"));
+        assert!(resolved.is_absolute());
+
+        fs::remove_dir_all(&dir).ok();
+    }
 }
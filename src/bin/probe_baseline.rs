@@ -0,0 +1,438 @@
+//! Diffs a fresh `probe --matrix` run against a committed boundary-record
+//! baseline so CI gates on security boundary regressions, not just on the
+//! probe exit code.
+//!
+//! Records are aligned by identity (primary capability ID + run mode) rather
+//! than by array position, since probes can be added, removed, or reordered
+//! between runs. Pipe a fresh run in:
+//!
+//!     probe --matrix | probe --baseline --baseline-file baselines/fs.ndjson
+//!
+//! Pass `--refresh` to overwrite the baseline with the piped-in run instead of
+//! diffing against it, so a reviewer can approve intentional boundary changes
+//! the way `cargo insta`/snapshot-test workflows do.
+
+use anyhow::{Context, Result, anyhow, bail};
+use fencerunner::emit_support::{validate_capability_id, validate_status};
+use fencerunner::{
+    BoundaryObject, BoundarySchema, CapabilityIndex, find_repo_root, read_boundary_objects,
+    resolve_boundary_schema_path, resolve_catalog_path,
+};
+use std::collections::{BTreeMap, BTreeSet};
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("{err:#}");
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<()> {
+    let cli = Cli::parse()?;
+    let repo_root = find_repo_root()?;
+    let catalog_path = resolve_catalog_path(&repo_root, cli.catalog_path.as_deref());
+    let capability_index = CapabilityIndex::load(&catalog_path).with_context(|| {
+        format!("loading capability catalog from {}", catalog_path.display())
+    })?;
+    let boundary_schema_path =
+        resolve_boundary_schema_path(&repo_root, cli.boundary_schema_path.as_deref())?;
+    let boundary_schema = BoundarySchema::load(&boundary_schema_path).with_context(|| {
+        format!(
+            "loading boundary schema from {}",
+            boundary_schema_path.display()
+        )
+    })?;
+
+    let stdin = io::stdin();
+    if stdin.is_terminal() {
+        bail!(
+            "probe --baseline expects boundary-object NDJSON on stdin (e.g. probe --matrix | probe --baseline --baseline-file PATH)"
+        );
+    }
+    let fresh = read_and_validate(BufReader::new(stdin.lock()), &boundary_schema)?;
+
+    if cli.refresh {
+        write_baseline(&cli.baseline_path, &fresh)?;
+        println!(
+            "Wrote {} record(s) to baseline {}",
+            fresh.len(),
+            cli.baseline_path.display()
+        );
+        return Ok(());
+    }
+
+    let baseline = load_baseline(&cli.baseline_path, &boundary_schema, &capability_index)?;
+    let entries = diff_records(&baseline, &fresh);
+    let stdout = io::stdout();
+    render_diff(&entries, &mut stdout.lock())?;
+
+    if entries.iter().any(DiffEntry::is_regression) {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn read_and_validate<R: BufRead>(
+    reader: R,
+    boundary_schema: &BoundarySchema,
+) -> Result<Vec<BoundaryObject>> {
+    let records = read_boundary_objects(reader).map_err(|err| anyhow!(err))?;
+    for record in &records {
+        let value = serde_json::to_value(record)?;
+        boundary_schema
+            .validate(&value)
+            .map_err(|err| anyhow!(err.to_string()))?;
+    }
+    Ok(records)
+}
+
+/// Load the committed baseline, sanity-checking every entry the same way
+/// `emit-record` sanity-checks a freshly emitted record.
+fn load_baseline(
+    path: &Path,
+    boundary_schema: &BoundarySchema,
+    capability_index: &CapabilityIndex,
+) -> Result<Vec<BoundaryObject>> {
+    if !path.is_file() {
+        return Ok(Vec::new());
+    }
+    let file = File::open(path).with_context(|| format!("opening baseline {}", path.display()))?;
+    let records = read_and_validate(BufReader::new(file), boundary_schema)
+        .with_context(|| format!("reading baseline {}", path.display()))?;
+
+    for record in &records {
+        validate_status(&record.result.observed_result)
+            .with_context(|| format!("baseline {}", path.display()))?;
+        validate_capability_id(
+            capability_index,
+            &record.probe.primary_capability_id,
+            "baseline primary capability id",
+        )
+        .with_context(|| format!("baseline {}", path.display()))?;
+    }
+    Ok(records)
+}
+
+fn write_baseline(path: &Path, records: &[BoundaryObject]) -> Result<()> {
+    let mut sorted: Vec<&BoundaryObject> = records.iter().collect();
+    sorted.sort_by(|a, b| identity_key(a).cmp(&identity_key(b)));
+
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("creating baseline directory {}", parent.display()))?;
+        }
+    }
+
+    let mut out =
+        File::create(path).with_context(|| format!("creating baseline {}", path.display()))?;
+    for record in sorted {
+        serde_json::to_writer(&mut out, record)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Identity key for aligning baseline and fresh records: primary capability ID
+/// plus run mode, since the same capability can be probed under several modes.
+fn identity_key(record: &BoundaryObject) -> (String, String) {
+    (
+        record.probe.primary_capability_id.0.clone(),
+        record.run.mode.clone(),
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiffKind {
+    Unchanged,
+    StatusChanged,
+    NewlyAppeared,
+    Missing,
+}
+
+struct DiffEntry {
+    key: (String, String),
+    kind: DiffKind,
+    baseline_result: Option<String>,
+    fresh_result: Option<String>,
+}
+
+impl DiffEntry {
+    /// A regression is either a probe that silently disappeared, or one whose
+    /// boundary held (`success`) in the baseline but no longer does. A
+    /// status change in the other direction (e.g. `denied` -> `success`) is
+    /// reported but does not fail the gate on its own.
+    fn is_regression(&self) -> bool {
+        match self.kind {
+            DiffKind::Missing => true,
+            DiffKind::StatusChanged => {
+                self.baseline_result.as_deref() == Some("success")
+                    && self.fresh_result.as_deref() != Some("success")
+            }
+            DiffKind::Unchanged | DiffKind::NewlyAppeared => false,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self.kind {
+            DiffKind::Unchanged => "unchanged",
+            DiffKind::StatusChanged => "status-changed",
+            DiffKind::NewlyAppeared => "new",
+            DiffKind::Missing => "missing",
+        }
+    }
+}
+
+fn diff_records(baseline: &[BoundaryObject], fresh: &[BoundaryObject]) -> Vec<DiffEntry> {
+    let baseline_map: BTreeMap<(String, String), &BoundaryObject> =
+        baseline.iter().map(|record| (identity_key(record), record)).collect();
+    let fresh_map: BTreeMap<(String, String), &BoundaryObject> =
+        fresh.iter().map(|record| (identity_key(record), record)).collect();
+
+    let keys: BTreeSet<(String, String)> = baseline_map
+        .keys()
+        .chain(fresh_map.keys())
+        .cloned()
+        .collect();
+
+    keys.into_iter()
+        .map(|key| {
+            let baseline_record = baseline_map.get(&key);
+            let fresh_record = fresh_map.get(&key);
+            match (baseline_record, fresh_record) {
+                (Some(b), Some(f)) => {
+                    let baseline_result = b.result.observed_result.clone();
+                    let fresh_result = f.result.observed_result.clone();
+                    let kind = if baseline_result == fresh_result {
+                        DiffKind::Unchanged
+                    } else {
+                        DiffKind::StatusChanged
+                    };
+                    DiffEntry {
+                        key,
+                        kind,
+                        baseline_result: Some(baseline_result),
+                        fresh_result: Some(fresh_result),
+                    }
+                }
+                (None, Some(f)) => DiffEntry {
+                    key,
+                    kind: DiffKind::NewlyAppeared,
+                    baseline_result: None,
+                    fresh_result: Some(f.result.observed_result.clone()),
+                },
+                (Some(b), None) => DiffEntry {
+                    key,
+                    kind: DiffKind::Missing,
+                    baseline_result: Some(b.result.observed_result.clone()),
+                    fresh_result: None,
+                },
+                (None, None) => unreachable!("key was collected from one of the two maps"),
+            }
+        })
+        .collect()
+}
+
+fn render_diff(entries: &[DiffEntry], writer: &mut impl Write) -> io::Result<()> {
+    let regressions = entries.iter().filter(|entry| entry.is_regression()).count();
+    writeln!(writer, "probe baseline diff")?;
+    writeln!(writer, "==========================")?;
+    writeln!(writer, "capabilities checked: {}", entries.len())?;
+    writeln!(writer, "regressions         : {}", regressions)?;
+    writeln!(writer)?;
+
+    for entry in entries {
+        let (capability_id, mode) = &entry.key;
+        let marker = if entry.is_regression() { "!" } else { " " };
+        writeln!(
+            writer,
+            "[{}] {:<14} capability={} mode={} baseline={} fresh={}",
+            marker,
+            entry.label(),
+            capability_id,
+            mode,
+            entry.baseline_result.as_deref().unwrap_or("-"),
+            entry.fresh_result.as_deref().unwrap_or("-"),
+        )?;
+    }
+    Ok(())
+}
+
+struct Cli {
+    baseline_path: PathBuf,
+    boundary_schema_path: Option<PathBuf>,
+    catalog_path: Option<PathBuf>,
+    refresh: bool,
+}
+
+impl Cli {
+    fn parse() -> Result<Self> {
+        let mut args = env::args_os();
+        let _program = args.next();
+
+        let mut baseline_path = None;
+        let mut boundary_schema_path = None;
+        let mut catalog_path = None;
+        let mut refresh = false;
+
+        while let Some(arg) = args.next() {
+            let arg_str = arg
+                .to_str()
+                .ok_or_else(|| anyhow!("invalid UTF-8 in argument"))?;
+            match arg_str {
+                "--baseline-file" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--baseline-file requires a value"))?;
+                    baseline_path = Some(PathBuf::from(
+                        value
+                            .into_string()
+                            .map_err(|_| anyhow!("--baseline-file must be valid UTF-8"))?,
+                    ));
+                }
+                "--boundary" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--boundary requires a value"))?;
+                    boundary_schema_path = Some(PathBuf::from(
+                        value
+                            .into_string()
+                            .map_err(|_| anyhow!("--boundary must be valid UTF-8"))?,
+                    ));
+                }
+                "--catalog" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--catalog requires a value"))?;
+                    catalog_path = Some(PathBuf::from(
+                        value
+                            .into_string()
+                            .map_err(|_| anyhow!("--catalog must be valid UTF-8"))?,
+                    ));
+                }
+                "--refresh" => refresh = true,
+                "--help" | "-h" => usage(0),
+                other => bail!("unknown argument: {other}"),
+            }
+        }
+
+        Ok(Self {
+            baseline_path: baseline_path
+                .ok_or_else(|| anyhow!("--baseline-file PATH is required"))?,
+            boundary_schema_path,
+            catalog_path,
+            refresh,
+        })
+    }
+}
+
+fn usage(code: i32) -> ! {
+    eprintln!(
+        "Usage: probe --baseline --baseline-file PATH [--refresh] [--boundary PATH] [--catalog PATH]\n\nOptions:\n  --baseline-file PATH      Committed boundary-record baseline (NDJSON).\n  --refresh                 Overwrite the baseline with the piped-in run instead of diffing.\n  --boundary PATH           Override boundary-object schema path.\n  --catalog PATH            Override capability catalog path.\n  --help                    Show this help text.\n\nReads boundary-object NDJSON from stdin, e.g.:\n  probe --matrix | probe --baseline --baseline-file baselines/fs.ndjson"
+    );
+    std::process::exit(code);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fencerunner::{
+        CapabilityCategory, CapabilityContext, CapabilityId, CapabilityLayer, CapabilitySnapshot,
+    };
+
+    fn sample(capability_id: &str, mode: &str, result: &str) -> BoundaryObject {
+        BoundaryObject {
+            schema_version: "cfbo-v1".to_string(),
+            schema_key: None,
+            capabilities_schema_version: None,
+            stack: fencerunner::StackInfo {
+                sandbox_mode: Some(mode.to_string()),
+                container_image: None,
+                os: "Linux".to_string(),
+            },
+            probe: fencerunner::ProbeInfo {
+                id: format!("probe_{capability_id}"),
+                version: "1".to_string(),
+                primary_capability_id: CapabilityId(capability_id.to_string()),
+                secondary_capability_ids: Vec::new(),
+            },
+            run: fencerunner::RunInfo {
+                mode: mode.to_string(),
+                workspace_root: Some("/tmp".to_string()),
+                command: "true".to_string(),
+            },
+            operation: fencerunner::OperationInfo {
+                category: "fs".to_string(),
+                verb: "read".to_string(),
+                target: "/tmp/sample".to_string(),
+                args: serde_json::json!({}),
+            },
+            result: fencerunner::ResultInfo {
+                observed_result: result.to_string(),
+                raw_exit_code: Some(0),
+                errno: None,
+                message: None,
+                error_detail: None,
+            },
+            payload: fencerunner::Payload {
+                stdout_snippet: None,
+                stderr_snippet: None,
+                raw: serde_json::json!({}),
+            },
+            capability_context: CapabilityContext {
+                primary: CapabilitySnapshot {
+                    id: CapabilityId(capability_id.to_string()),
+                    category: CapabilityCategory::Filesystem,
+                    layer: CapabilityLayer::OsSandbox,
+                },
+                secondary: Vec::new(),
+                resolved_grant: None,
+            },
+        }
+    }
+
+    #[test]
+    fn regression_when_success_turns_non_success() {
+        let baseline = vec![sample("cap_a", "baseline", "success")];
+        let fresh = vec![sample("cap_a", "baseline", "denied")];
+        let entries = diff_records(&baseline, &fresh);
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].is_regression());
+    }
+
+    #[test]
+    fn no_regression_when_denied_turns_success() {
+        let baseline = vec![sample("cap_a", "baseline", "denied")];
+        let fresh = vec![sample("cap_a", "baseline", "success")];
+        let entries = diff_records(&baseline, &fresh);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].kind, DiffKind::StatusChanged);
+        assert!(!entries[0].is_regression());
+    }
+
+    #[test]
+    fn missing_capability_is_a_regression_and_new_one_is_not() {
+        let baseline = vec![sample("cap_a", "baseline", "success")];
+        let fresh = vec![sample("cap_b", "baseline", "success")];
+        let entries = diff_records(&baseline, &fresh);
+        assert_eq!(entries.len(), 2);
+
+        let missing = entries
+            .iter()
+            .find(|entry| entry.key.0 == "cap_a")
+            .expect("missing entry present");
+        assert_eq!(missing.kind, DiffKind::Missing);
+        assert!(missing.is_regression());
+
+        let new_entry = entries
+            .iter()
+            .find(|entry| entry.key.0 == "cap_b")
+            .expect("new entry present");
+        assert_eq!(new_entry.kind, DiffKind::NewlyAppeared);
+        assert!(!new_entry.is_regression());
+    }
+}
@@ -4,17 +4,29 @@
 //! stable while resolving the real helper paths (preferring the synced `bin/`
 //! artifacts). It also injects `FENCE_ROOT` when possible so helpers can
 //! locate probes and fixtures even when invoked from an installed location.
+//!
+//! Beyond the built-in flags, `[alias]` entries in `FENCE_ROOT/.fencerunner.toml`
+//! (mirroring cargo's `[alias]` config) expand to a `probe --target` invocation,
+//! so teams can name common targeted-probe incantations instead of retyping them.
 
 use anyhow::{Context, Result, bail};
 use fencerunner::{
     find_repo_root, resolve_helper_binary,
     runtime::{find_on_path, helper_is_executable},
 };
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
 use std::env;
 use std::ffi::OsString;
+use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+/// Bounded recursion depth for alias-to-alias expansion; guards against cycles
+/// that `expand_alias`'s visited-set check alone wouldn't catch with a typo'd
+/// but still-acyclic chain (e.g. a thousand-entry alias relay).
+const MAX_ALIAS_DEPTH: usize = 8;
+
 fn main() {
     if let Err(err) = run() {
         eprintln!("{err:#}");
@@ -23,8 +35,8 @@ fn main() {
 }
 
 fn run() -> Result<()> {
-    let cli = Cli::parse()?;
     let repo_root = find_repo_root().ok();
+    let cli = Cli::parse(repo_root.as_deref())?;
 
     run_helper(&cli, repo_root.as_deref())
 }
@@ -39,6 +51,9 @@ enum CommandTarget {
     Matrix,
     Listen,
     Target,
+    Baseline,
+    Diff,
+    OsMatrix,
 }
 
 impl CommandTarget {
@@ -47,12 +62,15 @@ impl CommandTarget {
             CommandTarget::Matrix => "probe-matrix",
             CommandTarget::Listen => "probe-listen",
             CommandTarget::Target => "probe-target",
+            CommandTarget::Baseline => "probe-baseline",
+            CommandTarget::Diff => "probe-diff",
+            CommandTarget::OsMatrix => "probe-os-matrix",
         }
     }
 }
 
 impl Cli {
-    fn parse() -> Result<Self> {
+    fn parse(repo_root: Option<&Path>) -> Result<Self> {
         let mut args = env::args_os();
         let _program = args.next();
 
@@ -62,27 +80,153 @@ impl Cli {
 
         let flag_str = flag
             .to_str()
-            .with_context(|| "Invalid UTF-8 in command flag")?;
+            .with_context(|| "Invalid UTF-8 in command flag")?
+            .to_string();
+
+        let rest: Vec<OsString> = args.collect();
 
-        let command = match flag_str {
-            "--matrix" | "-m" => CommandTarget::Matrix,
-            "--listen" | "-l" => CommandTarget::Listen,
-            "--target" | "-t" => CommandTarget::Target,
+        match flag_str.as_str() {
+            "--matrix" | "-m" => {
+                return Ok(Self {
+                    command: CommandTarget::Matrix,
+                    trailing_args: rest,
+                });
+            }
+            "--listen" | "-l" => {
+                return Ok(Self {
+                    command: CommandTarget::Listen,
+                    trailing_args: rest,
+                });
+            }
+            "--target" | "-t" => {
+                return Ok(Self {
+                    command: CommandTarget::Target,
+                    trailing_args: rest,
+                });
+            }
+            "--baseline" | "-b" => {
+                return Ok(Self {
+                    command: CommandTarget::Baseline,
+                    trailing_args: rest,
+                });
+            }
+            "--diff" | "-d" => {
+                return Ok(Self {
+                    command: CommandTarget::Diff,
+                    trailing_args: rest,
+                });
+            }
+            "--os-matrix" | "-o" => {
+                return Ok(Self {
+                    command: CommandTarget::OsMatrix,
+                    trailing_args: rest,
+                });
+            }
             "--help" | "-h" => usage(0),
-            _ => usage(1),
-        };
+            _ => {}
+        }
+
+        let aliases = load_aliases(repo_root)?;
+        let expanded = expand_alias(&flag_str, &aliases)
+            .with_context(|| format!("resolving '{flag_str}' as a [alias] entry"))?;
+
+        let mut trailing_args: Vec<OsString> =
+            expanded.into_iter().map(OsString::from).collect();
+        trailing_args.extend(rest);
 
-        let trailing_args = args.collect();
         Ok(Self {
-            command,
+            command: CommandTarget::Target,
             trailing_args,
         })
     }
 }
 
+/// Expand `name` against `aliases`, chasing alias-to-alias references (cargo's
+/// `[alias]` tables allow this) up to [`MAX_ALIAS_DEPTH`] and bailing on cycles.
+fn expand_alias(name: &str, aliases: &BTreeMap<String, Vec<String>>) -> Result<Vec<String>> {
+    let mut seen = BTreeSet::new();
+    let mut current = name.to_string();
+    let mut suffix: Vec<String> = Vec::new();
+
+    loop {
+        if !seen.insert(current.clone()) {
+            bail!("alias cycle detected while expanding '{name}' (repeated '{current}')");
+        }
+        if seen.len() > MAX_ALIAS_DEPTH {
+            bail!("alias expansion of '{name}' exceeded max depth ({MAX_ALIAS_DEPTH})");
+        }
+
+        let Some(tokens) = aliases.get(&current) else {
+            bail!("unknown command, flag, or alias: {current}");
+        };
+        let Some((head, rest)) = tokens.split_first() else {
+            bail!("alias '{current}' expands to no arguments");
+        };
+
+        if !aliases.contains_key(head) {
+            let mut expanded = tokens.clone();
+            expanded.extend(suffix);
+            return Ok(expanded);
+        }
+
+        let mut new_suffix = rest.to_vec();
+        new_suffix.extend(suffix);
+        suffix = new_suffix;
+        current = head.clone();
+    }
+}
+
+/// Load `[alias]` entries from `FENCE_ROOT/.fencerunner.toml`, if present.
+///
+/// Each entry is either a single string (split on whitespace) or a list of
+/// strings. A missing config file is not an error; a malformed one is.
+fn load_aliases(repo_root: Option<&Path>) -> Result<BTreeMap<String, Vec<String>>> {
+    let Some(root) = repo_root else {
+        return Ok(BTreeMap::new());
+    };
+    let path = root.join(".fencerunner.toml");
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(_) => return Ok(BTreeMap::new()),
+    };
+
+    let config: AliasConfigFile = toml::from_str(&contents)
+        .with_context(|| format!("parsing alias config {}", path.display()))?;
+
+    Ok(config
+        .alias
+        .into_iter()
+        .map(|(name, value)| (name, value.into_tokens()))
+        .collect())
+}
+
+#[derive(Deserialize, Default)]
+struct AliasConfigFile {
+    #[serde(default)]
+    alias: BTreeMap<String, AliasValue>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AliasValue {
+    Single(String),
+    List(Vec<String>),
+}
+
+impl AliasValue {
+    fn into_tokens(self) -> Vec<String> {
+        match self {
+            AliasValue::Single(command) => {
+                command.split_whitespace().map(str::to_string).collect()
+            }
+            AliasValue::List(tokens) => tokens,
+        }
+    }
+}
+
 fn usage(code: i32) -> ! {
     eprintln!(
-        "Usage: probe (--matrix | --listen | --target) [args]\n\nCommands:\n  --matrix, -m   Run the full probe matrix once and emit boundary records (NDJSON).\n  --listen, -l   Read boundary-object JSON from stdin and print a human summary.\n  --target, -t   Run a targeted probe subset (see probe-target --help).\n\nExamples:\n  probe --matrix | probe --listen\n  probe --target --probe fs_read_workspace_readme --mode baseline"
+        "Usage: probe (--matrix | --listen | --target | --baseline | --diff | --os-matrix | ALIAS) [args]\n\nCommands:\n  --matrix, -m     Run the full probe matrix once and emit boundary records (NDJSON).\n  --listen, -l     Read boundary-object JSON from stdin and print a human summary.\n  --target, -t     Run a targeted probe subset (see probe-target --help).\n  --baseline, -b   Diff a fresh matrix run against a committed baseline (see probe-baseline --help).\n  --diff, -d       Diff a baseline-mode run against a sandboxed-mode run from the same matrix (see probe-diff --help).\n  --os-matrix, -o  Classify a matrix run against a per-(os, sandbox_mode) expectation table (see probe-os-matrix --help).\n  ALIAS            Any [alias] name from FENCE_ROOT/.fencerunner.toml, expanded into\n                   a probe --target invocation (mirrors cargo's [alias] config).\n\nExamples:\n  probe --matrix | probe --listen\n  probe --target --probe fs_read_workspace_readme --mode baseline\n  probe --matrix | probe --baseline --baseline-file baselines/fs.ndjson\n  probe --diff --baseline-file baseline.ndjson --target-file target.ndjson\n  probe --os-matrix --records-file run.ndjson --expectations-file os_expectations.json"
     );
     std::process::exit(code);
 }
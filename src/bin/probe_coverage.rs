@@ -0,0 +1,256 @@
+//! Cross-references the probe corpus's declared capability ids against the
+//! capability catalog, to answer "which capabilities have no probe, and which
+//! probes reference a capability the catalog doesn't know about?"
+//!
+//! Unlike `probe-diff`/`probe-os-matrix` (which classify *emitted* boundary
+//! records from a run), `probe-coverage` never executes anything: it parses
+//! every `.sh` script under `probes/` with [`fencerunner::ProbeMetadata`] and
+//! tallies `primary_capability`/`secondary_capabilities` against
+//! [`fencerunner::CapabilityIndex`] (see [`fencerunner::coverage`]). The
+//! report has four parts: catalog capabilities with zero referencing probes
+//! ("gaps"), referencing-probe counts per capability, probe-declared ids
+//! absent from the catalog ("orphans"), and scripts whose capability ids
+//! contain a `$`-substitution that couldn't be statically resolved.
+//!
+//!     probe-coverage
+//!     probe-coverage --format json > coverage.json
+//!     probe-coverage --dot coverage.dot && dot -Tsvg coverage.dot -o coverage.svg
+//!
+//! Exits non-zero when any orphan is found, since a probe referencing an
+//! unknown capability id usually means the catalog or the script drifted,
+//! or when [`fencerunner::coverage::evaluate_coverage`]'s verdict flags an
+//! uncovered `Critical` capability — so a CI step can gate on a real gap in
+//! the catalog's most important capabilities, not just orphaned references.
+//! `--dot` renders the same coverage data [`fencerunner::coverage::build_probe_coverage_map`]
+//! builds as a Graphviz graph instead, so operators can eyeball gaps as a
+//! picture rather than a flat list.
+
+use anyhow::{Context, Result, anyhow, bail};
+use fencerunner::coverage::{
+    CoverageVerdict, ProbeCoverageAccounting, account_probe_coverage, build_probe_coverage_map,
+    evaluate_coverage, filter_coverage_probes, render_coverage_dot,
+};
+use fencerunner::reporter::{self, OutputFormat, Verbosity, format_counts};
+use fencerunner::{
+    CapabilityIndex, ProbeMetadata, collect_probe_scripts, find_repo_root, resolve_catalog_path,
+};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("{err:#}");
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<()> {
+    let cli = Cli::parse()?;
+    let verbosity = Verbosity::from_flags(cli.quiet, cli.verbose)?;
+    let repo_root = find_repo_root()?;
+
+    let catalog_path = resolve_catalog_path(&repo_root, cli.catalog_path.as_deref());
+    let capability_index = CapabilityIndex::load(&catalog_path)
+        .with_context(|| format!("loading capability catalog from {}", catalog_path.display()))?;
+
+    let probes_root = cli
+        .probes_root
+        .clone()
+        .unwrap_or_else(|| repo_root.join("probes"));
+    let scripts = collect_probe_scripts(&[probes_root.clone()])
+        .with_context(|| format!("collecting probe scripts under {}", probes_root.display()))?;
+
+    let mut probes = Vec::new();
+    for script in &scripts {
+        match ProbeMetadata::from_script(script) {
+            Ok(metadata) => probes.push(metadata),
+            Err(err) => reporter::diagnostic(
+                verbosity,
+                &format!("probe-coverage: skipping {}: {err:#}", script.display()),
+            ),
+        }
+    }
+    let probes = filter_coverage_probes(&probes);
+
+    let accounting = account_probe_coverage(&capability_index, &probes);
+    // `build_probe_coverage_map` bails on an orphaned capability id, which
+    // `account_probe_coverage` already reports separately below, so a
+    // verdict is simply unavailable (not a hard error) when orphans exist.
+    let coverage_map = build_probe_coverage_map(&capability_index, &probes).ok();
+    let verdict = coverage_map
+        .as_ref()
+        .map(|coverage_map| evaluate_coverage(coverage_map, &capability_index));
+
+    if let Some(dot_path) = &cli.dot_path {
+        let coverage_map = coverage_map.as_ref().ok_or_else(|| {
+            anyhow!("cannot render --dot: coverage map failed to build, see orphans above")
+        })?;
+        let dot = render_coverage_dot(coverage_map);
+        fs::write(dot_path, dot)
+            .with_context(|| format!("writing coverage graph to {}", dot_path.display()))?;
+    }
+
+    match cli.format {
+        OutputFormat::Quiet => {}
+        OutputFormat::Human => print!("{}", render_human(&accounting, verdict.as_ref())),
+        OutputFormat::Json => {
+            let report = serde_json::json!({
+                "accounting": accounting,
+                "verdict": verdict,
+            });
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        OutputFormat::Jsonl => {
+            bail!("probe-coverage does not support --format jsonl (use json or human)")
+        }
+    }
+
+    let mut exit_nonzero = false;
+    if !accounting.orphans.is_empty() {
+        reporter::diagnostic(
+            verbosity,
+            &format!(
+                "probe-coverage: {} orphaned capability reference(s) found",
+                accounting.orphans.len()
+            ),
+        );
+        exit_nonzero = true;
+    }
+    if let Some(verdict) = &verdict {
+        if verdict.should_fail {
+            reporter::diagnostic(
+                verbosity,
+                "probe-coverage: at least one uncovered Critical capability found",
+            );
+            exit_nonzero = true;
+        }
+    }
+    if exit_nonzero {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn render_human(accounting: &ProbeCoverageAccounting, verdict: Option<&CoverageVerdict>) -> String {
+    let total = accounting.probe_counts.len();
+    let covered = total - accounting.gaps.len();
+    let mut out = format!("capabilities covered: {covered}/{total}\n");
+    out.push_str(&format!(
+        "probe counts   : {}\n",
+        format_counts(&accounting.probe_counts, "none")
+    ));
+
+    if accounting.gaps.is_empty() {
+        out.push_str("gaps           : none\n");
+    } else {
+        out.push_str(&format!(
+            "gaps           : {}\n",
+            accounting.gaps.join(", ")
+        ));
+    }
+
+    if accounting.orphans.is_empty() {
+        out.push_str("orphans        : none\n");
+    } else {
+        out.push_str("orphans        :\n");
+        for (id, probe_ids) in &accounting.orphans {
+            out.push_str(&format!("  {id}: {}\n", probe_ids.join(", ")));
+        }
+    }
+
+    if accounting.unresolved_scripts.is_empty() {
+        out.push_str("unresolved     : none\n");
+    } else {
+        out.push_str("unresolved     :\n");
+        for script in &accounting.unresolved_scripts {
+            out.push_str(&format!("  {}\n", script.display()));
+        }
+    }
+
+    match verdict {
+        Some(verdict) if verdict.severities.is_empty() => {
+            out.push_str("verdict        : pass\n");
+        }
+        Some(verdict) => {
+            out.push_str(&format!(
+                "verdict        : {}\n",
+                if verdict.should_fail { "FAIL" } else { "warn" }
+            ));
+            for (id, severity) in &verdict.severities {
+                out.push_str(&format!("  {id}: {severity:?}\n"));
+            }
+        }
+        None => out.push_str("verdict        : unavailable (see orphans above)\n"),
+    }
+
+    out
+}
+
+struct Cli {
+    catalog_path: Option<PathBuf>,
+    probes_root: Option<PathBuf>,
+    format: OutputFormat,
+    quiet: bool,
+    verbose: bool,
+    dot_path: Option<PathBuf>,
+}
+
+impl Cli {
+    fn parse() -> Result<Self> {
+        let mut args = env::args_os();
+        let _program = args.next();
+
+        let mut catalog_path = None;
+        let mut probes_root = None;
+        let mut format = OutputFormat::Human;
+        let mut quiet = false;
+        let mut verbose = false;
+        let mut dot_path = None;
+
+        while let Some(arg) = args.next() {
+            let arg_str = arg
+                .to_str()
+                .ok_or_else(|| anyhow!("invalid UTF-8 in argument"))?;
+            match arg_str {
+                "--catalog" => catalog_path = Some(next_path("--catalog", &mut args)?),
+                "--probes-root" => probes_root = Some(next_path("--probes-root", &mut args)?),
+                "--format" => format = OutputFormat::parse(&next_value("--format", &mut args)?)?,
+                "--quiet" => quiet = true,
+                "--verbose" => verbose = true,
+                "--dot" => dot_path = Some(next_path("--dot", &mut args)?),
+                "--help" | "-h" => usage(0),
+                other => bail!("unknown argument: {other}"),
+            }
+        }
+
+        Ok(Self {
+            catalog_path,
+            probes_root,
+            format,
+            quiet,
+            verbose,
+            dot_path,
+        })
+    }
+}
+
+fn next_value(flag: &str, args: &mut env::ArgsOs) -> Result<String> {
+    let value = args
+        .next()
+        .ok_or_else(|| anyhow!("{flag} requires a value"))?;
+    value
+        .into_string()
+        .map_err(|_| anyhow!("{flag} must be valid UTF-8"))
+}
+
+fn next_path(flag: &str, args: &mut env::ArgsOs) -> Result<PathBuf> {
+    Ok(PathBuf::from(next_value(flag, args)?))
+}
+
+fn usage(code: i32) -> ! {
+    eprintln!(
+        "Usage: probe-coverage [options]\n\nOptions:\n  --catalog PATH       Override capability catalog path (or set CATALOG_PATH).\n  --probes-root PATH   Override the probes/ directory to scan (default: repo_root/probes).\n  --format FORMAT      Report output format: human (default), json, or quiet.\n  --dot PATH           Write the capability->probe coverage graph as Graphviz DOT to PATH.\n  --quiet              Suppress stderr diagnostics.\n  --verbose            Print extra stderr diagnostics.\n  --help               Show this help text.\n\nExits non-zero when any probe references a CapabilityId absent from the catalog, or when an uncovered Critical capability is found."
+    );
+    std::process::exit(code);
+}
@@ -0,0 +1,173 @@
+//! Diffs a `baseline`-mode boundary-record stream against a sandboxed-mode
+//! (`target`) stream from the same matrix run, to answer "where did the
+//! sandbox actually bite?" instead of leaving the two NDJSON streams
+//! disjoint.
+//!
+//! Unlike `probe --baseline` (which gates a fresh run against a *committed*
+//! snapshot over time), `probe-diff` joins two runs from the *same* matrix
+//! invocation on `(probe.id, operation.category, operation.verb,
+//! operation.target, probe.primary_capability_id)` and classifies each
+//! matched pair's `result.observed_result` transition, grouped by capability
+//! id (see [`fencerunner::boundary_diff`]).
+//!
+//!     probe --matrix | ... > baseline.ndjson   # run with MODES=baseline
+//!     probe --matrix | ... > target.ndjson     # run with MODES=container
+//!     probe-diff --baseline-file baseline.ndjson --target-file target.ndjson
+
+use anyhow::{Context, Result, anyhow, bail};
+use fencerunner::boundary_diff::{diff_boundary_streams, render_diff_human};
+use fencerunner::reporter::{self, OutputFormat, Verbosity};
+use fencerunner::{
+    BoundaryObject, BoundarySchema, CapabilityIndex, find_repo_root, read_boundary_objects,
+    resolve_boundary_schema_path, resolve_catalog_path,
+};
+use std::env;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("{err:#}");
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<()> {
+    let cli = Cli::parse()?;
+    let verbosity = Verbosity::from_flags(cli.quiet, cli.verbose)?;
+    let repo_root = find_repo_root()?;
+    let catalog_path = resolve_catalog_path(&repo_root, cli.catalog_path.as_deref());
+    let capability_index = CapabilityIndex::load(&catalog_path)
+        .with_context(|| format!("loading capability catalog from {}", catalog_path.display()))?;
+    let boundary_schema_path =
+        resolve_boundary_schema_path(&repo_root, cli.boundary_schema_path.as_deref())?;
+    let boundary_schema = BoundarySchema::load(&boundary_schema_path).with_context(|| {
+        format!(
+            "loading boundary schema from {}",
+            boundary_schema_path.display()
+        )
+    })?;
+
+    let baseline = load_records(&cli.baseline_path, &boundary_schema)?;
+    let target = load_records(&cli.target_path, &boundary_schema)?;
+
+    let report = diff_boundary_streams(&baseline, &target, Some(&capability_index));
+
+    match cli.format {
+        OutputFormat::Quiet => {}
+        OutputFormat::Human => print!("{}", render_diff_human(&report)),
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        OutputFormat::Jsonl => {
+            for summary in &report.capabilities {
+                println!("{}", serde_json::to_string(summary)?);
+            }
+            for unpaired in &report.unpaired {
+                println!("{}", serde_json::to_string(unpaired)?);
+            }
+        }
+    }
+
+    if report.leak_count > 0 {
+        reporter::diagnostic(
+            verbosity,
+            &format!(
+                "probe-diff: {} capability leak(s) detected",
+                report.leak_count
+            ),
+        );
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn load_records(path: &Path, boundary_schema: &BoundarySchema) -> Result<Vec<BoundaryObject>> {
+    let file =
+        File::open(path).with_context(|| format!("opening boundary stream {}", path.display()))?;
+    let records = read_boundary_objects(BufReader::new(file))
+        .map_err(|err| anyhow!(err))
+        .with_context(|| format!("reading boundary stream {}", path.display()))?;
+    for record in &records {
+        let value = serde_json::to_value(record)?;
+        boundary_schema
+            .validate(&value)
+            .map_err(|err| anyhow!(err.to_string()))
+            .with_context(|| format!("validating {}", path.display()))?;
+    }
+    Ok(records)
+}
+
+struct Cli {
+    baseline_path: PathBuf,
+    target_path: PathBuf,
+    catalog_path: Option<PathBuf>,
+    boundary_schema_path: Option<PathBuf>,
+    format: OutputFormat,
+    quiet: bool,
+    verbose: bool,
+}
+
+impl Cli {
+    fn parse() -> Result<Self> {
+        let mut args = env::args_os();
+        let _program = args.next();
+
+        let mut baseline_path = None;
+        let mut target_path = None;
+        let mut catalog_path = None;
+        let mut boundary_schema_path = None;
+        let mut format = OutputFormat::Human;
+        let mut quiet = false;
+        let mut verbose = false;
+
+        while let Some(arg) = args.next() {
+            let arg_str = arg
+                .to_str()
+                .ok_or_else(|| anyhow!("invalid UTF-8 in argument"))?;
+            match arg_str {
+                "--baseline-file" => baseline_path = Some(next_path("--baseline-file", &mut args)?),
+                "--target-file" => target_path = Some(next_path("--target-file", &mut args)?),
+                "--catalog" => catalog_path = Some(next_path("--catalog", &mut args)?),
+                "--boundary" => boundary_schema_path = Some(next_path("--boundary", &mut args)?),
+                "--format" => format = OutputFormat::parse(&next_value("--format", &mut args)?)?,
+                "--quiet" => quiet = true,
+                "--verbose" => verbose = true,
+                "--help" | "-h" => usage(0),
+                other => bail!("unknown argument: {other}"),
+            }
+        }
+
+        Ok(Self {
+            baseline_path: baseline_path
+                .ok_or_else(|| anyhow!("--baseline-file PATH is required"))?,
+            target_path: target_path.ok_or_else(|| anyhow!("--target-file PATH is required"))?,
+            catalog_path,
+            boundary_schema_path,
+            format,
+            quiet,
+            verbose,
+        })
+    }
+}
+
+fn next_value(flag: &str, args: &mut env::ArgsOs) -> Result<String> {
+    let value = args
+        .next()
+        .ok_or_else(|| anyhow!("{flag} requires a value"))?;
+    value
+        .into_string()
+        .map_err(|_| anyhow!("{flag} must be valid UTF-8"))
+}
+
+fn next_path(flag: &str, args: &mut env::ArgsOs) -> Result<PathBuf> {
+    Ok(PathBuf::from(next_value(flag, args)?))
+}
+
+fn usage(code: i32) -> ! {
+    eprintln!(
+        "Usage: probe-diff --baseline-file PATH --target-file PATH [options]\n\nOptions:\n  --baseline-file PATH      Boundary-record NDJSON from the baseline-mode run.\n  --target-file PATH        Boundary-record NDJSON from the sandboxed-mode run.\n  --catalog PATH            Override capability catalog path (or set CATALOG_PATH).\n  --boundary PATH           Override boundary-object schema path (or set BOUNDARY_PATH).\n  --format FORMAT           Report output format: jsonl, json, human (default), or quiet.\n  --quiet                   Suppress stderr diagnostics.\n  --verbose                 Print extra stderr diagnostics.\n  --help                    Show this help text.\n\nExits non-zero when any capability leaked (succeeded in both baseline and target)."
+    );
+    std::process::exit(code);
+}
@@ -5,21 +5,35 @@
 //! - export probe-facing environment expected by probe scripts and `emit-record`
 //! - honor workspace overrides without silently falling back to host defaults
 
-use anyhow::{Context, Result, bail};
+use anyhow::{Context, Result, anyhow, bail};
 use fencerunner::connectors::{CommandSpec, RunMode, plan_for_mode};
+use fencerunner::emit_support::{PayloadArgs, TextSource, validate_status};
+use fencerunner::enforcement::{EnforcementPlan, Platform};
+use fencerunner::execution_backend::{
+    BackendKind, BackendRequest, DEFAULT_BACKEND, ExecutionBackend, allowed_backend_names,
+    backend_for,
+};
+use fencerunner::execution_trace::{capture_direct, trace_requested, wrap_without_ptrace};
 use fencerunner::fence_run_support::{
-    WorkspaceOverride, WorkspacePlan, canonicalize_path, resolve_probe_metadata,
+    ContainmentPolicy, ContainmentViolation, ResolvedProbeMetadata, WorkspaceOverride,
+    WorkspacePlan, canonicalize_path, reject_containment_violation, resolve_probe_metadata,
     workspace_plan_from_override, workspace_tmpdir_plan,
 };
+use fencerunner::handshake::VersionInfo;
+use fencerunner::problem_matcher::{MatchedFields, ProblemMatcher, first_match};
+use fencerunner::runtime::{CommandLogSpan, Verbosity};
 use fencerunner::{
-    ProbeMetadata, find_repo_root, resolve_boundary_schema_path, resolve_catalog_path,
+    BoundarySchema, CapabilityId, CapabilityIndex, CapabilitySnapshot, ProbeMetadata, StackInfo,
+    find_repo_root, resolve_boundary_schema_path, resolve_catalog_path, resolve_helper_binary,
     resolve_probe,
 };
+use serde_json::{Value, json};
 use std::env;
 use std::ffi::OsString;
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
+use std::process::{Command, Output, Stdio};
 
 fn main() {
     if let Err(err) = run() {
@@ -33,17 +47,65 @@ fn run() -> Result<()> {
     let repo_root = find_repo_root()?;
     let catalog_path = resolve_catalog_path(&repo_root, args.catalog_path.as_deref());
     let boundary_path = resolve_boundary_schema_path(&repo_root, args.boundary_path.as_deref())?;
+
+    if args.protocol_version_query {
+        return print_protocol_version(&catalog_path, &boundary_path);
+    }
+
     let workspace_root = canonicalize_path(&repo_root);
-    let workspace_plan = determine_workspace_plan(&workspace_root, args.workspace_override)?;
+    let containment_policy = ContainmentPolicy::repo_root(&workspace_root);
+    let workspace_plan =
+        determine_workspace_plan(&workspace_root, args.workspace_override, &containment_policy)?;
+    reject_containment_violation("workspace root", workspace_plan.containment_error.as_ref())?;
     let resolved_probe = resolve_probe(&workspace_root, &args.probe_name)?;
     let parsed_metadata = ProbeMetadata::from_script(&resolved_probe.path)?;
-    let _resolved_metadata = resolve_probe_metadata(&resolved_probe, parsed_metadata)?;
+    let problem_matchers = parsed_metadata
+        .problem_matchers
+        .iter()
+        .map(|pattern| ProblemMatcher::compile(pattern))
+        .collect::<Result<Vec<_>>>()?;
+    let secondary_capabilities = parsed_metadata.secondary_capabilities.clone();
+    let resolved_metadata = resolve_probe_metadata(&resolved_probe, parsed_metadata)?;
     ensure_probe_executable(&resolved_probe.path)?;
-    let workspace_tmpdir = workspace_tmpdir_plan(&workspace_plan, &workspace_root);
+    let workspace_tmpdir =
+        workspace_tmpdir_plan(&workspace_plan, &workspace_root, &containment_policy);
+    reject_containment_violation(
+        "workspace tmpdir",
+        workspace_tmpdir.containment_error.as_ref(),
+    )?;
     let command_cwd = command_cwd_for(&workspace_plan, &workspace_root);
 
-    let platform = detect_platform().unwrap_or_else(|| env::consts::OS.to_string());
-    let mode_plan = plan_for_mode(&args.run_mode, &platform, &resolved_probe.path, None)?;
+    // Mode availability/isolation gating now goes through `connectors`'s own
+    // cfg(...) evaluator (see `host_cfg_map`), so this no longer needs to
+    // shell out to `uname` itself; the platform argument only remains for
+    // callers that still pass one explicitly.
+    let mode_plan = plan_for_mode(&args.run_mode, env::consts::OS, &resolved_probe.path, None)?;
+
+    // Loaded eagerly (even when no problem matchers are declared) to keep
+    // this path's shape consistent with `emit-record`'s; only needed again if
+    // a matcher actually fires and a record must be synthesized/validated.
+    let capability_index = CapabilityIndex::load(&catalog_path)
+        .with_context(|| format!("loading capability catalog {}", catalog_path.display()))?;
+    let boundary_schema = BoundarySchema::load(&boundary_path)
+        .with_context(|| format!("loading boundary schema {}", boundary_path.display()))?;
+
+    let matcher_context = MatcherContext {
+        repo_root: repo_root.clone(),
+        run_mode: mode_plan.run_mode.as_str().to_string(),
+        workspace_root: workspace_plan
+            .export_value
+            .as_ref()
+            .map(|value| value.to_string_lossy().into_owned()),
+        capability_index,
+        boundary_schema,
+        problem_matchers,
+        resolved_metadata,
+        secondary_capabilities,
+    };
+
+    let backend = backend_for(args.backend);
+    let trace = trace_requested(args.trace);
+    let verbosity = Verbosity::resolve(args.verbose);
 
     run_command(
         mode_plan.command,
@@ -54,16 +116,56 @@ fn run() -> Result<()> {
         &command_cwd,
         &catalog_path,
         &boundary_path,
+        backend.as_ref(),
+        args.backend,
+        trace,
+        verbosity,
+        &resolved_probe.id,
+        &matcher_context,
     )?;
     Ok(())
 }
 
+/// Context the problem-matcher fallback needs to synthesize and validate a
+/// boundary object in place of a probe's unparseable stdout.
+struct MatcherContext {
+    repo_root: PathBuf,
+    run_mode: String,
+    workspace_root: Option<String>,
+    capability_index: CapabilityIndex,
+    boundary_schema: BoundarySchema,
+    problem_matchers: Vec<ProblemMatcher>,
+    resolved_metadata: ResolvedProbeMetadata,
+    secondary_capabilities: Vec<CapabilityId>,
+}
+
+/// Answer a `--protocol-version` query with this binary's [`VersionInfo`]
+/// advertisement, resolved against the catalog/boundary schema it would
+/// otherwise use to run a probe. `probe-matrix` invokes this before running
+/// the matrix to negotiate compatibility.
+fn print_protocol_version(catalog_path: &Path, boundary_path: &Path) -> Result<()> {
+    let capabilities = CapabilityIndex::load(catalog_path)
+        .with_context(|| format!("loading capability catalog {}", catalog_path.display()))?;
+    let boundary_schema = BoundarySchema::load(boundary_path)
+        .with_context(|| format!("loading boundary schema {}", boundary_path.display()))?;
+    let info = VersionInfo::current(boundary_schema.schema_key(), &capabilities.key().0);
+    println!(
+        "{}",
+        serde_json::to_string(&info).context("serializing VersionInfo")?
+    );
+    Ok(())
+}
+
 struct CliArgs {
     workspace_override: Option<WorkspaceOverride>,
     catalog_path: Option<PathBuf>,
     boundary_path: Option<PathBuf>,
+    backend: BackendKind,
+    trace: bool,
+    verbose: u32,
     run_mode: String,
     probe_name: String,
+    protocol_version_query: bool,
 }
 
 impl CliArgs {
@@ -72,9 +174,29 @@ impl CliArgs {
         let mut workspace_override = None;
         let mut catalog_path = None;
         let mut boundary_path = None;
+        let mut backend = DEFAULT_BACKEND;
+        let mut trace = false;
+        let mut verbose = 0;
+        let mut protocol_version_query = false;
         let mut positionals = Vec::new();
 
         while let Some(arg) = args_iter.next() {
+            if arg == "--protocol-version" {
+                protocol_version_query = true;
+                continue;
+            }
+            if arg == "--trace" {
+                trace = true;
+                continue;
+            }
+            if arg == "-v" || arg == "--verbose" {
+                verbose += 1;
+                continue;
+            }
+            if arg == "-vv" {
+                verbose += 2;
+                continue;
+            }
             if arg.starts_with("--workspace-root=") {
                 let value = arg.split_once('=').map(|(_, v)| v).unwrap_or("");
                 workspace_override = Some(parse_workspace_override(value));
@@ -90,6 +212,11 @@ impl CliArgs {
                 boundary_path = Some(PathBuf::from(value));
                 continue;
             }
+            if arg.starts_with("--backend=") {
+                let value = arg.split_once('=').map(|(_, v)| v).unwrap_or("");
+                backend = parse_backend(value);
+                continue;
+            }
 
             match arg.as_str() {
                 "--workspace-root" => {
@@ -113,6 +240,13 @@ impl CliArgs {
                     });
                     boundary_path = Some(PathBuf::from(value));
                 }
+                "--backend" => {
+                    let value = args_iter.next().unwrap_or_else(|| {
+                        eprintln!("Missing name for --backend");
+                        usage();
+                    });
+                    backend = parse_backend(&value);
+                }
                 "-h" | "--help" => usage(),
                 _ if arg.starts_with("--") => {
                     eprintln!("Unknown option: {arg}");
@@ -126,6 +260,20 @@ impl CliArgs {
             }
         }
 
+        if protocol_version_query {
+            return Ok(Self {
+                workspace_override,
+                catalog_path,
+                boundary_path,
+                backend,
+                trace,
+                verbose,
+                run_mode: String::new(),
+                probe_name: String::new(),
+                protocol_version_query: true,
+            });
+        }
+
         if positionals.len() != 2 {
             usage();
         }
@@ -134,15 +282,31 @@ impl CliArgs {
             workspace_override,
             catalog_path,
             boundary_path,
+            backend,
+            trace,
+            verbose,
             run_mode: positionals[0].clone(),
             probe_name: positionals[1].clone(),
+            protocol_version_query: false,
         })
     }
 }
 
+/// Validate a `--backend` value up front so an unsupported name fails fast
+/// with the allowed list rather than surfacing later as an opaque spawn error.
+fn parse_backend(value: &str) -> BackendKind {
+    BackendKind::try_from(value).unwrap_or_else(|_| {
+        eprintln!(
+            "Unknown backend '{value}' (expected one of: {})",
+            allowed_backend_names().join(", ")
+        );
+        usage();
+    })
+}
+
 fn usage() -> ! {
     eprintln!(
-        "Usage: probe-exec [--workspace-root PATH] [--catalog PATH] [--boundary PATH] MODE PROBE_NAME\n\nOverrides:\n  --workspace-root PATH     Export PATH via FENCE_WORKSPACE_ROOT (defaults to repo root).\n                            Pass an empty string to defer to emit-record's git/pwd fallback.\n  --catalog PATH            Override capability catalog path (or set CATALOG_PATH).\n  --boundary PATH           Override boundary-object schema path (or set BOUNDARY_PATH).\n\nEnvironment:\n  FENCE_WORKSPACE_ROOT      When set, takes precedence over the default repo root export."
+        "Usage: probe-exec [--workspace-root PATH] [--catalog PATH] [--boundary PATH] [--backend NAME] [--trace] [-v|-vv] MODE PROBE_NAME\n       probe-exec --protocol-version\n\nOverrides:\n  --workspace-root PATH     Export PATH via FENCE_WORKSPACE_ROOT (defaults to repo root).\n                            Pass an empty string to defer to emit-record's git/pwd fallback.\n  --catalog PATH            Override capability catalog path (or set CATALOG_PATH).\n  --boundary PATH           Override boundary-object schema path (or set BOUNDARY_PATH).\n  --backend NAME            How to launch the probe: direct (default), namespace, or oci.\n  --trace                   Capture an execution trace (see FENCE_TRACE) and print it as a\n                            second NDJSON line after the probe's boundary object.\n  -v, --verbose             Log the resolved command, argv, cwd, run mode, and fence env\n                            before spawning, plus elapsed time/exit code after (repeatable;\n                            -vv or a second -v also prints captured stdout/stderr).\n  --protocol-version        Print this helper's VersionInfo as JSON and exit.\n\nEnvironment:\n  FENCE_WORKSPACE_ROOT      When set, takes precedence over the default repo root export.\n  FENCE_TRACE               Set to `1` to request tracing the same way --trace does.\n  FENCE_LOG                 Set command-log verbosity (0/1/2 or quiet/verbose/debug)\n                            independent of -v; the more detailed of the two wins."
     );
     std::process::exit(1);
 }
@@ -158,11 +322,12 @@ fn parse_workspace_override(value: &str) -> WorkspaceOverride {
 fn determine_workspace_plan(
     default_root: &Path,
     cli_override: Option<WorkspaceOverride>,
+    policy: &ContainmentPolicy,
 ) -> Result<WorkspacePlan> {
     // CLI override wins; otherwise honor FENCE_WORKSPACE_ROOT if set, and only
     // then fall back to the repo root.
     if let Some(override_value) = cli_override {
-        return Ok(workspace_plan_from_override(override_value));
+        return Ok(workspace_plan_from_override(override_value, policy));
     }
 
     let env_override = ["FENCE_WORKSPACE_ROOT"]
@@ -174,12 +339,16 @@ fn determine_workspace_plan(
         });
 
     if let Some(value) = env_override {
-        return Ok(workspace_plan_from_override(value));
+        return Ok(workspace_plan_from_override(value, policy));
     }
 
-    Ok(WorkspacePlan {
-        export_value: Some(default_root.as_os_str().to_os_string()),
-    })
+    // The default root is the containment policy's own repo root, trusted by
+    // construction, so it goes through `TrustedPath` rather than `UsePath`'s
+    // redundant self-check.
+    Ok(workspace_plan_from_override(
+        WorkspaceOverride::TrustedPath(default_root.as_os_str().to_os_string()),
+        policy,
+    ))
 }
 
 /// Pick the working directory for probe execution. Prefer the exported workspace
@@ -216,20 +385,14 @@ fn has_execute_bit(metadata: &fs::Metadata) -> bool {
     }
 }
 
-fn detect_platform() -> Option<String> {
-    let output = Command::new("uname")
-        .arg("-s")
-        .stdout(Stdio::piped())
-        .stderr(Stdio::null())
-        .output()
-        .ok()?;
-    if !output.status.success() {
-        return None;
-    }
-    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    if value.is_empty() { None } else { Some(value) }
-}
-
+/// Run the probe command through the selected [`ExecutionBackend`],
+/// capturing its stdout/stderr so a declared problem matcher can salvage
+/// non-JSON output (see [`emit_probe_output`]) before forwarding them and
+/// exiting with the probe's own status code. When tracing was requested, an
+/// [`fencerunner::execution_trace::ExecutionTrace`] is captured alongside and
+/// printed as a second NDJSON line after the probe's own output. A
+/// [`CommandLogSpan`] brackets the spawn itself so `-v`/`FENCE_LOG` users see
+/// what was planned and how it finished.
 fn run_command(
     spec: CommandSpec,
     run_mode: &RunMode,
@@ -239,32 +402,293 @@ fn run_command(
     command_cwd: &Path,
     catalog_path: &Path,
     boundary_path: &Path,
+    backend: &dyn ExecutionBackend,
+    backend_kind: BackendKind,
+    trace: bool,
+    verbosity: Verbosity,
+    probe_id: &str,
+    matcher_context: &MatcherContext,
 ) -> Result<()> {
-    let mut command = Command::new(&spec.program);
-    for arg in &spec.args {
-        command.arg(arg);
-    }
-    command.current_dir(command_cwd);
-    command.env("FENCE_RUN_MODE", run_mode.as_str());
-    command.env("FENCE_SANDBOX_MODE", sandbox_mode);
-    command.env("CATALOG_PATH", catalog_path);
-    command.env("BOUNDARY_PATH", boundary_path);
+    let mut env: Vec<(OsString, OsString)> = vec![
+        (
+            OsString::from("FENCE_RUN_MODE"),
+            OsString::from(run_mode.as_str()),
+        ),
+        (OsString::from("FENCE_SANDBOX_MODE"), sandbox_mode.clone()),
+        (
+            OsString::from("CATALOG_PATH"),
+            catalog_path.as_os_str().to_os_string(),
+        ),
+        (
+            OsString::from("BOUNDARY_PATH"),
+            boundary_path.as_os_str().to_os_string(),
+        ),
+    ];
     if let Some(value) = workspace_plan.export_value.as_ref() {
-        command.env("FENCE_WORKSPACE_ROOT", value);
+        env.push((OsString::from("FENCE_WORKSPACE_ROOT"), value.clone()));
     }
     if let Some(tmpdir) = workspace_tmpdir {
-        command.env("TMPDIR", tmpdir);
+        env.push((OsString::from("TMPDIR"), tmpdir.as_os_str().to_os_string()));
     }
 
-    let status = command
-        .status()
-        .with_context(|| format!("Failed to execute {}", spec.program.to_string_lossy()))?;
-    if !status.success() {
-        if let Some(code) = status.code() {
-            std::process::exit(code);
-        } else {
-            bail!("Probe terminated by signal");
-        }
+    let workspace_root = workspace_plan.export_value.as_ref().map(PathBuf::from);
+    let command_display = command_display(&spec);
+    let enforcement_plan = namespace_enforcement_plan(backend_kind, matcher_context)?;
+    let request = BackendRequest {
+        command: &spec,
+        command_cwd,
+        env: &env,
+        workspace_root: workspace_root.as_deref(),
+        enforcement_plan: enforcement_plan.as_ref(),
+    };
+
+    let log_span = CommandLogSpan::start(
+        verbosity,
+        &spec.program,
+        &spec.args,
+        command_cwd,
+        run_mode.as_str(),
+        &env,
+    );
+
+    let (output, trace_record) = if trace && backend_kind == BackendKind::Direct {
+        let (output, trace) = capture_direct(
+            &spec.program,
+            &spec.args,
+            &env,
+            command_cwd,
+            probe_id,
+            run_mode.as_str(),
+        )?;
+        (output, Some(trace))
+    } else if trace {
+        let (output, trace) = wrap_without_ptrace(
+            &spec.program,
+            &spec.args,
+            &env,
+            probe_id,
+            run_mode.as_str(),
+            || backend.run(&request),
+        )?;
+        (output, Some(trace))
+    } else {
+        (backend.run(&request)?, None)
+    };
+    let code = output.status.code();
+    log_span.finish(code, &output.stdout, &output.stderr);
+
+    emit_probe_output(&output, matcher_context, &command_display)?;
+    if let Some(trace_record) = trace_record.as_ref() {
+        let mut line = serde_json::to_vec(trace_record).context("serializing execution trace")?;
+        line.push(b'\n');
+        std::io::stdout().write_all(&line)?;
+    }
+
+    if output.status.success() {
+        return Ok(());
     }
+    if let Some(code) = code {
+        std::process::exit(code);
+    }
+    bail!("Probe terminated by signal")
+}
+
+/// The Linux capability set the `namespace` backend should enforce for this
+/// probe, derived by lowering its primary capability (see
+/// [`fencerunner::enforcement`]). Only the `namespace` backend enforces
+/// capabilities today, so other backends get `None` rather than computing a
+/// plan nothing will read. A capability with no known Linux lowering (e.g.
+/// one that only grounds on macOS) falls back to an empty plan, which drops
+/// every capability — the module's fail-closed default, not an error.
+fn namespace_enforcement_plan(
+    backend_kind: BackendKind,
+    ctx: &MatcherContext,
+) -> Result<Option<EnforcementPlan>> {
+    if backend_kind != BackendKind::Namespace {
+        return Ok(None);
+    }
+    let primary_capability = ctx
+        .capability_index
+        .capability(&ctx.resolved_metadata.primary_capability)
+        .ok_or_else(|| {
+            anyhow!(
+                "Unable to resolve capability metadata for {}",
+                ctx.resolved_metadata.primary_capability.0
+            )
+        })?;
+    let plan = primary_capability
+        .snapshot()
+        .lower(Platform::Linux)
+        .unwrap_or_default();
+    Ok(Some(plan))
+}
+
+fn command_display(spec: &CommandSpec) -> String {
+    let mut parts = vec![spec.program.to_string_lossy().into_owned()];
+    parts.extend(
+        spec.args
+            .iter()
+            .map(|arg| arg.to_string_lossy().into_owned()),
+    );
+    parts.join(" ")
+}
+
+/// Decide what to print on this binary's own stdout/stderr for a completed
+/// probe run: the probe's own JSON unchanged when it parses, a record
+/// synthesized from a declared problem matcher when it doesn't, or (when no
+/// matcher fires, or the synthesized record fails validation) the probe's
+/// original unparseable bytes unchanged so `probe-matrix`'s existing
+/// malformed-probe handling still applies.
+fn emit_probe_output(output: &Output, ctx: &MatcherContext, command: &str) -> Result<()> {
+    let stdout_to_print = if serde_json::from_slice::<Value>(&output.stdout).is_ok() {
+        output.stdout.clone()
+    } else {
+        let stdout_text = String::from_utf8_lossy(&output.stdout);
+        let stderr_text = String::from_utf8_lossy(&output.stderr);
+        let combined = format!("{stdout_text}{stderr_text}");
+
+        match first_match(&ctx.problem_matchers, &combined) {
+            Some(matched) => {
+                match build_fallback_record(
+                    ctx,
+                    &matched,
+                    &stdout_text,
+                    &stderr_text,
+                    command,
+                    output.status.code().map(i64::from),
+                ) {
+                    Ok(record) => {
+                        let mut line = serde_json::to_vec(&record)
+                            .context("serializing synthesized boundary object")?;
+                        line.push(b'\n');
+                        line
+                    }
+                    Err(err) => {
+                        eprintln!(
+                            "probe-exec: problem matcher fired but record synthesis failed: {err:#}"
+                        );
+                        output.stdout.clone()
+                    }
+                }
+            }
+            None => output.stdout.clone(),
+        }
+    };
+
+    std::io::stdout().write_all(&stdout_to_print)?;
+    std::io::stderr().write_all(&output.stderr)?;
     Ok(())
 }
+
+/// Synthesize a boundary object from a matched problem-matcher line, filling
+/// run/probe context from the probe's already-resolved metadata, and
+/// validating the result against the boundary schema before handing it back.
+/// Captured stdout/stderr are preserved verbatim under `payload.raw`.
+fn build_fallback_record(
+    ctx: &MatcherContext,
+    matched: &MatchedFields,
+    stdout_text: &str,
+    stderr_text: &str,
+    command: &str,
+    raw_exit_code: Option<i64>,
+) -> Result<Value> {
+    validate_status(&matched.status)?;
+
+    let primary_capability = ctx
+        .capability_index
+        .capability(&ctx.resolved_metadata.primary_capability)
+        .ok_or_else(|| {
+            anyhow!(
+                "Unable to resolve capability metadata for {}",
+                ctx.resolved_metadata.primary_capability.0
+            )
+        })?;
+    let secondary_capabilities = ctx
+        .secondary_capabilities
+        .iter()
+        .map(|id| {
+            ctx.capability_index
+                .capability(id)
+                .ok_or_else(|| anyhow!("Unable to resolve capability metadata for {}", id.0))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let detect_stack = resolve_helper_binary(&ctx.repo_root, "detect-stack")?;
+    let stack_raw = run_command_json(&detect_stack, &[&ctx.run_mode])
+        .with_context(|| format!("Failed to execute {}", detect_stack.display()))?;
+    let stack: StackInfo = serde_json::from_value(stack_raw)
+        .context("detect-stack emitted JSON that does not match the current stack schema")?;
+
+    let schema_key = ctx.boundary_schema.schema_key().ok_or_else(|| {
+        anyhow!("boundary schema is missing a schema_key; provide a descriptor file")
+    })?;
+
+    let mut payload_args = PayloadArgs::default();
+    payload_args.set_stdout(TextSource::Inline(stdout_text.to_string()))?;
+    payload_args.set_stderr(TextSource::Inline(stderr_text.to_string()))?;
+    payload_args
+        .raw_mut()
+        .insert_string("stdout".to_string(), stdout_text.to_string());
+    payload_args
+        .raw_mut()
+        .insert_string("stderr".to_string(), stderr_text.to_string());
+    let payload = payload_args.build()?;
+
+    let primary_capability_snapshot = primary_capability.snapshot();
+    let secondary_capability_snapshots: Vec<CapabilitySnapshot> = secondary_capabilities
+        .iter()
+        .map(|cap| cap.snapshot())
+        .collect();
+
+    let record = json!({
+        "schema_version": ctx.boundary_schema.schema_version(),
+        "schema_key": schema_key,
+        "capabilities_schema_version": ctx.capability_index.key(),
+        "stack": stack,
+        "probe": {
+            "id": ctx.resolved_metadata.id,
+            "version": ctx.resolved_metadata.version,
+            "primary_capability_id": ctx.resolved_metadata.primary_capability,
+            "secondary_capability_ids": ctx.secondary_capabilities,
+        },
+        "run": {
+            "mode": ctx.run_mode,
+            "workspace_root": ctx.workspace_root,
+            "command": command,
+        },
+        "operation": {
+            "category": matched.category,
+            "verb": matched.verb,
+            "target": matched.target,
+            "args": Value::Object(Default::default()),
+        },
+        "result": {
+            "observed_result": matched.status,
+            "raw_exit_code": raw_exit_code,
+            "errno": matched.errno,
+            "message": matched.message,
+            "error_detail": Value::Null,
+        },
+        "payload": payload,
+        "capability_context": {
+            "primary": primary_capability_snapshot,
+            "secondary": secondary_capability_snapshots,
+        }
+    });
+
+    ctx.boundary_schema.validate(&record)?;
+    Ok(record)
+}
+
+fn run_command_json(path: &Path, args: &[&str]) -> Result<Value> {
+    let output = Command::new(path)
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("{} failed: {stderr}", path.display());
+    }
+    serde_json::from_slice(&output.stdout).context("Failed to parse command output as JSON")
+}
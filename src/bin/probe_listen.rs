@@ -3,16 +3,29 @@
 //! This binary intentionally stays text-only so it can sit in pipelines like
 //! `probe --matrix | probe --listen`. It leans on the shared
 //! boundary reader so it understands the exact boundary schema without rolling
-//! bespoke parsers.
+//! bespoke parsers. Pass `--junit` to get a JUnit XML `<testsuites>` report
+//! instead, suitable for wiring into CI test-result collectors; the process
+//! exits nonzero whenever any record failed. Pass `--expect PATH` to turn the
+//! listener into a CI gate that checks every record against a spec file
+//! instead (see [`render_expectations_report`]). Pass `--rules PATH` to
+//! classify each record against a lint-style rule file and drive the exit
+//! code off the worst severity seen (see [`evaluate_rules`]). Pass
+//! `--filter EXPR` to narrow the record set before any of the above run
+//! (see [`parse_filter_expr`]). Pass `--format json` to get a single
+//! machine-readable object instead of the text summary (see
+//! [`render_json_report`]).
 
 use anyhow::{Context, Result, anyhow, bail};
 use fencerunner::{
-    BoundaryObject, BoundaryReadError, BoundarySchema, find_repo_root, read_boundary_objects,
-    resolve_boundary_schema_path,
+    BoundaryObject, BoundaryReadError, BoundarySchema, emit_support::prune_null_fields,
+    find_repo_root, read_boundary_objects, resolve_boundary_schema_path,
 };
+use regex::Regex;
+use serde::Deserialize;
 use std::collections::{BTreeMap, BTreeSet};
 use std::env;
 use std::fmt;
+use std::fs;
 use std::io::{self, BufRead, BufReader, IsTerminal};
 use std::path::{Path, PathBuf};
 
@@ -37,23 +50,72 @@ fn run() -> Result<()> {
     }
 
     let reader = BufReader::new(stdin.lock());
+    let mut records =
+        read_and_validate_records(reader, &boundary_schema).map_err(|err| match err {
+            ListenError::Boundary(inner) => anyhow!(inner),
+            ListenError::Validation(message) => anyhow!(message),
+            ListenError::Serialize(inner) => anyhow!(inner),
+            ListenError::Write(inner) => anyhow!(inner),
+            ListenError::ExpectationMismatch(_) => {
+                unreachable!("read_and_validate_records never returns ExpectationMismatch")
+            }
+            ListenError::Filter(_) => {
+                unreachable!("read_and_validate_records never returns Filter")
+            }
+        })?;
+
+    let original_total = records.len();
+    if let Some(filter_expr) = &cli.filter_expr {
+        let filter = parse_filter_expr(filter_expr).map_err(|message| anyhow!(message))?;
+        records.retain(|record| filter.matches(record));
+    }
+
     let mut output = String::new();
-    render_listen_output(reader, &mut output, &boundary_schema).map_err(|err| match err {
-        ListenError::Boundary(inner) => anyhow!(inner),
-        ListenError::Validation(message) => anyhow!(message),
-        ListenError::Serialize(inner) => anyhow!(inner),
-        ListenError::Write(inner) => anyhow!(inner),
-    })?;
+    if let Some(expect_path) = &cli.expect_path {
+        let expectations = load_expectations(expect_path)?;
+        let result = render_expectations_report(&records, &expectations, cli.strict, &mut output);
+        print!("{}", output);
+        return match result {
+            Ok(()) => Ok(()),
+            Err(ListenError::ExpectationMismatch(failed)) => {
+                eprintln!("{failed} record(s) failed expectations");
+                std::process::exit(1);
+            }
+            Err(ListenError::Write(inner)) => Err(anyhow!(inner)),
+            Err(_) => bail!("unexpected error rendering expectations report"),
+        };
+    }
+
+    if cli.junit {
+        render_junit_report(&records, &mut output).map_err(ListenError::Write)?;
+        print!("{}", output);
+        if records.iter().any(|record| !is_passing_result(record)) {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if cli.format == OutputFormat::Json {
+        render_json_report(&records, &mut output).map_err(ListenError::Write)?;
+        print!("{}", output);
+        return Ok(());
+    }
+
+    let rules = load_rules(cli.rules_path.as_deref())?;
+    let max_severity = render_records(&records, &rules, original_total, &mut output)
+        .map_err(ListenError::Write)?;
     print!("{}", output);
+    if let Some(severity) = max_severity {
+        std::process::exit(severity.exit_code());
+    }
     Ok(())
 }
 
-/// Read NDJSON from `reader`, summarize, and render into the provided writer.
-pub fn render_listen_output<R: BufRead, W: fmt::Write>(
+/// Read NDJSON from `reader` and validate every record against `boundary_schema`.
+fn read_and_validate_records<R: BufRead>(
     reader: R,
-    writer: &mut W,
     boundary_schema: &BoundarySchema,
-) -> Result<(), ListenError> {
+) -> Result<Vec<BoundaryObject>, ListenError> {
     let records = read_boundary_objects(reader).map_err(ListenError::Boundary)?;
     for record in &records {
         let value = serde_json::to_value(record).map_err(ListenError::Serialize)?;
@@ -61,20 +123,54 @@ pub fn render_listen_output<R: BufRead, W: fmt::Write>(
             .validate(&value)
             .map_err(|err| ListenError::Validation(err.to_string()))?;
     }
-    render_records(&records, writer).map_err(ListenError::Write)
+    Ok(records)
+}
+
+/// Read NDJSON from `reader`, optionally narrow it with `filter_expr` (see
+/// [`parse_filter_expr`]), then render into the provided writer as either the
+/// text summary or, under `OutputFormat::Json`, a single JSON report (see
+/// [`render_json_report`]).
+pub fn render_listen_output<R: BufRead, W: fmt::Write>(
+    reader: R,
+    writer: &mut W,
+    boundary_schema: &BoundarySchema,
+    filter_expr: Option<&str>,
+    format: OutputFormat,
+) -> Result<(), ListenError> {
+    let mut records = read_and_validate_records(reader, boundary_schema)?;
+    let original_total = records.len();
+    if let Some(expr) = filter_expr {
+        let filter = parse_filter_expr(expr).map_err(ListenError::Filter)?;
+        records.retain(|record| filter.matches(record));
+    }
+
+    if format == OutputFormat::Json {
+        return render_json_report(&records, writer).map_err(ListenError::Write);
+    }
+
+    render_records(&records, &[], original_total, writer)
+        .map(|_| ())
+        .map_err(ListenError::Write)
 }
 
 #[derive(Debug, Default)]
 struct ListenStats {
     total_records: usize,
+    original_total: usize,
     distinct_probes: usize,
     results: BTreeMap<String, usize>,
     modes: BTreeMap<String, usize>,
+    severities: BTreeMap<String, usize>,
 }
 
-fn summarize_records(records: &[BoundaryObject]) -> ListenStats {
+fn summarize_records(
+    records: &[BoundaryObject],
+    outcomes: &[Option<RuleOutcome>],
+    original_total: usize,
+) -> ListenStats {
     let mut stats = ListenStats::default();
     stats.total_records = records.len();
+    stats.original_total = original_total;
     stats.distinct_probes = records
         .iter()
         .map(|record| record.probe.id.as_str())
@@ -89,23 +185,56 @@ fn summarize_records(records: &[BoundaryObject]) -> ListenStats {
         *stats.modes.entry(record.run.mode.clone()).or_insert(0) += 1;
     }
 
+    for outcome in outcomes.iter().flatten() {
+        *stats
+            .severities
+            .entry(outcome.severity.as_str().to_string())
+            .or_insert(0) += 1;
+    }
+
     stats
 }
 
-fn render_records(records: &[BoundaryObject], writer: &mut impl fmt::Write) -> fmt::Result {
-    let stats = summarize_records(records);
+/// Render `records`, evaluating `rules` against each one, and return the
+/// highest severity matched across all records (if any) so the caller can
+/// drive the process exit code.
+fn render_records(
+    records: &[BoundaryObject],
+    rules: &[Rule],
+    original_total: usize,
+    writer: &mut impl fmt::Write,
+) -> Result<Option<Severity>, fmt::Error> {
+    let outcomes: Vec<Option<RuleOutcome>> = records
+        .iter()
+        .map(|record| evaluate_rules(record, rules))
+        .collect();
+
+    let stats = summarize_records(records, &outcomes, original_total);
     render_summary(&stats, writer)?;
     writeln!(writer)?;
-    for (idx, record) in records.iter().enumerate() {
-        render_record(idx + 1, record, writer)?;
+    for (idx, (record, outcome)) in records.iter().zip(outcomes.iter()).enumerate() {
+        render_record(idx + 1, record, outcome.as_ref(), writer)?;
     }
-    Ok(())
+
+    Ok(outcomes
+        .iter()
+        .flatten()
+        .map(|outcome| outcome.severity)
+        .max())
 }
 
 fn render_summary(stats: &ListenStats, writer: &mut impl fmt::Write) -> fmt::Result {
     writeln!(writer, "probe listen summary")?;
     writeln!(writer, "==========================")?;
-    writeln!(writer, "total records  : {}", stats.total_records)?;
+    if stats.original_total != stats.total_records {
+        writeln!(
+            writer,
+            "total records  : {} (filtered from {})",
+            stats.total_records, stats.original_total
+        )?;
+    } else {
+        writeln!(writer, "total records  : {}", stats.total_records)?;
+    }
     writeln!(writer, "distinct probes: {}", stats.distinct_probes)?;
     writeln!(
         writer,
@@ -117,15 +246,41 @@ fn render_summary(stats: &ListenStats, writer: &mut impl fmt::Write) -> fmt::Res
         "modes          : {}",
         format_counts(&stats.modes, "none")
     )?;
+    if !stats.severities.is_empty() {
+        writeln!(
+            writer,
+            "severities     : {}",
+            format_counts(&stats.severities, "none")
+        )?;
+    }
     Ok(())
 }
 
-fn render_record(idx: usize, record: &BoundaryObject, writer: &mut impl fmt::Write) -> fmt::Result {
-    writeln!(
-        writer,
-        "[#{}] {:<7} mode={} probe={}",
-        idx, record.result.observed_result, record.run.mode, record.probe.id
-    )?;
+fn render_record(
+    idx: usize,
+    record: &BoundaryObject,
+    outcome: Option<&RuleOutcome>,
+    writer: &mut impl fmt::Write,
+) -> fmt::Result {
+    match outcome {
+        Some(outcome) => writeln!(
+            writer,
+            "[#{}] {} {:<7} mode={} probe={}",
+            idx,
+            outcome.severity.as_str().to_uppercase(),
+            record.result.observed_result,
+            record.run.mode,
+            record.probe.id
+        )?,
+        None => writeln!(
+            writer,
+            "[#{}] {:<7} mode={} probe={}",
+            idx, record.result.observed_result, record.run.mode, record.probe.id
+        )?,
+    }
+    if let Some(outcome) = outcome {
+        writeln!(writer, "  rule:      {}", outcome.message)?;
+    }
     let capability = &record.capability_context.primary;
     writeln!(
         writer,
@@ -195,6 +350,185 @@ fn truncate_line(line: &str) -> String {
     shortened
 }
 
+/// Truncate `snippet` the same way [`write_snippet`] does for the text
+/// renderer, but return it as an owned string (or `None` for blank input) so
+/// [`render_json_report`] can drop it from the object entirely via
+/// [`prune_null_fields`] instead of emitting an empty string.
+fn truncated_snippet(snippet: &str) -> Option<String> {
+    let trimmed = snippet.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    let mut lines = trimmed.lines();
+    let mut kept: Vec<String> = Vec::new();
+    for _ in 0..MAX_SNIPPET_LINES {
+        match lines.next() {
+            Some(line) => kept.push(truncate_line(line)),
+            None => break,
+        }
+    }
+    if lines.next().is_some() {
+        kept.push("…".to_string());
+    }
+    Some(kept.join("\n"))
+}
+
+/// Render `records` as a single machine-readable JSON object: the same
+/// counts [`render_summary`] reports in text form (`total_records`,
+/// `distinct_probes`, `results`, `modes`) plus a compact `records` array.
+/// Unset or empty fields (no message, no snippets) are omitted rather than
+/// serialized as `null`/`""`, following the convention established by
+/// [`prune_null_fields`].
+fn render_json_report(records: &[BoundaryObject], writer: &mut impl fmt::Write) -> fmt::Result {
+    let stats = summarize_records(records, &[], records.len());
+
+    let record_entries: Vec<serde_json::Value> = records
+        .iter()
+        .enumerate()
+        .map(|(idx, record)| {
+            serde_json::json!({
+                "index": idx + 1,
+                "result": record.result.observed_result,
+                "mode": record.run.mode,
+                "probe": record.probe.id,
+                "capability": {
+                    "id": record.capability_context.primary.id.0,
+                    "category": record.capability_context.primary.category.as_str(),
+                    "layer": record.capability_context.primary.layer.as_str(),
+                },
+                "operation": {
+                    "verb": record.operation.verb,
+                    "target": record.operation.target,
+                },
+                "message": record.result.message,
+                "stdout_snippet": record
+                    .payload
+                    .stdout_snippet
+                    .as_deref()
+                    .and_then(truncated_snippet),
+                "stderr_snippet": record
+                    .payload
+                    .stderr_snippet
+                    .as_deref()
+                    .and_then(truncated_snippet),
+            })
+        })
+        .collect();
+
+    let mut report = serde_json::json!({
+        "total_records": stats.total_records,
+        "distinct_probes": stats.distinct_probes,
+        "results": stats.results,
+        "modes": stats.modes,
+        "records": record_entries,
+    });
+    prune_null_fields(&mut report);
+
+    let rendered = serde_json::to_string_pretty(&report).map_err(|_| fmt::Error)?;
+    writeln!(writer, "{rendered}")
+}
+
+/// Render `records` as a JUnit `<testsuites>` document, one `<testcase>` per
+/// record named after its primary capability ID.
+///
+/// `success` maps to a pass, `partial` is always a failure (flagged with a
+/// distinct `type` so CI can tell "degraded" apart from "denied"/"error"), and
+/// any other observed result is reported as a `<failure>` carrying the
+/// stdout/stderr snippets as its message body.
+fn render_junit_report(records: &[BoundaryObject], writer: &mut impl fmt::Write) -> fmt::Result {
+    let failures = records
+        .iter()
+        .filter(|record| !is_passing_result(record))
+        .count();
+
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<testsuites tests="{}" failures="{}">"#,
+        records.len(),
+        failures
+    )?;
+    writeln!(
+        writer,
+        r#"  <testsuite name="probe-listen" tests="{}" failures="{}">"#,
+        records.len(),
+        failures
+    )?;
+
+    for record in records {
+        render_junit_case(record, writer)?;
+    }
+
+    writeln!(writer, "  </testsuite>")?;
+    writeln!(writer, "</testsuites>")?;
+    Ok(())
+}
+
+fn render_junit_case(record: &BoundaryObject, writer: &mut impl fmt::Write) -> fmt::Result {
+    let capability = &record.capability_context.primary;
+    writeln!(
+        writer,
+        r#"    <testcase name="{}" classname="{}">"#,
+        xml_escape(&capability.id.0),
+        xml_escape(&record.probe.id),
+    )?;
+
+    if let Some(failure_type) = junit_failure_type(&record.result.observed_result) {
+        let message = record
+            .result
+            .message
+            .as_deref()
+            .unwrap_or("boundary probe did not succeed");
+        writeln!(
+            writer,
+            r#"      <failure type="{}" message="{}">"#,
+            xml_escape(failure_type),
+            xml_escape(message)
+        )?;
+        if let Some(stdout) = record.payload.stdout_snippet.as_deref() {
+            writeln!(writer, "stdout:\n{}", xml_escape(stdout))?;
+        }
+        if let Some(stderr) = record.payload.stderr_snippet.as_deref() {
+            writeln!(writer, "stderr:\n{}", xml_escape(stderr))?;
+        }
+        writeln!(writer, "      </failure>")?;
+    }
+
+    writeln!(writer, "    </testcase>")?;
+    Ok(())
+}
+
+/// `None` for a passing result, otherwise the JUnit `failure` `type` attribute
+/// to use for the observed result (`partial` is kept distinct from the rest so
+/// CI can tell "degraded" apart from "denied"/"error").
+fn junit_failure_type(observed_result: &str) -> Option<&str> {
+    match observed_result {
+        "success" => None,
+        "partial" => Some("partial"),
+        other => Some(other),
+    }
+}
+
+fn is_passing_result(record: &BoundaryObject) -> bool {
+    junit_failure_type(&record.result.observed_result).is_none()
+}
+
+fn xml_escape(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+    for ch in input.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
 fn format_counts(map: &BTreeMap<String, usize>, empty_label: &str) -> String {
     if map.is_empty() {
         return empty_label.to_string();
@@ -211,10 +545,653 @@ pub enum ListenError {
     Validation(String),
     Serialize(serde_json::Error),
     Write(fmt::Error),
+    /// Carries the number of records that failed their expectation entry.
+    ExpectationMismatch(usize),
+    /// Carries a human-readable reason a `--filter` expression was rejected.
+    Filter(String),
+}
+
+/// One `[<probe-id>]` (or `["<probe-id>@<mode>"]`) entry from an `--expect`
+/// spec file: an expected observed result plus optional regex patterns that
+/// the stdout/stderr snippets or result message must match.
+#[derive(Debug, Clone, Deserialize)]
+struct ExpectationEntry {
+    result: Option<String>,
+    stdout: Option<String>,
+    stderr: Option<String>,
+    message: Option<String>,
+}
+
+fn load_expectations(path: &Path) -> Result<BTreeMap<String, ExpectationEntry>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("reading expectations file {}", path.display()))?;
+    toml::from_str(&contents)
+        .with_context(|| format!("parsing expectations file {}", path.display()))
+}
+
+/// Look up `record`'s expectation entry, preferring a `probe_id@mode` key over
+/// the bare `probe_id` so a spec can override expectations per run mode.
+fn lookup_expectation<'a>(
+    record: &BoundaryObject,
+    expectations: &'a BTreeMap<String, ExpectationEntry>,
+) -> Option<&'a ExpectationEntry> {
+    let scoped_key = format!("{}@{}", record.probe.id, record.run.mode);
+    expectations
+        .get(&scoped_key)
+        .or_else(|| expectations.get(&record.probe.id))
+}
+
+enum ExpectationOutcome {
+    Pass,
+    Fail(Vec<String>),
+    Uncovered,
+}
+
+fn evaluate_expectation(
+    record: &BoundaryObject,
+    expectations: &BTreeMap<String, ExpectationEntry>,
+) -> ExpectationOutcome {
+    let Some(entry) = lookup_expectation(record, expectations) else {
+        return ExpectationOutcome::Uncovered;
+    };
+
+    let mut failures = Vec::new();
+    if let Some(expected_result) = &entry.result {
+        if expected_result != &record.result.observed_result {
+            failures.push(format!(
+                "result: expected '{expected_result}', observed '{}'",
+                record.result.observed_result
+            ));
+        }
+    }
+    check_pattern(
+        "stdout",
+        entry.stdout.as_deref(),
+        record.payload.stdout_snippet.as_deref(),
+        &mut failures,
+    );
+    check_pattern(
+        "stderr",
+        entry.stderr.as_deref(),
+        record.payload.stderr_snippet.as_deref(),
+        &mut failures,
+    );
+    check_pattern(
+        "message",
+        entry.message.as_deref(),
+        record.result.message.as_deref(),
+        &mut failures,
+    );
+
+    if failures.is_empty() {
+        ExpectationOutcome::Pass
+    } else {
+        ExpectationOutcome::Fail(failures)
+    }
+}
+
+fn check_pattern(
+    label: &str,
+    pattern: Option<&str>,
+    actual: Option<&str>,
+    failures: &mut Vec<String>,
+) {
+    let Some(pattern) = pattern else {
+        return;
+    };
+    let regex = match Regex::new(pattern) {
+        Ok(regex) => regex,
+        Err(err) => {
+            failures.push(format!("{label}: invalid regex /{pattern}/: {err}"));
+            return;
+        }
+    };
+    let text = actual.unwrap_or("");
+    if !regex.is_match(text) {
+        failures.push(format!(
+            "{label}: expected to match /{pattern}/, observed '{text}'"
+        ));
+    }
+}
+
+/// Render a PASS/FAIL/UNCOVERED line per record plus a failures summary, and
+/// return [`ListenError::ExpectationMismatch`] if any record should fail the
+/// gate (a genuine mismatch, or an uncovered record under `--strict`).
+fn render_expectations_report(
+    records: &[BoundaryObject],
+    expectations: &BTreeMap<String, ExpectationEntry>,
+    strict: bool,
+    writer: &mut impl fmt::Write,
+) -> Result<(), ListenError> {
+    let mut failed = 0usize;
+    let mut uncovered = 0usize;
+    let mut passed = 0usize;
+
+    writeln!(writer, "probe listen expectations").map_err(ListenError::Write)?;
+    writeln!(writer, "==========================").map_err(ListenError::Write)?;
+
+    for record in records {
+        match evaluate_expectation(record, expectations) {
+            ExpectationOutcome::Pass => {
+                passed += 1;
+                writeln!(
+                    writer,
+                    "PASS      probe={} mode={}",
+                    record.probe.id, record.run.mode
+                )
+                .map_err(ListenError::Write)?;
+            }
+            ExpectationOutcome::Fail(reasons) => {
+                failed += 1;
+                writeln!(
+                    writer,
+                    "FAIL      probe={} mode={}",
+                    record.probe.id, record.run.mode
+                )
+                .map_err(ListenError::Write)?;
+                for reason in reasons {
+                    writeln!(writer, "  - {reason}").map_err(ListenError::Write)?;
+                }
+            }
+            ExpectationOutcome::Uncovered => {
+                uncovered += 1;
+                if strict {
+                    failed += 1;
+                }
+                writeln!(
+                    writer,
+                    "UNCOVERED probe={} mode={}",
+                    record.probe.id, record.run.mode
+                )
+                .map_err(ListenError::Write)?;
+            }
+        }
+    }
+
+    writeln!(writer).map_err(ListenError::Write)?;
+    writeln!(
+        writer,
+        "passed={passed} failed={failed} uncovered={uncovered}"
+    )
+    .map_err(ListenError::Write)?;
+
+    if failed > 0 {
+        return Err(ListenError::ExpectationMismatch(failed));
+    }
+    Ok(())
+}
+
+/// Severity assigned to a record by a matched [`Rule`], ordered so the
+/// maximum across every matched rule (and every record) drives the exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Severity {
+    Info,
+    Warn,
+    Error,
+}
+
+impl Severity {
+    fn as_str(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warn => "warn",
+            Severity::Error => "error",
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self> {
+        match value {
+            "info" => Ok(Severity::Info),
+            "warn" => Ok(Severity::Warn),
+            "error" => Ok(Severity::Error),
+            other => bail!("unknown rule severity '{other}' (expected info, warn, or error)"),
+        }
+    }
+
+    /// Process exit code when this is the worst severity seen: `info` never
+    /// changes today's (zero) exit behavior, `warn` and `error` do.
+    fn exit_code(self) -> i32 {
+        match self {
+            Severity::Info => 0,
+            Severity::Warn => 1,
+            Severity::Error => 2,
+        }
+    }
+}
+
+/// Record field a rule [`Clause`] can select on, matching the boundary-object
+/// fields the request enumerates (`capability_context.primary.category`,
+/// `.layer`, `run.mode`, `operation.verb`, `result.observed_result`).
+#[derive(Debug, Clone, Copy)]
+enum RuleField {
+    Category,
+    Layer,
+    Mode,
+    Verb,
+    Result,
+}
+
+impl RuleField {
+    fn parse(value: &str) -> Result<Self> {
+        match value {
+            "category" => Ok(RuleField::Category),
+            "layer" => Ok(RuleField::Layer),
+            "mode" => Ok(RuleField::Mode),
+            "verb" => Ok(RuleField::Verb),
+            "result" => Ok(RuleField::Result),
+            other => bail!(
+                "unknown rule field '{other}' (expected category, layer, mode, verb, or result)"
+            ),
+        }
+    }
+
+    fn value_of<'a>(self, record: &'a BoundaryObject) -> &'a str {
+        match self {
+            RuleField::Category => record.capability_context.primary.category.as_str(),
+            RuleField::Layer => record.capability_context.primary.layer.as_str(),
+            RuleField::Mode => record.run.mode.as_str(),
+            RuleField::Verb => record.operation.verb.as_str(),
+            RuleField::Result => record.result.observed_result.as_str(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum RuleOp {
+    Eq,
+    Ne,
+}
+
+#[derive(Debug, Clone)]
+struct RuleClause {
+    field: RuleField,
+    op: RuleOp,
+    value: String,
+}
+
+impl RuleClause {
+    fn matches(&self, record: &BoundaryObject) -> bool {
+        let actual = self.field.value_of(record);
+        match self.op {
+            RuleOp::Eq => actual == self.value,
+            RuleOp::Ne => actual != self.value,
+        }
+    }
+}
+
+/// A conjunction of [`RuleClause`]s (today's grammar is `AND`-only, matching
+/// the condition shape the request's example spells out).
+#[derive(Debug, Clone)]
+struct RuleCondition(Vec<RuleClause>);
+
+impl RuleCondition {
+    fn matches(&self, record: &BoundaryObject) -> bool {
+        self.0.iter().all(|clause| clause.matches(record))
+    }
+}
+
+fn parse_rule_condition(raw: &str) -> Result<RuleCondition> {
+    let clauses = raw
+        .split("AND")
+        .map(parse_rule_clause)
+        .collect::<Result<Vec<_>>>()
+        .with_context(|| format!("parsing rule condition '{raw}'"))?;
+    if clauses.is_empty() {
+        bail!("rule condition '{raw}' has no clauses");
+    }
+    Ok(RuleCondition(clauses))
+}
+
+fn parse_rule_clause(raw: &str) -> Result<RuleClause> {
+    let raw = raw.trim();
+    let (field_part, op, value_part) = if let Some((field, value)) = raw.split_once("!=") {
+        (field, RuleOp::Ne, value)
+    } else if let Some((field, value)) = raw.split_once("==") {
+        (field, RuleOp::Eq, value)
+    } else {
+        bail!("clause '{raw}' is missing an == or != operator");
+    };
+
+    let field = RuleField::parse(field_part.trim())?;
+    let value = value_part.trim().trim_matches('"').to_string();
+    Ok(RuleClause { field, op, value })
+}
+
+/// One `[[rule]]` entry from a `--rules` spec file.
+#[derive(Debug, Clone, Deserialize)]
+struct RuleSpec {
+    condition: String,
+    severity: String,
+    message: String,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct RulesFile {
+    #[serde(default)]
+    rule: Vec<RuleSpec>,
+}
+
+/// A compiled `[[rule]]` entry: a condition to match, the severity to assign
+/// when it matches, and a message template (`{probe}`, `{mode}`, `{result}`,
+/// `{category}`, `{layer}`, `{verb}`, `{target}`, `{message}` placeholders).
+struct Rule {
+    condition: RuleCondition,
+    severity: Severity,
+    message: String,
+}
+
+/// Load and compile rules from `path`. No `--rules` flag at all (`path` is
+/// `None`) or a file with no `[[rule]]` entries both preserve today's
+/// behavior: every record renders with no severity tag and the exit code
+/// stays at 0.
+fn load_rules(path: Option<&Path>) -> Result<Vec<Rule>> {
+    let Some(path) = path else {
+        return Ok(Vec::new());
+    };
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("reading rules file {}", path.display()))?;
+    let rules_file: RulesFile = toml::from_str(&contents)
+        .with_context(|| format!("parsing rules file {}", path.display()))?;
+
+    rules_file
+        .rule
+        .into_iter()
+        .map(|spec| {
+            Ok(Rule {
+                condition: parse_rule_condition(&spec.condition)?,
+                severity: Severity::parse(&spec.severity)?,
+                message: spec.message,
+            })
+        })
+        .collect()
+}
+
+/// Outcome of matching `record` against a rule set: the highest severity
+/// among every matched rule, plus that rule's interpolated message.
+struct RuleOutcome {
+    severity: Severity,
+    message: String,
+}
+
+/// Evaluate every rule against `record` independently (so a future version
+/// can run them in parallel) and keep the highest severity matched, breaking
+/// ties in favor of whichever rule appears first in the file.
+fn evaluate_rules(record: &BoundaryObject, rules: &[Rule]) -> Option<RuleOutcome> {
+    let mut best: Option<RuleOutcome> = None;
+    for rule in rules {
+        if !rule.condition.matches(record) {
+            continue;
+        }
+        let is_new_best = match &best {
+            Some(current) => rule.severity > current.severity,
+            None => true,
+        };
+        if is_new_best {
+            best = Some(RuleOutcome {
+                severity: rule.severity,
+                message: interpolate_rule_message(&rule.message, record),
+            });
+        }
+    }
+    best
+}
+
+fn interpolate_rule_message(template: &str, record: &BoundaryObject) -> String {
+    template
+        .replace("{probe}", &record.probe.id)
+        .replace("{mode}", &record.run.mode)
+        .replace("{result}", &record.result.observed_result)
+        .replace(
+            "{category}",
+            record.capability_context.primary.category.as_str(),
+        )
+        .replace("{layer}", record.capability_context.primary.layer.as_str())
+        .replace("{verb}", &record.operation.verb)
+        .replace("{target}", &record.operation.target)
+        .replace("{message}", record.result.message.as_deref().unwrap_or(""))
+}
+
+/// Record field a `--filter` [`FilterPredicate`] can select on. Covers the
+/// identity/classification fields plus the free-text fields (`message` and
+/// the stdout/stderr snippets) that `~` regex predicates are meant for.
+#[derive(Debug, Clone, Copy)]
+enum FilterField {
+    Result,
+    Mode,
+    Probe,
+    Category,
+    Layer,
+    Verb,
+    Target,
+    Message,
+    Stdout,
+    Stderr,
+}
+
+impl FilterField {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "result" => Ok(FilterField::Result),
+            "mode" => Ok(FilterField::Mode),
+            "probe" => Ok(FilterField::Probe),
+            "category" => Ok(FilterField::Category),
+            "layer" => Ok(FilterField::Layer),
+            "verb" => Ok(FilterField::Verb),
+            "target" => Ok(FilterField::Target),
+            "message" => Ok(FilterField::Message),
+            "stdout" => Ok(FilterField::Stdout),
+            "stderr" => Ok(FilterField::Stderr),
+            other => Err(format!(
+                "unknown filter field '{other}' (expected result, mode, probe, category, layer, verb, target, message, stdout, or stderr)"
+            )),
+        }
+    }
+
+    fn value_of<'a>(self, record: &'a BoundaryObject) -> &'a str {
+        match self {
+            FilterField::Result => record.result.observed_result.as_str(),
+            FilterField::Mode => record.run.mode.as_str(),
+            FilterField::Probe => record.probe.id.as_str(),
+            FilterField::Category => record.capability_context.primary.category.as_str(),
+            FilterField::Layer => record.capability_context.primary.layer.as_str(),
+            FilterField::Verb => record.operation.verb.as_str(),
+            FilterField::Target => record.operation.target.as_str(),
+            FilterField::Message => record.result.message.as_deref().unwrap_or(""),
+            FilterField::Stdout => record.payload.stdout_snippet.as_deref().unwrap_or(""),
+            FilterField::Stderr => record.payload.stderr_snippet.as_deref().unwrap_or(""),
+        }
+    }
+}
+
+enum FilterComparison {
+    Eq(String),
+    Ne(String),
+    Match(Regex),
+}
+
+struct FilterPredicate {
+    field: FilterField,
+    comparison: FilterComparison,
+}
+
+impl FilterPredicate {
+    fn matches(&self, record: &BoundaryObject) -> bool {
+        let actual = self.field.value_of(record);
+        match &self.comparison {
+            FilterComparison::Eq(value) => actual == value,
+            FilterComparison::Ne(value) => actual != value,
+            FilterComparison::Match(regex) => regex.is_match(actual),
+        }
+    }
+}
+
+/// Parsed `--filter` expression: predicates (`field=value`, `field!=value`,
+/// `field~pattern`) joined by `AND`/`OR` with an optional leading `NOT`.
+enum FilterExpr {
+    Predicate(FilterPredicate),
+    Not(Box<FilterExpr>),
+    And(Box<FilterExpr>, Box<FilterExpr>),
+    Or(Box<FilterExpr>, Box<FilterExpr>),
+}
+
+impl FilterExpr {
+    fn matches(&self, record: &BoundaryObject) -> bool {
+        match self {
+            FilterExpr::Predicate(predicate) => predicate.matches(record),
+            FilterExpr::Not(inner) => !inner.matches(record),
+            FilterExpr::And(lhs, rhs) => lhs.matches(record) && rhs.matches(record),
+            FilterExpr::Or(lhs, rhs) => lhs.matches(record) || rhs.matches(record),
+        }
+    }
+}
+
+/// Parse a `--filter` expression: `field OP value` predicates (OP is `=`,
+/// `!=`, or `~` for a regex match) joined left-to-right by `AND` (binds
+/// tighter) and `OR`, with an optional prefix `NOT`. Values may be quoted to
+/// include whitespace, e.g. `message~"no such file"`.
+fn parse_filter_expr(raw: &str) -> Result<FilterExpr, String> {
+    let tokens = tokenize_filter(raw)?;
+    if tokens.is_empty() {
+        return Err("filter expression is empty".to_string());
+    }
+    let mut pos = 0;
+    let expr = parse_filter_or(&tokens, &mut pos)?;
+    if pos != tokens.len() {
+        return Err(format!(
+            "unexpected trailing token '{}' in filter expression '{raw}'",
+            tokens[pos]
+        ));
+    }
+    Ok(expr)
+}
+
+fn tokenize_filter(raw: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for ch in raw.chars() {
+        match ch {
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(ch);
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(format!(
+            "unterminated quoted value in filter expression '{raw}'"
+        ));
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    Ok(tokens)
+}
+
+fn parse_filter_or(tokens: &[String], pos: &mut usize) -> Result<FilterExpr, String> {
+    let mut expr = parse_filter_and(tokens, pos)?;
+    while tokens.get(*pos).map(String::as_str) == Some("OR") {
+        *pos += 1;
+        let rhs = parse_filter_and(tokens, pos)?;
+        expr = FilterExpr::Or(Box::new(expr), Box::new(rhs));
+    }
+    Ok(expr)
+}
+
+fn parse_filter_and(tokens: &[String], pos: &mut usize) -> Result<FilterExpr, String> {
+    let mut expr = parse_filter_unary(tokens, pos)?;
+    while tokens.get(*pos).map(String::as_str) == Some("AND") {
+        *pos += 1;
+        let rhs = parse_filter_unary(tokens, pos)?;
+        expr = FilterExpr::And(Box::new(expr), Box::new(rhs));
+    }
+    Ok(expr)
+}
+
+fn parse_filter_unary(tokens: &[String], pos: &mut usize) -> Result<FilterExpr, String> {
+    if tokens.get(*pos).map(String::as_str) == Some("NOT") {
+        *pos += 1;
+        let inner = parse_filter_unary(tokens, pos)?;
+        return Ok(FilterExpr::Not(Box::new(inner)));
+    }
+    let Some(token) = tokens.get(*pos) else {
+        return Err("expected a predicate, found end of expression".to_string());
+    };
+    *pos += 1;
+    parse_filter_predicate(token).map(FilterExpr::Predicate)
+}
+
+fn parse_filter_predicate(token: &str) -> Result<FilterPredicate, String> {
+    let (field_part, op, value_part) = if let Some((field, value)) = token.split_once("!=") {
+        (field, "!=", value)
+    } else if let Some((field, value)) = token.split_once('~') {
+        (field, "~", value)
+    } else if let Some((field, value)) = token.split_once('=') {
+        (field, "=", value)
+    } else {
+        return Err(format!(
+            "predicate '{token}' is missing an =, !=, or ~ operator"
+        ));
+    };
+
+    let field = FilterField::parse(field_part)?;
+    let value = unquote_filter_value(value_part);
+
+    let comparison = match op {
+        "!=" => FilterComparison::Ne(value),
+        "=" => FilterComparison::Eq(value),
+        "~" => {
+            let regex = Regex::new(&value)
+                .map_err(|err| format!("predicate '{token}' has an invalid regex: {err}"))?;
+            FilterComparison::Match(regex)
+        }
+        _ => unreachable!("parse_filter_predicate only splits on =, !=, or ~"),
+    };
+
+    Ok(FilterPredicate { field, comparison })
+}
+
+fn unquote_filter_value(value: &str) -> String {
+    value
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .unwrap_or(value)
+        .to_string()
+}
+
+/// Output mode selected by `--format`: the default human-readable text
+/// summary, or a single `--format json` report (see [`render_json_report`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+
+impl OutputFormat {
+    fn parse(value: &str) -> Result<Self> {
+        match value {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            other => bail!("unknown format '{other}' (expected text or json)"),
+        }
+    }
 }
 
 struct Cli {
     boundary_schema_path: Option<PathBuf>,
+    junit: bool,
+    expect_path: Option<PathBuf>,
+    strict: bool,
+    rules_path: Option<PathBuf>,
+    filter_expr: Option<String>,
+    format: OutputFormat,
 }
 
 impl Cli {
@@ -222,6 +1199,12 @@ impl Cli {
         let mut args = env::args_os();
         let _program = args.next();
         let mut boundary_schema_path = None;
+        let mut junit = false;
+        let mut expect_path = None;
+        let mut strict = false;
+        let mut rules_path = None;
+        let mut filter_expr = None;
+        let mut format = OutputFormat::Text;
 
         while let Some(arg) = args.next() {
             let arg_str = arg
@@ -238,6 +1221,47 @@ impl Cli {
                             .map_err(|_| anyhow!("--boundary must be valid UTF-8"))?,
                     ));
                 }
+                "--junit" => junit = true,
+                "--expect" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--expect requires a value"))?;
+                    expect_path = Some(PathBuf::from(
+                        value
+                            .into_string()
+                            .map_err(|_| anyhow!("--expect must be valid UTF-8"))?,
+                    ));
+                }
+                "--strict" => strict = true,
+                "--rules" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--rules requires a value"))?;
+                    rules_path = Some(PathBuf::from(
+                        value
+                            .into_string()
+                            .map_err(|_| anyhow!("--rules must be valid UTF-8"))?,
+                    ));
+                }
+                "--filter" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--filter requires a value"))?;
+                    filter_expr = Some(
+                        value
+                            .into_string()
+                            .map_err(|_| anyhow!("--filter must be valid UTF-8"))?,
+                    );
+                }
+                "--format" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--format requires a value"))?;
+                    let value = value
+                        .into_string()
+                        .map_err(|_| anyhow!("--format must be valid UTF-8"))?;
+                    format = OutputFormat::parse(&value)?;
+                }
                 "--help" | "-h" => usage(0),
                 other => bail!("unknown argument: {other}"),
             }
@@ -245,6 +1269,12 @@ impl Cli {
 
         Ok(Self {
             boundary_schema_path,
+            junit,
+            expect_path,
+            strict,
+            rules_path,
+            filter_expr,
+            format,
         })
     }
 }
@@ -279,7 +1309,7 @@ fn repo_relative(base: Option<&Path>, candidate: &Path) -> PathBuf {
 
 fn usage(code: i32) -> ! {
     eprintln!(
-        "Usage: probe --listen [--boundary PATH]\n\nOptions:\n  --boundary PATH           Override boundary-object schema path (or set BOUNDARY_PATH).\n  --help                    Show this help text."
+        "Usage: probe --listen [--boundary PATH] [--filter EXPR] [--format {{text|json}}] [--junit] [--expect PATH [--strict]] [--rules PATH]\n\nOptions:\n  --boundary PATH           Override boundary-object schema path (or set BOUNDARY_PATH).\n  --filter EXPR             Narrow the record set before summarizing or rendering. EXPR is\n                            field OP value predicates (field one of result, mode, probe,\n                            category, layer, verb, target, message, stdout, stderr; OP is\n                            =, !=, or ~ for a regex match) joined by AND/OR with an optional\n                            leading NOT, e.g. 'result=denied AND category=fs'. Quote values\n                            with spaces. The summary reports both the filtered and total count.\n  --format {{text|json}}     Select the output shape (default text). json emits a single object\n                            with total_records, distinct_probes, results, modes, and a compact\n                            records array (index, result, mode, probe, capability, operation,\n                            and truncated message/snippets), omitting unset or empty fields.\n  --junit                   Emit a JUnit XML <testsuites> report instead of the text summary,\n                            one <testcase> per record, and exit nonzero if any case failed.\n  --expect PATH             Gate on a TOML expectations file: [<probe-id>] (or\n                            [\"<probe-id>@<mode>\"]) entries with result/stdout/stderr/\n                            message (regex) checks. Prints PASS/FAIL/UNCOVERED per record and\n                            exits nonzero on any FAIL.\n  --strict                  With --expect, treat UNCOVERED records as failures too.\n  --rules PATH              Classify records with a TOML rule file: [[rule]] entries with a\n                            condition (\"result == denied AND layer == os_sandbox\"), a severity\n                            (info/warn/error), and a message template ({{probe}}, {{mode}}, {{result}},\n                            {{category}}, {{layer}}, {{verb}}, {{target}}, {{message}}). Tags each rendered\n                            record with its highest matched severity and exits 2 on error, 1 on\n                            warn, 0 otherwise. An empty or absent rules file preserves today's\n                            behavior.\n  --help                    Show this help text."
     );
     std::process::exit(code);
 }
@@ -305,8 +1335,14 @@ mod tests {
     fn renders_summary_and_records_for_golden_snippet() {
         let reader = golden_snippet_reader();
         let mut output = String::new();
-        render_listen_output(reader, &mut output, &boundary_schema())
-            .expect("render should succeed");
+        render_listen_output(
+            reader,
+            &mut output,
+            &boundary_schema(),
+            None,
+            OutputFormat::Text,
+        )
+        .expect("render should succeed");
 
         assert!(output.contains("total records  : 3"));
         assert!(
@@ -325,8 +1361,14 @@ mod tests {
         let cursor = Cursor::new(Vec::<u8>::new());
         let reader = BufReader::new(cursor);
         let mut output = String::new();
-        render_listen_output(reader, &mut output, &boundary_schema())
-            .expect("empty input should succeed");
+        render_listen_output(
+            reader,
+            &mut output,
+            &boundary_schema(),
+            None,
+            OutputFormat::Text,
+        )
+        .expect("empty input should succeed");
         assert!(output.contains("total records  : 0"));
 
         let mut record = minimal_record();
@@ -337,12 +1379,354 @@ mod tests {
             BufReader::new(Cursor::new(ndjson.into_bytes())),
             &mut buffer,
             &boundary_schema(),
+            None,
+            OutputFormat::Text,
         )
         .unwrap();
         assert!(buffer.contains("[#1]"));
         assert!(buffer.contains(&record.probe.id));
     }
 
+    #[test]
+    fn junit_report_marks_success_pass_and_others_as_failures() {
+        let reader = golden_snippet_reader();
+        let records = read_and_validate_records(reader, &boundary_schema())
+            .expect("records should read and validate");
+
+        let mut output = String::new();
+        render_junit_report(&records, &mut output).expect("render should succeed");
+
+        assert!(output.contains(r#"<testsuites tests="3" failures="2">"#));
+        assert!(output.contains(r#"<failure type="denied""#));
+        assert!(output.contains(r#"<failure type="partial""#));
+        assert!(records.iter().any(is_passing_result));
+        assert!(records.iter().filter(|r| !is_passing_result(r)).count() == 2);
+    }
+
+    #[test]
+    fn junit_report_escapes_special_characters_in_messages() {
+        let mut record = minimal_record();
+        record.result.observed_result = "denied".to_string();
+        record.result.message = Some("blocked <open> \"rule\" & friends".to_string());
+
+        let mut output = String::new();
+        render_junit_report(std::slice::from_ref(&record), &mut output)
+            .expect("render should succeed");
+
+        assert!(output.contains("blocked &lt;open&gt; &quot;rule&quot; &amp; friends"));
+        assert!(!output.contains("<open>"));
+    }
+
+    #[test]
+    fn expectations_report_passes_on_matching_result_and_patterns() {
+        let record = minimal_record();
+        let mut expectations = BTreeMap::new();
+        expectations.insert(
+            "sample_probe".to_string(),
+            ExpectationEntry {
+                result: Some("success".to_string()),
+                stdout: Some("^hel+o$".to_string()),
+                stderr: None,
+                message: Some("sample".to_string()),
+            },
+        );
+
+        let mut output = String::new();
+        let result = render_expectations_report(
+            std::slice::from_ref(&record),
+            &expectations,
+            false,
+            &mut output,
+        );
+        assert!(result.is_ok());
+        assert!(output.contains("PASS      probe=sample_probe"));
+        assert!(output.contains("passed=1 failed=0 uncovered=0"));
+    }
+
+    #[test]
+    fn expectations_report_fails_on_result_and_pattern_mismatch() {
+        let record = minimal_record();
+        let mut expectations = BTreeMap::new();
+        expectations.insert(
+            "sample_probe".to_string(),
+            ExpectationEntry {
+                result: Some("denied".to_string()),
+                stdout: Some("^nomatch$".to_string()),
+                stderr: None,
+                message: None,
+            },
+        );
+
+        let mut output = String::new();
+        let result = render_expectations_report(
+            std::slice::from_ref(&record),
+            &expectations,
+            false,
+            &mut output,
+        );
+        assert!(matches!(result, Err(ListenError::ExpectationMismatch(1))));
+        assert!(output.contains("FAIL      probe=sample_probe"));
+        assert!(output.contains("expected 'denied', observed 'success'"));
+        assert!(output.contains("expected to match /^nomatch$/"));
+    }
+
+    #[test]
+    fn expectations_report_treats_uncovered_as_failure_only_when_strict() {
+        let record = minimal_record();
+        let expectations = BTreeMap::new();
+
+        let mut lenient_output = String::new();
+        let lenient = render_expectations_report(
+            std::slice::from_ref(&record),
+            &expectations,
+            false,
+            &mut lenient_output,
+        );
+        assert!(lenient.is_ok());
+        assert!(lenient_output.contains("UNCOVERED probe=sample_probe"));
+        assert!(lenient_output.contains("passed=0 failed=0 uncovered=1"));
+
+        let mut strict_output = String::new();
+        let strict = render_expectations_report(
+            std::slice::from_ref(&record),
+            &expectations,
+            true,
+            &mut strict_output,
+        );
+        assert!(matches!(strict, Err(ListenError::ExpectationMismatch(1))));
+    }
+
+    #[test]
+    fn expectations_lookup_prefers_mode_scoped_entry() {
+        let mut expectations = BTreeMap::new();
+        expectations.insert(
+            "sample_probe".to_string(),
+            ExpectationEntry {
+                result: Some("success".to_string()),
+                stdout: None,
+                stderr: None,
+                message: None,
+            },
+        );
+        expectations.insert(
+            "sample_probe@baseline".to_string(),
+            ExpectationEntry {
+                result: Some("denied".to_string()),
+                stdout: None,
+                stderr: None,
+                message: None,
+            },
+        );
+
+        let record = minimal_record();
+        let entry = lookup_expectation(&record, &expectations).expect("entry found");
+        assert_eq!(entry.result.as_deref(), Some("denied"));
+    }
+
+    #[test]
+    fn rule_matches_on_equality_and_interpolates_message() {
+        let record = minimal_record();
+        let rules = vec![Rule {
+            condition: parse_rule_condition("result == success AND layer == os_sandbox").unwrap(),
+            severity: Severity::Warn,
+            message: "{probe} saw {result} under {layer}".to_string(),
+        }];
+
+        let outcome = evaluate_rules(&record, &rules).expect("rule should match");
+        assert_eq!(outcome.severity, Severity::Warn);
+        assert_eq!(outcome.message, "sample_probe saw success under os_sandbox");
+    }
+
+    #[test]
+    fn rule_with_unmatched_clause_does_not_fire() {
+        let record = minimal_record();
+        let rules = vec![Rule {
+            condition: parse_rule_condition("result == denied").unwrap(),
+            severity: Severity::Error,
+            message: "should not appear".to_string(),
+        }];
+
+        assert!(evaluate_rules(&record, &rules).is_none());
+    }
+
+    #[test]
+    fn highest_severity_among_matching_rules_wins() {
+        let record = minimal_record();
+        let rules = vec![
+            Rule {
+                condition: parse_rule_condition("result == success").unwrap(),
+                severity: Severity::Info,
+                message: "info".to_string(),
+            },
+            Rule {
+                condition: parse_rule_condition("category == filesystem").unwrap(),
+                severity: Severity::Error,
+                message: "error".to_string(),
+            },
+        ];
+
+        let outcome = evaluate_rules(&record, &rules).expect("rule should match");
+        assert_eq!(outcome.severity, Severity::Error);
+        assert_eq!(outcome.message, "error");
+    }
+
+    #[test]
+    fn render_records_tags_matched_severity_and_reports_max_for_exit_code() {
+        let record = minimal_record();
+        let rules = vec![Rule {
+            condition: parse_rule_condition("result == success").unwrap(),
+            severity: Severity::Warn,
+            message: "keep an eye on {probe}".to_string(),
+        }];
+
+        let mut output = String::new();
+        let max_severity =
+            render_records(std::slice::from_ref(&record), &rules, 1, &mut output).unwrap();
+        assert_eq!(max_severity, Some(Severity::Warn));
+        assert!(output.contains("[#1] WARN success mode=baseline probe=sample_probe"));
+        assert!(output.contains("rule:      keep an eye on sample_probe"));
+        assert!(output.contains("severities     : warn=1"));
+    }
+
+    #[test]
+    fn render_records_with_no_rules_omits_severity_tags_and_exit_code() {
+        let record = minimal_record();
+        let mut output = String::new();
+        let max_severity =
+            render_records(std::slice::from_ref(&record), &[], 1, &mut output).unwrap();
+        assert_eq!(max_severity, None);
+        assert!(output.contains("[#1] success mode=baseline probe=sample_probe"));
+        assert!(!output.contains("severities"));
+    }
+
+    #[test]
+    fn render_records_reports_filtered_count_against_original_total() {
+        let record = minimal_record();
+        let mut output = String::new();
+        render_records(std::slice::from_ref(&record), &[], 3, &mut output).unwrap();
+        assert!(output.contains("total records  : 1 (filtered from 3)"));
+    }
+
+    #[test]
+    fn filter_expr_matches_simple_equality_predicate() {
+        let record = minimal_record();
+        let filter = parse_filter_expr("result=success").unwrap();
+        assert!(filter.matches(&record));
+
+        let filter = parse_filter_expr("result=denied").unwrap();
+        assert!(!filter.matches(&record));
+    }
+
+    #[test]
+    fn filter_expr_combines_and_or_not_with_expected_precedence() {
+        let record = minimal_record();
+
+        assert!(parse_filter_expr("result=success AND category=filesystem")
+            .unwrap()
+            .matches(&record));
+        assert!(!parse_filter_expr("result=success AND category=network")
+            .unwrap()
+            .matches(&record));
+        assert!(parse_filter_expr("result=denied OR category=filesystem")
+            .unwrap()
+            .matches(&record));
+        assert!(parse_filter_expr("NOT result=denied")
+            .unwrap()
+            .matches(&record));
+        // AND binds tighter than OR: this reads as `result=denied OR (category=filesystem AND mode=baseline)`.
+        assert!(
+            parse_filter_expr("result=denied OR category=filesystem AND mode=baseline")
+                .unwrap()
+                .matches(&record)
+        );
+    }
+
+    #[test]
+    fn filter_expr_supports_quoted_regex_match_against_message() {
+        let record = minimal_record();
+        let filter = parse_filter_expr(r#"message~"^sample""#).unwrap();
+        assert!(filter.matches(&record));
+
+        let filter = parse_filter_expr(r#"message~"^nope""#).unwrap();
+        assert!(!filter.matches(&record));
+    }
+
+    #[test]
+    fn filter_expr_rejects_unknown_field_and_missing_operator() {
+        assert!(parse_filter_expr("bogus=success").is_err());
+        assert!(parse_filter_expr("result").is_err());
+    }
+
+    #[test]
+    fn render_listen_output_applies_filter_before_summarizing() {
+        let reader = golden_snippet_reader();
+        let mut output = String::new();
+        render_listen_output(
+            reader,
+            &mut output,
+            &boundary_schema(),
+            Some("result=success"),
+            OutputFormat::Text,
+        )
+        .unwrap();
+        assert!(output.contains("total records  : 1 (filtered from 3)"));
+    }
+
+    #[test]
+    fn render_listen_output_json_format_omits_empty_fields_and_truncates_snippets() {
+        let mut record = minimal_record();
+        record.result.message = None;
+        record.payload.stderr_snippet = None;
+        record.payload.stdout_snippet =
+            Some("line one\nline two\nline three\nline four".to_string());
+        let ndjson = serde_json::to_string(&record).unwrap();
+        let mut output = String::new();
+        render_listen_output(
+            BufReader::new(Cursor::new(ndjson.into_bytes())),
+            &mut output,
+            &boundary_schema(),
+            None,
+            OutputFormat::Json,
+        )
+        .unwrap();
+
+        let report: serde_json::Value = serde_json::from_str(&output).expect("valid JSON output");
+        assert_eq!(report["total_records"], 1);
+        assert_eq!(report["distinct_probes"], 1);
+        assert_eq!(report["results"]["success"], 1);
+        assert_eq!(report["modes"]["baseline"], 1);
+
+        let record_entry = &report["records"][0];
+        assert_eq!(record_entry["probe"], "sample_probe");
+        assert_eq!(record_entry["capability"]["id"], "cap_sample");
+        assert_eq!(record_entry["capability"]["category"], "filesystem");
+        assert!(
+            record_entry.get("message").is_none(),
+            "unset message should be omitted, not null"
+        );
+        assert!(
+            record_entry.get("stderr_snippet").is_none(),
+            "missing stderr snippet should be omitted"
+        );
+        assert!(
+            record_entry["stdout_snippet"]
+                .as_str()
+                .unwrap()
+                .contains('…'),
+            "a snippet longer than MAX_SNIPPET_LINES should be truncated"
+        );
+    }
+
+    #[test]
+    fn render_json_report_reports_empty_input_without_nulls() {
+        let mut output = String::new();
+        render_json_report(&[], &mut output).unwrap();
+        let report: serde_json::Value = serde_json::from_str(&output).expect("valid JSON output");
+        assert_eq!(report["total_records"], 0);
+        assert_eq!(report["distinct_probes"], 0);
+        assert_eq!(report["records"].as_array().unwrap().len(), 0);
+    }
+
     fn golden_snippet_reader() -> BufReader<Cursor<Vec<u8>>> {
         let records = vec![
             minimal_record_with_result("success"),
@@ -361,6 +1745,7 @@ mod tests {
             capabilities_schema_version: Some(default_catalog_key()),
             stack: fencerunner::StackInfo {
                 sandbox_mode: Some("baseline".to_string()),
+                container_image: None,
                 os: "Darwin".to_string(),
             },
             probe: fencerunner::ProbeInfo {
@@ -399,6 +1784,7 @@ mod tests {
                     layer: CapabilityLayer::OsSandbox,
                 },
                 secondary: Vec::new(),
+                resolved_grant: None,
             },
         }
     }
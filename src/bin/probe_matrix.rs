@@ -9,15 +9,23 @@ use anyhow::{Context, Result, anyhow, bail};
 use fencerunner::connectors::{
     Availability, RunMode, allowed_mode_names, default_mode_names, parse_modes,
 };
+use fencerunner::handshake::{VersionInfo, negotiate, query_protocol_version};
+use fencerunner::reporter::{self, OutputFormat, Verbosity};
 use fencerunner::{
-    Probe, find_repo_root, list_probes, resolve_boundary_schema_path, resolve_catalog_path,
-    resolve_helper_binary, resolve_probe, split_list,
+    BoundarySchema, CapabilityIndex, Probe, find_repo_root, list_probes,
+    resolve_boundary_schema_path, resolve_catalog_path, resolve_helper_binary, resolve_probe,
+    split_list,
 };
 use serde_json::Value;
 use std::{
     env,
     path::{Path, PathBuf},
     process::{Command, Stdio},
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
 };
 
 fn main() {
@@ -29,33 +37,31 @@ fn main() {
 
 fn run() -> Result<()> {
     let cli = Cli::parse()?;
+    let verbosity = Verbosity::from_flags(cli.quiet, cli.verbose)?;
     let repo_root = find_repo_root()?;
     let catalog_path = resolve_catalog_path(&repo_root, cli.catalog_path.as_deref());
     let boundary_schema_path =
         resolve_boundary_schema_path(&repo_root, cli.boundary_path.as_deref())?;
     let probes = resolve_probes(&repo_root)?;
     let modes = resolve_modes()?;
+    negotiate_helper_version(&repo_root, &catalog_path, &boundary_schema_path, verbosity)?;
 
-    let mut errors: Vec<String> = Vec::new();
-    for mode in modes {
-        for probe in &probes {
-            if let Err(err) = run_probe(
-                &repo_root,
-                probe,
-                mode,
-                &catalog_path,
-                &boundary_schema_path,
-            ) {
-                let message = format!(
-                    "probe {} in mode {} failed: {err:#}",
-                    probe.id,
-                    mode.as_str()
-                );
-                eprintln!("probe-matrix: {message}");
-                errors.push(message);
-            }
-        }
-    }
+    let tasks: Vec<(Probe, RunMode)> = modes
+        .iter()
+        .flat_map(|&mode| probes.iter().cloned().map(move |probe| (probe, mode)))
+        .collect();
+    let jobs = resolve_jobs(cli.jobs)?;
+
+    let errors = run_matrix(
+        &repo_root,
+        &catalog_path,
+        &boundary_schema_path,
+        &tasks,
+        jobs,
+        cli.ordered,
+        cli.format,
+        verbosity,
+    );
 
     if errors.is_empty() {
         Ok(())
@@ -114,13 +120,184 @@ fn resolve_probes(repo_root: &Path) -> Result<Vec<Probe>> {
     Ok(probes)
 }
 
-fn run_probe(
+/// Resolve the worker-thread count, mirroring Cargo's `--jobs`: the CLI flag
+/// wins, then `JOBS`, then the host's available parallelism.
+fn resolve_jobs(cli_jobs: Option<usize>) -> Result<usize> {
+    if let Some(jobs) = cli_jobs {
+        if jobs == 0 {
+            bail!("--jobs must be at least 1");
+        }
+        return Ok(jobs);
+    }
+
+    if let Ok(raw) = env::var("JOBS") {
+        let jobs: usize = raw
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid JOBS value: {raw}"))?;
+        if jobs == 0 {
+            bail!("JOBS must be at least 1");
+        }
+        return Ok(jobs);
+    }
+
+    Ok(thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1))
+}
+
+/// Run every `(probe, mode)` task across `jobs` worker threads pulling from a
+/// shared cursor, returning the failure messages collected along the way (in
+/// matrix order, regardless of which worker finished first).
+///
+/// Under [`OutputFormat::Jsonl`], each worker prints its own compact NDJSON
+/// line as soon as it's ready, serialized through a single mutex so lines
+/// from different workers never interleave — unless `ordered` is set, in
+/// which case lines are buffered per-task and flushed in matrix order only
+/// once every task has completed. [`OutputFormat::Json`] and
+/// [`OutputFormat::Human`] always buffer in matrix order, since both need
+/// the full result set before they can render; [`OutputFormat::Quiet`]
+/// drops successful records entirely.
+fn run_matrix(
+    repo_root: &Path,
+    catalog_path: &Path,
+    boundary_path: &Path,
+    tasks: &[(Probe, RunMode)],
+    jobs: usize,
+    ordered: bool,
+    format: OutputFormat,
+    verbosity: Verbosity,
+) -> Vec<String> {
+    let buffered = matches!(format, OutputFormat::Json | OutputFormat::Human)
+        || (format == OutputFormat::Jsonl && ordered);
+
+    let cursor = AtomicUsize::new(0);
+    let errors: Mutex<Vec<(usize, String)>> = Mutex::new(Vec::new());
+    let stdout_lock: Mutex<()> = Mutex::new(());
+    let slots: Mutex<Vec<Option<Value>>> = Mutex::new(vec![None; tasks.len()]);
+
+    let worker_count = jobs.min(tasks.len().max(1));
+    thread::scope(|scope| {
+        for _ in 0..worker_count {
+            scope.spawn(|| loop {
+                let index = cursor.fetch_add(1, Ordering::SeqCst);
+                let Some((probe, mode)) = tasks.get(index) else {
+                    break;
+                };
+
+                match execute_probe(repo_root, probe, *mode, catalog_path, boundary_path) {
+                    Ok(record) => {
+                        if buffered {
+                            slots.lock().expect("slots mutex poisoned")[index] = Some(record);
+                        } else if format == OutputFormat::Jsonl {
+                            let _guard = stdout_lock.lock().expect("stdout mutex poisoned");
+                            print_jsonl(&record);
+                        }
+                        // OutputFormat::Quiet drops the record entirely.
+                    }
+                    Err(err) => {
+                        let message = format!(
+                            "probe {} in mode {} failed: {err:#}",
+                            probe.id,
+                            mode.as_str()
+                        );
+                        reporter::diagnostic(verbosity, &format!("probe-matrix: {message}"));
+                        errors
+                            .lock()
+                            .expect("errors mutex poisoned")
+                            .push((index, message));
+                    }
+                }
+            });
+        }
+    });
+
+    if buffered {
+        let records: Vec<Value> = slots
+            .into_inner()
+            .expect("slots mutex poisoned")
+            .into_iter()
+            .flatten()
+            .collect();
+
+        match format {
+            OutputFormat::Jsonl => {
+                for record in &records {
+                    print_jsonl(record);
+                }
+            }
+            OutputFormat::Json => match reporter::render_json_records(&records) {
+                Ok(rendered) => println!("{rendered}"),
+                Err(err) => eprintln!("probe-matrix: failed to render json report: {err:#}"),
+            },
+            OutputFormat::Human => print!("{}", reporter::render_human_records(&records)),
+            OutputFormat::Quiet => {}
+        }
+    }
+
+    let mut errors = errors.into_inner().expect("errors mutex poisoned");
+    errors.sort_by_key(|(index, _)| *index);
+    errors.into_iter().map(|(_, message)| message).collect()
+}
+
+fn print_jsonl(record: &Value) {
+    match serde_json::to_string(record) {
+        Ok(line) => println!("{line}"),
+        Err(err) => eprintln!("probe-matrix: failed to serialize record: {err}"),
+    }
+}
+
+/// Query the `probe-exec` helper's advertised [`VersionInfo`] and negotiate
+/// it against the catalog/boundary schema this matrix resolved, before
+/// running a single probe.
+///
+/// Hard-fails on an incompatible major protocol version or a helper that
+/// doesn't advertise the expected schema/catalog key; a minor protocol skew
+/// is only warned about on stderr.
+fn negotiate_helper_version(
+    repo_root: &Path,
+    catalog_path: &Path,
+    boundary_path: &Path,
+    verbosity: Verbosity,
+) -> Result<()> {
+    let capabilities = CapabilityIndex::load(catalog_path)
+        .with_context(|| format!("loading capability catalog {}", catalog_path.display()))?;
+    let boundary_schema = BoundarySchema::load(boundary_path)
+        .with_context(|| format!("loading boundary schema {}", boundary_path.display()))?;
+    let local = VersionInfo::current(boundary_schema.schema_key(), &capabilities.key().0);
+
+    let helper = resolve_helper_binary(repo_root, "probe-exec")?;
+    let remote = query_protocol_version(&helper, repo_root)
+        .with_context(|| format!("querying protocol version from {}", helper.display()))?;
+
+    let outcome = negotiate(
+        &local,
+        &remote,
+        boundary_schema.schema_key(),
+        &capabilities.key().0,
+    );
+    for warning in &outcome.warnings {
+        reporter::diagnostic(verbosity, &format!("probe-matrix: warning: {warning}"));
+    }
+    if !outcome.is_compatible() {
+        bail!(
+            "probe-exec helper {} failed version negotiation:\n{}",
+            helper.display(),
+            outcome.errors.join("\n")
+        );
+    }
+    Ok(())
+}
+
+/// Run a single `(probe, mode)` pair via the `probe-exec` helper and return
+/// its emitted boundary object.
+fn execute_probe(
     repo_root: &Path,
     probe: &Probe,
     mode: RunMode,
     catalog_path: &Path,
     boundary_path: &Path,
-) -> Result<()> {
+) -> Result<Value> {
     let runner = resolve_helper_binary(repo_root, "probe-exec")?;
     let output = Command::new(&runner)
         .arg(mode.as_str())
@@ -149,14 +326,17 @@ fn run_probe(
             mode.as_str()
         )
     })?;
-    let compact = serde_json::to_string(&json_value)?;
-    println!("{compact}");
-    Ok(())
+    Ok(json_value)
 }
 
 struct Cli {
     catalog_path: Option<PathBuf>,
     boundary_path: Option<PathBuf>,
+    jobs: Option<usize>,
+    ordered: bool,
+    format: OutputFormat,
+    quiet: bool,
+    verbose: bool,
 }
 
 impl Cli {
@@ -165,6 +345,11 @@ impl Cli {
         let _program = args.next();
         let mut catalog_path = None;
         let mut boundary_path = None;
+        let mut jobs = None;
+        let mut ordered = false;
+        let mut format = OutputFormat::Jsonl;
+        let mut quiet = false;
+        let mut verbose = false;
 
         while let Some(arg) = args.next() {
             let arg_str = arg
@@ -173,6 +358,11 @@ impl Cli {
             match arg_str {
                 "--catalog" => catalog_path = Some(next_path("--catalog", &mut args)?),
                 "--boundary" => boundary_path = Some(next_path("--boundary", &mut args)?),
+                "--jobs" => jobs = Some(next_jobs(&mut args)?),
+                "--ordered" => ordered = true,
+                "--format" => format = OutputFormat::parse(&next_value("--format", &mut args)?)?,
+                "--quiet" => quiet = true,
+                "--verbose" => verbose = true,
                 "--help" | "-h" => usage(0),
                 other => bail!("unknown argument: {other}"),
             }
@@ -181,10 +371,36 @@ impl Cli {
         Ok(Self {
             catalog_path,
             boundary_path,
+            jobs,
+            ordered,
+            format,
+            quiet,
+            verbose,
         })
     }
 }
 
+fn next_value(flag: &str, args: &mut env::ArgsOs) -> Result<String> {
+    let value = args
+        .next()
+        .ok_or_else(|| anyhow!("{flag} requires a value"))?;
+    value
+        .into_string()
+        .map_err(|_| anyhow!("{flag} must be valid UTF-8"))
+}
+
+fn next_jobs(args: &mut env::ArgsOs) -> Result<usize> {
+    let value = args
+        .next()
+        .ok_or_else(|| anyhow!("--jobs requires a value"))?;
+    let value = value
+        .into_string()
+        .map_err(|_| anyhow!("--jobs must be valid UTF-8"))?;
+    value
+        .parse()
+        .with_context(|| format!("--jobs must be a positive integer, got '{value}'"))
+}
+
 fn next_path(flag: &str, args: &mut env::ArgsOs) -> Result<PathBuf> {
     let value = args
         .next()
@@ -202,7 +418,7 @@ fn next_path(flag: &str, args: &mut env::ArgsOs) -> Result<PathBuf> {
 
 fn usage(code: i32) -> ! {
     eprintln!(
-        "Usage: probe-matrix [--catalog PATH] [--boundary PATH]\n\nOptions:\n  --catalog PATH            Override capability catalog path (or set CATALOG_PATH).\n  --boundary PATH           Override boundary-object schema path (or set BOUNDARY_PATH).\n  --help                    Show this help text."
+        "Usage: probe-matrix [--catalog PATH] [--boundary PATH] [--jobs N] [--ordered] [--format jsonl|json|human|quiet] [--quiet] [--verbose]\n\nOptions:\n  --catalog PATH            Override capability catalog path (or set CATALOG_PATH).\n  --boundary PATH           Override boundary-object schema path (or set BOUNDARY_PATH).\n  --jobs N                  Number of worker threads (or set JOBS; defaults to available parallelism).\n  --ordered                 Buffer output and flush in matrix order instead of streaming as results complete.\n  --format FORMAT           Result output format: jsonl (default), json, human, or quiet.\n  --quiet                   Suppress stderr diagnostics (warnings, failure notices).\n  --verbose                 Print extra stderr diagnostics.\n  --help                    Show this help text."
     );
     std::process::exit(code);
 }
@@ -0,0 +1,175 @@
+//! Classifies a boundary-record stream against a declarative, per-(os,
+//! sandbox_mode) expectation table, to answer "does this capability still
+//! behave the way we expect on this host?" across every target the matrix
+//! runs on.
+//!
+//! Unlike `probe-diff` (which joins two runs from the same matrix invocation
+//! to find where a sandbox *changed* behavior), `probe-os-matrix` classifies
+//! one run's records independently against a committed table keyed by
+//! `(capability_id, os, sandbox_mode)` (see [`fencerunner::expectation_matrix`]),
+//! so a record can be flagged as deviating even with no baseline run to
+//! compare against.
+//!
+//!     probe --matrix > run.ndjson
+//!     probe-os-matrix --records-file run.ndjson --expectations-file os_expectations.json
+
+use anyhow::{Context, Result, anyhow, bail};
+use fencerunner::expectation_matrix::{ExpectationTable, classify_matrix, render_matrix_human};
+use fencerunner::reporter::{self, OutputFormat, Verbosity};
+use fencerunner::{
+    BoundaryObject, BoundarySchema, CapabilityIndex, find_repo_root, read_boundary_objects,
+    resolve_boundary_schema_path, resolve_catalog_path,
+};
+use std::env;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("{err:#}");
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<()> {
+    let cli = Cli::parse()?;
+    let verbosity = Verbosity::from_flags(cli.quiet, cli.verbose)?;
+    let repo_root = find_repo_root()?;
+    let catalog_path = resolve_catalog_path(&repo_root, cli.catalog_path.as_deref());
+    let capability_index = CapabilityIndex::load(&catalog_path)
+        .with_context(|| format!("loading capability catalog from {}", catalog_path.display()))?;
+    let boundary_schema_path =
+        resolve_boundary_schema_path(&repo_root, cli.boundary_schema_path.as_deref())?;
+    let boundary_schema = BoundarySchema::load(&boundary_schema_path).with_context(|| {
+        format!(
+            "loading boundary schema from {}",
+            boundary_schema_path.display()
+        )
+    })?;
+
+    let records = load_records(&cli.records_path, &boundary_schema)?;
+    let table =
+        ExpectationTable::load(&cli.expectations_path, &capability_index).with_context(|| {
+            format!(
+                "loading expectation table from {}",
+                cli.expectations_path.display()
+            )
+        })?;
+
+    let report = classify_matrix(&records, &table);
+
+    match cli.format {
+        OutputFormat::Quiet => {}
+        OutputFormat::Human => print!("{}", render_matrix_human(&report)),
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        OutputFormat::Jsonl => {
+            for entry in &report.entries {
+                println!("{}", serde_json::to_string(entry)?);
+            }
+        }
+    }
+
+    let deviations = report.unexpected_success + report.unexpected_failure;
+    if deviations > 0 {
+        reporter::diagnostic(
+            verbosity,
+            &format!("probe-os-matrix: {deviations} deviation(s) from the expectation table"),
+        );
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn load_records(path: &Path, boundary_schema: &BoundarySchema) -> Result<Vec<BoundaryObject>> {
+    let file =
+        File::open(path).with_context(|| format!("opening boundary stream {}", path.display()))?;
+    let records = read_boundary_objects(BufReader::new(file))
+        .map_err(|err| anyhow!(err))
+        .with_context(|| format!("reading boundary stream {}", path.display()))?;
+    for record in &records {
+        let value = serde_json::to_value(record)?;
+        boundary_schema
+            .validate(&value)
+            .map_err(|err| anyhow!(err.to_string()))
+            .with_context(|| format!("validating {}", path.display()))?;
+    }
+    Ok(records)
+}
+
+struct Cli {
+    records_path: PathBuf,
+    expectations_path: PathBuf,
+    catalog_path: Option<PathBuf>,
+    boundary_schema_path: Option<PathBuf>,
+    format: OutputFormat,
+    quiet: bool,
+    verbose: bool,
+}
+
+impl Cli {
+    fn parse() -> Result<Self> {
+        let mut args = env::args_os();
+        let _program = args.next();
+
+        let mut records_path = None;
+        let mut expectations_path = None;
+        let mut catalog_path = None;
+        let mut boundary_schema_path = None;
+        let mut format = OutputFormat::Human;
+        let mut quiet = false;
+        let mut verbose = false;
+
+        while let Some(arg) = args.next() {
+            let arg_str = arg
+                .to_str()
+                .ok_or_else(|| anyhow!("invalid UTF-8 in argument"))?;
+            match arg_str {
+                "--records-file" => records_path = Some(next_path("--records-file", &mut args)?),
+                "--expectations-file" => {
+                    expectations_path = Some(next_path("--expectations-file", &mut args)?)
+                }
+                "--catalog" => catalog_path = Some(next_path("--catalog", &mut args)?),
+                "--boundary" => boundary_schema_path = Some(next_path("--boundary", &mut args)?),
+                "--format" => format = OutputFormat::parse(&next_value("--format", &mut args)?)?,
+                "--quiet" => quiet = true,
+                "--verbose" => verbose = true,
+                "--help" | "-h" => usage(0),
+                other => bail!("unknown argument: {other}"),
+            }
+        }
+
+        Ok(Self {
+            records_path: records_path.ok_or_else(|| anyhow!("--records-file PATH is required"))?,
+            expectations_path: expectations_path
+                .ok_or_else(|| anyhow!("--expectations-file PATH is required"))?,
+            catalog_path,
+            boundary_schema_path,
+            format,
+            quiet,
+            verbose,
+        })
+    }
+}
+
+fn next_value(flag: &str, args: &mut env::ArgsOs) -> Result<String> {
+    let value = args
+        .next()
+        .ok_or_else(|| anyhow!("{flag} requires a value"))?;
+    value
+        .into_string()
+        .map_err(|_| anyhow!("{flag} must be valid UTF-8"))
+}
+
+fn next_path(flag: &str, args: &mut env::ArgsOs) -> Result<PathBuf> {
+    Ok(PathBuf::from(next_value(flag, args)?))
+}
+
+fn usage(code: i32) -> ! {
+    eprintln!(
+        "Usage: probe-os-matrix --records-file PATH --expectations-file PATH [options]\n\nOptions:\n  --records-file PATH       Boundary-record NDJSON from a `probe --matrix` run.\n  --expectations-file PATH Declarative (capability_id, os, sandbox_mode) -> expected_result table (JSON array).\n  --catalog PATH            Override capability catalog path (or set CATALOG_PATH).\n  --boundary PATH           Override boundary-object schema path (or set BOUNDARY_PATH).\n  --format FORMAT           Report output format: jsonl, json, human (default), or quiet.\n  --quiet                   Suppress stderr diagnostics.\n  --verbose                 Print extra stderr diagnostics.\n  --help                    Show this help text.\n\nExits non-zero when any record deviates from the expectation table for its (os, sandbox_mode)."
+    );
+    std::process::exit(code);
+}
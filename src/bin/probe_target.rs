@@ -1,20 +1,25 @@
 //! Targeted probe runner backing `probe --target`.
 //!
-//! The CLI selects a subset of probes by capability id or explicit probe id,
-//! fans out across the requested modes, and shells out to `probe-matrix` so
-//! the existing execution pipeline (probe-exec → emit-record) remains
-//! untouched.
+//! The CLI selects a subset of probes by capability id, explicit probe id, or
+//! a named `[set.<name>]` alias from `.fencerunner.toml` (a reusable probe
+//! grouping, mirroring cargo's `[alias]` config), fans out across the
+//! requested modes, and shells out to `probe-matrix` so the existing
+//! execution pipeline (probe-exec → emit-record) remains untouched.
 
 use anyhow::{Context, Result, anyhow, bail};
 use fencerunner::connectors::{
     Availability, RunMode, allowed_mode_names, default_mode_names, parse_modes,
 };
+use fencerunner::emit_support::did_you_mean;
+use fencerunner::reporter::{OutputFormat, Verbosity};
 use fencerunner::{
     CapabilityId, CapabilityIndex, Probe, ProbeMetadata, find_repo_root, list_probes,
     resolve_boundary_schema_path, resolve_catalog_path, resolve_helper_binary, resolve_probe,
 };
-use std::collections::BTreeSet;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
 use std::env;
+use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
@@ -27,6 +32,7 @@ fn main() {
 
 fn run() -> Result<()> {
     let cli = Cli::parse()?;
+    let verbosity = Verbosity::from_flags(cli.quiet, cli.verbose)?;
     let repo_root = find_repo_root()?;
     let catalog_path = resolve_catalog_path(&repo_root, cli.catalog_path.as_deref());
     let boundary_path = resolve_boundary_schema_path(&repo_root, cli.boundary_path.as_deref())?;
@@ -34,7 +40,7 @@ fn run() -> Result<()> {
     let plan = resolve_selection(&repo_root, &catalog_path, &cli.selection)?;
 
     if cli.list_only {
-        print_dry_run(&plan, &modes, cli.repeat);
+        print_dry_run(&plan, &modes, cli.repeat, cli.format);
         return Ok(());
     }
 
@@ -45,21 +51,52 @@ fn run() -> Result<()> {
         cli.repeat,
         &catalog_path,
         &boundary_path,
+        cli.format,
+        verbosity,
     )
 }
 
-fn print_dry_run(plan: &SelectionPlan, modes: &[RunMode], repeat: u32) {
+/// Print the resolved selection/modes without executing any probes.
+///
+/// Suppressed entirely under [`OutputFormat::Quiet`]; rendered as a single
+/// JSON object under [`OutputFormat::Json`]; otherwise printed as the
+/// original plain-text listing (there's no per-record stream to format
+/// differently between `jsonl` and `human` here).
+fn print_dry_run(plan: &SelectionPlan, modes: &[RunMode], repeat: u32, format: OutputFormat) {
+    if format == OutputFormat::Quiet {
+        return;
+    }
+
+    let mode_names: Vec<&str> = modes.iter().map(RunMode::as_str).collect();
+    if format == OutputFormat::Json {
+        let selection = match &plan.selection {
+            SelectionDescription::Capability(id) => {
+                serde_json::json!({"capability": id.0})
+            }
+            SelectionDescription::Probes(ids) => serde_json::json!({"probes": ids}),
+            SelectionDescription::Set(name) => serde_json::json!({"set": name}),
+        };
+        let envelope = serde_json::json!({
+            "dry_run": true,
+            "selection": selection,
+            "modes": mode_names,
+            "repeat": repeat,
+            "probes": plan.probes.iter().map(|probe| &probe.id).collect::<Vec<_>>(),
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&envelope).expect("dry-run envelope serializes")
+        );
+        return;
+    }
+
     println!("probe target (dry-run)");
     match &plan.selection {
         SelectionDescription::Capability(id) => println!("capability: {}", id.0),
         SelectionDescription::Probes(ids) => println!("probes: {}", ids.join(", ")),
+        SelectionDescription::Set(name) => println!("set: {name}"),
     }
-    let mode_names = modes
-        .iter()
-        .map(|mode| mode.as_str())
-        .collect::<Vec<_>>()
-        .join(", ");
-    println!("modes: {mode_names}");
+    println!("modes: {}", mode_names.join(", "));
     if repeat > 1 {
         println!("repeat: {repeat}");
     }
@@ -76,6 +113,8 @@ fn run_matrix(
     repeat: u32,
     catalog_path: &Path,
     boundary_path: &Path,
+    format: OutputFormat,
+    verbosity: Verbosity,
 ) -> Result<()> {
     if probes.is_empty() {
         bail!("no probes resolved for target run");
@@ -92,6 +131,13 @@ fn run_matrix(
         .collect::<Vec<_>>()
         .join(" ");
 
+    let format_arg = match format {
+        OutputFormat::Jsonl => "jsonl",
+        OutputFormat::Json => "json",
+        OutputFormat::Human => "human",
+        OutputFormat::Quiet => "quiet",
+    };
+
     for attempt in 0..repeat {
         let mut cmd = Command::new(&helper);
         cmd.current_dir(repo_root)
@@ -100,10 +146,17 @@ fn run_matrix(
             .env("PROBES", &probes_arg)
             .env("MODES", &modes_arg)
             .env("CATALOG_PATH", catalog_path)
-            .env("BOUNDARY_PATH", boundary_path);
+            .env("BOUNDARY_PATH", boundary_path)
+            .arg("--format")
+            .arg(format_arg);
         if env::var_os("FENCE_ROOT").is_none() {
             cmd.env("FENCE_ROOT", repo_root);
         }
+        if verbosity == Verbosity::Quiet {
+            cmd.arg("--quiet");
+        } else if verbosity == Verbosity::Verbose {
+            cmd.arg("--verbose");
+        }
 
         let status = cmd
             .status()
@@ -151,6 +204,7 @@ fn resolve_selection(
     match selection {
         Selection::Capability(id) => resolve_capability_selection(repo_root, catalog_path, id),
         Selection::Probes(ids) => resolve_probe_selection(repo_root, ids),
+        Selection::Set(name) => resolve_set_selection(repo_root, catalog_path, name),
     }
 }
 
@@ -161,8 +215,9 @@ fn resolve_capability_selection(
 ) -> Result<SelectionPlan> {
     let index = CapabilityIndex::load(catalog_path)?;
     if index.capability(id).is_none() {
+        let hint = did_you_mean(&id.0, index.ids().map(|id| id.0.as_str()));
         bail!(
-            "unknown capability '{}' (not present in bundled catalog)",
+            "unknown capability '{}' (not present in bundled catalog){hint}",
             id.0
         );
     }
@@ -203,7 +258,11 @@ fn resolve_probe_selection(repo_root: &Path, requested: &[String]) -> Result<Sel
     let mut probes = Vec::new();
     let mut seen = BTreeSet::new();
     for raw in requested {
-        let resolved = resolve_probe(repo_root, raw)?;
+        let resolved = resolve_probe(repo_root, raw).map_err(|err| {
+            let known = list_probes(repo_root).unwrap_or_default();
+            let hint = did_you_mean(raw, known.iter().map(|probe| probe.id.as_str()));
+            anyhow!("{err:#}{hint}")
+        })?;
         if seen.insert(resolved.id.clone()) {
             probes.push(resolved);
         }
@@ -215,6 +274,89 @@ fn resolve_probe_selection(repo_root: &Path, requested: &[String]) -> Result<Sel
     })
 }
 
+/// Resolve a `--set NAME` selection, expanding a `[set.NAME]` alias from
+/// `.fencerunner.toml` by unioning `probes_for_capability` results for each
+/// listed `caps` entry with `resolve_probe` results for each listed `probes`
+/// entry, the same way `resolve_probe_selection` dedups by `probe.id`.
+fn resolve_set_selection(
+    repo_root: &Path,
+    catalog_path: &Path,
+    name: &str,
+) -> Result<SelectionPlan> {
+    let sets = load_sets(repo_root)?;
+    let Some(entry) = sets.get(name) else {
+        let hint = did_you_mean(name, sets.keys().map(String::as_str));
+        bail!("unknown set '{name}' (no [set.{name}] entry in .fencerunner.toml){hint}");
+    };
+
+    let index = CapabilityIndex::load(catalog_path)?;
+    let mut probes = Vec::new();
+    let mut seen = BTreeSet::new();
+
+    for cap in &entry.caps {
+        let id = CapabilityId(cap.clone());
+        if index.capability(&id).is_none() {
+            let hint = did_you_mean(&id.0, index.ids().map(|id| id.0.as_str()));
+            bail!(
+                "set '{name}' references unknown capability '{}' (not present in bundled catalog){hint}",
+                id.0
+            );
+        }
+        for probe in probes_for_capability(repo_root, &id)? {
+            if seen.insert(probe.id.clone()) {
+                probes.push(probe);
+            }
+        }
+    }
+
+    for raw in &entry.probes {
+        let resolved = resolve_probe(repo_root, raw).map_err(|err| {
+            let known = list_probes(repo_root).unwrap_or_default();
+            let hint = did_you_mean(raw, known.iter().map(|probe| probe.id.as_str()));
+            anyhow!("set '{name}': {err:#}{hint}")
+        })?;
+        if seen.insert(resolved.id.clone()) {
+            probes.push(resolved);
+        }
+    }
+
+    probes.sort_by(|a, b| a.id.cmp(&b.id));
+
+    Ok(SelectionPlan {
+        selection: SelectionDescription::Set(name.to_string()),
+        probes,
+    })
+}
+
+/// Load `[set.NAME]` entries from `FENCE_ROOT/.fencerunner.toml`, if present.
+///
+/// A missing config file is not an error; a malformed one is.
+fn load_sets(repo_root: &Path) -> Result<BTreeMap<String, SetEntry>> {
+    let path = repo_root.join(".fencerunner.toml");
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(_) => return Ok(BTreeMap::new()),
+    };
+
+    let config: SetConfigFile = toml::from_str(&contents)
+        .with_context(|| format!("parsing set config {}", path.display()))?;
+    Ok(config.set)
+}
+
+#[derive(Deserialize, Default)]
+struct SetConfigFile {
+    #[serde(default)]
+    set: BTreeMap<String, SetEntry>,
+}
+
+#[derive(Deserialize, Default)]
+struct SetEntry {
+    #[serde(default)]
+    caps: Vec<String>,
+    #[serde(default)]
+    probes: Vec<String>,
+}
+
 struct SelectionPlan {
     selection: SelectionDescription,
     probes: Vec<Probe>,
@@ -223,12 +365,14 @@ struct SelectionPlan {
 enum SelectionDescription {
     Capability(CapabilityId),
     Probes(Vec<String>),
+    Set(String),
 }
 
 #[derive(Clone)]
 enum Selection {
     Capability(CapabilityId),
     Probes(Vec<String>),
+    Set(String),
 }
 
 struct Cli {
@@ -238,6 +382,9 @@ struct Cli {
     list_only: bool,
     catalog_path: Option<PathBuf>,
     boundary_path: Option<PathBuf>,
+    format: OutputFormat,
+    quiet: bool,
+    verbose: bool,
 }
 
 impl Cli {
@@ -247,11 +394,15 @@ impl Cli {
 
         let mut cap: Option<String> = None;
         let mut probes: Vec<String> = Vec::new();
+        let mut set: Option<String> = None;
         let mut modes: Vec<String> = Vec::new();
         let mut repeat: u32 = 1;
         let mut list_only = false;
         let mut catalog_path = None;
         let mut boundary_path = None;
+        let mut format = OutputFormat::Jsonl;
+        let mut quiet = false;
+        let mut verbose = false;
 
         while let Some(arg) = args.next() {
             let arg_str = arg
@@ -277,6 +428,13 @@ impl Cli {
                     let value = next_value("--probe", &mut args)?;
                     probes.push(normalize_token(value, "--probe")?);
                 }
+                "--set" => {
+                    let value = next_value("--set", &mut args)?;
+                    if set.is_some() {
+                        bail!("--set may only be specified once");
+                    }
+                    set = Some(normalize_token(value, "--set")?);
+                }
                 "--mode" => {
                     let value = next_value("--mode", &mut args)?;
                     modes.push(normalize_token(value, "--mode")?);
@@ -289,6 +447,12 @@ impl Cli {
                     }
                 }
                 "--list-only" => list_only = true,
+                "--format" => {
+                    let value = next_value("--format", &mut args)?;
+                    format = OutputFormat::parse(&value)?;
+                }
+                "--quiet" => quiet = true,
+                "--verbose" => verbose = true,
                 "--help" | "-h" => usage(0),
                 other => {
                     bail!("unknown argument: {other}");
@@ -296,14 +460,15 @@ impl Cli {
             }
         }
 
-        let selection = match (cap, probes.is_empty()) {
-            (Some(cap_id), true) => Selection::Capability(CapabilityId(cap_id)),
-            (None, false) => Selection::Probes(probes),
-            (Some(_), false) => {
-                bail!("Specify exactly one of --cap or --probe");
+        let selection = match (cap, probes.is_empty(), set) {
+            (Some(cap_id), true, None) => Selection::Capability(CapabilityId(cap_id)),
+            (None, false, None) => Selection::Probes(probes),
+            (None, true, Some(name)) => Selection::Set(name),
+            (None, true, None) => {
+                bail!("--cap, --probe, or --set is required for --target");
             }
-            (None, true) => {
-                bail!("--cap or --probe is required for --target");
+            _ => {
+                bail!("Specify exactly one of --cap, --probe, or --set");
             }
         };
 
@@ -314,6 +479,9 @@ impl Cli {
             list_only,
             catalog_path,
             boundary_path,
+            format,
+            quiet,
+            verbose,
         })
     }
 }
@@ -337,7 +505,7 @@ fn normalize_token(raw: String, flag: &str) -> Result<String> {
 
 fn usage(code: i32) -> ! {
     eprintln!(
-        "Usage: probe-target (--cap <capability-id> | --probe <probe-id>) [options]\n\nOptions:\n      --cap <id>            Run every probe whose primary capability matches <id>.\n      --probe <id>          Run a specific probe (repeatable).\n      --mode <mode>         Restrict modes (baseline only).\n      --repeat <n>          Rerun the selection n times (default: 1).\n      --catalog <path>      Override capability catalog path (or set CATALOG_PATH).\n      --boundary <path>     Override boundary-object schema path (or set BOUNDARY_PATH).\n      --list-only           Print the plan without executing probes.\n      --help                Show this help text.\n"
+        "Usage: probe-target (--cap <capability-id> | --probe <probe-id> | --set <name>) [options]\n\nOptions:\n      --cap <id>            Run every probe whose primary capability matches <id>.\n      --probe <id>          Run a specific probe (repeatable).\n      --set <name>          Run a [set.<name>] alias (caps/probes) from .fencerunner.toml.\n      --mode <mode>         Restrict modes (repeatable; default: every mode available on the host).\n      --repeat <n>          Rerun the selection n times (default: 1).\n      --catalog <path>      Override capability catalog path (or set CATALOG_PATH).\n      --boundary <path>     Override boundary-object schema path (or set BOUNDARY_PATH).\n      --list-only           Print the plan without executing probes.\n      --format <fmt>        Result output format: jsonl (default), json, human, or quiet.\n      --quiet               Suppress stderr diagnostics (forwarded to probe-matrix).\n      --verbose             Print extra stderr diagnostics (forwarded to probe-matrix).\n      --help                Show this help text.\n"
     );
     std::process::exit(code);
 }
@@ -6,7 +6,9 @@
 //! re-parsing ad-hoc maps. When attaching capability context, callers are
 //! expected to use snapshots from the capability catalog resolved at runtime.
 
-use crate::catalog::{Capability, CapabilityId, CapabilitySnapshot, CatalogKey, CatalogRepository};
+use crate::catalog::{
+    Capability, CapabilityGrant, CapabilityId, CapabilitySnapshot, CatalogKey, CatalogRepository,
+};
 use crate::schema_loader::{SchemaLoadOptions, load_json_schema};
 use anyhow::{Context, Result, bail};
 use jsonschema::JSONSchema;
@@ -55,6 +57,11 @@ pub struct BoundaryObject {
 pub struct StackInfo {
     #[serde(default)]
     pub sandbox_mode: Option<String>,
+    /// Image identity (e.g. `debian:stable-slim`) when the probe ran inside a
+    /// container, so records are attributable to the environment that
+    /// produced them. `None` outside the `oci` execution backend.
+    #[serde(default)]
+    pub container_image: Option<String>,
     pub os: String,
 }
 
@@ -130,6 +137,11 @@ pub struct CapabilityContext {
     pub primary: CapabilitySnapshot,
     #[serde(default)]
     pub secondary: Vec<CapabilitySnapshot>,
+    /// Leaf grant resolved by [`crate::catalog::verify_attenuation_chain`] when
+    /// the run carried a delegation chain. Absent for records emitted without
+    /// one, so older consumers see the same shape as before.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_grant: Option<CapabilityGrant>,
 }
 
 /// Errors that can occur while reading NDJSON boundary object streams.
@@ -158,6 +170,7 @@ impl BoundaryObject {
         self.capability_context = CapabilityContext {
             primary: primary.snapshot(),
             secondary: secondary.iter().map(|c| c.snapshot()).collect(),
+            resolved_grant: None,
         };
         self
     }
@@ -167,6 +180,35 @@ impl BoundaryObject {
     pub fn primary_capability_id(&self) -> &CapabilityId {
         &self.capability_context.primary.id
     }
+
+    /// Attach the leaf grant resolved from a verified delegation chain.
+    ///
+    /// Separate from [`Self::with_capabilities`] because the delegation chain
+    /// is optional per run; most probes never call this and keep
+    /// `resolved_grant` at `None`.
+    pub fn with_resolved_grant(mut self, grant: CapabilityGrant) -> Self {
+        self.capability_context.resolved_grant = Some(grant);
+        self
+    }
+
+    /// Diff this record against `expected`, masking `masked_pointers` (JSON
+    /// Pointers, see [`crate::snapshot::default_redactions`]) on both sides
+    /// first. Returns one [`crate::snapshot::FieldDiff`] per leaf path that
+    /// still differs, so tests can assert against a golden fixture without
+    /// hand-rolling field-by-field checks.
+    pub fn diff_against(
+        &self,
+        expected: &Value,
+        masked_pointers: &[String],
+    ) -> Result<Vec<crate::snapshot::FieldDiff>> {
+        let actual =
+            serde_json::to_value(self).context("serializing boundary object for snapshot diff")?;
+        Ok(crate::snapshot::diff_records(
+            &actual,
+            expected,
+            masked_pointers,
+        ))
+    }
 }
 
 impl CatalogRepository {
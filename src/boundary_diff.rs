@@ -0,0 +1,379 @@
+//! Baseline↔target capability-boundary diff.
+//!
+//! The harness happily runs probes under `baseline` and a sandboxed mode and
+//! emits a `BoundaryObject` for each, but nothing correlates the two streams
+//! to answer "where did the sandbox actually bite?" This module joins two
+//! streams of boundary objects on `(probe.id, operation.category,
+//! operation.verb, operation.target, probe.primary_capability_id)` and
+//! classifies how `result.observed_result` moved between the two runs,
+//! grouped by capability id so the per-capability counts and the list of
+//! leaking probes are immediately visible.
+
+use crate::catalog::CapabilityIndex;
+use crate::{BoundaryObject, CapabilityId};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+/// Match key a baseline record and a target record are joined on.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct MatchKey {
+    probe_id: String,
+    category: String,
+    verb: String,
+    target: String,
+    capability_id: String,
+}
+
+impl MatchKey {
+    fn from_record(record: &BoundaryObject) -> Self {
+        MatchKey {
+            probe_id: record.probe.id.clone(),
+            category: record.operation.category.clone(),
+            verb: record.operation.verb.clone(),
+            target: record.operation.target.clone(),
+            capability_id: record.probe.primary_capability_id.0.clone(),
+        }
+    }
+}
+
+/// How a matched pair's `result.observed_result` moved from baseline to
+/// target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BoundaryTransition {
+    /// `success` in baseline, `denied` or `error` in target: the sandbox
+    /// enforced a boundary that baseline didn't have.
+    Enforced,
+    /// `success` in both: the capability leaked through in the target run.
+    Leaked,
+    /// `denied` in both: the boundary was already closed; no regression.
+    ConsistentlyBlocked,
+    /// Any other pairing (e.g. a result newly appearing in target that
+    /// baseline didn't have denied, or either side erroring) that doesn't
+    /// fit one of the three recognized transitions above.
+    Other,
+}
+
+impl BoundaryTransition {
+    fn classify(baseline_result: &str, target_result: &str) -> Self {
+        match (baseline_result, target_result) {
+            ("success", "denied") | ("success", "error") => BoundaryTransition::Enforced,
+            ("success", "success") => BoundaryTransition::Leaked,
+            ("denied", "denied") => BoundaryTransition::ConsistentlyBlocked,
+            _ => BoundaryTransition::Other,
+        }
+    }
+}
+
+/// Which side of the diff an unpaired record came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnpairedSide {
+    BaselineOnly,
+    TargetOnly,
+}
+
+/// A `(probe, operation)` pair present in only one of the two streams.
+#[derive(Debug, Clone, Serialize)]
+pub struct UnpairedRecord {
+    pub probe_id: String,
+    pub capability_id: String,
+    pub category: String,
+    pub verb: String,
+    pub target: String,
+    pub side: UnpairedSide,
+}
+
+/// Per-capability rollup of matched-pair transitions and leaking probes.
+#[derive(Debug, Clone, Serialize)]
+pub struct CapabilityDiffSummary {
+    pub capability_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layer: Option<String>,
+    pub enforced: usize,
+    pub leaked: usize,
+    pub consistently_blocked: usize,
+    pub other: usize,
+    pub unpaired: usize,
+    pub leaking_probes: Vec<String>,
+}
+
+impl CapabilityDiffSummary {
+    fn new(capability_id: String, capabilities: Option<&CapabilityIndex>) -> Self {
+        let (category, layer) = capabilities
+            .and_then(|index| index.capability(&CapabilityId(capability_id.clone())))
+            .map(|capability| {
+                (
+                    Some(capability.category.as_str().to_string()),
+                    Some(capability.layer.as_str().to_string()),
+                )
+            })
+            .unwrap_or((None, None));
+        CapabilityDiffSummary {
+            capability_id,
+            category,
+            layer,
+            enforced: 0,
+            leaked: 0,
+            consistently_blocked: 0,
+            other: 0,
+            unpaired: 0,
+            leaking_probes: Vec::new(),
+        }
+    }
+}
+
+/// Full baseline↔target diff report.
+#[derive(Debug, Clone, Serialize)]
+pub struct BoundaryDiffReport {
+    pub capabilities: Vec<CapabilityDiffSummary>,
+    pub unpaired: Vec<UnpairedRecord>,
+    pub leak_count: usize,
+}
+
+/// Join `baseline` and `target` boundary-object streams and classify every
+/// matched pair, grouping the result by capability id. `capabilities`, when
+/// given, annotates each capability with its catalog `category`/`layer`.
+pub fn diff_boundary_streams(
+    baseline: &[BoundaryObject],
+    target: &[BoundaryObject],
+    capabilities: Option<&CapabilityIndex>,
+) -> BoundaryDiffReport {
+    let baseline_by_key: BTreeMap<MatchKey, &BoundaryObject> = baseline
+        .iter()
+        .map(|record| (MatchKey::from_record(record), record))
+        .collect();
+    let target_by_key: BTreeMap<MatchKey, &BoundaryObject> = target
+        .iter()
+        .map(|record| (MatchKey::from_record(record), record))
+        .collect();
+
+    let mut summaries: BTreeMap<String, CapabilityDiffSummary> = BTreeMap::new();
+    let mut unpaired = Vec::new();
+
+    for (key, baseline_record) in &baseline_by_key {
+        let summary = summaries
+            .entry(key.capability_id.clone())
+            .or_insert_with(|| CapabilityDiffSummary::new(key.capability_id.clone(), capabilities));
+
+        match target_by_key.get(key) {
+            Some(target_record) => {
+                let transition = BoundaryTransition::classify(
+                    &baseline_record.result.observed_result,
+                    &target_record.result.observed_result,
+                );
+                match transition {
+                    BoundaryTransition::Enforced => summary.enforced += 1,
+                    BoundaryTransition::Leaked => {
+                        summary.leaked += 1;
+                        summary.leaking_probes.push(key.probe_id.clone());
+                    }
+                    BoundaryTransition::ConsistentlyBlocked => summary.consistently_blocked += 1,
+                    BoundaryTransition::Other => summary.other += 1,
+                }
+            }
+            None => {
+                summary.unpaired += 1;
+                unpaired.push(unpaired_record(key, UnpairedSide::BaselineOnly));
+            }
+        }
+    }
+
+    for (key, _) in &target_by_key {
+        if baseline_by_key.contains_key(key) {
+            continue;
+        }
+        let summary = summaries
+            .entry(key.capability_id.clone())
+            .or_insert_with(|| CapabilityDiffSummary::new(key.capability_id.clone(), capabilities));
+        summary.unpaired += 1;
+        unpaired.push(unpaired_record(key, UnpairedSide::TargetOnly));
+    }
+
+    let leak_count = summaries.values().map(|summary| summary.leaked).sum();
+
+    BoundaryDiffReport {
+        capabilities: summaries.into_values().collect(),
+        unpaired,
+        leak_count,
+    }
+}
+
+fn unpaired_record(key: &MatchKey, side: UnpairedSide) -> UnpairedRecord {
+    UnpairedRecord {
+        probe_id: key.probe_id.clone(),
+        capability_id: key.capability_id.clone(),
+        category: key.category.clone(),
+        verb: key.verb.clone(),
+        target: key.target.clone(),
+        side,
+    }
+}
+
+/// Render a report as an aligned text table (one row per capability) plus a
+/// trailing leak-count line.
+pub fn render_diff_human(report: &BoundaryDiffReport) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{:<28}  {:<10}  {:>8}  {:>6}  {:>10}  {:>5}  {:>8}",
+        "CAPABILITY", "LAYER", "ENFORCED", "LEAKED", "BLOCKED", "OTHER", "UNPAIRED"
+    );
+    for summary in &report.capabilities {
+        let _ = writeln!(
+            out,
+            "{:<28}  {:<10}  {:>8}  {:>6}  {:>10}  {:>5}  {:>8}",
+            summary.capability_id,
+            summary.layer.as_deref().unwrap_or("-"),
+            summary.enforced,
+            summary.leaked,
+            summary.consistently_blocked,
+            summary.other,
+            summary.unpaired,
+        );
+        if !summary.leaking_probes.is_empty() {
+            let _ = writeln!(out, "    leaking: {}", summary.leaking_probes.join(", "));
+        }
+    }
+    let _ = writeln!(
+        out,
+        "\n{} capabilit(y/ies); {} leak(s); {} unpaired record(s)",
+        report.capabilities.len(),
+        report.leak_count,
+        report.unpaired.len()
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary::{
+        CapabilityContext, OperationInfo, Payload, ProbeInfo, ResultInfo, RunInfo, StackInfo,
+    };
+    use crate::catalog::{CapabilityCategory, CapabilityLayer, CapabilitySnapshot};
+
+    fn record(
+        probe_id: &str,
+        capability_id: &str,
+        mode: &str,
+        target: &str,
+        observed_result: &str,
+    ) -> BoundaryObject {
+        BoundaryObject {
+            schema_version: "boundary_event_v1".to_string(),
+            schema_key: None,
+            capabilities_schema_version: None,
+            stack: StackInfo {
+                sandbox_mode: None,
+                container_image: None,
+                os: "linux".to_string(),
+            },
+            probe: ProbeInfo {
+                id: probe_id.to_string(),
+                version: "1".to_string(),
+                primary_capability_id: CapabilityId(capability_id.to_string()),
+                secondary_capability_ids: Vec::new(),
+            },
+            run: RunInfo {
+                mode: mode.to_string(),
+                workspace_root: None,
+                command: "probe.sh".to_string(),
+            },
+            operation: OperationInfo {
+                category: "fs".to_string(),
+                verb: "read".to_string(),
+                target: target.to_string(),
+                args: serde_json::json!({}),
+            },
+            result: ResultInfo {
+                observed_result: observed_result.to_string(),
+                raw_exit_code: None,
+                errno: None,
+                message: None,
+                error_detail: None,
+            },
+            payload: Payload {
+                stdout_snippet: None,
+                stderr_snippet: None,
+                raw: serde_json::json!({}),
+            },
+            capability_context: CapabilityContext {
+                primary: CapabilitySnapshot {
+                    id: CapabilityId(capability_id.to_string()),
+                    category: CapabilityCategory::Filesystem,
+                    layer: CapabilityLayer::OsSandbox,
+                },
+                secondary: Vec::new(),
+                resolved_grant: None,
+            },
+        }
+    }
+
+    #[test]
+    fn diff_classifies_enforced_leaked_and_blocked_transitions() {
+        let baseline = vec![
+            record("probe-enforced", "cap_a", "baseline", "/tmp/a", "success"),
+            record("probe-leaked", "cap_a", "baseline", "/tmp/b", "success"),
+            record("probe-blocked", "cap_a", "baseline", "/tmp/c", "denied"),
+        ];
+        let target = vec![
+            record("probe-enforced", "cap_a", "target", "/tmp/a", "denied"),
+            record("probe-leaked", "cap_a", "target", "/tmp/b", "success"),
+            record("probe-blocked", "cap_a", "target", "/tmp/c", "denied"),
+        ];
+
+        let report = diff_boundary_streams(&baseline, &target, None);
+        assert_eq!(report.capabilities.len(), 1);
+        let summary = &report.capabilities[0];
+        assert_eq!(summary.enforced, 1);
+        assert_eq!(summary.leaked, 1);
+        assert_eq!(summary.consistently_blocked, 1);
+        assert_eq!(summary.leaking_probes, vec!["probe-leaked".to_string()]);
+        assert_eq!(report.leak_count, 1);
+    }
+
+    #[test]
+    fn diff_flags_unpaired_records_from_either_side() {
+        let baseline = vec![record(
+            "probe-baseline-only",
+            "cap_b",
+            "baseline",
+            "/tmp/d",
+            "success",
+        )];
+        let target = vec![record(
+            "probe-target-only",
+            "cap_c",
+            "target",
+            "/tmp/e",
+            "success",
+        )];
+
+        let report = diff_boundary_streams(&baseline, &target, None);
+        assert_eq!(report.unpaired.len(), 2);
+        assert!(report
+            .unpaired
+            .iter()
+            .any(|u| u.side == UnpairedSide::BaselineOnly && u.probe_id == "probe-baseline-only"));
+        assert!(report
+            .unpaired
+            .iter()
+            .any(|u| u.side == UnpairedSide::TargetOnly && u.probe_id == "probe-target-only"));
+        assert_eq!(report.leak_count, 0);
+    }
+
+    #[test]
+    fn render_diff_human_includes_capability_row_and_leak_summary() {
+        let baseline = vec![record("probe-a", "cap_a", "baseline", "/tmp/a", "success")];
+        let target = vec![record("probe-a", "cap_a", "target", "/tmp/a", "success")];
+        let report = diff_boundary_streams(&baseline, &target, None);
+
+        let rendered = render_diff_human(&report);
+        assert!(rendered.contains("cap_a"));
+        assert!(rendered.contains("1 leak(s)"));
+    }
+}
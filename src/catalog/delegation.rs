@@ -0,0 +1,349 @@
+//! UCAN-style capability attenuation across a delegation chain.
+//!
+//! `CapabilityIndex`/`CatalogRepository` only check catalog membership; they
+//! say nothing about whether a specific run was actually granted the rights
+//! it is exercising. A delegation chain is an ordered list of grants for one
+//! capability, root first, where each link may only narrow the one before
+//! it. This module verifies that invariant and resolves the leaf grant a
+//! probe's requested capability and operation must fit inside.
+
+use crate::catalog::identity::CapabilityId;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// One link in a delegation chain.
+///
+/// Mirrors `catalog::model::Operations`' allow/deny lists, but as sets so
+/// narrowing can be checked with subset comparisons instead of re-sorting
+/// vectors on every link.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapabilityGrant {
+    pub capability_id: CapabilityId,
+    #[serde(default)]
+    pub allowed_operations: BTreeSet<String>,
+    #[serde(default)]
+    pub denied_operations: BTreeSet<String>,
+}
+
+impl CapabilityGrant {
+    pub fn new(
+        capability_id: CapabilityId,
+        allowed_operations: impl IntoIterator<Item = String>,
+        denied_operations: impl IntoIterator<Item = String>,
+    ) -> Self {
+        Self {
+            capability_id,
+            allowed_operations: allowed_operations.into_iter().collect(),
+            denied_operations: denied_operations.into_iter().collect(),
+        }
+    }
+}
+
+/// Which half of the narrowing invariant a link violated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ViolationKind {
+    /// The child allowed an operation its parent did not allow.
+    BroadenedAllow,
+    /// The child dropped a denial its parent enforced.
+    NarrowedDeny,
+}
+
+/// Why a delegation chain, or a capability request against its leaf, was
+/// rejected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DelegationError {
+    /// No grants were supplied.
+    EmptyChain,
+    /// Two links in the chain named different capabilities; a chain
+    /// attenuates rights to a single capability, so this can never resolve.
+    InconsistentCapability {
+        expected: CapabilityId,
+        found: CapabilityId,
+    },
+    /// A link broadened rather than narrowed its parent.
+    Attenuation {
+        capability_id: CapabilityId,
+        operation: String,
+        kind: ViolationKind,
+    },
+    /// The requested capability id does not match what the chain grants.
+    NotGranted {
+        requested: CapabilityId,
+        granted: CapabilityId,
+    },
+    /// The requested operation is not in the leaf grant's allow list.
+    OperationNotPermitted {
+        capability_id: CapabilityId,
+        operation: String,
+    },
+    /// The requested operation is explicitly denied by the leaf grant.
+    OperationDenied {
+        capability_id: CapabilityId,
+        operation: String,
+    },
+}
+
+impl fmt::Display for DelegationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DelegationError::EmptyChain => write!(f, "delegation chain is empty"),
+            DelegationError::InconsistentCapability { expected, found } => write!(
+                f,
+                "delegation chain mixes capability ids: expected {} but found {}",
+                expected.0, found.0
+            ),
+            DelegationError::Attenuation {
+                capability_id,
+                operation,
+                kind,
+            } => {
+                let what = match kind {
+                    ViolationKind::BroadenedAllow => "broadened allowed operation",
+                    ViolationKind::NarrowedDeny => "dropped denied operation",
+                };
+                write!(
+                    f,
+                    "delegation chain violation for {}: child {what} '{operation}'",
+                    capability_id.0
+                )
+            }
+            DelegationError::NotGranted { requested, granted } => write!(
+                f,
+                "requested capability {} exceeds the attenuation chain, which only grants {}",
+                requested.0, granted.0
+            ),
+            DelegationError::OperationNotPermitted {
+                capability_id,
+                operation,
+            } => write!(
+                f,
+                "operation '{operation}' is not permitted for {} by the attenuation chain",
+                capability_id.0
+            ),
+            DelegationError::OperationDenied {
+                capability_id,
+                operation,
+            } => write!(
+                f,
+                "operation '{operation}' is denied for {} by the attenuation chain",
+                capability_id.0
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DelegationError {}
+
+/// Walk `chain` root-first, confirming every link only narrows its parent:
+/// `child.allowed ⊆ parent.allowed` and `child.denied ⊇ parent.denied`.
+///
+/// Returns the leaf (last) grant on success, or the first violation found.
+pub fn verify_attenuation_chain(
+    chain: &[CapabilityGrant],
+) -> Result<&CapabilityGrant, DelegationError> {
+    let root = chain.first().ok_or(DelegationError::EmptyChain)?;
+
+    for pair in chain.windows(2) {
+        let (parent, child) = (&pair[0], &pair[1]);
+        if child.capability_id != root.capability_id {
+            return Err(DelegationError::InconsistentCapability {
+                expected: root.capability_id.clone(),
+                found: child.capability_id.clone(),
+            });
+        }
+        if let Some(op) = child
+            .allowed_operations
+            .difference(&parent.allowed_operations)
+            .next()
+        {
+            return Err(DelegationError::Attenuation {
+                capability_id: child.capability_id.clone(),
+                operation: op.clone(),
+                kind: ViolationKind::BroadenedAllow,
+            });
+        }
+        if let Some(op) = parent
+            .denied_operations
+            .difference(&child.denied_operations)
+            .next()
+        {
+            return Err(DelegationError::Attenuation {
+                capability_id: child.capability_id.clone(),
+                operation: op.clone(),
+                kind: ViolationKind::NarrowedDeny,
+            });
+        }
+    }
+
+    Ok(chain.last().expect("chain is non-empty"))
+}
+
+/// Confirm that `requested` plus `operation` fit inside `leaf`, the resolved
+/// grant returned by [`verify_attenuation_chain`].
+pub fn check_requested_capability<'a>(
+    leaf: &'a CapabilityGrant,
+    requested: &CapabilityId,
+    operation: &str,
+) -> Result<&'a CapabilityGrant, DelegationError> {
+    if &leaf.capability_id != requested {
+        return Err(DelegationError::NotGranted {
+            requested: requested.clone(),
+            granted: leaf.capability_id.clone(),
+        });
+    }
+    if leaf.denied_operations.contains(operation) {
+        return Err(DelegationError::OperationDenied {
+            capability_id: leaf.capability_id.clone(),
+            operation: operation.to_string(),
+        });
+    }
+    if !leaf.allowed_operations.contains(operation) {
+        return Err(DelegationError::OperationNotPermitted {
+            capability_id: leaf.capability_id.clone(),
+            operation: operation.to_string(),
+        });
+    }
+    Ok(leaf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grant(allowed: &[&str], denied: &[&str]) -> CapabilityGrant {
+        CapabilityGrant::new(
+            CapabilityId("cap_fs_read_workspace_tree".to_string()),
+            allowed.iter().map(|s| s.to_string()),
+            denied.iter().map(|s| s.to_string()),
+        )
+    }
+
+    #[test]
+    fn single_grant_chain_resolves_to_itself() {
+        let chain = vec![grant(&["read"], &["write"])];
+        let leaf = verify_attenuation_chain(&chain).expect("valid chain");
+        assert_eq!(leaf, &chain[0]);
+    }
+
+    #[test]
+    fn narrowing_allow_and_widening_deny_is_valid() {
+        let chain = vec![
+            grant(&["read", "list"], &["write"]),
+            grant(&["read"], &["write", "list"]),
+        ];
+        let leaf = verify_attenuation_chain(&chain).expect("valid narrowing chain");
+        assert_eq!(
+            leaf.allowed_operations,
+            BTreeSet::from(["read".to_string()])
+        );
+    }
+
+    #[test]
+    fn broadening_allow_is_rejected() {
+        let chain = vec![grant(&["read"], &[]), grant(&["read", "write"], &[])];
+        let err = verify_attenuation_chain(&chain).expect_err("should reject broadened allow");
+        assert_eq!(
+            err,
+            DelegationError::Attenuation {
+                capability_id: CapabilityId("cap_fs_read_workspace_tree".to_string()),
+                operation: "write".to_string(),
+                kind: ViolationKind::BroadenedAllow,
+            }
+        );
+    }
+
+    #[test]
+    fn dropping_a_denial_is_rejected() {
+        let chain = vec![grant(&["read"], &["write"]), grant(&["read"], &[])];
+        let err = verify_attenuation_chain(&chain).expect_err("should reject dropped denial");
+        assert_eq!(
+            err,
+            DelegationError::Attenuation {
+                capability_id: CapabilityId("cap_fs_read_workspace_tree".to_string()),
+                operation: "write".to_string(),
+                kind: ViolationKind::NarrowedDeny,
+            }
+        );
+    }
+
+    #[test]
+    fn empty_chain_is_rejected() {
+        assert_eq!(
+            verify_attenuation_chain(&[]).expect_err("empty chain"),
+            DelegationError::EmptyChain
+        );
+    }
+
+    #[test]
+    fn inconsistent_capability_ids_are_rejected() {
+        let other = CapabilityGrant::new(
+            CapabilityId("cap_net_connect_loopback".to_string()),
+            ["connect".to_string()],
+            [],
+        );
+        let chain = vec![grant(&["read"], &[]), other];
+        let err = verify_attenuation_chain(&chain).expect_err("mixed capability ids");
+        assert_eq!(
+            err,
+            DelegationError::InconsistentCapability {
+                expected: CapabilityId("cap_fs_read_workspace_tree".to_string()),
+                found: CapabilityId("cap_net_connect_loopback".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn check_requested_capability_accepts_matching_leaf() {
+        let leaf = grant(&["read"], &["write"]);
+        let requested = CapabilityId("cap_fs_read_workspace_tree".to_string());
+        let resolved =
+            check_requested_capability(&leaf, &requested, "read").expect("read is permitted");
+        assert_eq!(resolved, &leaf);
+    }
+
+    #[test]
+    fn check_requested_capability_rejects_mismatched_id() {
+        let leaf = grant(&["read"], &[]);
+        let requested = CapabilityId("cap_net_connect_loopback".to_string());
+        let err = check_requested_capability(&leaf, &requested, "read")
+            .expect_err("capability id mismatch");
+        assert_eq!(
+            err,
+            DelegationError::NotGranted {
+                requested,
+                granted: CapabilityId("cap_fs_read_workspace_tree".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn check_requested_capability_rejects_denied_operation() {
+        let leaf = grant(&["read", "write"], &["write"]);
+        let requested = CapabilityId("cap_fs_read_workspace_tree".to_string());
+        let err =
+            check_requested_capability(&leaf, &requested, "write").expect_err("write is denied");
+        assert_eq!(
+            err,
+            DelegationError::OperationDenied {
+                capability_id: requested,
+                operation: "write".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn check_requested_capability_rejects_operation_outside_allow_list() {
+        let leaf = grant(&["read"], &[]);
+        let requested = CapabilityId("cap_fs_read_workspace_tree".to_string());
+        let err = check_requested_capability(&leaf, &requested, "write")
+            .expect_err("write was never allowed");
+        assert_eq!(
+            err,
+            DelegationError::OperationNotPermitted {
+                capability_id: requested,
+                operation: "write".to_string(),
+            }
+        );
+    }
+}
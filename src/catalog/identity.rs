@@ -8,6 +8,81 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 #[serde(transparent)]
 pub struct CatalogKey(pub String);
 
+/// Structured version parsed from a [`CatalogKey`]'s trailing `_vN` suffix
+/// (e.g. `macOS_codex_v1` -> family `macOS_codex`, major `1`, minor `0`).
+///
+/// A record's embedded `CatalogKey` only ever encodes the major component
+/// today, so `minor` parses as `0`; it exists so a future catalog revision
+/// can add additive, backward-compatible capability entries without bumping
+/// `major` and breaking older readers. Use [`CatalogVersion::negotiate`] to
+/// decide whether a reader holding `consumer` can safely interpret a record
+/// produced under `producer`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CatalogVersion {
+    pub family: String,
+    pub major: u32,
+    pub minor: u32,
+}
+
+/// Outcome of comparing a record's producer catalog version against the
+/// catalog a reader holds.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Compatibility {
+    /// Same family and version.
+    Exact,
+    /// Same family, same major version, and the consumer's catalog is at
+    /// least as new (`consumer.minor >= producer.minor`) — the consumer can
+    /// resolve every id the producer could have emitted.
+    BackwardCompatible,
+    /// Different family, or a major version mismatch, or the consumer's
+    /// catalog is older than the producer's (`consumer.minor < producer.minor`).
+    /// The consumer may not be able to resolve every id the record uses.
+    Incompatible,
+}
+
+impl CatalogVersion {
+    /// Parse a `CatalogKey` of the form `<family>_v<major>` into its
+    /// structured version, e.g. `macOS_codex_v1` -> family `macOS_codex`,
+    /// major `1`, minor `0`. Errors if the key has no `_v<digits>` suffix.
+    pub fn parse(key: &CatalogKey) -> Result<Self, String> {
+        let Some((family, version)) = key.0.rsplit_once("_v") else {
+            return Err(format!(
+                "catalog key '{}' has no '_v<N>' version suffix",
+                key.0
+            ));
+        };
+        if family.is_empty() {
+            return Err(format!("catalog key '{}' has an empty family", key.0));
+        }
+        let major: u32 = version.parse().map_err(|_| {
+            format!(
+                "catalog key '{}' has a non-numeric version '{version}'",
+                key.0
+            )
+        })?;
+        Ok(Self {
+            family: family.to_string(),
+            major,
+            minor: 0,
+        })
+    }
+
+    /// Decide whether `consumer` can safely interpret a record produced
+    /// under `producer`'s catalog version.
+    pub fn negotiate(producer: &CatalogVersion, consumer: &CatalogVersion) -> Compatibility {
+        if producer == consumer {
+            return Compatibility::Exact;
+        }
+        if producer.family == consumer.family
+            && producer.major == consumer.major
+            && consumer.minor >= producer.minor
+        {
+            return Compatibility::BackwardCompatible;
+        }
+        Compatibility::Incompatible
+    }
+}
+
 /// Stable identifier for an individual capability entry.
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
 #[serde(transparent)]
@@ -134,3 +209,109 @@ impl CapabilityLayer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_splits_family_and_major() {
+        let version = CatalogVersion::parse(&CatalogKey("macOS_codex_v1".to_string()))
+            .expect("key should parse");
+        assert_eq!(version.family, "macOS_codex");
+        assert_eq!(version.major, 1);
+        assert_eq!(version.minor, 0);
+    }
+
+    #[test]
+    fn parse_rejects_key_without_version_suffix() {
+        let err = CatalogVersion::parse(&CatalogKey("macOS_codex".to_string()))
+            .expect_err("key without _vN should fail");
+        assert!(err.contains("macOS_codex"));
+    }
+
+    #[test]
+    fn negotiate_reports_exact_for_identical_versions() {
+        let version = CatalogVersion {
+            family: "macOS_codex".to_string(),
+            major: 1,
+            minor: 0,
+        };
+        assert_eq!(
+            CatalogVersion::negotiate(&version, &version),
+            Compatibility::Exact
+        );
+    }
+
+    #[test]
+    fn negotiate_reports_backward_compatible_for_newer_consumer_minor() {
+        let producer = CatalogVersion {
+            family: "macOS_codex".to_string(),
+            major: 1,
+            minor: 0,
+        };
+        let consumer = CatalogVersion {
+            family: "macOS_codex".to_string(),
+            major: 1,
+            minor: 2,
+        };
+        assert_eq!(
+            CatalogVersion::negotiate(&producer, &consumer),
+            Compatibility::BackwardCompatible
+        );
+    }
+
+    #[test]
+    fn negotiate_reports_incompatible_for_different_family() {
+        let producer = CatalogVersion {
+            family: "macOS_codex".to_string(),
+            major: 1,
+            minor: 0,
+        };
+        let consumer = CatalogVersion {
+            family: "linux_codex".to_string(),
+            major: 1,
+            minor: 0,
+        };
+        assert_eq!(
+            CatalogVersion::negotiate(&producer, &consumer),
+            Compatibility::Incompatible
+        );
+    }
+
+    #[test]
+    fn negotiate_reports_incompatible_for_older_consumer_major() {
+        let producer = CatalogVersion {
+            family: "macOS_codex".to_string(),
+            major: 2,
+            minor: 0,
+        };
+        let consumer = CatalogVersion {
+            family: "macOS_codex".to_string(),
+            major: 1,
+            minor: 0,
+        };
+        assert_eq!(
+            CatalogVersion::negotiate(&producer, &consumer),
+            Compatibility::Incompatible
+        );
+    }
+
+    #[test]
+    fn negotiate_reports_incompatible_for_older_consumer_minor() {
+        let producer = CatalogVersion {
+            family: "macOS_codex".to_string(),
+            major: 1,
+            minor: 2,
+        };
+        let consumer = CatalogVersion {
+            family: "macOS_codex".to_string(),
+            major: 1,
+            minor: 0,
+        };
+        assert_eq!(
+            CatalogVersion::negotiate(&producer, &consumer),
+            Compatibility::Incompatible
+        );
+    }
+}
@@ -3,15 +3,22 @@
 //! The index enforces the expected catalog schema version and provides fast
 //! lookup by capability id. It is intentionally strict about duplicates and
 //! unknown schema versions so helper binaries cannot silently consume
-//! mismatched catalogs.
-
-use crate::catalog::load_catalog_from_path;
-use crate::catalog::{Capability, CapabilityCatalog, CapabilityId, CatalogKey, CatalogMetadata};
+//! mismatched catalogs. A catalog declaring an older, still-known version is
+//! transparently upcast via [`crate::catalog::migration`]; see
+//! [`CapabilityIndex::migrated_from`].
+
+use crate::catalog::migration::{CURRENT_SCHEMA_VERSION, SCHEMA_VERSION_CHAIN, migrate_to_current};
+use crate::catalog::model::{DocRef, PolicyLayer, Scope};
+use crate::catalog::{
+    Capability, CapabilityCatalog, CapabilityId, CapabilitySnapshot, CatalogKey, CatalogMetadata,
+    load_catalog_from_value,
+};
 use crate::schema_loader::{SchemaLoadOptions, load_json_schema};
 use anyhow::{Context, Result, bail};
 use serde_json::Value;
 use std::collections::{BTreeMap, BTreeSet};
 use std::env;
+use std::fs;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::Path;
@@ -27,25 +34,50 @@ pub struct CapabilityIndex {
     catalog_key: CatalogKey,
     catalog: CapabilityCatalog,
     by_id: BTreeMap<CapabilityId, Capability>,
+    migrated_from: Option<String>,
 }
 
 impl CapabilityIndex {
     /// Load and validate the catalog from disk.
     ///
     /// Validates the schema key, ensures capability ids are unique, and builds
-    /// a deterministic BTreeMap for fast lookups.
+    /// a deterministic BTreeMap for fast lookups. A catalog declaring an
+    /// older (but still known) `schema_version` is upcast to
+    /// `CURRENT_SCHEMA_VERSION` via the migration chain in
+    /// [`crate::catalog::migration`] before being deserialized; see
+    /// [`Self::migrated_from`].
     pub fn load(path: &Path) -> Result<Self> {
-        validate_against_schema(path)?;
+        let raw_value = read_catalog_value(path)?;
+        let declared_version = raw_value
+            .get("schema_version")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        validate_declared_schema_version(&declared_version)?;
+        validate_against_schema(path, &raw_value)?;
+
+        let (migrated_value, migrated_from) = migrate_to_current(raw_value, &declared_version)?;
+        if migrated_from.is_some() {
+            validate_against_schema(path, &migrated_value).with_context(|| {
+                format!(
+                    "validating catalog {} migrated to schema_version '{CURRENT_SCHEMA_VERSION}'",
+                    path.display()
+                )
+            })?;
+        }
 
-        let catalog =
-            load_catalog_from_path(path).with_context(|| format!("loading {}", path.display()))?;
+        let catalog = load_catalog_from_value(migrated_value)
+            .with_context(|| format!("loading {}", path.display()))?;
         validate_schema_version(&catalog.schema_version)?;
         validate_catalog_metadata(&catalog.catalog)?;
+        catalog.validate_implications()?;
         let by_id = build_index(&catalog)?;
         Ok(Self {
             catalog_key: catalog.catalog.key.clone(),
             catalog,
             by_id,
+            migrated_from,
         })
     }
 
@@ -54,6 +86,14 @@ impl CapabilityIndex {
         &self.catalog_key
     }
 
+    /// The `schema_version` the loaded catalog originally declared, if it
+    /// needed upcasting (via the migration chain) to reach the current
+    /// schema shape. `None` means the catalog was already current, or this
+    /// index was built by [`Self::merge`] rather than [`Self::load`].
+    pub fn migrated_from(&self) -> Option<&str> {
+        self.migrated_from.as_deref()
+    }
+
     /// Resolve a capability by id.
     ///
     /// Returns `None` instead of erroring; callers surface errors with the CLI
@@ -62,6 +102,16 @@ impl CapabilityIndex {
         self.by_id.get(id)
     }
 
+    /// Resolve a capability by id and build its compact boundary-object
+    /// snapshot in one step.
+    ///
+    /// Returns `None` instead of erroring, matching [`Self::capability`], so
+    /// callers surface errors with the CLI context that referenced the
+    /// missing id.
+    pub fn snapshot(&self, id: &CapabilityId) -> Option<CapabilitySnapshot> {
+        self.capability(id).map(Capability::snapshot)
+    }
+
     /// Iterates capability ids in stable order.
     pub fn ids(&self) -> impl Iterator<Item = &CapabilityId> {
         self.by_id.keys()
@@ -71,9 +121,148 @@ impl CapabilityIndex {
     pub fn catalog(&self) -> &CapabilityCatalog {
         &self.catalog
     }
+
+    /// Compose several loaded indexes (e.g. per-OS or per-policy-layer
+    /// snapshots) into one index under `key`.
+    ///
+    /// Capability entries that share an id are deduplicated: `operations.allow`
+    /// and `operations.deny` are concatenated and sorted+deduped, but a
+    /// `category`, `layer`, or `description` that differs between two entries
+    /// with the same id is a hard conflict. Fails with every conflicting field
+    /// rather than silently preferring one catalog over another.
+    pub fn merge(
+        key: CatalogKey,
+        indexes: &[&CapabilityIndex],
+    ) -> Result<Self, Vec<CapabilityMergeConflict>> {
+        match Self::merge_option(key, indexes) {
+            (Some(index), _) => Ok(index),
+            (None, conflicts) => Err(conflicts),
+        }
+    }
+
+    /// Like [`merge`](Self::merge), but always returns the best-effort merged
+    /// index alongside any conflicts, so callers can report every problem
+    /// found across all indexes instead of failing on the first one.
+    pub fn merge_option(
+        key: CatalogKey,
+        indexes: &[&CapabilityIndex],
+    ) -> (Option<Self>, Vec<CapabilityMergeConflict>) {
+        let mut conflicts = Vec::new();
+        let mut by_id: BTreeMap<CapabilityId, Capability> = BTreeMap::new();
+
+        for index in indexes {
+            for capability in index.by_id.values() {
+                match by_id.get_mut(&capability.id) {
+                    None => {
+                        by_id.insert(capability.id.clone(), capability.clone());
+                    }
+                    Some(existing) => merge_capability(existing, capability, &mut conflicts),
+                }
+            }
+        }
+
+        if !conflicts.is_empty() {
+            return (None, conflicts);
+        }
+
+        let catalog = CapabilityCatalog {
+            key: key.clone(),
+            scope: merged_scope(indexes),
+            docs: merged_docs(indexes),
+            capabilities: by_id.values().cloned().collect(),
+        };
+        (
+            Some(Self {
+                catalog_key: key,
+                catalog,
+                by_id,
+                migrated_from: None,
+            }),
+            conflicts,
+        )
+    }
 }
 
-fn validate_schema_version(schema_version: &str) -> Result<()> {
+/// One field that differed between two capability entries sharing an id
+/// during [`CapabilityIndex::merge`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapabilityMergeConflict {
+    pub id: CapabilityId,
+    pub field: String,
+    pub left: String,
+    pub right: String,
+}
+
+fn merge_capability(
+    existing: &mut Capability,
+    incoming: &Capability,
+    conflicts: &mut Vec<CapabilityMergeConflict>,
+) {
+    if existing.category != incoming.category {
+        conflicts.push(CapabilityMergeConflict {
+            id: existing.id.clone(),
+            field: "category".to_string(),
+            left: existing.category.as_str().to_string(),
+            right: incoming.category.as_str().to_string(),
+        });
+    }
+    if existing.layer != incoming.layer {
+        conflicts.push(CapabilityMergeConflict {
+            id: existing.id.clone(),
+            field: "layer".to_string(),
+            left: existing.layer.as_str().to_string(),
+            right: incoming.layer.as_str().to_string(),
+        });
+    }
+    if existing.description != incoming.description {
+        conflicts.push(CapabilityMergeConflict {
+            id: existing.id.clone(),
+            field: "description".to_string(),
+            left: existing.description.clone(),
+            right: incoming.description.clone(),
+        });
+    }
+
+    existing.operations.allow =
+        sorted_dedup_concat(&existing.operations.allow, &incoming.operations.allow);
+    existing.operations.deny =
+        sorted_dedup_concat(&existing.operations.deny, &incoming.operations.deny);
+}
+
+fn sorted_dedup_concat(left: &[String], right: &[String]) -> Vec<String> {
+    let combined: BTreeSet<String> = left.iter().chain(right.iter()).cloned().collect();
+    combined.into_iter().collect()
+}
+
+fn merged_scope(indexes: &[&CapabilityIndex]) -> Scope {
+    let mut policy_layers: BTreeMap<String, PolicyLayer> = BTreeMap::new();
+    let mut categories: BTreeMap<String, String> = BTreeMap::new();
+    for index in indexes {
+        for layer in &index.catalog.scope.policy_layers {
+            policy_layers.insert(layer.id.clone(), layer.clone());
+        }
+        categories.extend(index.catalog.scope.categories.clone());
+    }
+
+    let first_scope = indexes.first().map(|index| &index.catalog.scope);
+    Scope {
+        description: first_scope.map_or_else(String::new, |scope| scope.description.clone()),
+        notes: first_scope.and_then(|scope| scope.notes.clone()),
+        policy_layers: policy_layers.into_values().collect(),
+        categories,
+        limitations: first_scope.and_then(|scope| scope.limitations.clone()),
+    }
+}
+
+fn merged_docs(indexes: &[&CapabilityIndex]) -> BTreeMap<String, DocRef> {
+    let mut docs = BTreeMap::new();
+    for index in indexes {
+        docs.extend(index.catalog.docs.clone());
+    }
+    docs
+}
+
+fn validate_schema_version_format(schema_version: &str) -> Result<()> {
     if schema_version.is_empty() {
         bail!("schema_version must not be empty");
     }
@@ -88,6 +277,12 @@ fn validate_schema_version(schema_version: &str) -> Result<()> {
         );
     }
 
+    Ok(())
+}
+
+fn validate_schema_version(schema_version: &str) -> Result<()> {
+    validate_schema_version_format(schema_version)?;
+
     let allowed = allowed_schema_versions();
     if !allowed.contains(schema_version) {
         bail!(
@@ -100,6 +295,23 @@ fn validate_schema_version(schema_version: &str) -> Result<()> {
     Ok(())
 }
 
+/// Validates a catalog's *declared* (pre-migration) `schema_version` against
+/// the full migration chain rather than the single current version, so a
+/// catalog one or two revisions behind can still be loaded and upcast.
+fn validate_declared_schema_version(schema_version: &str) -> Result<()> {
+    validate_schema_version_format(schema_version)?;
+
+    if !SCHEMA_VERSION_CHAIN.contains(&schema_version) {
+        bail!(
+            "schema_version '{}' is not a known catalog schema version (known: {:?})",
+            schema_version,
+            SCHEMA_VERSION_CHAIN
+        );
+    }
+
+    Ok(())
+}
+
 fn allowed_schema_versions() -> BTreeSet<String> {
     BTreeSet::from_iter([default_catalog_schema_version()])
 }
@@ -119,7 +331,7 @@ fn catalog_schema_version_from_disk() -> Option<String> {
 }
 
 fn canonical_catalog_schema_path() -> PathBuf {
-    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("schema/capability_catalog.schema.json")
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(crate::CANONICAL_CAPABILITY_CATALOG_SCHEMA_PATH)
 }
 
 fn validate_catalog_metadata(meta: &CatalogMetadata) -> Result<()> {
@@ -205,12 +417,34 @@ fn build_index(catalog: &CapabilityCatalog) -> Result<BTreeMap<CapabilityId, Cap
     Ok(map)
 }
 
-fn validate_against_schema(catalog_path: &Path) -> Result<()> {
+/// Reads and parses the raw catalog at `path`, without deserializing it into
+/// `CapabilityCatalog` yet, so [`CapabilityIndex::load`] can inspect the
+/// declared `schema_version` and migrate the value before that step.
+///
+/// Accepts either JSON or TOML, dispatching on the file extension (`.toml` vs
+/// anything else), and transcodes TOML into the same `serde_json::Value`
+/// shape so the rest of the pipeline (migration, schema validation) doesn't
+/// need to know which format the file was written in.
+fn read_catalog_value(catalog_path: &Path) -> Result<Value> {
+    if catalog_path.extension().and_then(|ext| ext.to_str()) == Some("toml") {
+        let contents = fs::read_to_string(catalog_path)
+            .with_context(|| format!("opening catalog {}", catalog_path.display()))?;
+        let toml_value: toml::Value = toml::from_str(&contents)
+            .with_context(|| format!("parsing catalog {}", catalog_path.display()))?;
+        return serde_json::to_value(toml_value)
+            .with_context(|| format!("converting catalog {} to JSON", catalog_path.display()));
+    }
+
     let catalog_file = File::open(catalog_path)
         .with_context(|| format!("opening catalog {}", catalog_path.display()))?;
-    let catalog_value: Value = serde_json::from_reader(BufReader::new(catalog_file))
-        .with_context(|| format!("parsing catalog {}", catalog_path.display()))?;
+    serde_json::from_reader(BufReader::new(catalog_file))
+        .with_context(|| format!("parsing catalog {}", catalog_path.display()))
+}
 
+/// Validates an already-parsed catalog value against the JSON schema,
+/// called once against the raw (pre-migration) value and, if a migration
+/// ran, again against the migrated value.
+fn validate_against_schema(catalog_path: &Path, catalog_value: &Value) -> Result<()> {
     let catalog_version = catalog_value
         .get("schema_version")
         .and_then(Value::as_str)
@@ -230,7 +464,7 @@ fn validate_against_schema(catalog_path: &Path) -> Result<()> {
     )
     .with_context(|| format!("loading catalog schema {}", schema_path.display()))?;
 
-    if let Err(errors) = schema.compiled.validate(&catalog_value) {
+    if let Err(errors) = schema.compiled.validate(catalog_value) {
         let details = errors
             .map(|err| err.to_string())
             .collect::<Vec<_>>()
@@ -254,3 +488,264 @@ fn resolve_catalog_schema_path(catalog_path: &Path) -> PathBuf {
 
     PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("schema/capability_catalog.schema.json")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::CapabilityCategory;
+    use serde_json::json;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn index_with(capabilities: Value) -> Result<CapabilityIndex> {
+        let mut file = NamedTempFile::new()?;
+        serde_json::to_writer(
+            &mut file,
+            &json!({
+                "schema_version": "sandbox_catalog_v1",
+                "scope": {
+                    "description": "test",
+                    "policy_layers": [
+                        {"id": "os_sandbox", "description": "os"},
+                        {"id": "agent_runtime", "description": "agent"}
+                    ],
+                    "categories": {"filesystem": "fs", "process": "proc", "network": "net"}
+                },
+                "docs": {},
+                "capabilities": capabilities
+            }),
+        )?;
+        CapabilityIndex::load(file.path())
+    }
+
+    #[test]
+    fn merge_combines_disjoint_catalogs() {
+        let left = index_with(json!([{
+            "id": "cap_a",
+            "category": "filesystem",
+            "layer": "os_sandbox",
+            "description": "fixture a",
+            "operations": {"allow": ["read"], "deny": []}
+        }]))
+        .expect("left index loads");
+        let right = index_with(json!([{
+            "id": "cap_b",
+            "category": "process",
+            "layer": "os_sandbox",
+            "description": "fixture b",
+            "operations": {"allow": [], "deny": ["fork"]}
+        }]))
+        .expect("right index loads");
+
+        let merged = CapabilityIndex::merge(CatalogKey("merged_v1".to_string()), &[&left, &right])
+            .expect("disjoint catalogs should merge cleanly");
+
+        assert_eq!(
+            merged.ids().map(|id| id.0.clone()).collect::<Vec<_>>(),
+            vec!["cap_a".to_string(), "cap_b".to_string()]
+        );
+    }
+
+    #[test]
+    fn merge_concatenates_operation_lists_for_shared_capability() {
+        let left = index_with(json!([{
+            "id": "cap_a",
+            "category": "filesystem",
+            "layer": "os_sandbox",
+            "description": "fixture a",
+            "operations": {"allow": ["read"], "deny": []}
+        }]))
+        .expect("left index loads");
+        let right = index_with(json!([{
+            "id": "cap_a",
+            "category": "filesystem",
+            "layer": "os_sandbox",
+            "description": "fixture a",
+            "operations": {"allow": ["write", "read"], "deny": ["unlink"]}
+        }]))
+        .expect("right index loads");
+
+        let merged = CapabilityIndex::merge(CatalogKey("merged_v1".to_string()), &[&left, &right])
+            .expect("matching scalar fields should merge cleanly");
+
+        let capability = merged
+            .capability(&CapabilityId("cap_a".to_string()))
+            .expect("merged capability present");
+        assert_eq!(capability.operations.allow, vec!["read", "write"]);
+        assert_eq!(capability.operations.deny, vec!["unlink"]);
+    }
+
+    #[test]
+    fn merge_option_reports_conflict_for_differing_category() {
+        let left = index_with(json!([{
+            "id": "cap_a",
+            "category": "filesystem",
+            "layer": "os_sandbox",
+            "description": "fixture a",
+            "operations": {"allow": [], "deny": []}
+        }]))
+        .expect("left index loads");
+        let right = index_with(json!([{
+            "id": "cap_a",
+            "category": "process",
+            "layer": "os_sandbox",
+            "description": "fixture a",
+            "operations": {"allow": [], "deny": []}
+        }]))
+        .expect("right index loads");
+
+        let (merged, conflicts) =
+            CapabilityIndex::merge_option(CatalogKey("merged_v1".to_string()), &[&left, &right]);
+
+        assert!(merged.is_none());
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].field, "category");
+        assert_eq!(conflicts[0].id, CapabilityId("cap_a".to_string()));
+    }
+
+    #[test]
+    fn load_rejects_capability_implying_unknown_id() {
+        let err = index_with(json!([{
+            "id": "cap_a",
+            "category": "filesystem",
+            "layer": "os_sandbox",
+            "description": "fixture a",
+            "operations": {"allow": [], "deny": []},
+            "implies": ["cap_nonexistent"]
+        }]))
+        .expect_err("unknown implies target should fail to load");
+
+        assert!(err.to_string().contains("cap_a"));
+        assert!(err.to_string().contains("cap_nonexistent"));
+    }
+
+    #[test]
+    fn satisfies_follows_transitive_implies_edges() {
+        let index = index_with(json!([
+            {
+                "id": "fs.read",
+                "category": "filesystem",
+                "layer": "os_sandbox",
+                "description": "read files",
+                "operations": {"allow": ["read"], "deny": []}
+            },
+            {
+                "id": "fs.read_write",
+                "category": "filesystem",
+                "layer": "os_sandbox",
+                "description": "read and write files",
+                "operations": {"allow": ["read", "write"], "deny": []},
+                "implies": ["fs.read"]
+            }
+        ]))
+        .expect("index loads");
+
+        let report = index.catalog().satisfies(
+            &[
+                CapabilityId("fs.read".to_string()),
+                CapabilityId("fs.read_write".to_string()),
+            ],
+            &[CapabilityId("fs.read_write".to_string())],
+        );
+
+        assert!(report.is_satisfied());
+        assert_eq!(report.missing, std::collections::BTreeSet::new());
+    }
+
+    #[test]
+    fn satisfies_reports_missing_capabilities() {
+        let index = index_with(json!([{
+            "id": "fs.read",
+            "category": "filesystem",
+            "layer": "os_sandbox",
+            "description": "read files",
+            "operations": {"allow": ["read"], "deny": []}
+        }]))
+        .expect("index loads");
+
+        let report = index.catalog().satisfies(
+            &[
+                CapabilityId("fs.read".to_string()),
+                CapabilityId("fs.write".to_string()),
+            ],
+            &[CapabilityId("fs.read".to_string())],
+        );
+
+        assert!(!report.is_satisfied());
+        assert_eq!(
+            report.missing,
+            std::collections::BTreeSet::from([CapabilityId("fs.write".to_string())])
+        );
+    }
+
+    #[test]
+    fn snapshot_resolves_capability_by_id() {
+        let index = index_with(json!([{
+            "id": "cap_a",
+            "category": "filesystem",
+            "layer": "os_sandbox",
+            "description": "fixture a",
+            "operations": {"allow": ["read"], "deny": []}
+        }]))
+        .expect("index loads");
+
+        let snapshot = index
+            .snapshot(&CapabilityId("cap_a".to_string()))
+            .expect("snapshot resolves for a known id");
+        assert_eq!(snapshot.id, CapabilityId("cap_a".to_string()));
+        assert_eq!(snapshot.category, CapabilityCategory::Filesystem);
+    }
+
+    #[test]
+    fn snapshot_returns_none_for_unknown_id() {
+        let index = index_with(json!([{
+            "id": "cap_a",
+            "category": "filesystem",
+            "layer": "os_sandbox",
+            "description": "fixture a",
+            "operations": {"allow": [], "deny": []}
+        }]))
+        .expect("index loads");
+
+        assert!(index
+            .snapshot(&CapabilityId("cap_missing".to_string()))
+            .is_none());
+    }
+
+    #[test]
+    fn load_accepts_toml_catalog() {
+        let mut file = tempfile::Builder::new()
+            .suffix(".toml")
+            .tempfile()
+            .expect("create temp toml file");
+        let toml_body = r#"
+schema_version = "sandbox_catalog_v1"
+
+[scope]
+description = "toml fixture"
+categories = { filesystem = "fs" }
+
+[[scope.policy_layers]]
+id = "os_sandbox"
+description = "os"
+
+[[capabilities]]
+id = "cap_a"
+category = "filesystem"
+layer = "os_sandbox"
+description = "fixture a"
+
+[capabilities.operations]
+allow = ["read"]
+deny = []
+"#;
+        file.write_all(toml_body.as_bytes())
+            .expect("write toml fixture");
+
+        let index = CapabilityIndex::load(file.path()).expect("toml catalog loads");
+        let snapshot = index
+            .snapshot(&CapabilityId("cap_a".to_string()))
+            .expect("snapshot resolves for toml-loaded capability");
+        assert_eq!(snapshot.id, CapabilityId("cap_a".to_string()));
+    }
+}
@@ -0,0 +1,71 @@
+//! Migration chain for capability-catalog schema versions.
+//!
+//! `CapabilityIndex::load` only deserializes catalogs shaped like the newest
+//! entry in [`SCHEMA_VERSION_CHAIN`]. A catalog declaring an older (but still
+//! known) version is upcast through each adjacent pair's migration function,
+//! one step at a time, until it matches the chain's last entry; a declared
+//! version outside the chain is rejected outright. The chain has a single
+//! entry today, so [`MIGRATIONS`] is empty and nothing ever actually
+//! upcasts, but the walk in [`migrate_to_current`] is what a future
+//! `sandbox_catalog_v2` would hook into.
+
+use anyhow::{Result, bail};
+use serde_json::Value;
+
+/// Known catalog schema versions, oldest first. [`crate::catalog::CapabilityIndex::load`]
+/// accepts a catalog declaring any version in this chain.
+pub(crate) const SCHEMA_VERSION_CHAIN: &[&str] = &["sandbox_catalog_v1"];
+
+/// The newest schema version; catalogs are migrated up to this shape before
+/// being deserialized into `CapabilityCatalog`.
+pub(crate) const CURRENT_SCHEMA_VERSION: &str =
+    SCHEMA_VERSION_CHAIN[SCHEMA_VERSION_CHAIN.len() - 1];
+
+/// Upcasts a raw catalog `Value` declaring one schema version into the shape
+/// of the next version in [`SCHEMA_VERSION_CHAIN`]. Implementations must also
+/// rewrite the value's own `schema_version` field to the `to` version, since
+/// `CapabilityIndex::load` re-validates the migrated value against its
+/// declared version afterwards.
+type Migration = fn(Value) -> Value;
+
+/// Adjacent-pair migrations as `(from, to, upcast)`, in the same order as
+/// [`SCHEMA_VERSION_CHAIN`]. Empty until a second schema version exists to
+/// migrate towards.
+const MIGRATIONS: &[(&str, &str, Migration)] = &[];
+
+/// Upcast `value`, declared as `declared_version`, to
+/// [`CURRENT_SCHEMA_VERSION`] by applying each adjacent migration in
+/// sequence.
+///
+/// Returns the migrated value, plus `Some(declared_version)` if any
+/// migration ran (i.e. the catalog was behind `CURRENT_SCHEMA_VERSION`), or
+/// `None` if it was already current. Fails if `declared_version` is outside
+/// [`SCHEMA_VERSION_CHAIN`], or if the chain has a gap with no migration
+/// registered between two adjacent versions.
+pub(crate) fn migrate_to_current(
+    mut value: Value,
+    declared_version: &str,
+) -> Result<(Value, Option<String>)> {
+    if !SCHEMA_VERSION_CHAIN.contains(&declared_version) {
+        bail!(
+            "schema_version '{declared_version}' is not a known catalog schema version (known: {SCHEMA_VERSION_CHAIN:?})"
+        );
+    }
+
+    if declared_version == CURRENT_SCHEMA_VERSION {
+        return Ok((value, None));
+    }
+
+    let mut current = declared_version.to_string();
+    while current != CURRENT_SCHEMA_VERSION {
+        let Some((_, to, migrate)) = MIGRATIONS.iter().find(|(from, _, _)| *from == current) else {
+            bail!(
+                "no migration registered from schema_version '{current}' towards '{CURRENT_SCHEMA_VERSION}'"
+            );
+        };
+        value = migrate(value);
+        current = (*to).to_string();
+    }
+
+    Ok((value, Some(declared_version.to_string())))
+}
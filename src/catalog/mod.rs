@@ -5,16 +5,26 @@
 //! Types here mirror the schema fields; callers use `CapabilityIndex` for fast
 //! lookups and `CatalogRepository` when multiple catalogs are registered.
 
+pub mod delegation;
 pub mod identity;
 pub mod index;
+pub(crate) mod migration;
 pub mod model;
 pub mod repository;
 
+pub use delegation::{
+    check_requested_capability, verify_attenuation_chain, CapabilityGrant, DelegationError,
+    ViolationKind,
+};
 pub use identity::{
     CapabilityCategory, CapabilityId, CapabilityLayer, CapabilitySnapshot, CatalogKey,
+    CatalogVersion, Compatibility,
+};
+pub use index::{CapabilityIndex, CapabilityMergeConflict};
+pub use model::{
+    Capability, CapabilityCatalog, CapabilitySource, Criticality, DocRef, Operations,
+    SatisfactionReport, Scope,
 };
-pub use index::CapabilityIndex;
-pub use model::{Capability, CapabilityCatalog, CapabilitySource, DocRef, Operations, Scope};
 pub use repository::CatalogRepository;
 
-pub use model::load_catalog_from_path;
+pub use model::{load_catalog_from_path, load_catalog_from_value};
@@ -8,9 +8,10 @@
 use crate::catalog::identity::{
     CapabilityCategory, CapabilityId, CapabilityLayer, CapabilitySnapshot, CatalogKey,
 };
-use anyhow::Result;
-use serde::Deserialize;
-use std::collections::BTreeMap;
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::path::Path;
 
@@ -42,6 +43,11 @@ pub struct Scope {
 pub struct PolicyLayer {
     pub id: String,
     pub description: String,
+    /// Other layer or capability ids this layer subsumes, e.g. a
+    /// `full_sandbox` layer implying `partial_sandbox`. Used to build the
+    /// implication graph queried by [`CapabilityCatalog::satisfies`].
+    #[serde(default)]
+    pub implies: Vec<String>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -70,6 +76,31 @@ pub struct Capability {
     pub notes: Option<String>,
     #[serde(default)]
     pub sources: Vec<CapabilitySource>,
+    /// How much an uncovered probe gap for this capability should matter to a
+    /// CI gate (see [`crate::coverage::evaluate_coverage`]). Absent in most
+    /// catalog entries today, so it defaults to [`Criticality::Standard`]
+    /// rather than forcing every capability to declare one up front.
+    #[serde(default)]
+    pub criticality: Criticality,
+    /// Other capability or policy-layer ids this capability subsumes, e.g.
+    /// `fs.read_write` implying `fs.read`. Used to build the implication
+    /// graph queried by [`CapabilityCatalog::satisfies`].
+    #[serde(default)]
+    pub implies: Vec<String>,
+}
+
+/// How severely a missing probe for a capability should be treated by a CI
+/// coverage gate.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Criticality {
+    /// An uncovered gap here is a hard failure.
+    Critical,
+    /// An uncovered gap here is a warning but doesn't fail the build.
+    #[default]
+    Standard,
+    /// Uncovered gaps here are not reported at all.
+    Informational,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -102,9 +133,125 @@ impl Capability {
     }
 }
 
+/// Result of [`CapabilityCatalog::satisfies`]: which of the required
+/// capabilities are reachable from the provided set via the implication
+/// graph, and which are not.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SatisfactionReport {
+    pub satisfied: BTreeSet<CapabilityId>,
+    pub missing: BTreeSet<CapabilityId>,
+}
+
+impl SatisfactionReport {
+    /// True if every required capability was reachable.
+    pub fn is_satisfied(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+impl CapabilityCatalog {
+    /// Builds the implication graph from `implies` edges declared on policy
+    /// layers and capabilities, keyed by the source id (layer id or
+    /// `CapabilityId`).
+    fn implication_graph(&self) -> BTreeMap<String, Vec<String>> {
+        let mut graph = BTreeMap::new();
+        for layer in &self.scope.policy_layers {
+            graph.insert(layer.id.clone(), layer.implies.clone());
+        }
+        for capability in &self.capabilities {
+            graph.insert(capability.id.0.clone(), capability.implies.clone());
+        }
+        graph
+    }
+
+    /// Validates that every `implies` target named by a policy layer or
+    /// capability resolves to a known layer or capability id, erroring with
+    /// the offending source id otherwise.
+    pub fn validate_implications(&self) -> Result<()> {
+        let known_ids: BTreeSet<&str> = self
+            .scope
+            .policy_layers
+            .iter()
+            .map(|layer| layer.id.as_str())
+            .chain(self.capabilities.iter().map(|cap| cap.id.0.as_str()))
+            .collect();
+
+        for layer in &self.scope.policy_layers {
+            for target in &layer.implies {
+                if !known_ids.contains(target.as_str()) {
+                    bail!(
+                        "policy layer '{}' implies unknown id '{}'",
+                        layer.id,
+                        target
+                    );
+                }
+            }
+        }
+        for capability in &self.capabilities {
+            for target in &capability.implies {
+                if !known_ids.contains(target.as_str()) {
+                    bail!(
+                        "capability '{}' implies unknown id '{}'",
+                        capability.id.0,
+                        target
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Computes whether `provided` capabilities satisfy `required` ones,
+    /// following the `implies` edges declared on policy layers and
+    /// capabilities as a transitive closure (each provided id plus
+    /// everything reachable from it implies-wise).
+    pub fn satisfies(
+        &self,
+        required: &[CapabilityId],
+        provided: &[CapabilityId],
+    ) -> SatisfactionReport {
+        let graph = self.implication_graph();
+        let mut reachable: BTreeSet<String> = BTreeSet::new();
+        let mut stack: Vec<String> = provided.iter().map(|id| id.0.clone()).collect();
+
+        while let Some(id) = stack.pop() {
+            if !reachable.insert(id.clone()) {
+                continue;
+            }
+            if let Some(targets) = graph.get(&id) {
+                for target in targets {
+                    if !reachable.contains(target) {
+                        stack.push(target.clone());
+                    }
+                }
+            }
+        }
+
+        let mut satisfied = BTreeSet::new();
+        let mut missing = BTreeSet::new();
+        for id in required {
+            if reachable.contains(&id.0) {
+                satisfied.insert(id.clone());
+            } else {
+                missing.insert(id.clone());
+            }
+        }
+
+        SatisfactionReport { satisfied, missing }
+    }
+}
+
 /// Read and parse a capability catalog from disk without additional validation.
 pub fn load_catalog_from_path(path: &Path) -> Result<CapabilityCatalog> {
     let data = fs::read_to_string(path)?;
-    let catalog: CapabilityCatalog = serde_json::from_str(&data)?;
+    let value: Value = serde_json::from_str(&data)?;
+    load_catalog_from_value(value)
+}
+
+/// Deserialize an already-parsed catalog value without re-reading it from
+/// disk; used by [`crate::catalog::CapabilityIndex::load`] to finish loading
+/// a value after it has been upcast by the schema migration chain.
+pub fn load_catalog_from_value(value: Value) -> Result<CapabilityCatalog> {
+    let catalog: CapabilityCatalog = serde_json::from_value(value)?;
     Ok(catalog)
 }
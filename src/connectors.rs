@@ -1,52 +1,108 @@
 //! Connector registry for run modes.
 //!
-//! This module centralizes how run modes map to connectors (ambient only),
-//! sandbox defaults, and command planning. Binaries should rely on this
-//! registry instead of hard-coding mode strings so new connectors can be added
-//! in one place without changing public CLI flags or drifting from the
-//! boundary-object schema and `docs/probes.md`.
+//! This module centralizes how run modes map to connectors (ambient, Linux
+//! namespace, or container), sandbox defaults, and command planning. Binaries
+//! should rely on this registry instead of hard-coding mode strings so new
+//! connectors can be added in one place without changing public CLI flags or
+//! drifting from the boundary-object schema and `docs/probes.md`.
+//!
+//! It also hosts a small `cfg(...)` expression parser/evaluator (see
+//! [`host_cfg_map`] and [`eval_cfg_predicate`]) so mode specs and probe
+//! metadata can gate on rich platform predicates like
+//! `cfg(all(unix, not(target_os = "macos")))` instead of comparing a single
+//! raw platform string.
 
-use anyhow::{Result, bail};
+use anyhow::{Result, anyhow, bail};
+use std::collections::BTreeMap;
+use std::env;
 use std::ffi::OsString;
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ConnectorKind {
     Ambient,
+    Namespace,
+    Container,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum RunMode {
     Baseline,
+    /// Runs the probe inside a fresh, unprivileged Linux namespace sandbox
+    /// (user/mount/pid/net) via the `fence-netns` launcher, giving real
+    /// filesystem/network isolation without depending on the external Codex
+    /// sandbox or a container runtime.
+    Isolated,
+    /// Runs the probe inside a Docker/Podman container instead of as a local
+    /// child process, for sandbox observations that don't depend on the
+    /// host's own enforcement primitives.
+    Container,
 }
 
 impl RunMode {
     pub fn as_str(&self) -> &'static str {
         match self {
             RunMode::Baseline => "baseline",
+            RunMode::Isolated => "isolated",
+            RunMode::Container => "container",
         }
     }
 
     pub fn connector(&self) -> ConnectorKind {
         match self {
             RunMode::Baseline => ConnectorKind::Ambient,
+            RunMode::Isolated => ConnectorKind::Namespace,
+            RunMode::Container => ConnectorKind::Container,
         }
     }
 
     pub fn sandbox_env(&self, _override_value: Option<String>) -> OsString {
         match self {
             RunMode::Baseline => OsString::from(""),
+            RunMode::Isolated => OsString::from("namespace:unshare(user,mount,pid,net)"),
+            RunMode::Container => OsString::from(
+                detect_container_runtime()
+                    .map(|runtime| {
+                        format!(
+                            "container:{}:cap-drop=ALL,read-only,network=none",
+                            runtime.binary_name()
+                        )
+                    })
+                    .unwrap_or_else(|| "container".to_string()),
+            ),
         }
     }
 
     fn ensure_connector_present(&self) -> Result<()> {
-        Ok(())
+        match self {
+            RunMode::Baseline => Ok(()),
+            RunMode::Isolated => {
+                if namespace_isolation_available() {
+                    Ok(())
+                } else {
+                    bail!(
+                        "Isolated run mode requires unprivileged Linux user namespaces (see /proc/self/ns/user and /proc/sys/kernel/unprivileged_userns_clone)"
+                    );
+                }
+            }
+            RunMode::Container => {
+                if detect_container_runtime().is_some() {
+                    Ok(())
+                } else {
+                    bail!("Container run mode requires a usable docker or podman runtime on PATH");
+                }
+            }
+        }
     }
 
     fn command_spec(
         &self,
         _platform: Option<&str>,
         probe_path: &Path,
+        workspace_root: &Path,
+        workspace_tmpdir: Option<&Path>,
     ) -> Result<CommandSpec> {
         let probe_arg = probe_path.as_os_str().to_os_string();
         match self {
@@ -54,9 +110,49 @@ impl RunMode {
                 program: probe_arg,
                 args: Vec::new(),
             }),
+            RunMode::Isolated => Ok(CommandSpec {
+                // Resolved via PATH, same as `container` mode's runtime
+                // binary; `make build` syncs `fence-netns` alongside the
+                // other helpers.
+                program: OsString::from("fence-netns"),
+                args: vec![probe_arg],
+            }),
+            RunMode::Container => {
+                let runtime = detect_container_runtime().ok_or_else(|| {
+                    anyhow!("Container run mode requires a usable docker or podman runtime on PATH")
+                })?;
+                let workspace_mount = format!("{0}:{0}:ro", workspace_root.display());
+                let mut args = vec![OsString::from("run"), OsString::from("--rm")];
+                args.extend(isolation_args());
+                args.extend([OsString::from("-v"), OsString::from(workspace_mount)]);
+                if let Some(tmpdir) = workspace_tmpdir {
+                    let tmpdir_mount = format!("{0}:{0}:rw", tmpdir.display());
+                    args.extend([OsString::from("-v"), OsString::from(tmpdir_mount)]);
+                }
+                args.extend([
+                    OsString::from("-w"),
+                    workspace_root.as_os_str().to_os_string(),
+                ]);
+                for key in [
+                    "CATALOG_PATH",
+                    "BOUNDARY_PATH",
+                    "FENCE_RUN_MODE",
+                    "FENCE_SANDBOX_MODE",
+                    "FENCE_WORKSPACE_ROOT",
+                    "TMPDIR",
+                ] {
+                    args.push(OsString::from("-e"));
+                    args.push(OsString::from(key));
+                }
+                args.push(OsString::from(container_image()));
+                args.push(probe_arg);
+                Ok(CommandSpec {
+                    program: OsString::from(runtime.binary_name()),
+                    args,
+                })
+            }
         }
     }
-
 }
 
 impl TryFrom<&str> for RunMode {
@@ -65,11 +161,98 @@ impl TryFrom<&str> for RunMode {
     fn try_from(value: &str) -> Result<Self> {
         match value {
             "baseline" => Ok(RunMode::Baseline),
+            "isolated" => Ok(RunMode::Isolated),
+            "container" => Ok(RunMode::Container),
             other => bail!("Unknown mode: {other}"),
         }
     }
 }
 
+/// Docker/Podman image `container` run mode executes probes in. Override via
+/// `FENCE_CONTAINER_IMAGE` to match an image that carries the `emit-record`
+/// helper probe scripts depend on.
+const DEFAULT_CONTAINER_IMAGE: &str = "debian:stable-slim";
+
+pub(crate) fn container_image() -> String {
+    env::var("FENCE_CONTAINER_IMAGE").unwrap_or_else(|_| DEFAULT_CONTAINER_IMAGE.to_string())
+}
+
+/// A container runtime `container` run mode can shell out to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContainerRuntime {
+    Docker,
+    Podman,
+}
+
+impl ContainerRuntime {
+    pub(crate) fn binary_name(&self) -> &'static str {
+        match self {
+            ContainerRuntime::Docker => "docker",
+            ContainerRuntime::Podman => "podman",
+        }
+    }
+}
+
+/// Probe for a usable container runtime by running `<binary> info`,
+/// preferring Docker over Podman when both respond.
+pub(crate) fn detect_container_runtime() -> Option<ContainerRuntime> {
+    [ContainerRuntime::Docker, ContainerRuntime::Podman]
+        .into_iter()
+        .find(|runtime| runtime_responds(runtime.binary_name()))
+}
+
+fn runtime_responds(binary: &str) -> bool {
+    Command::new(binary)
+        .arg("info")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+/// Flags that make `container` mode's isolation real instead of cosmetic:
+/// every Linux capability is dropped, the root filesystem is read-only, and
+/// networking is disabled, so a probe that depends on any of those to
+/// succeed genuinely observes `denied` rather than quietly inheriting the
+/// host's rights through an otherwise-unrestricted container.
+fn isolation_args() -> Vec<OsString> {
+    vec![
+        OsString::from("--cap-drop=ALL"),
+        OsString::from("--read-only"),
+        OsString::from("--network=none"),
+        OsString::from("--security-opt=no-new-privileges"),
+    ]
+}
+
+/// Build the command for a `container`-mode preflight: run `mktemp -d
+/// TARGET` inside a throwaway container using the same runtime, image, and
+/// isolation flags `container` mode itself uses, with `workspace_tmpdir`
+/// bind-mounted read-write so the check actually exercises whether the
+/// daemon is reachable and the mount is writable rather than just whether
+/// the runtime binary exists. `TARGET` should be a path under
+/// `workspace_tmpdir`. Returns `None` when no container runtime is present
+/// on `PATH`; the caller treats that the same as "nothing to preflight"
+/// since [`RunMode::ensure_connector_present`] will fail the run itself with
+/// a clearer error later.
+pub fn container_preflight_command(workspace_tmpdir: &Path, target: &Path) -> Option<CommandSpec> {
+    let runtime = detect_container_runtime()?;
+    let tmpdir_mount = format!("{0}:{0}:rw", workspace_tmpdir.display());
+    let mut args = vec![OsString::from("run"), OsString::from("--rm")];
+    args.extend(isolation_args());
+    args.extend([OsString::from("-v"), OsString::from(tmpdir_mount)]);
+    args.push(OsString::from(container_image()));
+    args.extend([
+        OsString::from("mktemp"),
+        OsString::from("-d"),
+        target.as_os_str().to_os_string(),
+    ]);
+    Some(CommandSpec {
+        program: OsString::from(runtime.binary_name()),
+        args,
+    })
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ModePlan {
     pub run_mode: RunMode,
@@ -82,12 +265,14 @@ pub fn plan_for_mode(
     requested_mode: &str,
     _platform: &str,
     probe_path: &Path,
+    workspace_root: &Path,
+    workspace_tmpdir: Option<&Path>,
     sandbox_override: Option<String>,
 ) -> Result<ModePlan> {
     let run_mode = RunMode::try_from(requested_mode)?;
     run_mode.ensure_connector_present()?;
     let sandbox_env = run_mode.sandbox_env(sandbox_override);
-    let command = run_mode.command_spec(None, probe_path)?;
+    let command = run_mode.command_spec(None, probe_path, workspace_root, workspace_tmpdir)?;
 
     Ok(ModePlan {
         run_mode,
@@ -104,18 +289,32 @@ pub struct CommandSpec {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub struct Availability;
+pub struct Availability {
+    container_runtime: Option<ContainerRuntime>,
+}
 
 impl Availability {
     pub fn for_host() -> Self {
-        Availability
+        Self {
+            container_runtime: detect_container_runtime(),
+        }
+    }
+
+    /// The container runtime detected on this host, if any.
+    pub fn container_runtime(&self) -> Option<ContainerRuntime> {
+        self.container_runtime
     }
 }
 
 pub fn default_mode_names(availability: Availability) -> Vec<String> {
+    let cfg = host_cfg_map();
     MODE_SPECS
         .iter()
         .filter(|spec| (spec.default_gate)(&availability))
+        .filter(|spec| match spec.platform_cfg {
+            Some(predicate) => eval_cfg_predicate(predicate, &cfg).unwrap_or(false),
+            None => true,
+        })
         .map(|spec| spec.run_mode.as_str().to_string())
         .collect()
 }
@@ -138,20 +337,296 @@ fn always_available(_: &Availability) -> bool {
     true
 }
 
+fn container_available(availability: &Availability) -> bool {
+    availability.container_runtime().is_some()
+}
+
+fn isolated_available(_: &Availability) -> bool {
+    namespace_isolation_available()
+}
+
+/// Whether this host can plausibly run `isolated` mode: Linux only, with
+/// `/proc/self/ns/user` present and unprivileged user namespaces not
+/// explicitly disabled via the (Debian/Ubuntu-specific) sysctl. A host
+/// without that sysctl file is assumed permissive, matching upstream kernel
+/// defaults.
+///
+/// The Linux check goes through [`eval_cfg_predicate`]/[`host_cfg_map`]
+/// rather than the `cfg!()` macro: `cfg!()` only reflects the *compile*
+/// target, while a `cfg(...)` predicate evaluated against [`host_cfg_map`]
+/// reflects the actual running host (normalized `uname -s`), which matters
+/// for a binary built elsewhere and copied onto this machine.
+fn namespace_isolation_available() -> bool {
+    if !eval_cfg_predicate("cfg(target_os = \"linux\")", &host_cfg_map()).unwrap_or(false) {
+        return false;
+    }
+    if !Path::new("/proc/self/ns/user").exists() {
+        return false;
+    }
+    match fs::read_to_string("/proc/sys/kernel/unprivileged_userns_clone") {
+        Ok(value) => value.trim() == "1",
+        Err(_) => true,
+    }
+}
+
+/// Assemble the `cfg(...)` key/value facts for the host actually running
+/// this process: `target_arch` from [`env::consts::ARCH`], `target_os` from
+/// a normalized `uname -s` (falling back to [`env::consts::OS`] when `uname`
+/// isn't available), and the platform family (`unix`/`windows`) as a bare,
+/// keyless flag via [`env::consts::FAMILY`].
+pub fn host_cfg_map() -> BTreeMap<String, Option<String>> {
+    let mut cfg = BTreeMap::new();
+    let os = normalize_uname_os().unwrap_or_else(|| env::consts::OS.to_string());
+    cfg.insert("target_os".to_string(), Some(os));
+    cfg.insert(
+        "target_arch".to_string(),
+        Some(env::consts::ARCH.to_string()),
+    );
+    cfg.insert(env::consts::FAMILY.to_string(), None);
+    cfg
+}
+
+/// Run `uname -s` and map its output onto the same `target_os` vocabulary
+/// `rustc` uses (`Darwin` -> `macos`, `Linux` -> `linux`), so a predicate
+/// written as `cfg(target_os = "linux")` matches the way it would in source.
+fn normalize_uname_os() -> Option<String> {
+    let output = Command::new("uname")
+        .arg("-s")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let raw = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if raw.is_empty() {
+        return None;
+    }
+    Some(match raw.as_str() {
+        "Darwin" => "macos".to_string(),
+        "Linux" => "linux".to_string(),
+        other => other.to_lowercase(),
+    })
+}
+
+/// A parsed `cfg(...)` predicate, evaluated against a [`host_cfg_map`]-shaped
+/// fact table by [`eval_cfg_predicate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CfgExpr {
+    /// A bare identifier like `unix`, true when the key is present in the
+    /// cfg map (with or without a value).
+    Flag(String),
+    /// `key = "value"`, true when the map holds exactly that value for `key`.
+    KeyValue(String, String),
+    All(Vec<CfgExpr>),
+    Any(Vec<CfgExpr>),
+    Not(Box<CfgExpr>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CfgToken {
+    Ident(String),
+    Str(String),
+    Eq,
+    LParen,
+    RParen,
+    Comma,
+}
+
+fn tokenize_cfg(input: &str) -> Result<Vec<CfgToken>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some((idx, ch)) = chars.next() {
+        match ch {
+            c if c.is_whitespace() => continue,
+            '(' => tokens.push(CfgToken::LParen),
+            ')' => tokens.push(CfgToken::RParen),
+            ',' => tokens.push(CfgToken::Comma),
+            '=' => tokens.push(CfgToken::Eq),
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, c)) => value.push(c),
+                        None => bail!("unterminated string literal in cfg predicate: {input}"),
+                    }
+                }
+                tokens.push(CfgToken::Str(value));
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut ident = String::from(c);
+                while let Some((_, next)) = chars.peek() {
+                    if next.is_alphanumeric() || *next == '_' {
+                        ident.push(*next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(CfgToken::Ident(ident));
+            }
+            other => {
+                bail!("unexpected character '{other}' at byte {idx} in cfg predicate: {input}")
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct CfgParser<'a> {
+    tokens: &'a [CfgToken],
+    pos: usize,
+}
+
+impl<'a> CfgParser<'a> {
+    fn peek(&self) -> Option<&CfgToken> {
+        self.tokens.get(self.pos)
+    }
+
+    fn bump(&mut self) -> Option<&CfgToken> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: &CfgToken) -> Result<()> {
+        match self.bump() {
+            Some(token) if token == expected => Ok(()),
+            other => bail!("expected {expected:?}, found {other:?}"),
+        }
+    }
+
+    fn expect_ident(&mut self, expected: &str) -> Result<()> {
+        match self.bump() {
+            Some(CfgToken::Ident(name)) if name == expected => Ok(()),
+            other => bail!("expected identifier '{expected}', found {other:?}"),
+        }
+    }
+
+    /// `expr := ident | ident '=' string | ('all'|'any'|'not') '(' list ')'`
+    fn parse_expr(&mut self) -> Result<CfgExpr> {
+        match self.bump() {
+            Some(CfgToken::Ident(name)) => {
+                let name = name.clone();
+                match name.as_str() {
+                    "all" | "any" | "not" => {
+                        self.expect(&CfgToken::LParen)?;
+                        let list = self.parse_list()?;
+                        self.expect(&CfgToken::RParen)?;
+                        match name.as_str() {
+                            "all" => Ok(CfgExpr::All(list)),
+                            "any" => Ok(CfgExpr::Any(list)),
+                            _ => {
+                                let mut list = list;
+                                if list.len() != 1 {
+                                    bail!("'not' takes exactly one predicate, got {}", list.len());
+                                }
+                                Ok(CfgExpr::Not(Box::new(list.remove(0))))
+                            }
+                        }
+                    }
+                    _ => {
+                        if matches!(self.peek(), Some(CfgToken::Eq)) {
+                            self.bump();
+                            match self.bump() {
+                                Some(CfgToken::Str(value)) => {
+                                    Ok(CfgExpr::KeyValue(name, value.clone()))
+                                }
+                                other => {
+                                    bail!("expected string literal after '=', found {other:?}")
+                                }
+                            }
+                        } else {
+                            Ok(CfgExpr::Flag(name))
+                        }
+                    }
+                }
+            }
+            other => bail!("expected identifier, found {other:?}"),
+        }
+    }
+
+    fn parse_list(&mut self) -> Result<Vec<CfgExpr>> {
+        let mut list = Vec::new();
+        if matches!(self.peek(), Some(CfgToken::RParen)) {
+            return Ok(list);
+        }
+        loop {
+            list.push(self.parse_expr()?);
+            if matches!(self.peek(), Some(CfgToken::Comma)) {
+                self.bump();
+            } else {
+                break;
+            }
+        }
+        Ok(list)
+    }
+}
+
+fn eval_cfg_expr(expr: &CfgExpr, cfg: &BTreeMap<String, Option<String>>) -> bool {
+    match expr {
+        CfgExpr::Flag(name) => cfg.contains_key(name),
+        CfgExpr::KeyValue(key, value) => {
+            matches!(cfg.get(key), Some(Some(actual)) if actual == value)
+        }
+        CfgExpr::All(list) => list.iter().all(|expr| eval_cfg_expr(expr, cfg)),
+        CfgExpr::Any(list) => list.iter().any(|expr| eval_cfg_expr(expr, cfg)),
+        CfgExpr::Not(inner) => !eval_cfg_expr(inner, cfg),
+    }
+}
+
+/// Parse and evaluate a `cfg(...)` predicate (e.g. `cfg(target_os =
+/// "linux")`, `cfg(all(unix, not(target_os = "macos")))`) against a cfg map
+/// such as [`host_cfg_map`]'s. An unknown key evaluates to `false` rather
+/// than erroring; only malformed predicate syntax is an error.
+pub fn eval_cfg_predicate(predicate: &str, cfg: &BTreeMap<String, Option<String>>) -> Result<bool> {
+    let tokens = tokenize_cfg(predicate)?;
+    let mut parser = CfgParser {
+        tokens: &tokens,
+        pos: 0,
+    };
+    parser.expect_ident("cfg")?;
+    parser.expect(&CfgToken::LParen)?;
+    let expr = parser.parse_expr()?;
+    parser.expect(&CfgToken::RParen)?;
+    if parser.pos != tokens.len() {
+        bail!("unexpected trailing tokens after cfg(...) in predicate: {predicate}");
+    }
+    Ok(eval_cfg_expr(&expr, cfg))
+}
+
 struct ModeSpec {
     run_mode: RunMode,
     default_gate: fn(&Availability) -> bool,
+    /// An additional `cfg(...)` predicate a mode must satisfy on this host,
+    /// evaluated against [`host_cfg_map`] alongside `default_gate`. `None`
+    /// means no extra platform restriction beyond `default_gate`'s own check.
+    platform_cfg: Option<&'static str>,
 }
 
-const MODE_SPECS: &[ModeSpec] = &[ModeSpec {
-    run_mode: RunMode::Baseline,
-    default_gate: always_available,
-}];
+const MODE_SPECS: &[ModeSpec] = &[
+    ModeSpec {
+        run_mode: RunMode::Baseline,
+        default_gate: always_available,
+        platform_cfg: None,
+    },
+    ModeSpec {
+        run_mode: RunMode::Isolated,
+        default_gate: isolated_available,
+        platform_cfg: Some("cfg(target_os = \"linux\")"),
+    },
+    ModeSpec {
+        run_mode: RunMode::Container,
+        default_gate: container_available,
+        platform_cfg: None,
+    },
+];
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
 
     #[test]
     fn run_mode_parse_and_strings_round_trip() {
@@ -166,6 +641,8 @@ mod tests {
             "baseline",
             "Darwin",
             PathBuf::from("/tmp/probe.sh").as_path(),
+            PathBuf::from("/tmp").as_path(),
+            None,
             None,
         )
         .expect("baseline plan");
@@ -175,4 +652,138 @@ mod tests {
         assert_eq!(plan.command.args.len(), 0);
         assert_eq!(plan.command.program, OsString::from("/tmp/probe.sh"));
     }
+
+    #[test]
+    fn run_mode_container_parses_and_round_trips() {
+        let container = RunMode::try_from("container").expect("container parses");
+        assert_eq!(container.as_str(), "container");
+        assert_eq!(container.connector(), ConnectorKind::Container);
+    }
+
+    #[test]
+    fn run_mode_isolated_parses_and_round_trips() {
+        let isolated = RunMode::try_from("isolated").expect("isolated parses");
+        assert_eq!(isolated.as_str(), "isolated");
+        assert_eq!(isolated.connector(), ConnectorKind::Namespace);
+    }
+
+    #[test]
+    fn isolated_command_spec_reinvokes_fence_netns_launcher() {
+        let plan = plan_for_mode(
+            "isolated",
+            "Linux",
+            PathBuf::from("/repo/probes/fs/read.sh").as_path(),
+            PathBuf::from("/repo").as_path(),
+            None,
+            None,
+        );
+        // Only assert the shape of the command a gate-passing host would
+        // build; whether this host can actually gate `isolated` on is
+        // exercised by `ensure_connector_present`, not this test.
+        if let Ok(plan) = plan {
+            assert_eq!(plan.command.program, OsString::from("fence-netns"));
+            assert_eq!(
+                plan.command.args,
+                vec![OsString::from("/repo/probes/fs/read.sh")]
+            );
+        }
+    }
+
+    #[test]
+    fn isolation_args_drop_caps_and_disable_network() {
+        let args = isolation_args();
+        assert!(args.contains(&OsString::from("--cap-drop=ALL")));
+        assert!(args.contains(&OsString::from("--read-only")));
+        assert!(args.contains(&OsString::from("--network=none")));
+    }
+
+    #[test]
+    fn container_command_spec_mounts_workspace_and_tmpdir() {
+        let plan = plan_for_mode(
+            "container",
+            "Linux",
+            PathBuf::from("/repo/probes/fs/read.sh").as_path(),
+            PathBuf::from("/repo").as_path(),
+            Some(PathBuf::from("/repo/tmp").as_path()),
+            None,
+        );
+        // Only assert the shape of the command a host with a container
+        // runtime would build; whether this host actually has docker/podman
+        // is exercised by `ensure_connector_present`, not this test.
+        if let Ok(plan) = plan {
+            assert!(plan
+                .command
+                .args
+                .contains(&OsString::from("/repo:/repo:ro")));
+            assert!(plan
+                .command
+                .args
+                .contains(&OsString::from("/repo/tmp:/repo/tmp:rw")));
+            assert!(plan.command.args.contains(&OsString::from("-w")));
+            assert!(plan
+                .command
+                .args
+                .contains(&OsString::from("FENCE_WORKSPACE_ROOT")));
+        }
+    }
+
+    fn cfg_fixture() -> BTreeMap<String, Option<String>> {
+        let mut cfg = BTreeMap::new();
+        cfg.insert("target_os".to_string(), Some("linux".to_string()));
+        cfg.insert("target_arch".to_string(), Some("x86_64".to_string()));
+        cfg.insert("unix".to_string(), None);
+        cfg
+    }
+
+    #[test]
+    fn cfg_predicate_matches_key_value() {
+        let cfg = cfg_fixture();
+        assert!(eval_cfg_predicate("cfg(target_os = \"linux\")", &cfg).unwrap());
+        assert!(!eval_cfg_predicate("cfg(target_os = \"macos\")", &cfg).unwrap());
+    }
+
+    #[test]
+    fn cfg_predicate_matches_bare_flag() {
+        let cfg = cfg_fixture();
+        assert!(eval_cfg_predicate("cfg(unix)", &cfg).unwrap());
+        assert!(!eval_cfg_predicate("cfg(windows)", &cfg).unwrap());
+    }
+
+    #[test]
+    fn cfg_predicate_evaluates_all_any_not() {
+        let cfg = cfg_fixture();
+        assert!(eval_cfg_predicate("cfg(all(unix, target_os = \"linux\"))", &cfg).unwrap());
+        assert!(!eval_cfg_predicate("cfg(all(unix, target_os = \"macos\"))", &cfg).unwrap());
+        assert!(eval_cfg_predicate(
+            "cfg(any(target_arch = \"aarch64\", target_arch = \"x86_64\"))",
+            &cfg
+        )
+        .unwrap());
+        assert!(eval_cfg_predicate("cfg(not(target_os = \"macos\"))", &cfg).unwrap());
+        assert!(eval_cfg_predicate("cfg(all(unix, not(target_os = \"macos\")))", &cfg).unwrap());
+    }
+
+    #[test]
+    fn cfg_predicate_unknown_key_is_false_not_error() {
+        let cfg = cfg_fixture();
+        assert!(!eval_cfg_predicate("cfg(target_env = \"musl\")", &cfg).unwrap());
+    }
+
+    #[test]
+    fn cfg_predicate_rejects_malformed_syntax() {
+        let cfg = cfg_fixture();
+        assert!(eval_cfg_predicate("cfg(target_os = )", &cfg).is_err());
+        assert!(eval_cfg_predicate("cfg(not(unix, windows))", &cfg).is_err());
+        assert!(eval_cfg_predicate("target_os = \"linux\"", &cfg).is_err());
+    }
+
+    #[test]
+    fn host_cfg_map_reports_this_processs_compile_target() {
+        let cfg = host_cfg_map();
+        assert_eq!(
+            cfg.get("target_arch").cloned().flatten().as_deref(),
+            Some(env::consts::ARCH)
+        );
+        assert!(cfg.contains_key("target_os"));
+    }
 }
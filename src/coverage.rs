@@ -4,11 +4,17 @@
 //! identify gaps. Coverage intentionally ignores fixtures and known broken
 //! probes so only actionable entries surface.
 
-use crate::catalog::CapabilityIndex;
+use crate::catalog::{CapabilityId, CapabilityIndex, Criticality};
+use crate::metadata_validation::{
+    default_capability_extraction_rules, extract_capability_ids, find_json_files,
+};
 use crate::probe_metadata::ProbeMetadata;
-use anyhow::{Result, anyhow, bail};
+use anyhow::{Context, Result, anyhow, bail};
 use serde::Serialize;
-use std::collections::BTreeMap;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
 
 // Probes used by tests or contract fixtures should not count toward coverage.
 const IGNORED_PROBE_IDS: &[&str] = &["tests_fixture_probe", "tests_static_contract_broken"];
@@ -82,6 +88,68 @@ pub fn validate_coverage_against_map(
     Ok(())
 }
 
+/// CI-facing severity of one capability's coverage gap, mapped from its
+/// catalog [`Criticality`] the way a linter maps a rule hit to a diagnostic
+/// level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoverageSeverity {
+    /// An uncovered `Critical` capability: fails the gate.
+    Fail,
+    /// An uncovered `Standard` capability: reported but doesn't fail the gate.
+    Warn,
+}
+
+/// Severity-ranked coverage gaps produced by [`evaluate_coverage`], so a CI
+/// step can gate on `should_fail` without re-deriving severities itself.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CoverageVerdict {
+    /// Uncovered capability ids mapped to their severity. A capability whose
+    /// criticality is [`Criticality::Informational`], or that has a probe,
+    /// has no entry here.
+    pub severities: BTreeMap<String, CoverageSeverity>,
+    /// `true` when at least one uncovered capability is [`Criticality::Critical`].
+    pub should_fail: bool,
+}
+
+/// Rank `coverage`'s gaps by each capability's catalog-declared
+/// [`Criticality`]: an uncovered `Critical` capability becomes
+/// [`CoverageSeverity::Fail`] (and sets `should_fail`), an uncovered
+/// `Standard` capability becomes [`CoverageSeverity::Warn`], and an
+/// uncovered `Informational` capability is dropped entirely so teams can
+/// ratchet enforcement without blocking on every low-value capability.
+pub fn evaluate_coverage(
+    coverage: &BTreeMap<String, CoverageEntry>,
+    capabilities: &CapabilityIndex,
+) -> CoverageVerdict {
+    let mut severities = BTreeMap::new();
+    let mut should_fail = false;
+
+    for (id, entry) in coverage {
+        if entry.has_probe {
+            continue;
+        }
+        let criticality = capabilities
+            .capability(&CapabilityId(id.clone()))
+            .map(|capability| capability.criticality)
+            .unwrap_or_default();
+        let severity = match criticality {
+            Criticality::Critical => CoverageSeverity::Fail,
+            Criticality::Standard => CoverageSeverity::Warn,
+            Criticality::Informational => continue,
+        };
+        if severity == CoverageSeverity::Fail {
+            should_fail = true;
+        }
+        severities.insert(id.clone(), severity);
+    }
+
+    CoverageVerdict {
+        severities,
+        should_fail,
+    }
+}
+
 /// Filter out probes that should not affect coverage reporting.
 pub fn filter_coverage_probes(probes: &[ProbeMetadata]) -> Vec<ProbeMetadata> {
     probes
@@ -94,6 +162,265 @@ pub fn filter_coverage_probes(probes: &[ProbeMetadata]) -> Vec<ProbeMetadata> {
         .collect()
 }
 
+/// Render a capability→probe coverage map as a Graphviz DOT `digraph`: one
+/// ellipse node per capability, one `box` node per probe, and a `"cap_x" ->
+/// "probe_y"` edge for each probe that covers it. Capabilities with
+/// `has_probe == false` get `color=red, style=dashed` so coverage gaps are
+/// visible at a glance instead of requiring a flat-list read.
+pub fn render_coverage_dot(coverage: &BTreeMap<String, CoverageEntry>) -> String {
+    let mut out = String::from("digraph coverage {\n");
+    for (id, entry) in coverage {
+        if entry.has_probe {
+            out.push_str(&format!("  \"{id}\";\n"));
+        } else {
+            out.push_str(&format!("  \"{id}\" [color=red, style=dashed];\n"));
+        }
+    }
+
+    let probe_ids: BTreeSet<&str> = coverage
+        .values()
+        .flat_map(|entry| entry.probe_ids.iter().map(String::as_str))
+        .collect();
+    for probe_id in &probe_ids {
+        out.push_str(&format!("  \"{probe_id}\" [shape=box];\n"));
+    }
+
+    for (id, entry) in coverage {
+        for probe_id in &entry.probe_ids {
+            out.push_str(&format!("  \"{id}\" -> \"{probe_id}\";\n"));
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+/// Coverage total and percentage for one category or policy layer.
+pub struct CoverageStats {
+    pub total: usize,
+    pub covered: usize,
+    pub percentage: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+/// Capability coverage across both authored probes and emitted boundary
+/// objects, broken out by category and policy layer.
+///
+/// Unlike [`CoverageEntry`], which only tracks whether a probe exercises a
+/// capability as its primary target, this report also counts secondary
+/// references from probes and boundary-object `capability_context` blocks, so
+/// "exercised only in passing" capabilities can be told apart from those with
+/// no coverage at all.
+pub struct CapabilityCoverageReport {
+    /// Capability ids with zero primary or secondary references anywhere.
+    pub uncovered: Vec<String>,
+    /// Capability ids referenced only as a secondary capability, never primary.
+    pub secondary_only: Vec<String>,
+    pub category_coverage: BTreeMap<String, CoverageStats>,
+    pub layer_coverage: BTreeMap<String, CoverageStats>,
+}
+
+#[derive(Default)]
+struct CapabilityTally {
+    primary: usize,
+    secondary: usize,
+}
+
+/// Tally how often each capability is exercised by probes and by emitted
+/// boundary objects found under `dirs`, then summarize gaps.
+///
+/// Boundary objects are discovered the same way [`validate_boundary_objects`]
+/// finds them, and the same extraction rules classify a reference as primary
+/// or secondary. Capability ids unknown to `capabilities` are ignored here;
+/// [`validate_boundary_objects`] is responsible for flagging those.
+///
+/// [`validate_boundary_objects`]: crate::metadata_validation::validate_boundary_objects
+pub fn capability_coverage(
+    capabilities: &CapabilityIndex,
+    probes: &[ProbeMetadata],
+    dirs: &[PathBuf],
+) -> Result<CapabilityCoverageReport> {
+    let mut tallies: BTreeMap<CapabilityId, CapabilityTally> = capabilities
+        .ids()
+        .map(|id| (id.clone(), CapabilityTally::default()))
+        .collect();
+
+    for probe in probes {
+        if let Some(primary) = &probe.primary_capability {
+            if let Some(tally) = tallies.get_mut(primary) {
+                tally.primary += 1;
+            }
+        }
+        for secondary in &probe.secondary_capabilities {
+            if let Some(tally) = tallies.get_mut(secondary) {
+                tally.secondary += 1;
+            }
+        }
+    }
+
+    let rules = default_capability_extraction_rules();
+    for json_file in find_json_files(dirs)? {
+        let data = fs::read_to_string(&json_file)
+            .with_context(|| format!("reading {}", json_file.display()))?;
+        let value: Value = match serde_json::from_str(&data) {
+            Ok(value) => value,
+            // Malformed records are reported by validate_boundary_objects;
+            // coverage accounting just skips what it can't parse.
+            Err(_) => continue,
+        };
+        for extracted in extract_capability_ids(&value, &rules) {
+            let Some(tally) = tallies.get_mut(&extracted.id) else {
+                continue;
+            };
+            if extracted.rule_label.contains("secondary") {
+                tally.secondary += 1;
+            } else {
+                tally.primary += 1;
+            }
+        }
+    }
+
+    let mut uncovered = Vec::new();
+    let mut secondary_only = Vec::new();
+    let mut category_coverage: BTreeMap<String, CoverageStats> = BTreeMap::new();
+    let mut layer_coverage: BTreeMap<String, CoverageStats> = BTreeMap::new();
+
+    for (id, tally) in &tallies {
+        let capability = capabilities
+            .capability(id)
+            .expect("tally seeded from capabilities.ids()");
+        let covered = tally.primary > 0 || tally.secondary > 0;
+        if !covered {
+            uncovered.push(id.0.clone());
+        } else if tally.primary == 0 {
+            secondary_only.push(id.0.clone());
+        }
+
+        let category_stats = category_coverage
+            .entry(capability.category.as_str().to_string())
+            .or_default();
+        category_stats.total += 1;
+        category_stats.covered += covered as usize;
+
+        let layer_stats = layer_coverage
+            .entry(capability.layer.as_str().to_string())
+            .or_default();
+        layer_stats.total += 1;
+        layer_stats.covered += covered as usize;
+    }
+
+    for stats in category_coverage
+        .values_mut()
+        .chain(layer_coverage.values_mut())
+    {
+        stats.percentage = if stats.total == 0 {
+            0.0
+        } else {
+            (stats.covered as f64 / stats.total as f64) * 100.0
+        };
+    }
+
+    Ok(CapabilityCoverageReport {
+        uncovered,
+        secondary_only,
+        category_coverage,
+        layer_coverage,
+    })
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+/// Probe-corpus accounting for the `probe-coverage` command: referencing-probe
+/// counts per catalog capability, catalog capabilities no probe references
+/// ("gaps"), probe-declared capability ids absent from the catalog
+/// ("orphans"), and scripts whose capability ids couldn't be statically
+/// resolved.
+///
+/// Unlike [`build_probe_coverage_map`], this never errors and doesn't require
+/// `probe_name`/`primary_capability` to be present, so a coverage report is
+/// still useful when some scripts are incomplete; unlike
+/// [`capability_coverage`], an unknown capability id is reported as an orphan
+/// instead of being silently ignored.
+pub struct ProbeCoverageAccounting {
+    /// Catalog capability ids with zero referencing probes.
+    pub gaps: Vec<String>,
+    /// Referencing-probe counts for every capability id in the catalog.
+    pub probe_counts: BTreeMap<String, usize>,
+    /// Capability ids referenced by a probe but absent from the catalog,
+    /// mapped to the probes (by `probe_name`, falling back to script file
+    /// stem) that reference them.
+    pub orphans: BTreeMap<String, Vec<String>>,
+    /// Scripts with a `$`-substituted capability id (see
+    /// [`ProbeMetadata::has_dynamic_capability_reference`]).
+    pub unresolved_scripts: Vec<PathBuf>,
+}
+
+/// Build a [`ProbeCoverageAccounting`] from every probe's declared
+/// `primary_capability`/`secondary_capabilities` against `capabilities`.
+pub fn account_probe_coverage(
+    capabilities: &CapabilityIndex,
+    probes: &[ProbeMetadata],
+) -> ProbeCoverageAccounting {
+    let mut probe_counts: BTreeMap<String, usize> =
+        capabilities.ids().map(|id| (id.0.clone(), 0)).collect();
+    let mut orphans: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut unresolved_scripts = Vec::new();
+
+    for probe in probes {
+        if probe.has_dynamic_capability_reference {
+            unresolved_scripts.push(probe.script.clone());
+        }
+
+        let probe_label = probe_label(probe);
+        for id in probe_referenced_capabilities(probe) {
+            match probe_counts.get_mut(&id.0) {
+                Some(count) => *count += 1,
+                None => {
+                    let probe_labels = orphans.entry(id.0).or_default();
+                    if !probe_labels.contains(&probe_label) {
+                        probe_labels.push(probe_label.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    let gaps = probe_counts
+        .iter()
+        .filter(|(_, count)| **count == 0)
+        .map(|(id, _)| id.clone())
+        .collect();
+
+    for probe_labels in orphans.values_mut() {
+        probe_labels.sort();
+    }
+
+    ProbeCoverageAccounting {
+        gaps,
+        probe_counts,
+        orphans,
+        unresolved_scripts,
+    }
+}
+
+/// A probe's distinct primary+secondary capability ids, deduplicated so a
+/// script declaring the same id in both positions only counts once.
+fn probe_referenced_capabilities(probe: &ProbeMetadata) -> BTreeSet<CapabilityId> {
+    let mut ids: BTreeSet<CapabilityId> = probe.secondary_capabilities.iter().cloned().collect();
+    ids.extend(probe.primary_capability.clone());
+    ids
+}
+
+fn probe_label(probe: &ProbeMetadata) -> String {
+    probe.probe_name.clone().unwrap_or_else(|| {
+        probe
+            .script
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("unknown")
+            .to_string()
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -111,6 +438,12 @@ mod tests {
             probe_version: Some("1".to_string()),
             primary_capability: Some(CapabilityId("cap_missing".to_string())),
             secondary_capabilities: Vec::new(),
+            problem_matchers: Vec::new(),
+            platform_cfg: None,
+            has_dynamic_capability_reference: false,
+            expected_result: None,
+            expected_result_by_mode: BTreeMap::new(),
+            diagnostics: Vec::new(),
         };
         let err = build_probe_coverage_map(&caps, &[probe]).expect_err("unknown cap should fail");
         assert!(
@@ -119,6 +452,92 @@ mod tests {
         );
     }
 
+    #[test]
+    fn evaluate_coverage_ranks_gaps_by_criticality() {
+        let caps = criticality_index().expect("load criticality index");
+        let coverage: BTreeMap<String, CoverageEntry> = caps
+            .ids()
+            .map(|id| {
+                (
+                    id.0.clone(),
+                    CoverageEntry {
+                        has_probe: false,
+                        probe_ids: Vec::new(),
+                    },
+                )
+            })
+            .collect();
+
+        let verdict = evaluate_coverage(&coverage, &caps);
+
+        assert_eq!(
+            verdict.severities.get("cap_critical"),
+            Some(&CoverageSeverity::Fail)
+        );
+        assert_eq!(
+            verdict.severities.get("cap_standard"),
+            Some(&CoverageSeverity::Warn)
+        );
+        assert_eq!(verdict.severities.get("cap_informational"), None);
+        assert!(verdict.should_fail);
+    }
+
+    #[test]
+    fn evaluate_coverage_ignores_capabilities_with_a_probe() {
+        let caps = criticality_index().expect("load criticality index");
+        let mut coverage: BTreeMap<String, CoverageEntry> = caps
+            .ids()
+            .map(|id| {
+                (
+                    id.0.clone(),
+                    CoverageEntry {
+                        has_probe: false,
+                        probe_ids: Vec::new(),
+                    },
+                )
+            })
+            .collect();
+        coverage.get_mut("cap_critical").unwrap().has_probe = true;
+
+        let verdict = evaluate_coverage(&coverage, &caps);
+
+        assert_eq!(verdict.severities.get("cap_critical"), None);
+        assert!(!verdict.should_fail);
+    }
+
+    #[test]
+    fn render_coverage_dot_marks_uncovered_capabilities_and_boxes_probes() {
+        let caps = multi_cap_index().expect("load sample index");
+        let probe = ProbeMetadata {
+            script: PathBuf::from("probe.sh"),
+            probe_name: Some("probe".to_string()),
+            probe_version: Some("1".to_string()),
+            primary_capability: Some(CapabilityId("cap_a".to_string())),
+            secondary_capabilities: Vec::new(),
+            problem_matchers: Vec::new(),
+            platform_cfg: None,
+            has_dynamic_capability_reference: false,
+            expected_result: None,
+            expected_result_by_mode: BTreeMap::new(),
+            diagnostics: Vec::new(),
+        };
+        let coverage = build_probe_coverage_map(&caps, &[probe]).expect("build coverage map");
+
+        let dot = render_coverage_dot(&coverage);
+        assert!(dot.starts_with("digraph coverage {\n"));
+        assert!(dot.contains("\"cap_a\";\n"));
+        assert!(dot.contains("\"probe\" [shape=box];"));
+        assert!(dot.contains("\"cap_a\" -> \"probe\";"));
+        assert!(dot.ends_with("}\n"));
+
+        let uncovered = coverage
+            .iter()
+            .find(|(_, entry)| !entry.has_probe)
+            .map(|(id, _)| id.clone())
+            .expect("multi_cap_index has an uncovered capability");
+        assert!(dot.contains(&format!("\"{uncovered}\" [color=red, style=dashed];")));
+    }
+
     #[test]
     fn filter_coverage_probes_ignores_fixtures() {
         let probes = vec![
@@ -128,6 +547,12 @@ mod tests {
                 probe_version: None,
                 primary_capability: None,
                 secondary_capabilities: Vec::new(),
+                problem_matchers: Vec::new(),
+                platform_cfg: None,
+                has_dynamic_capability_reference: false,
+                expected_result: None,
+                expected_result_by_mode: BTreeMap::new(),
+                diagnostics: Vec::new(),
             },
             ProbeMetadata {
                 script: PathBuf::from("probe2.sh"),
@@ -135,6 +560,12 @@ mod tests {
                 probe_version: None,
                 primary_capability: None,
                 secondary_capabilities: Vec::new(),
+                problem_matchers: Vec::new(),
+                platform_cfg: None,
+                has_dynamic_capability_reference: false,
+                expected_result: None,
+                expected_result_by_mode: BTreeMap::new(),
+                diagnostics: Vec::new(),
             },
         ];
         let filtered = filter_coverage_probes(&probes);
@@ -161,4 +592,206 @@ mod tests {
         )?;
         CapabilityIndex::load(file.path())
     }
+
+    fn multi_cap_index() -> Result<CapabilityIndex> {
+        let mut file = NamedTempFile::new()?;
+        serde_json::to_writer(
+            &mut file,
+            &json!({
+                "schema_version": "sandbox_catalog_v1",
+                "scope": {"description": "test", "policy_layers": [], "categories": {}},
+                "docs": {},
+                "capabilities": [
+                    {
+                        "id": "cap_a",
+                        "category": "filesystem",
+                        "layer": "os_sandbox",
+                        "description": "fixture a",
+                        "operations": {"allow": [], "deny": []}
+                    },
+                    {
+                        "id": "cap_b",
+                        "category": "process",
+                        "layer": "os_sandbox",
+                        "description": "fixture b",
+                        "operations": {"allow": [], "deny": []}
+                    },
+                    {
+                        "id": "cap_c",
+                        "category": "network",
+                        "layer": "agent_runtime",
+                        "description": "fixture c",
+                        "operations": {"allow": [], "deny": []}
+                    }
+                ]
+            }),
+        )?;
+        CapabilityIndex::load(file.path())
+    }
+
+    fn criticality_index() -> Result<CapabilityIndex> {
+        let mut file = NamedTempFile::new()?;
+        serde_json::to_writer(
+            &mut file,
+            &json!({
+                "schema_version": "sandbox_catalog_v1",
+                "scope": {"description": "test", "policy_layers": [], "categories": {}},
+                "docs": {},
+                "capabilities": [
+                    {
+                        "id": "cap_critical",
+                        "category": "filesystem",
+                        "layer": "os_sandbox",
+                        "description": "fixture critical",
+                        "operations": {"allow": [], "deny": []},
+                        "criticality": "critical"
+                    },
+                    {
+                        "id": "cap_standard",
+                        "category": "process",
+                        "layer": "os_sandbox",
+                        "description": "fixture standard",
+                        "operations": {"allow": [], "deny": []},
+                        "criticality": "standard"
+                    },
+                    {
+                        "id": "cap_informational",
+                        "category": "network",
+                        "layer": "agent_runtime",
+                        "description": "fixture informational",
+                        "operations": {"allow": [], "deny": []},
+                        "criticality": "informational"
+                    }
+                ]
+            }),
+        )?;
+        CapabilityIndex::load(file.path())
+    }
+
+    #[test]
+    fn capability_coverage_splits_uncovered_and_secondary_only() {
+        let index = multi_cap_index().expect("multi cap index loads");
+        let probe = ProbeMetadata {
+            script: PathBuf::from("probe.sh"),
+            probe_name: Some("probe".to_string()),
+            probe_version: Some("1".to_string()),
+            primary_capability: Some(CapabilityId("cap_a".to_string())),
+            secondary_capabilities: vec![CapabilityId("cap_b".to_string())],
+            problem_matchers: Vec::new(),
+            platform_cfg: None,
+            has_dynamic_capability_reference: false,
+            expected_result: None,
+            expected_result_by_mode: BTreeMap::new(),
+            diagnostics: Vec::new(),
+        };
+
+        let report = capability_coverage(&index, &[probe], &[]).expect("coverage should compute");
+
+        assert_eq!(report.uncovered, vec!["cap_c".to_string()]);
+        assert_eq!(report.secondary_only, vec!["cap_b".to_string()]);
+
+        let filesystem = &report.category_coverage["filesystem"];
+        assert_eq!((filesystem.covered, filesystem.total), (1, 1));
+        assert_eq!(filesystem.percentage, 100.0);
+
+        let network = &report.category_coverage["network"];
+        assert_eq!((network.covered, network.total), (0, 1));
+        assert_eq!(network.percentage, 0.0);
+
+        let agent_runtime = &report.layer_coverage["agent_runtime"];
+        assert_eq!((agent_runtime.covered, agent_runtime.total), (0, 1));
+    }
+
+    #[test]
+    fn capability_coverage_counts_boundary_object_references() {
+        let index = multi_cap_index().expect("multi cap index loads");
+        let dir = tempfile::tempdir().expect("temp dir");
+        let bo_path = dir.path().join("bo.json");
+        let record = json!({
+            "schema_version": "cfbo-v1",
+            "capabilities_schema_version": "macOS_codex_v1",
+            "stack": {"os": "Darwin"},
+            "probe": {
+                "id": "probe",
+                "version": "1",
+                "primary_capability_id": "cap_c",
+                "secondary_capability_ids": []
+            },
+            "run": {"mode": "baseline", "workspace_root": "/tmp", "command": "true"},
+            "operation": {"category": "net", "verb": "connect", "target": "example", "args": {}},
+            "result": {"observed_result": "success", "raw_exit_code": 0, "errno": null, "message": null, "error_detail": null},
+            "payload": {"stdout_snippet": null, "stderr_snippet": null, "raw": {}},
+            "capability_context": {"primary": {"id": "cap_c", "category": "network", "layer": "agent_runtime"}, "secondary": []}
+        });
+        std::fs::write(&bo_path, serde_json::to_string(&record).unwrap()).unwrap();
+
+        let report = capability_coverage(&index, &[], &[dir.path().to_path_buf()])
+            .expect("coverage should compute");
+
+        assert!(!report.uncovered.contains(&"cap_c".to_string()));
+        assert_eq!(
+            report.uncovered,
+            vec!["cap_a".to_string(), "cap_b".to_string()]
+        );
+        assert!(report.secondary_only.is_empty());
+    }
+
+    #[test]
+    fn account_probe_coverage_reports_gaps_counts_and_orphans() {
+        let index = multi_cap_index().expect("multi cap index loads");
+        let probe = ProbeMetadata {
+            script: PathBuf::from("probe.sh"),
+            probe_name: Some("probe".to_string()),
+            probe_version: Some("1".to_string()),
+            primary_capability: Some(CapabilityId("cap_a".to_string())),
+            secondary_capabilities: vec![CapabilityId("cap_missing".to_string())],
+            problem_matchers: Vec::new(),
+            platform_cfg: None,
+            has_dynamic_capability_reference: false,
+            expected_result: None,
+            expected_result_by_mode: BTreeMap::new(),
+            diagnostics: Vec::new(),
+        };
+
+        let accounting = account_probe_coverage(&index, &[probe]);
+
+        assert_eq!(
+            accounting.gaps,
+            vec!["cap_b".to_string(), "cap_c".to_string()]
+        );
+        assert_eq!(accounting.probe_counts["cap_a"], 1);
+        assert_eq!(accounting.probe_counts["cap_b"], 0);
+        assert_eq!(
+            accounting.orphans.get("cap_missing"),
+            Some(&vec!["probe".to_string()])
+        );
+        assert!(accounting.unresolved_scripts.is_empty());
+    }
+
+    #[test]
+    fn account_probe_coverage_tracks_unresolved_scripts_separately() {
+        let index = multi_cap_index().expect("multi cap index loads");
+        let probe = ProbeMetadata {
+            script: PathBuf::from("dynamic_probe.sh"),
+            probe_name: None,
+            probe_version: None,
+            primary_capability: None,
+            secondary_capabilities: Vec::new(),
+            problem_matchers: Vec::new(),
+            platform_cfg: None,
+            has_dynamic_capability_reference: true,
+            expected_result: None,
+            expected_result_by_mode: BTreeMap::new(),
+            diagnostics: Vec::new(),
+        };
+
+        let accounting = account_probe_coverage(&index, &[probe]);
+
+        assert_eq!(
+            accounting.unresolved_scripts,
+            vec![PathBuf::from("dynamic_probe.sh")]
+        );
+        assert!(accounting.orphans.is_empty());
+        assert_eq!(accounting.gaps.len(), 3);
+    }
 }
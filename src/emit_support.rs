@@ -1,4 +1,5 @@
 use anyhow::{Context, Result, bail};
+use base64::Engine;
 use serde_json::{Map, Value, json};
 use std::collections::BTreeSet;
 use std::fs;
@@ -16,6 +17,7 @@ pub struct PayloadArgs {
     stdout: Option<TextSource>,
     stderr: Option<TextSource>,
     raw: JsonObjectBuilder,
+    merge_with_file: bool,
 }
 
 impl PayloadArgs {
@@ -27,6 +29,13 @@ impl PayloadArgs {
         Ok(())
     }
 
+    /// Opt in to layering inline payload flags on top of `--payload-file`
+    /// instead of rejecting the combination. The file becomes the base layer;
+    /// inline snippets/raw fields recursively override its leaves.
+    pub fn enable_merge_with_file(&mut self) {
+        self.merge_with_file = true;
+    }
+
     pub fn set_stdout(&mut self, source: TextSource) -> Result<()> {
         if self.stdout.is_some() {
             bail!("stdout snippet provided multiple times");
@@ -44,19 +53,43 @@ impl PayloadArgs {
     }
 
     pub fn build(self) -> Result<Value> {
-        if let Some(ref path) = self.payload_file {
-            if self.has_inline_fields() {
-                bail!("--payload-file cannot be combined with inline payload flags");
+        let PayloadArgs {
+            payload_file,
+            stdout,
+            stderr,
+            raw,
+            merge_with_file,
+        } = self;
+
+        if let Some(path) = payload_file {
+            let has_inline = stdout.is_some() || stderr.is_some() || !raw.is_empty();
+            if has_inline && !merge_with_file {
+                bail!(
+                    "--payload-file cannot be combined with inline payload flags (pass --payload-merge-file to layer them)"
+                );
             }
             if !path.is_file() {
                 bail!("Payload file not found: {}", path.display());
             }
-            return read_json_file(&path);
+            let base = read_json_file(&path)?;
+            if !has_inline {
+                return Ok(base);
+            }
+
+            let Value::Object(mut base_map) = base else {
+                bail!(
+                    "Payload file {} must be a JSON object to merge with inline payload flags",
+                    path.display()
+                );
+            };
+            let overlay = build_inline_overlay(stdout, stderr, raw)?;
+            merge_object(&mut base_map, &overlay)?;
+            return Ok(Value::Object(base_map));
         }
 
-        let stdout_snippet = build_snippet_value(self.stdout)?;
-        let stderr_snippet = build_snippet_value(self.stderr)?;
-        let raw = self.raw.build("payload raw object")?;
+        let stdout_snippet = build_snippet_value(stdout)?;
+        let stderr_snippet = build_snippet_value(stderr)?;
+        let raw = raw.build("payload raw object")?;
 
         Ok(json!({
             "stdout_snippet": stdout_snippet,
@@ -65,10 +98,6 @@ impl PayloadArgs {
         }))
     }
 
-    fn has_inline_fields(&self) -> bool {
-        self.stdout.is_some() || self.stderr.is_some() || !self.raw.is_empty()
-    }
-
     pub fn raw_mut(&mut self) -> &mut JsonObjectBuilder {
         &mut self.raw
     }
@@ -164,34 +193,94 @@ enum JsonValueSource {
 pub enum TextSource {
     Inline(String),
     File(PathBuf),
+    /// Like `File`, but always encoded as base64 regardless of UTF-8 validity.
+    BinaryFile(PathBuf),
 }
 
+/// Merge `source` onto `target`, recursing when both sides hold an object at
+/// the same key so nested fields layer instead of replacing wholesale; for
+/// arrays and scalars (or a type mismatch) the later `source` value wins.
 fn merge_object(target: &mut Map<String, Value>, source: &Map<String, Value>) -> Result<()> {
     for (key, value) in source {
-        target.insert(key.clone(), value.clone());
+        match (target.get_mut(key), value) {
+            (Some(Value::Object(target_obj)), Value::Object(source_obj)) => {
+                merge_object(target_obj, source_obj)?;
+            }
+            _ => {
+                target.insert(key.clone(), value.clone());
+            }
+        }
     }
     Ok(())
 }
 
+/// Build only the inline payload fields that were actually provided, so
+/// layering onto a `--payload-file` base doesn't clobber untouched leaves
+/// with nulls from snippets/raw fields the caller never set.
+fn build_inline_overlay(
+    stdout: Option<TextSource>,
+    stderr: Option<TextSource>,
+    raw: JsonObjectBuilder,
+) -> Result<Map<String, Value>> {
+    let mut overlay = Map::new();
+    if let Some(source) = stdout {
+        overlay.insert(
+            "stdout_snippet".to_string(),
+            build_snippet_value(Some(source))?,
+        );
+    }
+    if let Some(source) = stderr {
+        overlay.insert(
+            "stderr_snippet".to_string(),
+            build_snippet_value(Some(source))?,
+        );
+    }
+    if !raw.is_empty() {
+        overlay.insert("raw".to_string(), raw.build("payload raw object")?);
+    }
+    Ok(overlay)
+}
+
+/// Build the snippet value for a payload slot.
+///
+/// Inline text and UTF-8 file contents are emitted as plain strings, matching
+/// today's behavior. Files that aren't valid UTF-8 (or that were forced with
+/// `--payload-*-binary`) are instead emitted as `{ "encoding": "base64", "data":
+/// "<b64>" }` so probes that capture binary output (hexdumps, truncated core
+/// images) round-trip the exact bytes instead of being lossily decoded.
 fn build_snippet_value(source: Option<TextSource>) -> Result<Value> {
     let Some(src) = source else {
         return Ok(Value::Null);
     };
-    let text = read_text_source(&src)?;
-    Ok(Value::String(truncate_snippet(&text)))
-}
 
-fn read_text_source(source: &TextSource) -> Result<String> {
-    let raw = match source {
-        TextSource::Inline(value) => value.clone(),
+    match src {
+        TextSource::Inline(text) => Ok(Value::String(truncate_snippet(&clean_text(&text)))),
         TextSource::File(path) => {
-            if !path.is_file() {
-                bail!("Snippet file not found: {}", path.display());
+            let bytes = read_snippet_file(&path)?;
+            match std::str::from_utf8(&bytes) {
+                Ok(text) => Ok(Value::String(truncate_snippet(&clean_text(text)))),
+                Err(_) => Ok(base64_snippet(&bytes)),
             }
-            String::from_utf8_lossy(&fs::read(path)?).into_owned()
         }
-    };
-    Ok(clean_text(&raw))
+        TextSource::BinaryFile(path) => {
+            let bytes = read_snippet_file(&path)?;
+            Ok(base64_snippet(&bytes))
+        }
+    }
+}
+
+fn read_snippet_file(path: &Path) -> Result<Vec<u8>> {
+    if !path.is_file() {
+        bail!("Snippet file not found: {}", path.display());
+    }
+    Ok(fs::read(path)?)
+}
+
+fn base64_snippet(bytes: &[u8]) -> Value {
+    json!({
+        "encoding": "base64",
+        "data": base64::engine::general_purpose::STANDARD.encode(bytes),
+    })
 }
 
 fn clean_text(raw: &str) -> String {
@@ -220,8 +309,8 @@ fn read_json_file(path: &Path) -> Result<Value> {
 
 pub fn validate_status(status: &str) -> Result<()> {
     match status {
-        "success" | "denied" | "partial" | "error" => Ok(()),
-        other => bail!("Unknown status: {other} (expected success|denied|partial|error)"),
+        "success" | "denied" | "partial" | "error" | "skipped" => Ok(()),
+        other => bail!("Unknown status: {other} (expected success|denied|partial|error|skipped)"),
     }
 }
 
@@ -233,8 +322,9 @@ pub fn validate_capability_id(
     if capabilities.capability(value).is_some() {
         return Ok(());
     }
+    let hint = did_you_mean(&value.0, capabilities.ids().map(|id| id.0.as_str()));
     bail!(
-        "Unknown {label}: {}. Expected one of the IDs in schema/capabilities.json.",
+        "Unknown {label}: {}. Expected one of the IDs in schema/capabilities.json.{hint}",
         value.0
     );
 }
@@ -259,3 +349,121 @@ pub fn normalize_secondary_ids(
 pub fn not_empty(value: &String) -> bool {
     !value.is_empty()
 }
+
+/// Classic row-buffer Levenshtein edit distance between two strings.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let (m, n) = (a.len(), b.len());
+
+    let mut prev: Vec<usize> = (0..=n).collect();
+    let mut curr = vec![0usize; n + 1];
+
+    for i in 1..=m {
+        curr[0] = i;
+        for j in 1..=n {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            curr[j] = (prev[j] + 1).min(curr[j - 1] + 1).min(prev[j - 1] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[n]
+}
+
+/// Find the closest candidate to `input` by edit distance, surfacing it only
+/// when it's plausibly a typo: within 3 edits, or within a third of the
+/// input's length for longer identifiers.
+pub fn suggest_closest<'a>(
+    input: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let mut best: Option<(&str, usize)> = None;
+    for candidate in candidates {
+        let distance = levenshtein(input, candidate);
+        let is_better = match best {
+            Some((_, best_distance)) => distance < best_distance,
+            None => true,
+        };
+        if is_better {
+            best = Some((candidate, distance));
+        }
+    }
+
+    let (candidate, distance) = best?;
+    let threshold = (input.chars().count() / 3).max(3);
+    (distance <= threshold).then_some(candidate)
+}
+
+/// Format a `Did you mean '<candidate>'?` suffix, or an empty string when no
+/// candidate is close enough to suggest.
+pub fn did_you_mean<'a>(input: &str, candidates: impl IntoIterator<Item = &'a str>) -> String {
+    match suggest_closest(input, candidates) {
+        Some(candidate) => format!(" Did you mean '{candidate}'?"),
+        None => String::new(),
+    }
+}
+
+/// Parse a dotenv-style `KEY=VALUE` defaults file.
+///
+/// Blank lines and lines starting with `#` are skipped. Values may be quoted
+/// with matching single or double quotes, which are stripped; unquoted values
+/// are trimmed of surrounding whitespace. Keys are kept as written (callers map
+/// them onto CLI flags, e.g. `RUN_MODE` -> `--run-mode`).
+pub fn parse_defaults_file(path: &Path) -> Result<std::collections::BTreeMap<String, String>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("reading defaults file {}", path.display()))?;
+    let mut values = std::collections::BTreeMap::new();
+    for (line_no, raw_line) in contents.lines().enumerate() {
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            bail!(
+                "{}:{}: expected KEY=VALUE, got {raw_line:?}",
+                path.display(),
+                line_no + 1
+            );
+        };
+        let key = key.trim().to_string();
+        if key.is_empty() {
+            bail!("{}:{}: empty key", path.display(), line_no + 1);
+        }
+        values.insert(key, unquote(value.trim()));
+    }
+    Ok(values)
+}
+
+fn unquote(value: &str) -> String {
+    let bytes = value.as_bytes();
+    if bytes.len() >= 2 {
+        let (first, last) = (bytes[0], bytes[bytes.len() - 1]);
+        if (first == b'"' && last == b'"') || (first == b'\'' && last == b'\'') {
+            return value[1..value.len() - 1].to_string();
+        }
+    }
+    value.to_string()
+}
+
+/// Recursively drop object entries whose value is `Value::Null`.
+///
+/// Empty strings and empty objects/arrays are left alone; only explicit
+/// `null`s are pruned. Used by `emit-record --omit-empty` so consumers don't
+/// see unset optional fields spelled out as JSON `null`.
+pub fn prune_null_fields(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|_, v| !v.is_null());
+            for v in map.values_mut() {
+                prune_null_fields(v);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                prune_null_fields(item);
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,268 @@
+//! Lowers abstract `CapabilitySnapshot`s into concrete OS enforcement
+//! primitives for the `OsSandbox` policy layer.
+//!
+//! `CapabilityCategory`/`CapabilityLayer` describe a capability abstractly;
+//! nothing upstream maps that to what a sandbox actually has to configure to
+//! realize it. This module is that mapping: on Linux, categories become
+//! Linux capability set entries (`CAP_NET_ADMIN`, `CAP_SYS_PTRACE`, ...) to
+//! retain, with everything else implicitly dropped; on macOS, they become
+//! `sandbox-exec` profile allow operations. Only the `OsSandbox` layer has a
+//! known lowering today — `AgentRuntime` and any `Other(_)` category/layer
+//! fail closed via [`LoweringError`] rather than silently granting more than
+//! intended.
+
+use crate::catalog::{CapabilityCategory, CapabilityLayer, CapabilitySnapshot};
+use std::fmt;
+
+/// Target platform to lower a [`CapabilitySnapshot`] for.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Platform {
+    Linux,
+    MacOs,
+}
+
+impl Platform {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Platform::Linux => "linux",
+            Platform::MacOs => "macos",
+        }
+    }
+}
+
+/// A named Linux capability, per `capabilities(7)`. A thin wrapper around
+/// `caps::Capability` (the same enum `grounding.rs` parses catalog verbs
+/// against) rather than a second name table, so there is one source of
+/// truth for what counts as a valid Linux capability name.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LinuxCapability(pub caps::Capability);
+
+impl LinuxCapability {
+    /// The `CAP_*` name as used in `capabilities(7)` and capset tooling.
+    /// `caps::Capability`'s variants are already named this way, so `Debug`
+    /// renders the exact string.
+    pub fn as_str(&self) -> String {
+        format!("{:?}", self.0)
+    }
+
+    /// The lowercase name without the `CAP_` prefix, as `setpriv`'s
+    /// `--inh-caps`/`--ambient-caps` lists expect it.
+    pub fn setpriv_name(&self) -> String {
+        self.as_str()
+            .trim_start_matches("CAP_")
+            .to_ascii_lowercase()
+    }
+}
+
+/// One macOS sandbox-profile operation to splice into a `sandbox-exec`
+/// profile, in its `(allow ...)`/`(deny ...)` syntax.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SandboxProfileOp {
+    Allow(String),
+    Deny(String),
+}
+
+/// The concrete OS primitives required to realize a [`CapabilitySnapshot`],
+/// produced by [`CapabilitySnapshot::lower`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct EnforcementPlan {
+    /// Linux capabilities to retain in the process's capability set; every
+    /// capability not listed here should be dropped.
+    pub retain: Vec<LinuxCapability>,
+    /// macOS sandbox-profile operations to splice into the process's
+    /// `sandbox-exec` profile.
+    pub sandbox_ops: Vec<SandboxProfileOp>,
+}
+
+/// A [`CapabilitySnapshot`] that has no known lowering for the requested
+/// platform (an unsupported layer, or an `Other(_)` category/layer the
+/// mapping doesn't recognize), so the caller should fail closed instead of
+/// granting more access than intended.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LoweringError {
+    pub id: String,
+    pub platform: Platform,
+    pub reason: String,
+}
+
+impl fmt::Display for LoweringError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "capability '{}' has no known {} lowering: {}",
+            self.id,
+            self.platform.as_str(),
+            self.reason
+        )
+    }
+}
+
+impl std::error::Error for LoweringError {}
+
+impl CapabilitySnapshot {
+    /// Lower this snapshot into the OS primitives that realize it on
+    /// `platform`.
+    ///
+    /// Only the `OsSandbox` layer is lowered; `AgentRuntime` has no OS-level
+    /// primitive, and any `Other(_)` layer or category is unrecognized by
+    /// construction. Both fail with [`LoweringError`] rather than returning
+    /// an empty (and therefore falsely permissive) plan.
+    pub fn lower(&self, platform: Platform) -> Result<EnforcementPlan, LoweringError> {
+        if self.layer != CapabilityLayer::OsSandbox {
+            return Err(LoweringError {
+                id: self.id.0.clone(),
+                platform,
+                reason: format!(
+                    "layer '{}' has no OS-level enforcement primitive",
+                    self.layer.as_str()
+                ),
+            });
+        }
+
+        match platform {
+            Platform::Linux => self.lower_linux(platform),
+            Platform::MacOs => self.lower_macos(platform),
+        }
+    }
+
+    fn lower_linux(&self, platform: Platform) -> Result<EnforcementPlan, LoweringError> {
+        let retain = match &self.category {
+            CapabilityCategory::Process => vec![
+                LinuxCapability(caps::Capability::CAP_SYS_PTRACE),
+                LinuxCapability(caps::Capability::CAP_KILL),
+            ],
+            CapabilityCategory::Ipc => vec![
+                LinuxCapability(caps::Capability::CAP_IPC_OWNER),
+                LinuxCapability(caps::Capability::CAP_IPC_LOCK),
+            ],
+            CapabilityCategory::Network => vec![
+                LinuxCapability(caps::Capability::CAP_NET_ADMIN),
+                LinuxCapability(caps::Capability::CAP_NET_RAW),
+            ],
+            other => {
+                return Err(LoweringError {
+                    id: self.id.0.clone(),
+                    platform,
+                    reason: format!(
+                        "category '{}' has no known Linux capability mapping",
+                        other.as_str()
+                    ),
+                });
+            }
+        };
+
+        Ok(EnforcementPlan {
+            retain,
+            sandbox_ops: Vec::new(),
+        })
+    }
+
+    fn lower_macos(&self, platform: Platform) -> Result<EnforcementPlan, LoweringError> {
+        let sandbox_ops = match &self.category {
+            CapabilityCategory::Filesystem => vec![
+                SandboxProfileOp::Allow("file-read*".to_string()),
+                SandboxProfileOp::Allow("file-write*".to_string()),
+            ],
+            CapabilityCategory::Network => vec![
+                SandboxProfileOp::Allow("network-outbound".to_string()),
+                SandboxProfileOp::Allow("network-inbound".to_string()),
+            ],
+            CapabilityCategory::SandboxProfile => {
+                vec![SandboxProfileOp::Deny("default".to_string())]
+            }
+            other => {
+                return Err(LoweringError {
+                    id: self.id.0.clone(),
+                    platform,
+                    reason: format!(
+                        "category '{}' has no known macOS sandbox-profile mapping",
+                        other.as_str()
+                    ),
+                });
+            }
+        };
+
+        Ok(EnforcementPlan {
+            retain: Vec::new(),
+            sandbox_ops,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::CapabilityId;
+
+    fn snapshot(category: CapabilityCategory, layer: CapabilityLayer) -> CapabilitySnapshot {
+        CapabilitySnapshot {
+            id: CapabilityId("cap_test".to_string()),
+            category,
+            layer,
+        }
+    }
+
+    #[test]
+    fn lowers_network_category_to_linux_capabilities() {
+        let plan = snapshot(CapabilityCategory::Network, CapabilityLayer::OsSandbox)
+            .lower(Platform::Linux)
+            .expect("network should lower on linux");
+        assert_eq!(
+            plan.retain,
+            vec![
+                LinuxCapability(caps::Capability::CAP_NET_ADMIN),
+                LinuxCapability(caps::Capability::CAP_NET_RAW),
+            ]
+        );
+        assert!(plan.sandbox_ops.is_empty());
+    }
+
+    #[test]
+    fn linux_capability_names_match_setpriv_syntax() {
+        let cap = LinuxCapability(caps::Capability::CAP_NET_ADMIN);
+        assert_eq!(cap.as_str(), "CAP_NET_ADMIN");
+        assert_eq!(cap.setpriv_name(), "net_admin");
+    }
+
+    #[test]
+    fn lowers_filesystem_category_to_macos_sandbox_ops() {
+        let plan = snapshot(CapabilityCategory::Filesystem, CapabilityLayer::OsSandbox)
+            .lower(Platform::MacOs)
+            .expect("filesystem should lower on macos");
+        assert_eq!(
+            plan.sandbox_ops,
+            vec![
+                SandboxProfileOp::Allow("file-read*".to_string()),
+                SandboxProfileOp::Allow("file-write*".to_string()),
+            ]
+        );
+        assert!(plan.retain.is_empty());
+    }
+
+    #[test]
+    fn rejects_agent_runtime_layer() {
+        let err = snapshot(CapabilityCategory::Network, CapabilityLayer::AgentRuntime)
+            .lower(Platform::Linux)
+            .expect_err("agent_runtime layer has no OS primitive");
+        assert!(err.to_string().contains("agent_runtime"));
+    }
+
+    #[test]
+    fn rejects_unknown_category_on_linux() {
+        let err = snapshot(
+            CapabilityCategory::Other("exotic".to_string()),
+            CapabilityLayer::OsSandbox,
+        )
+        .lower(Platform::Linux)
+        .expect_err("unknown category has no known mapping");
+        assert!(err.to_string().contains("exotic"));
+    }
+
+    #[test]
+    fn rejects_unknown_category_on_macos() {
+        let err = snapshot(CapabilityCategory::Process, CapabilityLayer::OsSandbox)
+            .lower(Platform::MacOs)
+            .expect_err("process has no known macOS sandbox-profile mapping");
+        assert!(err.to_string().contains("process"));
+    }
+}
@@ -0,0 +1,271 @@
+//! Pluggable execution backends for running a resolved probe command.
+//!
+//! `probe-exec` always resolves and containment-checks the probe path first
+//! (see [`crate::resolve_probe`]); everything in this module runs strictly
+//! after that guard, and only decides *how* the already-resolved command is
+//! launched. The `direct` backend preserves today's behavior (a plain child
+//! process); `namespace` and `oci` add host isolation on top of the same
+//! [`BackendRequest`] so the workspace-root/tmpdir layout and environment
+//! stay identical across backends.
+
+use crate::connectors::{CommandSpec, container_image, detect_container_runtime};
+use crate::enforcement::EnforcementPlan;
+use anyhow::{Result, anyhow, bail};
+use std::ffi::OsString;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackendKind {
+    /// Run the probe as a direct child process (current behavior).
+    Direct,
+    /// Run the probe inside a fresh mount/UTS/IPC/PID namespace via `unshare`.
+    Namespace,
+    /// Run the probe inside a Docker/Podman container.
+    Oci,
+}
+
+impl BackendKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BackendKind::Direct => "direct",
+            BackendKind::Namespace => "namespace",
+            BackendKind::Oci => "oci",
+        }
+    }
+}
+
+impl TryFrom<&str> for BackendKind {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        match value {
+            "direct" => Ok(BackendKind::Direct),
+            "namespace" => Ok(BackendKind::Namespace),
+            "oci" => Ok(BackendKind::Oci),
+            other => bail!("Unknown backend: {other}"),
+        }
+    }
+}
+
+pub const DEFAULT_BACKEND: BackendKind = BackendKind::Direct;
+
+pub fn allowed_backend_names() -> Vec<&'static str> {
+    [
+        BackendKind::Direct,
+        BackendKind::Namespace,
+        BackendKind::Oci,
+    ]
+    .iter()
+    .map(BackendKind::as_str)
+    .collect()
+}
+
+/// Everything an [`ExecutionBackend`] needs to launch the resolved probe
+/// command. Fields mirror what `probe-exec` already assembles for the
+/// `direct` path, so adding a backend never requires widening this struct's
+/// callers.
+pub struct BackendRequest<'a> {
+    pub command: &'a CommandSpec,
+    pub command_cwd: &'a Path,
+    /// Environment variables to export into the child (`FENCE_RUN_MODE`,
+    /// `CATALOG_PATH`, etc.), in the order they should be applied.
+    pub env: &'a [(OsString, OsString)],
+    /// The workspace root the probe is scoped to, when one is exported.
+    /// `namespace` bind-mounts this path read-only and `oci` read-write;
+    /// `direct` ignores it since the child already inherits the host
+    /// filesystem.
+    pub workspace_root: Option<&'a Path>,
+    /// The Linux capability set the probe's primary capability lowers to
+    /// (see [`crate::catalog::CapabilitySnapshot::lower`]), when known. Only
+    /// `namespace` enforces it today; `direct` and `oci` ignore it.
+    pub enforcement_plan: Option<&'a EnforcementPlan>,
+}
+
+/// Launches an already-resolved, already-containment-checked probe command.
+pub trait ExecutionBackend {
+    fn run(&self, request: &BackendRequest) -> Result<Output>;
+}
+
+/// Runs the probe as a plain child process. The only backend guaranteed to
+/// work without extra host tooling, and the default.
+pub struct DirectBackend;
+
+impl ExecutionBackend for DirectBackend {
+    fn run(&self, request: &BackendRequest) -> Result<Output> {
+        let mut command = command_with_env(request);
+        command.output().map_err(|err| {
+            anyhow!(
+                "Failed to execute {}: {err}",
+                request.command.program.to_string_lossy()
+            )
+        })
+    }
+}
+
+/// Runs the probe inside a fresh mount/UTS/IPC/PID namespace via the
+/// `unshare` helper from util-linux, bind-mounting the workspace root
+/// read-only inside that namespace so host isolation holds even though the
+/// probe process still runs directly on this machine's kernel. When
+/// `request.enforcement_plan` is set, also drops every Linux capability
+/// except the ones the probe's primary capability lowers to (via `setpriv`)
+/// before handing off to the probe's own argv.
+pub struct NamespaceBackend;
+
+impl ExecutionBackend for NamespaceBackend {
+    fn run(&self, request: &BackendRequest) -> Result<Output> {
+        if !binary_on_path("unshare") {
+            bail!("namespace backend requires `unshare` (util-linux) on PATH");
+        }
+        if request.enforcement_plan.is_some() && !binary_on_path("setpriv") {
+            bail!(
+                "namespace backend requires `setpriv` (util-linux) on PATH to enforce capabilities"
+            );
+        }
+
+        let mut command = Command::new("unshare");
+        command
+            .arg("--mount")
+            .arg("--uts")
+            .arg("--ipc")
+            .arg("--pid")
+            .arg("--fork")
+            .arg("--");
+        command
+            .arg("sh")
+            .arg("-c")
+            .arg(namespace_shell_script(request));
+        command.arg("sh");
+        append_probe_argv(&mut command, request.command);
+        apply_env_and_cwd(&mut command, request);
+        command.stdout(Stdio::piped());
+        command.stderr(Stdio::piped());
+        command
+            .output()
+            .map_err(|err| anyhow!("Failed to execute unshare: {err}"))
+    }
+}
+
+/// Bind-mount the workspace root onto itself read-only (safe only because
+/// `unshare --mount` already gave this process its own mount namespace, so
+/// the remount doesn't escape to the host), drop every Linux capability not
+/// named in `request.enforcement_plan` via `setpriv` (see
+/// [`capability_drop_exec`]), then hand off to the probe's own argv.
+/// Skipped when no workspace root was exported, matching `direct`'s no-op in
+/// that case.
+fn namespace_shell_script(request: &BackendRequest) -> String {
+    let exec = capability_drop_exec(request.enforcement_plan);
+    match request.workspace_root {
+        Some(root) => {
+            let root = root.to_string_lossy();
+            format!("mount --bind '{root}' '{root}' && mount -o remount,bind,ro '{root}' && {exec}")
+        }
+        None => exec,
+    }
+}
+
+/// The final `exec` clause of the namespace shell script: plain `exec "$@"`
+/// when no enforcement plan was supplied, or a `setpriv` invocation dropping
+/// every Linux capability except `plan.retain` otherwise. An empty
+/// `plan.retain` (a category with no known Linux lowering, or a layer with
+/// none at all) drops every capability, matching [`crate::enforcement`]'s
+/// fail-closed stance.
+fn capability_drop_exec(plan: Option<&EnforcementPlan>) -> String {
+    let Some(plan) = plan else {
+        return "exec \"$@\"".to_string();
+    };
+
+    let caps_list = plan
+        .retain
+        .iter()
+        .map(|cap| format!(",+{}", cap.setpriv_name()))
+        .collect::<String>();
+    format!("exec setpriv --inh-caps=-all{caps_list} --ambient-caps=-all{caps_list} -- \"$@\"")
+}
+
+fn append_probe_argv(command: &mut Command, spec: &CommandSpec) {
+    command.arg(&spec.program);
+    for arg in &spec.args {
+        command.arg(arg);
+    }
+}
+
+/// Runs the probe inside a Docker/Podman container, bind-mounting the
+/// workspace root read-write (probes need to write workspace-scoped fixtures
+/// the same way they do under `direct`/`namespace`) and forwarding the same
+/// environment those backends export, plus `FENCE_ROOT`/`FENCE_WORKSPACE_ROOT`
+/// (mirroring how `probe_cli` injects `FENCE_ROOT` for the helpers it spawns)
+/// and the resolved image identity, so differential runs against `direct` can
+/// attribute divergent `result.observed_result`/`errno` fields to the
+/// container that produced them via [`crate::boundary::StackInfo`].
+pub struct OciBackend;
+
+impl ExecutionBackend for OciBackend {
+    fn run(&self, request: &BackendRequest) -> Result<Output> {
+        let runtime = detect_container_runtime().ok_or_else(|| {
+            anyhow!("oci backend requires a usable docker or podman runtime on PATH")
+        })?;
+        let image = container_image();
+
+        let mut command = Command::new(runtime.binary_name());
+        command.arg("run").arg("--rm");
+        if let Some(root) = request.workspace_root {
+            let mount = format!("{0}:{0}:rw", root.display());
+            command.arg("-v").arg(mount);
+            command
+                .arg("-e")
+                .arg(format!("FENCE_ROOT={}", root.display()));
+            command
+                .arg("-e")
+                .arg(format!("FENCE_WORKSPACE_ROOT={}", root.display()));
+        }
+        for (key, _) in request.env {
+            command.arg("-e").arg(key);
+        }
+        command
+            .arg("-e")
+            .arg(format!("FENCE_CONTAINER_IMAGE={image}"));
+        command.arg(&image);
+        append_probe_argv(&mut command, request.command);
+        apply_env_and_cwd(&mut command, request);
+        command.stdout(Stdio::piped());
+        command.stderr(Stdio::piped());
+        command
+            .output()
+            .map_err(|err| anyhow!("Failed to execute {}: {err}", runtime.binary_name()))
+    }
+}
+
+fn command_with_env(request: &BackendRequest) -> Command {
+    let mut command = Command::new(&request.command.program);
+    for arg in &request.command.args {
+        command.arg(arg);
+    }
+    apply_env_and_cwd(&mut command, request);
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+    command
+}
+
+fn apply_env_and_cwd(command: &mut Command, request: &BackendRequest) {
+    command.current_dir(request.command_cwd);
+    for (key, value) in request.env {
+        command.env(key, value);
+    }
+}
+
+fn binary_on_path(name: &str) -> bool {
+    let Some(path_var) = std::env::var_os("PATH") else {
+        return false;
+    };
+    std::env::split_paths(&path_var).any(|dir| dir.join(name).is_file())
+}
+
+/// Construct the backend selected by `kind`.
+pub fn backend_for(kind: BackendKind) -> Box<dyn ExecutionBackend> {
+    match kind {
+        BackendKind::Direct => Box::new(DirectBackend),
+        BackendKind::Namespace => Box::new(NamespaceBackend),
+        BackendKind::Oci => Box::new(OciBackend),
+    }
+}
@@ -0,0 +1,732 @@
+//! Opt-in execution tracing for a single probe run.
+//!
+//! Enabled via `--trace` / `FENCE_TRACE=1`, this wraps `probe-exec`'s command
+//! execution to capture an [`ExecutionTrace`]: the resolved program/argv, the
+//! exported environment keys, start/end timestamps, the exit status, and —
+//! on Linux/x86_64 via `ptrace(2)` — the files opened, sockets connected to,
+//! paths unlinked, and child processes spawned along the way. Tracing never
+//! changes what a probe observes (no syscalls are blocked, only inspected),
+//! and a host that can't support ptrace falls back to process-level metadata
+//! rather than failing the run; see [`capture_direct`] and
+//! [`wrap_without_ptrace`].
+//!
+//! Ops are kept in one flat, arena-style `Vec` rather than a nested tree so
+//! serialization stays cheap and stable regardless of fork depth; each op is
+//! self-describing (tagged by `kind`), mirroring [`crate::provenance::ProvenanceNode`]'s
+//! shape for the same reason. `Open`/`Connect`/`Unlink` carry the syscall's
+//! raw return value rather than a pre-classified verdict — [`classify_result`]
+//! maps it to the same success/denied/error vocabulary [`crate::boundary`]
+//! uses elsewhere, but callers that want the raw errno still have it.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::ffi::OsString;
+use std::process::Output;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// One observed operation during a traced run, referenced by its position in
+/// [`ExecutionTrace::ops`] rather than nested inline. `path`/`addr` fields on
+/// `Open`/`Connect`/`Unlink` are resolved against the tracee's tracked `cwd`
+/// at the time of the call (updated on `chdir`), so they're always usable as
+/// a standalone target without a caller needing separate cwd bookkeeping.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TraceOp {
+    Exec {
+        path: String,
+        argv: Vec<String>,
+    },
+    Open {
+        path: String,
+        flags: i32,
+        result: i64,
+    },
+    Connect {
+        addr: String,
+        result: i64,
+    },
+    Unlink {
+        path: String,
+        result: i64,
+    },
+    Spawn {
+        pid: i32,
+        ppid: i32,
+    },
+}
+
+impl TraceOp {
+    /// Short verb naming this op, for callers that feed it into something
+    /// like `emit-record`'s `--verb` flag.
+    pub fn verb(&self) -> &'static str {
+        match self {
+            TraceOp::Exec { .. } => "exec",
+            TraceOp::Open { .. } => "open",
+            TraceOp::Connect { .. } => "connect",
+            TraceOp::Unlink { .. } => "unlink",
+            TraceOp::Spawn { .. } => "spawn",
+        }
+    }
+
+    /// The op's primary subject (path, address, or pid), for a generic
+    /// `--target` value.
+    pub fn target(&self) -> String {
+        match self {
+            TraceOp::Exec { path, .. } => path.clone(),
+            TraceOp::Open { path, .. } => path.clone(),
+            TraceOp::Connect { addr, .. } => addr.clone(),
+            TraceOp::Unlink { path, .. } => path.clone(),
+            TraceOp::Spawn { pid, .. } => pid.to_string(),
+        }
+    }
+
+    /// The raw syscall return value this op observed, if any. `Exec`/`Spawn`
+    /// don't carry one: `exec` either replaces the image (no return to see)
+    /// or the traced process would have vanished, and `Spawn` is synthesized
+    /// from a fork-family ptrace event rather than a syscall return.
+    pub fn result(&self) -> Option<i64> {
+        match self {
+            TraceOp::Open { result, .. }
+            | TraceOp::Connect { result, .. }
+            | TraceOp::Unlink { result, .. } => Some(*result),
+            TraceOp::Exec { .. } | TraceOp::Spawn { .. } => None,
+        }
+    }
+}
+
+/// Classify a syscall's raw return value the same way [`crate::boundary`]'s
+/// success/denied/error vocabulary works elsewhere: a non-negative return is
+/// `success`,
+/// `EPERM`/`EACCES` are `denied` (the sandbox said no), anything else
+/// negative is `error` (something else went wrong).
+pub fn classify_result(result: i64) -> (&'static str, Option<String>) {
+    if result >= 0 {
+        return ("success", None);
+    }
+    match -result {
+        1 => ("denied", Some("EPERM".to_string())),
+        13 => ("denied", Some("EACCES".to_string())),
+        other => ("error", Some(format!("errno {other}"))),
+    }
+}
+
+/// Parse one op per NDJSON line, tolerating a truncated trailing line (e.g.
+/// the probe was killed mid-write to the trace sink): a line that fails to
+/// parse is dropped rather than aborting the whole read.
+pub fn parse_trace_ops_ndjson(raw: &str) -> Vec<TraceOp> {
+    raw.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.is_empty() {
+                return None;
+            }
+            serde_json::from_str::<TraceOp>(line).ok()
+        })
+        .collect()
+}
+
+/// A structured record of one probe's execution, written as an extra NDJSON
+/// line alongside its boundary object.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutionTrace {
+    pub probe_id: String,
+    pub mode: String,
+    pub program: String,
+    pub argv: Vec<String>,
+    pub env_keys: Vec<String>,
+    pub started_at: f64,
+    pub ended_at: f64,
+    pub exit_code: Option<i32>,
+    /// Whether `ops` beyond the initial `Exec` entry were actually observed
+    /// via ptrace, versus this being the process-level-only fallback.
+    pub ptrace_available: bool,
+    pub ops: Vec<TraceOp>,
+}
+
+/// Whether tracing was requested for this invocation: the CLI flag wins,
+/// otherwise `FENCE_TRACE=1`.
+pub fn trace_requested(cli_flag: bool) -> bool {
+    cli_flag || env::var("FENCE_TRACE").as_deref() == Ok("1")
+}
+
+fn now_seconds() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0)
+}
+
+fn env_keys(env: &[(OsString, OsString)]) -> Vec<String> {
+    env.iter()
+        .map(|(key, _)| key.to_string_lossy().into_owned())
+        .collect()
+}
+
+fn argv_strings(argv: &[OsString]) -> Vec<String> {
+    argv.iter()
+        .map(|a| a.to_string_lossy().into_owned())
+        .collect()
+}
+
+/// Run `program argv` under the platform's best available tracer, returning
+/// both its [`Output`] and the resulting [`ExecutionTrace`]. Used for the
+/// `direct` execution backend, the only one this module can attach ptrace to
+/// directly; other backends should use [`wrap_without_ptrace`] instead.
+pub fn capture_direct(
+    program: &std::ffi::OsStr,
+    argv: &[OsString],
+    env: &[(OsString, OsString)],
+    cwd: &std::path::Path,
+    probe_id: &str,
+    mode: &str,
+) -> Result<(Output, ExecutionTrace)> {
+    imp::capture(program, argv, env, cwd, probe_id, mode)
+}
+
+/// Wrap an existing `run` closure (e.g. a non-`direct` [`crate::execution_backend::ExecutionBackend`])
+/// with process-level trace metadata only: no ptrace, just start/end
+/// timestamps, the resolved program/argv/env keys, and the exit code. This is
+/// also what [`capture_direct`] falls back to on a host without ptrace.
+pub fn wrap_without_ptrace<F>(
+    program: &std::ffi::OsStr,
+    argv: &[OsString],
+    env: &[(OsString, OsString)],
+    probe_id: &str,
+    mode: &str,
+    run: F,
+) -> Result<(Output, ExecutionTrace)>
+where
+    F: FnOnce() -> Result<Output>,
+{
+    let started_at = now_seconds();
+    let output = run()?;
+    let ended_at = now_seconds();
+
+    let trace = ExecutionTrace {
+        probe_id: probe_id.to_string(),
+        mode: mode.to_string(),
+        program: program.to_string_lossy().into_owned(),
+        argv: argv_strings(argv),
+        env_keys: env_keys(env),
+        started_at,
+        ended_at,
+        exit_code: output.status.code(),
+        ptrace_available: false,
+        ops: vec![TraceOp::Exec {
+            path: program.to_string_lossy().into_owned(),
+            argv: argv_strings(argv),
+        }],
+    };
+    Ok((output, trace))
+}
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+mod imp {
+    use super::{
+        ExecutionTrace, TraceOp, argv_strings, env_keys, now_seconds, wrap_without_ptrace,
+    };
+    use anyhow::{Context, Result};
+    use std::collections::{HashMap, HashSet};
+    use std::ffi::{OsStr, OsString};
+    use std::io::Read;
+    use std::os::unix::process::{CommandExt, ExitStatusExt};
+    use std::path::Path;
+    use std::process::{Command, ExitStatus, Output, Stdio};
+    use std::thread;
+
+    const PEEK_WORD_BYTES: usize = 8;
+    const MAX_PATH_BYTES: usize = 4096;
+
+    pub fn capture(
+        program: &OsStr,
+        argv: &[OsString],
+        env: &[(OsString, OsString)],
+        cwd: &Path,
+        probe_id: &str,
+        mode: &str,
+    ) -> Result<(Output, ExecutionTrace)> {
+        let started_at = now_seconds();
+        match run_traced(program, argv, env, cwd) {
+            Ok((output, ops)) => Ok((
+                output,
+                ExecutionTrace {
+                    probe_id: probe_id.to_string(),
+                    mode: mode.to_string(),
+                    program: program.to_string_lossy().into_owned(),
+                    argv: argv_strings(argv),
+                    env_keys: env_keys(env),
+                    started_at,
+                    ended_at: now_seconds(),
+                    exit_code: None, // filled in below
+                    ptrace_available: true,
+                    ops,
+                },
+            )),
+            Err(err) => {
+                eprintln!(
+                    "execution_trace: ptrace unavailable, falling back to metadata only: {err:#}"
+                );
+                wrap_without_ptrace(program, argv, env, probe_id, mode, || {
+                    run_untraced(program, argv, env, cwd)
+                })
+            }
+        }
+        .map(|(output, mut trace)| {
+            trace.exit_code = output.status.code();
+            trace.ended_at = now_seconds();
+            (output, trace)
+        })
+    }
+
+    fn run_untraced(
+        program: &OsStr,
+        argv: &[OsString],
+        env: &[(OsString, OsString)],
+        cwd: &Path,
+    ) -> Result<Output> {
+        let mut command = Command::new(program);
+        command.args(argv);
+        command.current_dir(cwd);
+        for (key, value) in env {
+            command.env(key, value);
+        }
+        command.stdout(Stdio::piped());
+        command.stderr(Stdio::piped());
+        command
+            .output()
+            .with_context(|| format!("Failed to execute {}", program.to_string_lossy()))
+    }
+
+    /// Run `program argv` under `ptrace(2)`, stepping through syscalls to
+    /// observe `open`/`openat`/`connect`/`unlink`/`unlinkat`/`chdir` and
+    /// fork-family events; `execve` calls after the initial exec are not
+    /// recorded. Returns the
+    /// collected [`TraceOp`]s alongside the process's captured [`Output`].
+    fn run_traced(
+        program: &OsStr,
+        argv: &[OsString],
+        env: &[(OsString, OsString)],
+        cwd: &Path,
+    ) -> Result<(Output, Vec<TraceOp>)> {
+        let mut command = Command::new(program);
+        command.args(argv);
+        command.current_dir(cwd);
+        for (key, value) in env {
+            command.env(key, value);
+        }
+        command.stdout(Stdio::piped());
+        command.stderr(Stdio::piped());
+        unsafe {
+            command.pre_exec(|| {
+                if libc::ptrace(
+                    libc::PTRACE_TRACEME,
+                    0,
+                    0 as *mut libc::c_void,
+                    0 as *mut libc::c_void,
+                ) != 0
+                {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+
+        let mut child = command
+            .spawn()
+            .with_context(|| format!("Failed to execute {}", program.to_string_lossy()))?;
+        let root_pid = child.id() as libc::pid_t;
+
+        let mut stdout_pipe = child.stdout.take().context("probe stdout not piped")?;
+        let mut stderr_pipe = child.stderr.take().context("probe stderr not piped")?;
+        let stdout_reader = thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = stdout_pipe.read_to_end(&mut buf);
+            buf
+        });
+        let stderr_reader = thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = stderr_pipe.read_to_end(&mut buf);
+            buf
+        });
+
+        let mut ops = vec![TraceOp::Exec {
+            path: program.to_string_lossy().into_owned(),
+            argv: argv_strings(argv),
+        }];
+        let initial_cwd = cwd.to_string_lossy().into_owned();
+        let status = trace_loop(root_pid, &mut ops, &initial_cwd)?;
+
+        let stdout = stdout_reader.join().unwrap_or_default();
+        let stderr = stderr_reader.join().unwrap_or_default();
+        Ok((
+            Output {
+                status,
+                stdout,
+                stderr,
+            },
+            ops,
+        ))
+    }
+
+    /// A syscall observed at its entry stop, held until the matching exit
+    /// stop supplies the return value. `Chdir` never becomes a [`TraceOp`]:
+    /// it only updates `cwds` so later `Open`/`Connect`/`Unlink` ops resolve
+    /// relative paths correctly.
+    enum PendingOp {
+        Open { path: String, flags: i32 },
+        Connect { addr: String },
+        Unlink { path: String },
+        Chdir { path: String },
+    }
+
+    /// Step the traced process (and any descendants it forks) through
+    /// syscall-entry/exit stops until the root pid exits, recording
+    /// `open`/`openat`/`connect`/`unlink`-family calls as [`TraceOp`]s and
+    /// fork events as [`TraceOp::Spawn`]. Best-effort: a read that fails
+    /// (e.g. a race with process exit) just drops that one observation
+    /// rather than aborting the trace.
+    fn trace_loop(
+        root_pid: libc::pid_t,
+        ops: &mut Vec<TraceOp>,
+        initial_cwd: &str,
+    ) -> Result<ExitStatus> {
+        let mut status: libc::c_int = 0;
+        // Initial stop: the TRACEME'd process delivers SIGTRAP to itself on
+        // its first successful execve, before any of our own code runs here.
+        wait_for(root_pid, &mut status)?;
+
+        let options = libc::PTRACE_O_TRACEEXEC
+            | libc::PTRACE_O_TRACEFORK
+            | libc::PTRACE_O_TRACEVFORK
+            | libc::PTRACE_O_TRACECLONE
+            | libc::PTRACE_O_TRACESYSGOOD;
+        unsafe {
+            libc::ptrace(
+                libc::PTRACE_SETOPTIONS,
+                root_pid,
+                0,
+                options as *mut libc::c_void,
+            );
+        }
+
+        let mut live: HashSet<libc::pid_t> = HashSet::new();
+        live.insert(root_pid);
+        let mut in_syscall: HashSet<libc::pid_t> = HashSet::new();
+        let mut pending: HashMap<libc::pid_t, PendingOp> = HashMap::new();
+        let mut cwds: HashMap<libc::pid_t, String> = HashMap::new();
+        cwds.insert(root_pid, initial_cwd.to_string());
+        let mut seen_opens: HashSet<(libc::pid_t, String)> = HashSet::new();
+
+        resume(root_pid)?;
+
+        loop {
+            let mut wait_status: libc::c_int = 0;
+            let pid = unsafe { libc::waitpid(-1, &mut wait_status, 0) };
+            if pid <= 0 {
+                break;
+            }
+
+            if libc::WIFEXITED(wait_status) || libc::WIFSIGNALED(wait_status) {
+                live.remove(&pid);
+                if pid == root_pid {
+                    return Ok(ExitStatus::from_raw(wait_status));
+                }
+                continue;
+            }
+
+            if !libc::WIFSTOPPED(wait_status) {
+                continue;
+            }
+
+            let event = (wait_status >> 8) & 0xff;
+            let is_fork_event = matches!(
+                event,
+                e if e == libc::PTRACE_EVENT_FORK
+                    || e == libc::PTRACE_EVENT_VFORK
+                    || e == libc::PTRACE_EVENT_CLONE
+            );
+            if is_fork_event {
+                let mut new_pid: libc::c_ulong = 0;
+                unsafe {
+                    libc::ptrace(
+                        libc::PTRACE_GETEVENTMSG,
+                        pid,
+                        0,
+                        &mut new_pid as *mut libc::c_ulong as *mut libc::c_void,
+                    );
+                }
+                ops.push(TraceOp::Spawn {
+                    pid: new_pid as i32,
+                    ppid: pid,
+                });
+                live.insert(new_pid as libc::pid_t);
+                // A forked child inherits its parent's cwd at fork time.
+                let parent_cwd = cwds
+                    .get(&pid)
+                    .cloned()
+                    .unwrap_or_else(|| initial_cwd.to_string());
+                cwds.insert(new_pid as libc::pid_t, parent_cwd);
+            } else if libc::WSTOPSIG(wait_status) == (libc::SIGTRAP | 0x80) {
+                // Syscall-stop (PTRACE_O_TRACESYSGOOD tags these distinctly
+                // from a plain SIGTRAP). The entry half resolves arguments
+                // (paths/addresses) and stashes them in `pending`; the exit
+                // half reads the return value and turns the pair into a
+                // `TraceOp` (or a `cwds` update, for `chdir`).
+                let entering = in_syscall.insert(pid);
+                if entering {
+                    if let Ok(Some(op)) = inspect_syscall_entry(pid) {
+                        pending.insert(pid, op);
+                    }
+                } else {
+                    in_syscall.remove(&pid);
+                    if let Some(op) = pending.remove(&pid) {
+                        let result = get_regs(pid).map(|regs| regs.rax as i64).unwrap_or(-1);
+                        match op {
+                            PendingOp::Chdir { path } => {
+                                if result >= 0 {
+                                    let cwd = cwds.get(&pid).map(String::as_str);
+                                    cwds.insert(pid, resolve_relative(cwd, &path));
+                                }
+                            }
+                            PendingOp::Open { path, flags } => {
+                                let cwd = cwds.get(&pid).map(String::as_str);
+                                let resolved = resolve_relative(cwd, &path);
+                                if result < 0 || seen_opens.insert((pid, resolved.clone())) {
+                                    ops.push(TraceOp::Open {
+                                        path: resolved,
+                                        flags,
+                                        result,
+                                    });
+                                }
+                            }
+                            PendingOp::Connect { addr } => {
+                                ops.push(TraceOp::Connect { addr, result });
+                            }
+                            PendingOp::Unlink { path } => {
+                                let cwd = cwds.get(&pid).map(String::as_str);
+                                let resolved = resolve_relative(cwd, &path);
+                                ops.push(TraceOp::Unlink {
+                                    path: resolved,
+                                    result,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+
+            let _ = resume(pid);
+        }
+
+        // The root process vanished without a clean WIFEXITED/WIFSIGNALED
+        // observation (e.g. waitpid(-1) drained unrelated state first).
+        Ok(ExitStatus::from_raw(0))
+    }
+
+    /// Resolve `path` against `cwd` when it isn't already absolute, mirroring
+    /// how the kernel resolves a relative path against a process's cwd.
+    fn resolve_relative(cwd: Option<&str>, path: &str) -> String {
+        if path.starts_with('/') {
+            path.to_string()
+        } else if let Some(cwd) = cwd {
+            format!("{}/{}", cwd.trim_end_matches('/'), path)
+        } else {
+            path.to_string()
+        }
+    }
+
+    fn wait_for(pid: libc::pid_t, status: &mut libc::c_int) -> Result<()> {
+        loop {
+            let rc = unsafe { libc::waitpid(pid, status, 0) };
+            if rc == pid {
+                return Ok(());
+            }
+            if rc < 0 && std::io::Error::last_os_error().kind() != std::io::ErrorKind::Interrupted {
+                anyhow::bail!("waitpid failed: {}", std::io::Error::last_os_error());
+            }
+        }
+    }
+
+    fn resume(pid: libc::pid_t) -> Result<()> {
+        let rc = unsafe { libc::ptrace(libc::PTRACE_SYSCALL, pid, 0, 0) };
+        if rc != 0 {
+            anyhow::bail!("PTRACE_SYSCALL failed: {}", std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// Read this tracee's registers at a syscall-entry stop and, for a
+    /// syscall we care about, resolve its arguments (path, socket address)
+    /// out of its memory into a [`PendingOp`] awaiting the matching exit stop.
+    fn inspect_syscall_entry(pid: libc::pid_t) -> Result<Option<PendingOp>> {
+        let regs = get_regs(pid)?;
+        match regs.orig_rax as i64 {
+            n if n == libc::SYS_open => {
+                let path =
+                    read_cstring(pid, regs.rdi).unwrap_or_else(|_| String::from("<unreadable>"));
+                Ok(Some(PendingOp::Open {
+                    path,
+                    flags: regs.rsi as i32,
+                }))
+            }
+            n if n == libc::SYS_openat => {
+                let path =
+                    read_cstring(pid, regs.rsi).unwrap_or_else(|_| String::from("<unreadable>"));
+                Ok(Some(PendingOp::Open {
+                    path,
+                    flags: regs.rdx as i32,
+                }))
+            }
+            n if n == libc::SYS_connect => {
+                let len = (regs.rdx as usize).min(128);
+                let bytes = read_bytes(pid, regs.rsi, len);
+                Ok(Some(PendingOp::Connect {
+                    addr: format_sockaddr(&bytes),
+                }))
+            }
+            n if n == libc::SYS_unlink => {
+                let path =
+                    read_cstring(pid, regs.rdi).unwrap_or_else(|_| String::from("<unreadable>"));
+                Ok(Some(PendingOp::Unlink { path }))
+            }
+            n if n == libc::SYS_unlinkat => {
+                let path =
+                    read_cstring(pid, regs.rsi).unwrap_or_else(|_| String::from("<unreadable>"));
+                Ok(Some(PendingOp::Unlink { path }))
+            }
+            n if n == libc::SYS_chdir => {
+                let path =
+                    read_cstring(pid, regs.rdi).unwrap_or_else(|_| String::from("<unreadable>"));
+                Ok(Some(PendingOp::Chdir { path }))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Read `len` raw bytes out of the tracee's address space, for arguments
+    /// (like a `struct sockaddr`) that aren't NUL-terminated strings.
+    fn read_bytes(pid: libc::pid_t, addr: u64, len: usize) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(len);
+        let mut offset = 0u64;
+        while bytes.len() < len {
+            std::io::Error::last_os_error(); // clear any stale errno before PEEKDATA
+            let word = unsafe {
+                libc::ptrace(
+                    libc::PTRACE_PEEKDATA,
+                    pid,
+                    (addr + offset) as *mut libc::c_void,
+                    0 as *mut libc::c_void,
+                )
+            };
+            for &byte in word.to_ne_bytes().iter() {
+                if bytes.len() >= len {
+                    break;
+                }
+                bytes.push(byte);
+            }
+            offset += PEEK_WORD_BYTES as u64;
+        }
+        bytes
+    }
+
+    /// Render a `struct sockaddr` as `ip:port` (AF_INET) or the socket path
+    /// (AF_UNIX); anything else is reported as `"unknown"` rather than
+    /// guessing at a layout we haven't modeled.
+    fn format_sockaddr(bytes: &[u8]) -> String {
+        if bytes.len() < 2 {
+            return "unknown".to_string();
+        }
+        let family = u16::from_ne_bytes([bytes[0], bytes[1]]);
+        match family as i32 {
+            libc::AF_INET if bytes.len() >= 8 => {
+                let port = u16::from_be_bytes([bytes[2], bytes[3]]);
+                format!("{}.{}.{}.{}:{port}", bytes[4], bytes[5], bytes[6], bytes[7])
+            }
+            libc::AF_UNIX => {
+                let path_bytes = &bytes[2..];
+                let end = path_bytes
+                    .iter()
+                    .position(|&b| b == 0)
+                    .unwrap_or(path_bytes.len());
+                String::from_utf8_lossy(&path_bytes[..end]).into_owned()
+            }
+            _ => "unknown".to_string(),
+        }
+    }
+
+    fn get_regs(pid: libc::pid_t) -> Result<libc::user_regs_struct> {
+        let mut regs: libc::user_regs_struct = unsafe { std::mem::zeroed() };
+        let rc = unsafe {
+            libc::ptrace(
+                libc::PTRACE_GETREGS,
+                pid,
+                0,
+                &mut regs as *mut libc::user_regs_struct as *mut libc::c_void,
+            )
+        };
+        if rc != 0 {
+            anyhow::bail!("PTRACE_GETREGS failed: {}", std::io::Error::last_os_error());
+        }
+        Ok(regs)
+    }
+
+    /// Read a NUL-terminated string out of the tracee's address space one
+    /// word at a time via `PTRACE_PEEKDATA`, bounded by [`MAX_PATH_BYTES`].
+    fn read_cstring(pid: libc::pid_t, addr: u64) -> Result<String> {
+        let mut bytes = Vec::new();
+        let mut offset = 0u64;
+        'words: while bytes.len() < MAX_PATH_BYTES {
+            std::io::Error::last_os_error(); // clear any stale errno before PEEKDATA
+            let word = unsafe {
+                libc::ptrace(
+                    libc::PTRACE_PEEKDATA,
+                    pid,
+                    (addr + offset) as *mut libc::c_void,
+                    0 as *mut libc::c_void,
+                )
+            };
+            let word_bytes = word.to_ne_bytes();
+            for &byte in word_bytes.iter().take(PEEK_WORD_BYTES) {
+                if byte == 0 {
+                    break 'words;
+                }
+                bytes.push(byte);
+            }
+            offset += PEEK_WORD_BYTES as u64;
+        }
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+#[cfg(not(all(target_os = "linux", target_arch = "x86_64")))]
+mod imp {
+    use super::{ExecutionTrace, wrap_without_ptrace};
+    use anyhow::Result;
+    use std::ffi::{OsStr, OsString};
+    use std::path::Path;
+    use std::process::{Command, Output, Stdio};
+
+    pub fn capture(
+        program: &OsStr,
+        argv: &[OsString],
+        env: &[(OsString, OsString)],
+        cwd: &Path,
+        probe_id: &str,
+        mode: &str,
+    ) -> Result<(Output, ExecutionTrace)> {
+        wrap_without_ptrace(program, argv, env, probe_id, mode, || {
+            let mut command = Command::new(program);
+            command.args(argv);
+            command.current_dir(cwd);
+            for (key, value) in env {
+                command.env(key, value);
+            }
+            command.stdout(Stdio::piped());
+            command.stderr(Stdio::piped());
+            command.output().map_err(|err| {
+                anyhow::anyhow!("Failed to execute {}: {err}", program.to_string_lossy())
+            })
+        })
+    }
+}
@@ -0,0 +1,406 @@
+//! Cross-target expectation matrix: per-(os, sandbox_mode) expected outcomes.
+//!
+//! `# fence-expect MODE=RESULT` directives (see `fence-run`) bind one probe
+//! script to one run-mode string. This module instead loads a declarative
+//! table that associates each capability id with the `observed_result` it's
+//! expected to produce across every (os, sandbox_mode) combination the suite
+//! cares about, so the same probe set can gate differently on Darwin vs Linux
+//! without forking probe scripts. [`ExpectationTable::load`] validates every
+//! referenced capability id against an already-loaded [`CapabilityIndex`];
+//! [`classify_matrix`] then joins a stream of [`BoundaryObject`]s against the
+//! table and classifies each as [`MatrixOutcome::Matched`],
+//! [`MatrixOutcome::UnexpectedSuccess`], or
+//! [`MatrixOutcome::UnexpectedFailure`].
+
+use crate::catalog::CapabilityIndex;
+use crate::{BoundaryObject, CapabilityId};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+/// One row of a declarative expectation table file: the `observed_result` a
+/// capability is expected to produce on a given (os, sandbox_mode) pair.
+/// `sandbox_mode: None` matches any sandbox mode under `os` not named by a
+/// more specific row.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpectationRow {
+    pub capability_id: CapabilityId,
+    pub os: String,
+    #[serde(default)]
+    pub sandbox_mode: Option<String>,
+    pub expected_result: String,
+}
+
+/// A loaded, catalog-validated expectation table.
+#[derive(Debug, Clone, Default)]
+pub struct ExpectationTable {
+    rows: BTreeMap<(CapabilityId, String, Option<String>), String>,
+}
+
+impl ExpectationTable {
+    /// Load a declarative expectation table (a JSON array of
+    /// [`ExpectationRow`]) from `path`, failing if it references a capability
+    /// id that isn't in `capabilities` (reusing the already-loaded
+    /// [`CapabilityIndex`] rather than re-resolving a catalog path here).
+    pub fn load(path: &Path, capabilities: &CapabilityIndex) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("reading expectation table {}", path.display()))?;
+        let rows: Vec<ExpectationRow> = serde_json::from_str(&contents)
+            .with_context(|| format!("parsing expectation table {}", path.display()))?;
+
+        let mut unknown_ids: Vec<String> = rows
+            .iter()
+            .filter(|row| capabilities.capability(&row.capability_id).is_none())
+            .map(|row| row.capability_id.0.clone())
+            .collect();
+        if !unknown_ids.is_empty() {
+            unknown_ids.sort();
+            unknown_ids.dedup();
+            bail!(
+                "expectation table {} references unknown capability id(s): {}",
+                path.display(),
+                unknown_ids.join(", ")
+            );
+        }
+
+        let table = rows
+            .into_iter()
+            .map(|row| {
+                (
+                    (row.capability_id, row.os, row.sandbox_mode),
+                    row.expected_result,
+                )
+            })
+            .collect();
+        Ok(Self { rows: table })
+    }
+
+    /// Look up the expected `observed_result` for `capability_id` under
+    /// `os`/`sandbox_mode`. Falls back to the OS-wide row (no `sandbox_mode`
+    /// named) when no row names this exact sandbox mode, so a table doesn't
+    /// need to repeat an OS-invariant expectation for every mode. `None` when
+    /// neither row is declared, meaning the table takes no position on this
+    /// capability.
+    pub fn expected_result(
+        &self,
+        capability_id: &CapabilityId,
+        os: &str,
+        sandbox_mode: Option<&str>,
+    ) -> Option<&str> {
+        if let Some(mode) = sandbox_mode {
+            let key = (
+                capability_id.clone(),
+                os.to_string(),
+                Some(mode.to_string()),
+            );
+            if let Some(result) = self.rows.get(&key) {
+                return Some(result.as_str());
+            }
+        }
+        let key = (capability_id.clone(), os.to_string(), None);
+        self.rows.get(&key).map(String::as_str)
+    }
+}
+
+/// How a probe's observed result compares to the expectation table for the
+/// detected `(os, sandbox_mode)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatrixOutcome {
+    /// The observed result matches the table, or the table took no position
+    /// on this capability.
+    Matched,
+    /// The table expected a non-`success` result but the probe succeeded.
+    UnexpectedSuccess,
+    /// The table expected `success` but the probe reported something else,
+    /// or the table expected one non-`success` result and the probe
+    /// reported a different non-`success` one.
+    UnexpectedFailure,
+}
+
+impl MatrixOutcome {
+    fn classify(expected: Option<&str>, observed: &str) -> Self {
+        match expected {
+            None => MatrixOutcome::Matched,
+            Some(expected) if expected == observed => MatrixOutcome::Matched,
+            Some(expected) if expected != "success" && observed == "success" => {
+                MatrixOutcome::UnexpectedSuccess
+            }
+            Some(_) => MatrixOutcome::UnexpectedFailure,
+        }
+    }
+}
+
+/// One probe's classification against the expectation table.
+#[derive(Debug, Clone, Serialize)]
+pub struct MatrixEntry {
+    pub probe_id: String,
+    pub capability_id: String,
+    pub os: String,
+    pub sandbox_mode: Option<String>,
+    pub expected_result: Option<String>,
+    pub observed_result: String,
+    pub outcome: MatrixOutcome,
+}
+
+/// Full expectation-matrix report: one [`MatrixEntry`] per record, plus
+/// rollup counts so a runner can gate on any deviation without re-scanning.
+#[derive(Debug, Clone, Serialize)]
+pub struct MatrixReport {
+    pub entries: Vec<MatrixEntry>,
+    pub matched: usize,
+    pub unexpected_success: usize,
+    pub unexpected_failure: usize,
+}
+
+/// Classify every record in `records` against `table`, keyed by each record's
+/// own `stack.os`/`stack.sandbox_mode` so a single run mixing hosts still
+/// gates correctly.
+pub fn classify_matrix(records: &[BoundaryObject], table: &ExpectationTable) -> MatrixReport {
+    let mut entries = Vec::with_capacity(records.len());
+    let mut matched = 0;
+    let mut unexpected_success = 0;
+    let mut unexpected_failure = 0;
+
+    for record in records {
+        let capability_id = &record.probe.primary_capability_id;
+        let os = &record.stack.os;
+        let sandbox_mode = record.stack.sandbox_mode.as_deref();
+        let expected = table.expected_result(capability_id, os, sandbox_mode);
+        let outcome = MatrixOutcome::classify(expected, &record.result.observed_result);
+
+        match outcome {
+            MatrixOutcome::Matched => matched += 1,
+            MatrixOutcome::UnexpectedSuccess => unexpected_success += 1,
+            MatrixOutcome::UnexpectedFailure => unexpected_failure += 1,
+        }
+
+        entries.push(MatrixEntry {
+            probe_id: record.probe.id.clone(),
+            capability_id: capability_id.0.clone(),
+            os: os.clone(),
+            sandbox_mode: sandbox_mode.map(str::to_string),
+            expected_result: expected.map(str::to_string),
+            observed_result: record.result.observed_result.clone(),
+            outcome,
+        });
+    }
+
+    MatrixReport {
+        entries,
+        matched,
+        unexpected_success,
+        unexpected_failure,
+    }
+}
+
+/// Render a report as an aligned text table (one row per deviating entry)
+/// plus a trailing rollup line. Matched entries are omitted from the table
+/// since a clean matrix run is the common case and shouldn't scroll past.
+pub fn render_matrix_human(report: &MatrixReport) -> String {
+    let mut out = String::new();
+    let deviations: Vec<&MatrixEntry> = report
+        .entries
+        .iter()
+        .filter(|entry| entry.outcome != MatrixOutcome::Matched)
+        .collect();
+
+    if deviations.is_empty() {
+        let _ = writeln!(out, "All {} probe(s) matched expectations", report.matched);
+        return out;
+    }
+
+    let _ = writeln!(
+        out,
+        "{:<24}  {:<24}  {:<8}  {:<14}  {:<10}  {:<10}",
+        "PROBE", "CAPABILITY", "OS", "MODE", "EXPECTED", "OBSERVED"
+    );
+    for entry in &deviations {
+        let _ = writeln!(
+            out,
+            "{:<24}  {:<24}  {:<8}  {:<14}  {:<10}  {:<10}",
+            entry.probe_id,
+            entry.capability_id,
+            entry.os,
+            entry.sandbox_mode.as_deref().unwrap_or("-"),
+            entry.expected_result.as_deref().unwrap_or("-"),
+            entry.observed_result,
+        );
+    }
+    let _ = writeln!(
+        out,
+        "\n{} matched; {} unexpected success(es); {} unexpected failure(s)",
+        report.matched, report.unexpected_success, report.unexpected_failure
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary::{
+        CapabilityContext, OperationInfo, Payload, ProbeInfo, ResultInfo, RunInfo, StackInfo,
+    };
+    use crate::catalog::{CapabilityCategory, CapabilityLayer, CapabilitySnapshot};
+
+    fn record(
+        probe_id: &str,
+        capability_id: &str,
+        os: &str,
+        sandbox_mode: Option<&str>,
+        observed_result: &str,
+    ) -> BoundaryObject {
+        BoundaryObject {
+            schema_version: "boundary_event_v1".to_string(),
+            schema_key: None,
+            capabilities_schema_version: None,
+            stack: StackInfo {
+                sandbox_mode: sandbox_mode.map(str::to_string),
+                container_image: None,
+                os: os.to_string(),
+            },
+            probe: ProbeInfo {
+                id: probe_id.to_string(),
+                version: "1".to_string(),
+                primary_capability_id: CapabilityId(capability_id.to_string()),
+                secondary_capability_ids: Vec::new(),
+            },
+            run: RunInfo {
+                mode: sandbox_mode.unwrap_or("baseline").to_string(),
+                workspace_root: None,
+                command: "probe.sh".to_string(),
+            },
+            operation: OperationInfo {
+                category: "fs".to_string(),
+                verb: "read".to_string(),
+                target: "/tmp/a".to_string(),
+                args: serde_json::json!({}),
+            },
+            result: ResultInfo {
+                observed_result: observed_result.to_string(),
+                raw_exit_code: None,
+                errno: None,
+                message: None,
+                error_detail: None,
+            },
+            payload: Payload {
+                stdout_snippet: None,
+                stderr_snippet: None,
+                raw: serde_json::json!({}),
+            },
+            capability_context: CapabilityContext {
+                primary: CapabilitySnapshot {
+                    id: CapabilityId(capability_id.to_string()),
+                    category: CapabilityCategory::Filesystem,
+                    layer: CapabilityLayer::OsSandbox,
+                },
+                secondary: Vec::new(),
+                resolved_grant: None,
+            },
+        }
+    }
+
+    fn table(rows: Vec<ExpectationRow>) -> ExpectationTable {
+        ExpectationTable {
+            rows: rows
+                .into_iter()
+                .map(|row| {
+                    (
+                        (row.capability_id, row.os, row.sandbox_mode),
+                        row.expected_result,
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn expected_result_falls_back_from_mode_specific_to_os_wide_row() {
+        let table = table(vec![
+            ExpectationRow {
+                capability_id: CapabilityId("cap_a".to_string()),
+                os: "linux".to_string(),
+                sandbox_mode: None,
+                expected_result: "success".to_string(),
+            },
+            ExpectationRow {
+                capability_id: CapabilityId("cap_a".to_string()),
+                os: "linux".to_string(),
+                sandbox_mode: Some("container".to_string()),
+                expected_result: "denied".to_string(),
+            },
+        ]);
+
+        let cap_a = CapabilityId("cap_a".to_string());
+        assert_eq!(
+            table.expected_result(&cap_a, "linux", Some("container")),
+            Some("denied")
+        );
+        assert_eq!(
+            table.expected_result(&cap_a, "linux", Some("baseline")),
+            Some("success")
+        );
+        assert_eq!(table.expected_result(&cap_a, "darwin", None), None);
+    }
+
+    #[test]
+    fn classify_matrix_reports_matched_and_unexpected_outcomes() {
+        let table = table(vec![
+            ExpectationRow {
+                capability_id: CapabilityId("cap_a".to_string()),
+                os: "linux".to_string(),
+                sandbox_mode: Some("container".to_string()),
+                expected_result: "denied".to_string(),
+            },
+            ExpectationRow {
+                capability_id: CapabilityId("cap_b".to_string()),
+                os: "linux".to_string(),
+                sandbox_mode: None,
+                expected_result: "success".to_string(),
+            },
+        ]);
+
+        let records = vec![
+            record(
+                "probe-leaks",
+                "cap_a",
+                "linux",
+                Some("container"),
+                "success",
+            ),
+            record("probe-ok", "cap_a", "linux", Some("container"), "denied"),
+            record("probe-regresses", "cap_b", "linux", None, "denied"),
+            record("probe-no-expectation", "cap_c", "linux", None, "success"),
+        ];
+
+        let report = classify_matrix(&records, &table);
+        assert_eq!(report.matched, 2);
+        assert_eq!(report.unexpected_success, 1);
+        assert_eq!(report.unexpected_failure, 1);
+        assert_eq!(report.entries[0].outcome, MatrixOutcome::UnexpectedSuccess);
+        assert_eq!(report.entries[2].outcome, MatrixOutcome::UnexpectedFailure);
+    }
+
+    #[test]
+    fn render_matrix_human_omits_matched_entries_and_summarizes_deviations() {
+        let table = table(vec![ExpectationRow {
+            capability_id: CapabilityId("cap_a".to_string()),
+            os: "linux".to_string(),
+            sandbox_mode: None,
+            expected_result: "denied".to_string(),
+        }]);
+        let records = vec![
+            record("probe-ok", "cap_a", "linux", None, "denied"),
+            record("probe-leaks", "cap_a", "linux", None, "success"),
+        ];
+
+        let report = classify_matrix(&records, &table);
+        let rendered = render_matrix_human(&report);
+        assert!(!rendered.contains("probe-ok"));
+        assert!(rendered.contains("probe-leaks"));
+        assert!(rendered.contains("1 matched"));
+    }
+}
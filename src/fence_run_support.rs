@@ -1,5 +1,5 @@
 use crate::{CapabilityId, Probe, ProbeMetadata};
-use anyhow::{Result, anyhow};
+use anyhow::{Result, anyhow, bail};
 use std::ffi::OsString;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -12,19 +12,96 @@ use std::path::{Path, PathBuf};
 #[derive(Clone)]
 pub enum WorkspaceOverride {
     UsePath(OsString),
+    /// Like `UsePath`, but bypasses `ContainmentPolicy` checking entirely,
+    /// for callers that already trust an absolute path by construction
+    /// (e.g. a fixture root built by the caller itself) and don't want it
+    /// rejected for resolving outside the default allowed roots.
+    TrustedPath(OsString),
     SkipExport,
 }
 
 pub struct WorkspacePlan {
     pub export_value: Option<OsString>,
+    /// Set instead of `export_value` when a `UsePath` override resolved
+    /// outside every root in the `ContainmentPolicy` it was checked against.
+    pub containment_error: Option<ContainmentViolation>,
 }
 
-/// Decide how the workspace root should be exported to probes.
-pub fn workspace_plan_from_override(value: WorkspaceOverride) -> WorkspacePlan {
+/// An allow-list of canonicalized roots that an exported workspace or tmp
+/// path must stay inside. Guards against a symlinked `workspace`/`tmp`
+/// directory silently redirecting probe writes outside the repo.
+#[derive(Clone)]
+pub struct ContainmentPolicy {
+    pub allowed_roots: Vec<PathBuf>,
+}
+
+impl ContainmentPolicy {
+    /// A policy whose only allowed root is `repo_root` (canonicalized).
+    pub fn repo_root(repo_root: &Path) -> Self {
+        Self {
+            allowed_roots: vec![canonicalize_path(repo_root)],
+        }
+    }
+
+    /// Resolves symlinks in `candidate` and checks the result against each
+    /// allowed root component-by-component, so e.g. `/repo/tmp2` is not
+    /// mistaken for a descendant of an allowed `/repo/tmp` root. Returns the
+    /// canonicalized path on success.
+    pub fn check(&self, candidate: &Path) -> Result<PathBuf, ContainmentViolation> {
+        let canonical = canonicalize_path(candidate);
+        let canonical_components: Vec<_> = canonical.components().collect();
+        let contained = self.allowed_roots.iter().any(|root| {
+            let root_components: Vec<_> = root.components().collect();
+            canonical_components.len() >= root_components.len()
+                && canonical_components[..root_components.len()] == root_components[..]
+        });
+
+        if contained {
+            Ok(canonical)
+        } else {
+            Err(ContainmentViolation {
+                path: canonical,
+                allowed_roots: self.allowed_roots.clone(),
+            })
+        }
+    }
+}
+
+/// A workspace or tmp path that resolved outside every root in a
+/// `ContainmentPolicy`, naming the escaping path and the roots it was
+/// checked against so callers can emit a descriptive preflight record
+/// instead of exporting the path.
+#[derive(Clone, Debug)]
+pub struct ContainmentViolation {
+    pub path: PathBuf,
+    pub allowed_roots: Vec<PathBuf>,
+}
+
+/// Decide how the workspace root should be exported to probes, enforcing
+/// `policy` for any `UsePath` override (`TrustedPath` and `SkipExport` bypass
+/// it).
+pub fn workspace_plan_from_override(
+    value: WorkspaceOverride,
+    policy: &ContainmentPolicy,
+) -> WorkspacePlan {
     match value {
-        WorkspaceOverride::SkipExport => WorkspacePlan { export_value: None },
-        WorkspaceOverride::UsePath(path) => WorkspacePlan {
+        WorkspaceOverride::SkipExport => WorkspacePlan {
+            export_value: None,
+            containment_error: None,
+        },
+        WorkspaceOverride::TrustedPath(path) => WorkspacePlan {
             export_value: Some(canonicalize_os_string(&path)),
+            containment_error: None,
+        },
+        WorkspaceOverride::UsePath(path) => match policy.check(Path::new(&path)) {
+            Ok(canonical) => WorkspacePlan {
+                export_value: Some(canonical.into_os_string()),
+                containment_error: None,
+            },
+            Err(violation) => WorkspacePlan {
+                export_value: None,
+                containment_error: Some(violation),
+            },
         },
     }
 }
@@ -43,11 +120,19 @@ pub fn canonicalize_os_string(value: &OsString) -> OsString {
 pub struct TmpdirPlan {
     pub path: Option<PathBuf>,
     pub last_error: Option<(PathBuf, String)>,
+    /// Set instead of `path` when a candidate tmp dir was created
+    /// successfully but resolved outside every root in `policy`.
+    pub containment_error: Option<ContainmentViolation>,
 }
 
-/// Decide where TMPDIR should point for a run and capture the last failure so
-/// the caller can emit a descriptive preflight record.
-pub fn workspace_tmpdir_plan(workspace_plan: &WorkspacePlan, repo_root: &Path) -> TmpdirPlan {
+/// Decide where TMPDIR should point for a run, enforcing `policy` against the
+/// resolved candidate, and capture the last failure so the caller can emit a
+/// descriptive preflight record.
+pub fn workspace_tmpdir_plan(
+    workspace_plan: &WorkspacePlan,
+    repo_root: &Path,
+    policy: &ContainmentPolicy,
+) -> TmpdirPlan {
     let mut candidates = Vec::new();
     if let Some(value) = workspace_plan.export_value.as_ref() {
         candidates.push(PathBuf::from(value).join("tmp"));
@@ -59,12 +144,22 @@ pub fn workspace_tmpdir_plan(workspace_plan: &WorkspacePlan, repo_root: &Path) -
     let mut last_error = None;
     for candidate in candidates {
         match fs::create_dir_all(&candidate) {
-            Ok(()) => {
-                return TmpdirPlan {
-                    path: Some(canonicalize_path(&candidate)),
-                    last_error: None,
-                };
-            }
+            Ok(()) => match policy.check(&candidate) {
+                Ok(canonical) => {
+                    return TmpdirPlan {
+                        path: Some(canonical),
+                        last_error: None,
+                        containment_error: None,
+                    };
+                }
+                Err(violation) => {
+                    return TmpdirPlan {
+                        path: None,
+                        last_error: None,
+                        containment_error: Some(violation),
+                    };
+                }
+            },
             Err(err) => last_error = Some((candidate, err.to_string())),
         }
     }
@@ -72,7 +167,25 @@ pub fn workspace_tmpdir_plan(workspace_plan: &WorkspacePlan, repo_root: &Path) -
     TmpdirPlan {
         path: None,
         last_error,
+        containment_error: None,
+    }
+}
+
+/// Hard-fail when a resolved workspace/tmpdir path escaped every root in the
+/// `ContainmentPolicy` it was checked against, instead of letting the plan's
+/// `None` export/path value silently skip the env var the probe expects.
+pub fn reject_containment_violation(
+    what: &str,
+    violation: Option<&ContainmentViolation>,
+) -> Result<()> {
+    if let Some(violation) = violation {
+        bail!(
+            "{what} {} escapes allowed roots {:?}",
+            violation.path.display(),
+            violation.allowed_roots
+        );
     }
+    Ok(())
 }
 
 pub struct ResolvedProbeMetadata {
@@ -0,0 +1,220 @@
+//! Cross-references catalog capabilities against real OS enforcement
+//! primitives.
+//!
+//! A capability's `category`/`layer` pair and its declared
+//! `operations.allow`/`operations.deny` verbs are easy to get "plausible but
+//! wrong" by hand: a typo'd layer, or a verb that stopped corresponding to
+//! any enforceable control. [`validate_capability_grounding`] resolves each
+//! `os_sandbox` capability to a concrete primitive for the target platform —
+//! the Linux capability set (via the `caps` crate) plus the
+//! seccomp/filesystem primitives this harness also enforces on Linux, or the
+//! Seatbelt/os_sandbox operation vocabulary on Darwin — and reports anything
+//! that can't be grounded.
+
+use crate::catalog::{CapabilityCategory, CapabilityIndex, CapabilityLayer};
+use anyhow::Result;
+
+/// `os_sandbox` categories this harness can ground in a real control on
+/// either supported platform. `sandbox_profile`/`agent_sandbox_policy` are
+/// meta-categories describing the harness's own policy surface, not a raw OS
+/// primitive, so they never ground here even when tagged `os_sandbox`.
+fn category_grounded(category: &CapabilityCategory) -> bool {
+    matches!(
+        category,
+        CapabilityCategory::Filesystem
+            | CapabilityCategory::Process
+            | CapabilityCategory::Network
+            | CapabilityCategory::Sysctl
+            | CapabilityCategory::Ipc
+    )
+}
+
+/// Seatbelt operation name prefixes the catalog's `operations.allow/deny`
+/// verbs are expected to draw from. Not exhaustive — just the vocabulary
+/// this harness currently knows how to ground a verb against.
+const DARWIN_OPERATION_PREFIXES: &[&str] = &[
+    "file-read",
+    "file-write",
+    "file-ioctl",
+    "network-outbound",
+    "network-inbound",
+    "process-fork",
+    "process-exec",
+    "sysctl-read",
+    "sysctl-write",
+    "mach-lookup",
+    "signal",
+];
+
+fn darwin_verb_grounded(verb: &str) -> bool {
+    DARWIN_OPERATION_PREFIXES
+        .iter()
+        .any(|prefix| verb.starts_with(prefix))
+}
+
+/// Linux verbs that ground in a real seccomp/filesystem primitive rather
+/// than a named Linux capability.
+const LINUX_NON_CAPABILITY_PRIMITIVES: &[&str] = &[
+    "seccomp-deny",
+    "open",
+    "openat",
+    "connect",
+    "bind",
+    "ptrace",
+    "mount",
+    "unshare",
+];
+
+fn linux_verb_grounded(verb: &str) -> bool {
+    verb.parse::<caps::Capability>().is_ok() || LINUX_NON_CAPABILITY_PRIMITIVES.contains(&verb)
+}
+
+/// Validate that every `os_sandbox` capability's `category` and declared
+/// verbs correspond to a real enforcement mechanism on `platform`
+/// (`"Darwin"` or `"Linux"`, matching [`crate::boundary::StackInfo::os`]).
+///
+/// `agent_runtime` capabilities describe the harness's own policy surface
+/// rather than an OS primitive, so they are not checked here.
+pub fn validate_capability_grounding(
+    capabilities: &CapabilityIndex,
+    platform: &str,
+) -> Result<Vec<String>> {
+    let verb_grounded: fn(&str) -> bool = match platform {
+        "Darwin" => darwin_verb_grounded,
+        "Linux" => linux_verb_grounded,
+        other => anyhow::bail!("unsupported platform '{other}', expected 'Darwin' or 'Linux'"),
+    };
+
+    let mut errors = Vec::new();
+    for id in capabilities.ids() {
+        let capability = capabilities
+            .capability(id)
+            .expect("id came from capabilities.ids()");
+
+        if capability.layer != CapabilityLayer::OsSandbox {
+            continue;
+        }
+
+        if !category_grounded(&capability.category) {
+            errors.push(format!(
+                "{}: category '{}' has no known {platform} enforcement primitive",
+                id.0,
+                capability.category.as_str()
+            ));
+            continue;
+        }
+
+        for verb in capability
+            .operations
+            .allow
+            .iter()
+            .chain(&capability.operations.deny)
+        {
+            if !verb_grounded(verb) {
+                errors.push(format!(
+                    "{}: verb '{verb}' does not correspond to a known {platform} primitive",
+                    id.0
+                ));
+            }
+        }
+    }
+
+    Ok(errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::NamedTempFile;
+
+    fn index_with(capabilities: serde_json::Value) -> Result<CapabilityIndex> {
+        let mut file = NamedTempFile::new()?;
+        serde_json::to_writer(
+            &mut file,
+            &json!({
+                "schema_version": "sandbox_catalog_v1",
+                "scope": {
+                    "description": "test",
+                    "policy_layers": [
+                        {"id": "os_sandbox", "description": "os"},
+                        {"id": "agent_runtime", "description": "agent"}
+                    ],
+                    "categories": {
+                        "filesystem": "fs",
+                        "process": "proc",
+                        "sandbox_profile": "meta"
+                    }
+                },
+                "docs": {},
+                "capabilities": capabilities
+            }),
+        )?;
+        CapabilityIndex::load(file.path())
+    }
+
+    #[test]
+    fn validate_capability_grounding_accepts_known_darwin_verbs() {
+        let index = index_with(json!([{
+            "id": "cap_fs_read_workspace_tree",
+            "category": "filesystem",
+            "layer": "os_sandbox",
+            "description": "fixture",
+            "operations": {"allow": ["file-read*"], "deny": ["file-write*"]}
+        }]))
+        .expect("index loads");
+
+        let errors = validate_capability_grounding(&index, "Darwin").expect("grounding should run");
+        assert!(errors.is_empty(), "expected no errors, got {errors:?}");
+    }
+
+    #[test]
+    fn validate_capability_grounding_flags_unknown_verb() {
+        let index = index_with(json!([{
+            "id": "cap_fs_read_workspace_tree",
+            "category": "filesystem",
+            "layer": "os_sandbox",
+            "description": "fixture",
+            "operations": {"allow": ["totally-made-up-verb"], "deny": []}
+        }]))
+        .expect("index loads");
+
+        let errors = validate_capability_grounding(&index, "Darwin").expect("grounding should run");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("totally-made-up-verb"));
+    }
+
+    #[test]
+    fn validate_capability_grounding_flags_ungroundable_category() {
+        let index = index_with(json!([{
+            "id": "cap_meta_profile",
+            "category": "sandbox_profile",
+            "layer": "os_sandbox",
+            "description": "fixture",
+            "operations": {"allow": [], "deny": []}
+        }]))
+        .expect("index loads");
+
+        let errors = validate_capability_grounding(&index, "Darwin").expect("grounding should run");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("sandbox_profile"));
+    }
+
+    #[test]
+    fn validate_capability_grounding_skips_agent_runtime_layer() {
+        let index = index_with(json!([{
+            "id": "cap_agent_policy",
+            "category": "sandbox_profile",
+            "layer": "agent_runtime",
+            "description": "fixture",
+            "operations": {"allow": ["anything-goes"], "deny": []}
+        }]))
+        .expect("index loads");
+
+        let errors = validate_capability_grounding(&index, "Darwin").expect("grounding should run");
+        assert!(
+            errors.is_empty(),
+            "agent_runtime capabilities should not be OS-grounded, got {errors:?}"
+        );
+    }
+}
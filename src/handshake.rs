@@ -0,0 +1,233 @@
+//! Protocol/version handshake between `probe-matrix` and its `probe-exec`
+//! helpers.
+//!
+//! `probe-matrix` shells out to `probe-exec` for every probe/mode pair and,
+//! until now, trusted the emitted boundary object without checking that the
+//! helper actually speaks the same protocol or agrees on the schema/catalog
+//! keys the matrix resolved. [`VersionInfo`] plus [`negotiate`] make that
+//! contract explicit: each helper answers a `--protocol-version` query with
+//! its own advertisement, which the matrix compares against what it loaded
+//! before running anything — mirroring a `distant version`-style handshake.
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Protocol version this binary speaks. Bump the major component for a
+/// breaking change to the `--protocol-version` query or its response shape;
+/// bump the minor component for additive, backward-compatible changes.
+pub const PROTOCOL_VERSION: (u16, u16) = (1, 0);
+
+/// A helper's self-reported version/compatibility advertisement, exchanged as
+/// JSON via `probe-exec --protocol-version`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VersionInfo {
+    pub server_version: String,
+    pub protocol: (u16, u16),
+    pub schema_keys: Vec<String>,
+    pub capability_schema_versions: Vec<String>,
+}
+
+impl VersionInfo {
+    /// Build the advertisement this binary would itself return from a
+    /// `--protocol-version` query, given the boundary schema key and catalog
+    /// key it has resolved.
+    pub fn current(schema_key: Option<&str>, capability_schema_version: &str) -> Self {
+        Self {
+            server_version: env!("CARGO_PKG_VERSION").to_string(),
+            protocol: PROTOCOL_VERSION,
+            schema_keys: schema_key
+                .map(|key| vec![key.to_string()])
+                .unwrap_or_default(),
+            capability_schema_versions: vec![capability_schema_version.to_string()],
+        }
+    }
+}
+
+/// Outcome of comparing a helper's [`VersionInfo`] against what the matrix
+/// expects.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NegotiationResult {
+    /// Hard failures: an incompatible major protocol version, or a helper
+    /// that doesn't advertise the expected schema/catalog key.
+    pub errors: Vec<String>,
+    /// Soft failures the matrix should surface but not abort on: a minor
+    /// protocol skew.
+    pub warnings: Vec<String>,
+}
+
+impl NegotiationResult {
+    pub fn is_compatible(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Compare a helper's advertised `remote` [`VersionInfo`] against `local` (the
+/// version this matrix speaks) and the boundary schema key / capability
+/// catalog key the matrix resolved.
+///
+/// Hard-fails on an incompatible major protocol version or a helper that
+/// doesn't advertise the expected `schema_key`/capability schema version;
+/// only warns on a minor protocol skew, since that's expected to stay
+/// backward-compatible.
+pub fn negotiate(
+    local: &VersionInfo,
+    remote: &VersionInfo,
+    expected_schema_key: Option<&str>,
+    expected_capability_schema_version: &str,
+) -> NegotiationResult {
+    let mut result = NegotiationResult::default();
+
+    if remote.protocol.0 != local.protocol.0 {
+        result.errors.push(format!(
+            "helper '{}' speaks protocol {}.{}, incompatible with this matrix's {}.{}",
+            remote.server_version,
+            remote.protocol.0,
+            remote.protocol.1,
+            local.protocol.0,
+            local.protocol.1
+        ));
+    } else if remote.protocol.1 != local.protocol.1 {
+        result.warnings.push(format!(
+            "helper '{}' speaks protocol {}.{}, this matrix speaks {}.{} (minor skew)",
+            remote.server_version,
+            remote.protocol.0,
+            remote.protocol.1,
+            local.protocol.0,
+            local.protocol.1
+        ));
+    }
+
+    if let Some(expected_schema_key) = expected_schema_key {
+        if !remote
+            .schema_keys
+            .iter()
+            .any(|key| key == expected_schema_key)
+        {
+            result.errors.push(format!(
+                "helper '{}' does not advertise boundary schema_key '{expected_schema_key}' (advertises {:?})",
+                remote.server_version, remote.schema_keys
+            ));
+        }
+    }
+
+    if !remote
+        .capability_schema_versions
+        .iter()
+        .any(|version| version == expected_capability_schema_version)
+    {
+        result.errors.push(format!(
+            "helper '{}' does not advertise capability schema '{expected_capability_schema_version}' (advertises {:?})",
+            remote.server_version, remote.capability_schema_versions
+        ));
+    }
+
+    result
+}
+
+/// Invoke `helper --protocol-version` and parse its `VersionInfo` response.
+///
+/// `repo_root` is used as the working directory so the helper resolves
+/// repo-relative paths the same way it would for a real probe run.
+pub fn query_protocol_version(helper: &Path, repo_root: &Path) -> Result<VersionInfo> {
+    let output = Command::new(helper)
+        .arg("--protocol-version")
+        .current_dir(repo_root)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .output()
+        .with_context(|| format!("Failed to query protocol version from {}", helper.display()))?;
+
+    if !output.status.success() {
+        bail!(
+            "{} --protocol-version returned non-zero exit code {}",
+            helper.display(),
+            output.status.code().unwrap_or(-1)
+        );
+    }
+
+    serde_json::from_slice(&output.stdout)
+        .with_context(|| format!("Failed to parse VersionInfo from {}", helper.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(
+        protocol: (u16, u16),
+        schema_keys: &[&str],
+        capability_schema_versions: &[&str],
+    ) -> VersionInfo {
+        VersionInfo {
+            server_version: "test-helper".to_string(),
+            protocol,
+            schema_keys: schema_keys.iter().map(|s| s.to_string()).collect(),
+            capability_schema_versions: capability_schema_versions
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn negotiate_accepts_matching_versions() {
+        let local = info((1, 0), &["cfbo-v1"], &["macOS_codex_v1"]);
+        let remote = info((1, 0), &["cfbo-v1"], &["macOS_codex_v1"]);
+
+        let outcome = negotiate(&local, &remote, Some("cfbo-v1"), "macOS_codex_v1");
+        assert!(outcome.is_compatible());
+        assert!(outcome.warnings.is_empty());
+    }
+
+    #[test]
+    fn negotiate_warns_on_minor_skew() {
+        let local = info((1, 2), &["cfbo-v1"], &["macOS_codex_v1"]);
+        let remote = info((1, 0), &["cfbo-v1"], &["macOS_codex_v1"]);
+
+        let outcome = negotiate(&local, &remote, Some("cfbo-v1"), "macOS_codex_v1");
+        assert!(outcome.is_compatible());
+        assert_eq!(outcome.warnings.len(), 1);
+    }
+
+    #[test]
+    fn negotiate_hard_fails_on_major_mismatch() {
+        let local = info((1, 0), &["cfbo-v1"], &["macOS_codex_v1"]);
+        let remote = info((2, 0), &["cfbo-v1"], &["macOS_codex_v1"]);
+
+        let outcome = negotiate(&local, &remote, Some("cfbo-v1"), "macOS_codex_v1");
+        assert!(!outcome.is_compatible());
+        assert_eq!(outcome.errors.len(), 1);
+        assert!(outcome.errors[0].contains("incompatible"));
+    }
+
+    #[test]
+    fn negotiate_rejects_missing_schema_key() {
+        let local = info((1, 0), &["cfbo-v1"], &["macOS_codex_v1"]);
+        let remote = info((1, 0), &["cfbo-v0"], &["macOS_codex_v1"]);
+
+        let outcome = negotiate(&local, &remote, Some("cfbo-v1"), "macOS_codex_v1");
+        assert!(!outcome.is_compatible());
+        assert!(outcome.errors[0].contains("schema_key"));
+    }
+
+    #[test]
+    fn negotiate_rejects_missing_capability_schema_version() {
+        let local = info((1, 0), &["cfbo-v1"], &["macOS_codex_v1"]);
+        let remote = info((1, 0), &["cfbo-v1"], &["macOS_codex_v0"]);
+
+        let outcome = negotiate(&local, &remote, Some("cfbo-v1"), "macOS_codex_v1");
+        assert!(!outcome.is_compatible());
+        assert!(outcome.errors[0].contains("capability schema"));
+    }
+
+    #[test]
+    fn negotiate_skips_schema_key_check_when_not_expected() {
+        let local = info((1, 0), &[], &["macOS_codex_v1"]);
+        let remote = info((1, 0), &[], &["macOS_codex_v1"]);
+
+        let outcome = negotiate(&local, &remote, None, "macOS_codex_v1");
+        assert!(outcome.is_compatible());
+    }
+}
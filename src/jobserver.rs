@@ -0,0 +1,253 @@
+//! GNU Make jobserver client protocol, so parallel binaries in this crate
+//! cooperate with an enclosing `make -jN` instead of oversubscribing it.
+//!
+//! A jobserver is a pool of single-byte tokens passed around a pipe (or a
+//! named FIFO): acquiring a token means reading one byte, releasing it means
+//! writing the same byte back. Every client always owns one *implicit* token
+//! for free (the slot `make` grants the recipe itself); every additional
+//! concurrent job must acquire an explicit token first. When no jobserver is
+//! inherited via `MAKEFLAGS`, [`JobServer::new_standalone`] creates one of
+//! our own, priming the pipe with `parallelism - 1` tokens so `parallelism`
+//! jobs (counting the implicit slot) can run at once.
+
+use anyhow::Result;
+use std::env;
+
+/// Resolve a jobserver the way `fence-bang` does: inherit one advertised in
+/// `MAKEFLAGS` when present, otherwise create a standalone one sized by
+/// `requested_jobs` (falling back to `FENCE_JOBS`, then the host's available
+/// parallelism, mirroring `probe-matrix`'s `resolve_jobs`).
+pub fn from_environment(requested_jobs: Option<usize>) -> Result<JobServer> {
+    if let Some(makeflags) = env::var_os("MAKEFLAGS") {
+        if let Some(server) = JobServer::from_makeflags(&makeflags.to_string_lossy())? {
+            return Ok(server);
+        }
+    }
+    JobServer::new_standalone(resolve_parallelism(requested_jobs)?)
+}
+
+/// Resolve requested parallelism: CLI flag wins, then `FENCE_JOBS`, then the
+/// host's available parallelism.
+fn resolve_parallelism(cli_jobs: Option<usize>) -> Result<usize> {
+    use anyhow::{Context, bail};
+
+    if let Some(jobs) = cli_jobs {
+        if jobs == 0 {
+            bail!("--jobs must be at least 1");
+        }
+        return Ok(jobs);
+    }
+
+    if let Ok(raw) = env::var("FENCE_JOBS") {
+        let jobs: usize = raw
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid FENCE_JOBS value: {raw}"))?;
+        if jobs == 0 {
+            bail!("FENCE_JOBS must be at least 1");
+        }
+        return Ok(jobs);
+    }
+
+    Ok(std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1))
+}
+
+#[cfg(unix)]
+mod unix_impl {
+    use super::resolve_parallelism;
+    use anyhow::{Context, Result, bail};
+    use std::io;
+    use std::os::unix::io::RawFd;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// A connected jobserver: either inherited from a parent `make -jN` via
+    /// `MAKEFLAGS`, or one this process created and primed itself.
+    pub struct JobServer {
+        read_fd: RawFd,
+        write_fd: RawFd,
+        implicit_available: AtomicBool,
+    }
+
+    /// A held job slot. Dropping it returns the token to the pool (or frees
+    /// the implicit slot for reuse) so the next `acquire` can proceed.
+    pub enum JobSlot<'a> {
+        Implicit(&'a JobServer),
+        Token(&'a JobServer),
+    }
+
+    impl JobServer {
+        pub(super) fn from_makeflags(makeflags: &str) -> Result<Option<Self>> {
+            for token in makeflags.split_whitespace() {
+                if let Some(auth) = token
+                    .strip_prefix("--jobserver-auth=")
+                    .or_else(|| token.strip_prefix("--jobserver-fds="))
+                {
+                    return Self::from_auth(auth).map(Some);
+                }
+            }
+            Ok(None)
+        }
+
+        fn from_auth(auth: &str) -> Result<Self> {
+            if let Some(path) = auth.strip_prefix("fifo:") {
+                let write_fd = open_fifo(path, libc::O_WRONLY)?;
+                let read_fd = open_fifo(path, libc::O_RDONLY)?;
+                return Ok(Self::from_fds(read_fd, write_fd));
+            }
+
+            let (read_str, write_str) = auth
+                .split_once(',')
+                .with_context(|| format!("malformed --jobserver-auth value: {auth}"))?;
+            let read_fd: RawFd = read_str
+                .parse()
+                .with_context(|| format!("invalid jobserver read fd in '{auth}'"))?;
+            let write_fd: RawFd = write_str
+                .parse()
+                .with_context(|| format!("invalid jobserver write fd in '{auth}'"))?;
+            Ok(Self::from_fds(read_fd, write_fd))
+        }
+
+        fn from_fds(read_fd: RawFd, write_fd: RawFd) -> Self {
+            JobServer {
+                read_fd,
+                write_fd,
+                implicit_available: AtomicBool::new(true),
+            }
+        }
+
+        /// Create a standalone jobserver (no inherited `make`), priming the
+        /// pipe with `parallelism - 1` tokens.
+        pub(super) fn new_standalone(parallelism: usize) -> Result<Self> {
+            let mut fds: [RawFd; 2] = [0, 0];
+            let rc = unsafe { libc::pipe(fds.as_mut_ptr()) };
+            if rc != 0 {
+                bail!(
+                    "failed to create jobserver pipe: {}",
+                    io::Error::last_os_error()
+                );
+            }
+            let server = Self::from_fds(fds[0], fds[1]);
+            for _ in 0..parallelism.saturating_sub(1) {
+                server.release_token()?;
+            }
+            Ok(server)
+        }
+
+        /// Acquire a job slot, blocking until one is available. Returns the
+        /// implicit slot for free when it isn't already held, otherwise
+        /// reads an explicit token from the pipe/FIFO.
+        pub fn acquire(&self) -> Result<JobSlot<'_>> {
+            if self
+                .implicit_available
+                .compare_exchange(true, false, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+            {
+                return Ok(JobSlot::Implicit(self));
+            }
+
+            let mut byte: u8 = 0;
+            loop {
+                let rc =
+                    unsafe { libc::read(self.read_fd, &mut byte as *mut u8 as *mut _, 1) };
+                if rc == 1 {
+                    return Ok(JobSlot::Token(self));
+                }
+                if rc < 0 {
+                    let err = io::Error::last_os_error();
+                    if err.kind() == io::ErrorKind::Interrupted {
+                        continue;
+                    }
+                    bail!("failed to read jobserver token: {err}");
+                }
+                bail!("jobserver pipe closed unexpectedly");
+            }
+        }
+
+        fn release_token(&self) -> Result<()> {
+            let byte: u8 = b'+';
+            loop {
+                let rc =
+                    unsafe { libc::write(self.write_fd, &byte as *const u8 as *const _, 1) };
+                if rc == 1 {
+                    return Ok(());
+                }
+                if rc < 0 {
+                    let err = io::Error::last_os_error();
+                    if err.kind() == io::ErrorKind::Interrupted {
+                        continue;
+                    }
+                    bail!("failed to write jobserver token: {err}");
+                }
+                bail!("failed to write jobserver token: short write");
+            }
+        }
+
+        /// The `--jobserver-auth=R,W` value to export via `MAKEFLAGS` for
+        /// child processes, so a spawned `fence-run` invocation (or a
+        /// nested `make`) can cooperate with this same pool.
+        pub fn auth_arg(&self) -> String {
+            format!("--jobserver-auth={},{}", self.read_fd, self.write_fd)
+        }
+    }
+
+    impl Drop for JobSlot<'_> {
+        fn drop(&mut self) {
+            match self {
+                JobSlot::Implicit(server) => {
+                    server.implicit_available.store(true, Ordering::SeqCst);
+                }
+                JobSlot::Token(server) => {
+                    if let Err(err) = server.release_token() {
+                        eprintln!("fence-bang: failed to release jobserver token: {err:#}");
+                    }
+                }
+            }
+        }
+    }
+
+    fn open_fifo(path: &str, flags: i32) -> Result<RawFd> {
+        let c_path = std::ffi::CString::new(path)
+            .with_context(|| format!("invalid jobserver fifo path: {path}"))?;
+        let fd = unsafe { libc::open(c_path.as_ptr(), flags) };
+        if fd < 0 {
+            bail!(
+                "failed to open jobserver fifo {path}: {}",
+                io::Error::last_os_error()
+            );
+        }
+        Ok(fd)
+    }
+}
+
+#[cfg(not(unix))]
+mod unix_impl {
+    use anyhow::{Result, bail};
+
+    pub struct JobServer;
+
+    pub enum JobSlot<'a> {
+        _Unreachable(&'a JobServer),
+    }
+
+    impl JobServer {
+        pub(super) fn from_makeflags(_makeflags: &str) -> Result<Option<Self>> {
+            Ok(None)
+        }
+
+        pub(super) fn new_standalone(_parallelism: usize) -> Result<Self> {
+            bail!("jobserver support requires a unix target")
+        }
+
+        pub fn acquire(&self) -> Result<JobSlot<'_>> {
+            bail!("jobserver support requires a unix target")
+        }
+
+        pub fn auth_arg(&self) -> String {
+            String::new()
+        }
+    }
+}
+
+pub use unix_impl::{JobServer, JobSlot};
@@ -0,0 +1,252 @@
+//! JUnit XML rendering for gate binaries.
+//!
+//! Fencerunner's gates (`probe-gate`, `fence-run matrix`, `probe-listen
+//! --expect`) historically only print free-form human text and set an exit
+//! code, which works for a human at a terminal but gives CI nothing to parse.
+//! [`JunitSuite`]/[`JunitCase`] are a small, format-agnostic result shape any
+//! gate can build, and [`render_junit_xml`] turns them into the
+//! `<testsuites>`/`<testsuite>`/`<testcase>` document most CI systems already
+//! know how to ingest, without pulling in an XML crate for three elements.
+
+use std::fmt::Write as _;
+
+/// One gate phase (e.g. "static contract", "dynamic contract", a run mode),
+/// rendered as a `<testsuite>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JunitSuite {
+    pub name: String,
+    pub cases: Vec<JunitCase>,
+}
+
+/// Outcome of one [`JunitCase`]: a pass, or a failure/error carrying the
+/// captured diagnostic text. JUnit distinguishes a checked assertion that
+/// didn't hold (`<failure>`) from the case not completing at all
+/// (`<error>`); callers that don't need the distinction can just always use
+/// `Failure`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JunitOutcome {
+    Pass,
+    Failure(String),
+    Error(String),
+}
+
+impl JunitOutcome {
+    fn is_failure(&self) -> bool {
+        matches!(self, JunitOutcome::Failure(_))
+    }
+
+    fn is_error(&self) -> bool {
+        matches!(self, JunitOutcome::Error(_))
+    }
+}
+
+/// One checked probe within a [`JunitSuite`], rendered as a `<testcase>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JunitCase {
+    pub probe_id: String,
+    /// Overrides the `<testcase classname="...">` attribute for this case;
+    /// falls back to the enclosing [`JunitSuite::name`] when `None`, which is
+    /// the right default for gates with one classname per suite. Callers
+    /// that key classname per case instead (e.g. fence-listen's capability
+    /// id) set it explicitly.
+    pub classname: Option<String>,
+    pub outcome: JunitOutcome,
+}
+
+impl JunitSuite {
+    pub fn failure_count(&self) -> usize {
+        self.cases
+            .iter()
+            .filter(|case| case.outcome.is_failure())
+            .count()
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.cases
+            .iter()
+            .filter(|case| case.outcome.is_error())
+            .count()
+    }
+}
+
+/// Render `suites` as a single JUnit XML document: one root `<testsuites>`
+/// wrapping one `<testsuite>` per phase, each containing one `<testcase>`
+/// per probe id and a `<failure>`/`<error>` child carrying the captured
+/// diagnostic text for any probe that didn't pass that phase's gate.
+pub fn render_junit_xml(suites: &[JunitSuite]) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(out, "<testsuites>");
+    for suite in suites {
+        let total = suite.cases.len();
+        let failures = suite.failure_count();
+        let errors = suite.error_count();
+        let _ = writeln!(
+            out,
+            "  <testsuite name=\"{}\" tests=\"{total}\" failures=\"{failures}\" errors=\"{errors}\">",
+            escape_xml(&suite.name)
+        );
+        for case in &suite.cases {
+            let classname = case.classname.as_deref().unwrap_or(&suite.name);
+            match &case.outcome {
+                JunitOutcome::Pass => {
+                    let _ = writeln!(
+                        out,
+                        "    <testcase name=\"{}\" classname=\"{}\"/>",
+                        escape_xml(&case.probe_id),
+                        escape_xml(classname)
+                    );
+                }
+                JunitOutcome::Failure(message) => {
+                    let _ = writeln!(
+                        out,
+                        "    <testcase name=\"{}\" classname=\"{}\">",
+                        escape_xml(&case.probe_id),
+                        escape_xml(classname)
+                    );
+                    let _ = writeln!(
+                        out,
+                        "      <failure message=\"gate failed\">{}</failure>",
+                        escape_xml(message)
+                    );
+                    let _ = writeln!(out, "    </testcase>");
+                }
+                JunitOutcome::Error(message) => {
+                    let _ = writeln!(
+                        out,
+                        "    <testcase name=\"{}\" classname=\"{}\">",
+                        escape_xml(&case.probe_id),
+                        escape_xml(classname)
+                    );
+                    let _ = writeln!(
+                        out,
+                        "      <error message=\"gate error\">{}</error>",
+                        escape_xml(message)
+                    );
+                    let _ = writeln!(out, "    </testcase>");
+                }
+            }
+        }
+        let _ = writeln!(out, "  </testsuite>");
+    }
+    out.push_str("</testsuites>\n");
+    out
+}
+
+fn escape_xml(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '&' => "&amp;".to_string(),
+            '<' => "&lt;".to_string(),
+            '>' => "&gt;".to_string(),
+            '"' => "&quot;".to_string(),
+            '\'' => "&apos;".to_string(),
+            other => other.to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_junit_xml_reports_passing_and_failing_cases() {
+        let suites = vec![JunitSuite {
+            name: "static-contract".to_string(),
+            cases: vec![
+                JunitCase {
+                    probe_id: "fs-read".to_string(),
+                    classname: None,
+                    outcome: JunitOutcome::Pass,
+                },
+                JunitCase {
+                    probe_id: "net-connect".to_string(),
+                    classname: None,
+                    outcome: JunitOutcome::Failure("missing # fence-expect directive".to_string()),
+                },
+            ],
+        }];
+
+        let xml = render_junit_xml(&suites);
+        assert!(xml.contains(
+            "<testsuite name=\"static-contract\" tests=\"2\" failures=\"1\" errors=\"0\">"
+        ));
+        assert!(xml.contains("<testcase name=\"fs-read\" classname=\"static-contract\"/>"));
+        assert!(xml.contains("<testcase name=\"net-connect\" classname=\"static-contract\">"));
+        assert!(xml.contains("missing # fence-expect directive"));
+    }
+
+    #[test]
+    fn render_junit_xml_reports_error_cases_separately_from_failures() {
+        let suites = vec![JunitSuite {
+            name: "listen".to_string(),
+            cases: vec![JunitCase {
+                probe_id: "probe-crash".to_string(),
+                classname: None,
+                outcome: JunitOutcome::Error("probe terminated by signal".to_string()),
+            }],
+        }];
+
+        let xml = render_junit_xml(&suites);
+        assert!(xml.contains("tests=\"1\" failures=\"0\" errors=\"1\""));
+        assert!(xml.contains("<error message=\"gate error\">probe terminated by signal</error>"));
+    }
+
+    #[test]
+    fn render_junit_xml_escapes_reserved_characters() {
+        let suites = vec![JunitSuite {
+            name: "static-contract".to_string(),
+            cases: vec![JunitCase {
+                probe_id: "cap<\"test\">".to_string(),
+                classname: None,
+                outcome: JunitOutcome::Failure("a & b".to_string()),
+            }],
+        }];
+
+        let xml = render_junit_xml(&suites);
+        assert!(xml.contains("cap&lt;&quot;test&quot;&gt;"));
+        assert!(xml.contains("a &amp; b"));
+    }
+
+    #[test]
+    fn render_junit_xml_uses_per_case_classname_override_when_set() {
+        let suites = vec![JunitSuite {
+            name: "fence-listen".to_string(),
+            cases: vec![JunitCase {
+                probe_id: "denied_probe".to_string(),
+                classname: Some("cap_sample".to_string()),
+                outcome: JunitOutcome::Failure("denied".to_string()),
+            }],
+        }];
+
+        let xml = render_junit_xml(&suites);
+        assert!(xml.contains("<testcase name=\"denied_probe\" classname=\"cap_sample\">"));
+    }
+
+    #[test]
+    fn failure_count_counts_only_failing_cases() {
+        let suite = JunitSuite {
+            name: "static-contract".to_string(),
+            cases: vec![
+                JunitCase {
+                    probe_id: "a".to_string(),
+                    classname: None,
+                    outcome: JunitOutcome::Pass,
+                },
+                JunitCase {
+                    probe_id: "b".to_string(),
+                    classname: None,
+                    outcome: JunitOutcome::Failure("boom".to_string()),
+                },
+                JunitCase {
+                    probe_id: "c".to_string(),
+                    classname: None,
+                    outcome: JunitOutcome::Error("boom".to_string()),
+                },
+            ],
+        };
+        assert_eq!(suite.failure_count(), 1);
+        assert_eq!(suite.error_count(), 1);
+    }
+}
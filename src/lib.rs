@@ -11,33 +11,85 @@ use serde_json::Value;
 use std::collections::BTreeMap;
 use std::{
     env,
+    fmt,
     fs,
+    io::{BufRead, BufReader, Read},
     path::{Path, PathBuf},
 };
 
 pub mod boundary;
+pub mod boundary_diff;
 pub mod catalog;
 pub mod connectors;
 pub mod coverage;
 pub mod emit_support;
+pub mod enforcement;
+pub mod execution_backend;
+pub mod execution_trace;
+pub mod expectation_matrix;
 pub mod fence_run_support;
+pub mod grounding;
+pub mod handshake;
+pub mod jobserver;
+pub mod junit;
 pub mod metadata_validation;
 pub mod probe_metadata;
+pub mod problem_matcher;
+pub mod provenance;
+pub mod reporter;
 pub mod runtime;
 pub(crate) mod schema_loader;
+pub mod signing;
+pub mod snapshot;
 
 pub use boundary::{
     BoundaryObject, BoundaryReadError, BoundarySchema, CapabilityContext, OperationInfo, Payload,
     ProbeInfo, ResultInfo, RunInfo, StackInfo, read_boundary_objects,
 };
+pub use boundary_diff::{
+    BoundaryDiffReport, BoundaryTransition, CapabilityDiffSummary, UnpairedRecord, UnpairedSide,
+    diff_boundary_streams, render_diff_human,
+};
 pub use catalog::{
-    Capability, CapabilityCatalog, CapabilityCategory, CapabilityId, CapabilityIndex,
-    CapabilityLayer, CapabilitySnapshot, CatalogKey, CatalogRepository, DEFAULT_CATALOG_PATH,
-    load_catalog_from_path,
+    Capability, CapabilityCatalog, CapabilityCategory, CapabilityGrant, CapabilityId,
+    CapabilityIndex, CapabilityLayer, CapabilityMergeConflict, CapabilitySnapshot, CatalogKey,
+    CatalogRepository, CatalogVersion, Compatibility, Criticality, DEFAULT_CATALOG_PATH,
+    DelegationError, SatisfactionReport, ViolationKind, check_requested_capability,
+    load_catalog_from_path, verify_attenuation_chain,
+};
+pub use coverage::{
+    CapabilityCoverageReport, CoverageEntry, CoverageStats, CoverageSeverity, CoverageVerdict,
+    build_probe_coverage_map, capability_coverage, evaluate_coverage, filter_coverage_probes,
+    render_coverage_dot,
+};
+pub use execution_trace::{
+    ExecutionTrace, TraceOp, capture_direct, classify_result, parse_trace_ops_ndjson,
+    trace_requested, wrap_without_ptrace,
+};
+pub use expectation_matrix::{
+    ExpectationRow, ExpectationTable, MatrixEntry, MatrixOutcome, MatrixReport, classify_matrix,
+    render_matrix_human,
+};
+pub use grounding::validate_capability_grounding;
+pub use handshake::{NegotiationResult, VersionInfo, negotiate, query_protocol_version};
+pub use jobserver::{JobServer, JobSlot};
+pub use junit::{JunitCase, JunitOutcome, JunitSuite, render_junit_xml};
+pub use metadata_validation::{
+    CapabilityExtractionRule, load_capability_extraction_rules, validate_boundary_objects,
+    validate_probe_capabilities, validate_schema_compatibility,
 };
-pub use coverage::{CoverageEntry, build_probe_coverage_map, filter_coverage_probes};
-pub use metadata_validation::{validate_boundary_objects, validate_probe_capabilities};
 pub use probe_metadata::{ProbeMetadata, collect_probe_scripts};
+pub use provenance::{
+    ProvenanceEdge, ProvenanceGraph, ProvenanceNode, build_provenance_graph,
+    provenance_graph_from_dirs, to_dot,
+};
+pub use reporter::{OutputFormat, Verbosity};
+pub use signing::{sign_record, verify_record};
+pub use snapshot::{
+    FieldDiff, accept_golden_snapshots, check_boundary_object_against_template, compare_boundary,
+    default_redactions, diff_against_golden, diff_against_template, diff_records,
+    render_field_diffs,
+};
 
 // === Repository discovery and helper resolution ===
 const ROOT_SENTINEL: &str = "bin/.gitkeep";
@@ -47,6 +99,8 @@ const ENV_BOUNDARY_SCHEMA_PATH: &str = "BOUNDARY_PATH";
 const DEFAULTS_MANIFEST_PATH: &str = "catalogs/defaults.json";
 pub const DEFAULT_BOUNDARY_SCHEMA_PATH: &str = "catalogs/cfbo-v1.json";
 pub const CANONICAL_BOUNDARY_SCHEMA_PATH: &str = "schema/boundary_object_schema.json";
+pub const CANONICAL_CAPABILITY_CATALOG_SCHEMA_PATH: &str =
+    "schema/capability_catalog.schema.json";
 
 /// Default paths for catalog and boundary descriptors, resolved relative to a repo root.
 #[derive(Debug, Clone)]
@@ -229,11 +283,99 @@ pub fn split_list(value: &str) -> Vec<String> {
         .collect()
 }
 
+/// One NDJSON line that failed to parse as a [`BoundaryObject`], collected by
+/// [`collect_boundary_stream_lenient`] instead of aborting the whole read.
+#[derive(Debug)]
+pub struct LineError {
+    pub line_no: usize,
+    pub raw: String,
+    pub cause: serde_json::Error,
+}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: unable to parse boundary object ({})",
+            self.line_no, self.cause
+        )
+    }
+}
+
+impl std::error::Error for LineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.cause)
+    }
+}
+
+/// Parse a boundary-object NDJSON stream line-by-line as bytes arrive from
+/// `reader`, yielding one result per non-blank line.
+///
+/// This is the streaming counterpart to [`parse_json_stream`]'s whole-buffer
+/// fast path and is what piped stdin should use by default: a single partial
+/// write late in a long matrix run only poisons the lines after it, not every
+/// record already parsed. The iterator itself never stops at a bad line —
+/// `.collect::<Result<Vec<_>, _>>()` gives the strict, bail-on-first-error
+/// behavior, while [`collect_boundary_stream_lenient`] keeps going and
+/// reports every bad line instead.
+pub fn parse_boundary_stream<R: Read>(
+    reader: R,
+) -> impl Iterator<Item = Result<BoundaryObject, LineError>> {
+    let mut lines = BufReader::new(reader).lines();
+    let mut line_no = 0usize;
+    std::iter::from_fn(move || loop {
+        let line = match lines.next()? {
+            Ok(line) => line,
+            Err(err) => {
+                line_no += 1;
+                return Some(Err(LineError {
+                    line_no,
+                    raw: String::new(),
+                    cause: serde_json::Error::from(err),
+                }));
+            }
+        };
+        line_no += 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        return Some(
+            serde_json::from_str::<BoundaryObject>(trimmed).map_err(|cause| LineError {
+                line_no,
+                raw: trimmed.to_string(),
+                cause,
+            }),
+        );
+    })
+}
+
+/// Consume `reader` as a boundary-object NDJSON stream, keeping every line
+/// that parses and collecting a [`LineError`] for every line that doesn't,
+/// instead of aborting on the first one.
+pub fn collect_boundary_stream_lenient<R: Read>(
+    reader: R,
+) -> (Vec<BoundaryObject>, Vec<LineError>) {
+    let mut records = Vec::new();
+    let mut errors = Vec::new();
+    for item in parse_boundary_stream(reader) {
+        match item {
+            Ok(record) => records.push(record),
+            Err(err) => errors.push(err),
+        }
+    }
+    (records, errors)
+}
+
 /// Parse a boundary-object stream from stdin, accepting either NDJSON or a JSON array.
 ///
 /// The parser mirrors the listener contract: empty input is an error, single
 /// boundary objects or arrays are accepted, and NDJSON streams are parsed
-/// line-by-line so partial writes do not break the whole run.
+/// line-by-line (via [`parse_boundary_stream`]) so partial writes do not break
+/// the whole run. This whole-buffer entry point always applies the strict,
+/// bail-on-first-error behavior; callers reading directly from a pipe that
+/// want to tolerate a partially-corrupt stream should use
+/// [`collect_boundary_stream_lenient`] instead.
 pub fn parse_json_stream(input: &str) -> Result<Vec<BoundaryObject>> {
     let trimmed = input.trim();
     if trimmed.is_empty() {
@@ -254,16 +396,9 @@ pub fn parse_json_stream(input: &str) -> Result<Vec<BoundaryObject>> {
         };
     }
 
-    let mut records = Vec::new();
-    for (idx, line) in trimmed.lines().enumerate() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
-        let obj: BoundaryObject = serde_json::from_str(line)
-            .with_context(|| format!("Unable to parse boundary object from line {}", idx + 1))?;
-        records.push(obj);
-    }
+    let records: Vec<BoundaryObject> = parse_boundary_stream(trimmed.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| anyhow::anyhow!(err))?;
 
     if records.is_empty() {
         bail!("No boundary objects found in input stream");
@@ -324,6 +459,21 @@ pub fn resolve_probe(repo_root: &Path, identifier: &str) -> Result<Probe> {
             if let Ok(canonical) = fs::canonicalize(&candidate) {
                 if canonical.starts_with(&probes_root) {
                     if let Some(stem) = canonical.file_stem().and_then(|s| s.to_str()) {
+                        if let Some(predicate) = probe_platform_cfg(&canonical) {
+                            let cfg = connectors::host_cfg_map();
+                            let allowed = connectors::eval_cfg_predicate(&predicate, &cfg)
+                                .with_context(|| {
+                                    format!(
+                                        "invalid platform_cfg in probe {}: {predicate}",
+                                        canonical.display()
+                                    )
+                                })?;
+                            if !allowed {
+                                bail!(
+                                    "Probe {identifier} is excluded on this host by its platform_cfg ({predicate})"
+                                );
+                            }
+                        }
                         return Ok(Probe {
                             id: stem.to_string(),
                             path: canonical,
@@ -337,6 +487,16 @@ pub fn resolve_probe(repo_root: &Path, identifier: &str) -> Result<Probe> {
     bail!("Probe not found: {identifier}")
 }
 
+/// A probe's `platform_cfg="cfg(...)"` declaration, if its script parses
+/// cleanly. Scripts that fail to read are treated as having no predicate so a
+/// transient I/O error surfaces later (e.g. when the probe is actually run)
+/// rather than here.
+fn probe_platform_cfg(path: &Path) -> Option<String> {
+    ProbeMetadata::from_script(path)
+        .ok()
+        .and_then(|metadata| metadata.platform_cfg)
+}
+
 /// List all probe scripts under `probes/`.
 ///
 /// Only `.sh` files are considered, and the file stem becomes the probe id.
@@ -355,6 +515,20 @@ pub fn list_probes(repo_root: &Path) -> Result<Vec<Probe>> {
             continue;
         }
         let canonical = fs::canonicalize(&path)?;
+        if let Some(predicate) = probe_platform_cfg(&canonical) {
+            let cfg = connectors::host_cfg_map();
+            match connectors::eval_cfg_predicate(&predicate, &cfg) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(err) => {
+                    eprintln!(
+                        "warning: skipping probe {} with invalid platform_cfg ({predicate}): {err:#}",
+                        canonical.display()
+                    );
+                    continue;
+                }
+            }
+        }
         if let Some(stem) = canonical.file_stem().and_then(|s| s.to_str()) {
             results.insert(
                 stem.to_string(),
@@ -403,6 +577,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_boundary_stream_yields_one_result_per_line() {
+        let serialized =
+            serde_json::to_string(&sample_record_json()).expect("serialize sample record");
+        let ndjson = format!("{0}\nnot-json\n{0}\n", serialized);
+
+        let results: Vec<_> = parse_boundary_stream(ndjson.as_bytes()).collect();
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok());
+        let err = results[1].as_ref().expect_err("line 2 should fail");
+        assert_eq!(err.line_no, 2);
+        assert_eq!(err.raw, "not-json");
+        assert!(results[2].is_ok());
+    }
+
+    #[test]
+    fn collect_boundary_stream_lenient_keeps_good_lines_and_reports_bad_ones() {
+        let serialized =
+            serde_json::to_string(&sample_record_json()).expect("serialize sample record");
+        let ndjson = format!("{0}\nnot-json\n{0}\n", serialized);
+
+        let (records, errors) = collect_boundary_stream_lenient(ndjson.as_bytes());
+        assert_eq!(records.len(), 2, "both valid lines should survive");
+        assert_eq!(errors.len(), 1, "the malformed line should be reported");
+        assert_eq!(errors[0].line_no, 2);
+    }
+
     fn sample_record_json() -> serde_json::Value {
         json!({
             "schema_version": "boundary_event_v1",
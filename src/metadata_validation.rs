@@ -4,14 +4,110 @@
 //! capability IDs and that stored boundary objects remain in sync with the
 //! current catalog snapshot.
 
-use crate::catalog::{CapabilityId, CapabilityIndex};
+use crate::catalog::{CapabilityId, CapabilityIndex, CatalogKey, CatalogVersion, Compatibility};
 use crate::probe_metadata::ProbeMetadata;
-use anyhow::Result;
+use anyhow::{Context, Result};
+use serde::Deserialize;
 use serde_json::Value;
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// A capability-bearing location described by a JSONPath expression rather
+/// than a hardcoded [`Value::pointer`] lookup, so new boundary-object shapes
+/// or schema revisions can be covered by editing a config file instead of
+/// recompiling the validator.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CapabilityExtractionRule {
+    /// Human-readable name surfaced in validation errors, e.g.
+    /// `"capability_context.secondary[*].id"`.
+    pub label: String,
+    /// JSONPath expression evaluated against each parsed boundary object.
+    pub path: String,
+}
+
+/// Default rules, equivalent to the four `Value::pointer` lookups this
+/// extractor replaces.
+pub(crate) fn default_capability_extraction_rules() -> Vec<CapabilityExtractionRule> {
+    [
+        (
+            "probe.primary_capability_id",
+            "$.probe.primary_capability_id",
+        ),
+        (
+            "probe.secondary_capability_ids[*]",
+            "$.probe.secondary_capability_ids[*]",
+        ),
+        (
+            "capability_context.primary.id",
+            "$.capability_context.primary.id",
+        ),
+        (
+            "capability_context.secondary[*].id",
+            "$.capability_context.secondary[*].id",
+        ),
+    ]
+    .into_iter()
+    .map(|(label, path)| CapabilityExtractionRule {
+        label: label.to_string(),
+        path: path.to_string(),
+    })
+    .collect()
+}
+
+/// Load extraction rules from a JSON config file (an array of
+/// `{"label": ..., "path": ...}` objects).
+pub fn load_capability_extraction_rules(path: &Path) -> Result<Vec<CapabilityExtractionRule>> {
+    let data = fs::read_to_string(path)
+        .with_context(|| format!("reading extraction rules {}", path.display()))?;
+    serde_json::from_str(&data)
+        .with_context(|| format!("parsing extraction rules {}", path.display()))
+}
+
+/// A capability ID found by a rule, along with which rule and JSON path
+/// located it, so callers can report *where* an unknown capability came from
+/// rather than a flat "references unknown capability" string.
+///
+/// `rule_label` doubles as the primary/secondary discriminator: labels for
+/// secondary-slot rules contain `"secondary"` (see
+/// [`default_capability_extraction_rules`]), which coverage accounting relies
+/// on to split tallies without a separate enum.
+pub(crate) struct ExtractedCapability {
+    pub(crate) id: CapabilityId,
+    pub(crate) rule_label: String,
+    pub(crate) json_path: String,
+}
+
+pub(crate) fn extract_capability_ids(
+    value: &Value,
+    rules: &[CapabilityExtractionRule],
+) -> Vec<ExtractedCapability> {
+    let mut found = Vec::new();
+    for rule in rules {
+        let Ok(matches) = jsonpath_lib::select(value, &rule.path) else {
+            continue;
+        };
+        for matched in matches {
+            if let Some(id) = matched.as_str() {
+                found.push(ExtractedCapability {
+                    id: CapabilityId(id.to_string()),
+                    rule_label: rule.label.clone(),
+                    json_path: rule.path.clone(),
+                });
+            }
+        }
+    }
+    found
+}
+
+/// Checks every probe's declared capability ids against `capabilities`, the
+/// same way [`crate::coverage::account_probe_coverage`] does for coverage
+/// accounting, but for reporting the problems directly to a user. Each
+/// caret-annotated [`ProbeMetadata::diagnostics`] entry collected while
+/// scraping the script (a `$`-substituted id, a missing
+/// `primary_capability_id=`) is rendered via
+/// [`crate::probe_metadata::ProbeDiagnostic::render`] alongside the
+/// unknown-id checks below.
 pub fn validate_probe_capabilities(
     capabilities: &CapabilityIndex,
     probes: &[ProbeMetadata],
@@ -21,8 +117,17 @@ pub fn validate_probe_capabilities(
     let mut errors = Vec::new();
     for probe in probes {
         let display = probe.script.display();
+        for diagnostic in &probe.diagnostics {
+            errors.push(diagnostic.render(&probe.script));
+        }
         let Some(primary) = &probe.primary_capability else {
-            errors.push(format!("{display} is missing primary_capability_id"));
+            // `parse_primary_capability` records a diagnostic when the
+            // declaration is absent or `$`-substituted; this fallback covers an
+            // empty value and hand-built `ProbeMetadata` fixtures, and only
+            // reports when no other diagnostic was collected for the probe.
+            if probe.diagnostics.is_empty() {
+                errors.push(format!("{display} is missing primary_capability_id"));
+            }
             continue;
         };
         if capabilities.capability(primary).is_none() {
@@ -46,7 +151,17 @@ pub fn validate_probe_capabilities(
 pub fn validate_boundary_objects(
     capabilities: &CapabilityIndex,
     dirs: &[PathBuf],
+    rules: Option<&[CapabilityExtractionRule]>,
 ) -> Result<Vec<String>> {
+    let default_rules;
+    let rules = match rules {
+        Some(rules) => rules,
+        None => {
+            default_rules = default_capability_extraction_rules();
+            &default_rules
+        }
+    };
+
     let mut errors = Vec::new();
     let json_files = find_json_files(dirs)?;
     for json_file in json_files {
@@ -66,18 +181,20 @@ pub fn validate_boundary_objects(
             }
         };
 
+        // Avoid spamming the same missing capability multiple times when it
+        // appears in both probe and context sections.
         let mut seen = BTreeSet::new();
-        for cap_id in extract_capability_ids(&value) {
-            // Avoid spamming the same missing capability multiple times when it
-            // appears in both probe and context sections.
-            if !seen.insert(cap_id.clone()) {
+        for extracted in extract_capability_ids(&value, rules) {
+            if !seen.insert(extracted.id.clone()) {
                 continue;
             }
-            if capabilities.capability(&cap_id).is_none() {
+            if capabilities.capability(&extracted.id).is_none() {
                 errors.push(format!(
-                    "{} references unknown capability '{}'",
+                    "{}: rule '{}' ({}) references unknown capability '{}'",
                     json_file.display(),
-                    cap_id.0
+                    extracted.rule_label,
+                    extracted.json_path,
+                    extracted.id.0
                 ));
             }
         }
@@ -85,7 +202,90 @@ pub fn validate_boundary_objects(
     Ok(errors)
 }
 
-fn find_json_files(dirs: &[PathBuf]) -> Result<Vec<PathBuf>> {
+/// Confirm every boundary object's `capabilities_schema_version` is
+/// compatible with the catalog `capabilities` was loaded from.
+///
+/// A record matching the catalog's own key always passes, as does one
+/// [`CatalogVersion::negotiate`] reports `Exact` or `BackwardCompatible`
+/// against the loaded catalog's version. `compatibility` additionally allows
+/// specific older record versions to validate against specific catalog keys
+/// (e.g. while a catalog migration is in flight); record versions absent from
+/// that table are flagged distinctly as "unknown/forward schema" rather than
+/// a plain mismatch, since the tool has no record of whether they predate or
+/// postdate the loaded catalog.
+pub fn validate_schema_compatibility(
+    capabilities: &CapabilityIndex,
+    dirs: &[PathBuf],
+    compatibility: Option<&BTreeMap<String, BTreeSet<String>>>,
+) -> Result<Vec<String>> {
+    let catalog_key = capabilities.key().0.as_str();
+    let mut errors = Vec::new();
+
+    for json_file in find_json_files(dirs)? {
+        let data = fs::read_to_string(&json_file)
+            .with_context(|| format!("reading {}", json_file.display()))?;
+        let value: Value = match serde_json::from_str(&data) {
+            Ok(value) => value,
+            Err(err) => {
+                errors.push(format!("{}: invalid JSON: {err}", json_file.display()));
+                continue;
+            }
+        };
+
+        let Some(record_version) = value
+            .get("capabilities_schema_version")
+            .and_then(Value::as_str)
+        else {
+            errors.push(format!(
+                "{}: missing capabilities_schema_version",
+                json_file.display()
+            ));
+            continue;
+        };
+
+        if record_version == catalog_key {
+            continue;
+        }
+
+        // Beyond an exact string match, defer to CatalogVersion::negotiate so a
+        // record produced under an additive, backward-compatible minor revision
+        // of the loaded catalog's family/major is accepted without needing an
+        // explicit `compatibility` table entry. Versions that don't parse as
+        // `<family>_v<major>` (or that negotiate as incompatible) fall through
+        // to the table/unknown-schema handling below.
+        let negotiated = match (
+            CatalogVersion::parse(&CatalogKey(record_version.to_string())),
+            CatalogVersion::parse(&CatalogKey(catalog_key.to_string())),
+        ) {
+            (Ok(record_version), Ok(catalog_version)) => {
+                CatalogVersion::negotiate(&record_version, &catalog_version)
+            }
+            _ => Compatibility::Incompatible,
+        };
+        if matches!(
+            negotiated,
+            Compatibility::Exact | Compatibility::BackwardCompatible
+        ) {
+            continue;
+        }
+
+        match compatibility.and_then(|table| table.get(record_version)) {
+            Some(allowed) if allowed.contains(catalog_key) => {}
+            Some(_) => errors.push(format!(
+                "{}: capabilities_schema_version '{record_version}' is not compatible with catalog '{catalog_key}'",
+                json_file.display()
+            )),
+            None => errors.push(format!(
+                "{}: capabilities_schema_version '{record_version}' is unrecognized (unknown/forward schema, catalog is '{catalog_key}')",
+                json_file.display()
+            )),
+        }
+    }
+
+    Ok(errors)
+}
+
+pub(crate) fn find_json_files(dirs: &[PathBuf]) -> Result<Vec<PathBuf>> {
     let mut files = Vec::new();
     for dir in dirs {
         collect_json(dir, &mut files)?;
@@ -110,49 +310,6 @@ fn collect_json(dir: &Path, acc: &mut Vec<PathBuf>) -> Result<()> {
     Ok(())
 }
 
-fn extract_capability_ids(value: &Value) -> Vec<CapabilityId> {
-    let mut ids = Vec::new();
-    if let Some(id) = value
-        .pointer("/probe/primary_capability_id")
-        .and_then(Value::as_str)
-    {
-        ids.push(CapabilityId(id.to_string()));
-    }
-
-    if let Some(secondary) = value
-        .pointer("/probe/secondary_capability_ids")
-        .and_then(Value::as_array)
-    {
-        ids.extend(
-            secondary
-                .iter()
-                .filter_map(Value::as_str)
-                .map(|s| CapabilityId(s.to_string())),
-        );
-    }
-
-    if let Some(primary_ctx) = value
-        .pointer("/capability_context/primary/id")
-        .and_then(Value::as_str)
-    {
-        ids.push(CapabilityId(primary_ctx.to_string()));
-    }
-
-    if let Some(secondary_ctx) = value
-        .pointer("/capability_context/secondary")
-        .and_then(Value::as_array)
-    {
-        ids.extend(secondary_ctx.iter().filter_map(|entry| {
-            entry
-                .get("id")
-                .and_then(Value::as_str)
-                .map(|s| CapabilityId(s.to_string()))
-        }));
-    }
-
-    ids
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -169,12 +326,48 @@ mod tests {
             probe_version: Some("1".to_string()),
             primary_capability: Some(CapabilityId("cap_missing".to_string())),
             secondary_capabilities: vec![CapabilityId("cap_fs_read_workspace_tree".to_string())],
+            problem_matchers: Vec::new(),
+            platform_cfg: None,
+            has_dynamic_capability_reference: false,
+            expected_result: None,
+            expected_result_by_mode: BTreeMap::new(),
+            diagnostics: Vec::new(),
         };
         let errors = validate_probe_capabilities(&index, &[probe]);
         assert_eq!(errors.len(), 1);
         assert!(errors[0].contains("cap_missing"));
     }
 
+    #[test]
+    fn validate_probe_capabilities_renders_collected_diagnostics() {
+        use crate::probe_metadata::ProbeDiagnostic;
+
+        let index = sample_index().expect("sample index loads");
+        let probe = ProbeMetadata {
+            script: PathBuf::from("probe.sh"),
+            probe_name: Some("probe".to_string()),
+            probe_version: Some("1".to_string()),
+            primary_capability: Some(CapabilityId("cap_fs_read_workspace_tree".to_string())),
+            secondary_capabilities: Vec::new(),
+            problem_matchers: Vec::new(),
+            platform_cfg: None,
+            has_dynamic_capability_reference: true,
+            expected_result: None,
+            expected_result_by_mode: BTreeMap::new(),
+            diagnostics: vec![ProbeDiagnostic {
+                line: 12,
+                column: 23,
+                snippet: "secondary_capability_id=\"cap_$MODE\"".to_string(),
+                message: "dynamic capability id cannot be resolved statically".to_string(),
+            }],
+        };
+        let errors = validate_probe_capabilities(&index, &[probe]);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("dynamic capability id cannot be resolved statically"));
+        assert!(errors[0].contains("probe.sh:12:23"));
+        assert!(errors[0].contains('^'));
+    }
+
     #[test]
     fn validate_boundary_objects_reports_unknown_capabilities() {
         let index = sample_index().expect("sample index loads");
@@ -198,10 +391,12 @@ mod tests {
         });
         std::fs::write(&bo_path, serde_json::to_string(&record).unwrap()).unwrap();
 
-        let errors = validate_boundary_objects(&index, &[dir.path().to_path_buf()])
+        let errors = validate_boundary_objects(&index, &[dir.path().to_path_buf()], None)
             .expect("validation should run");
         assert_eq!(errors.len(), 1);
         assert!(errors[0].contains("cap_missing"));
+        assert!(errors[0].contains("probe.primary_capability_id"));
+        assert!(errors[0].contains("$.probe.primary_capability_id"));
     }
 
     #[test]
@@ -229,7 +424,7 @@ mod tests {
         });
         std::fs::write(&bo_path, serde_json::to_string(&record).unwrap()).unwrap();
 
-        let errors = validate_boundary_objects(&index, &[root.path().to_path_buf()])
+        let errors = validate_boundary_objects(&index, &[root.path().to_path_buf()], None)
             .expect("validation should run");
         assert!(
             errors.is_empty(),
@@ -237,6 +432,139 @@ mod tests {
         );
     }
 
+    #[test]
+    fn validate_boundary_objects_honors_custom_rules() {
+        let index = sample_index().expect("sample index loads");
+        let dir = tempfile::tempdir().expect("temp dir");
+        let bo_path = dir.path().join("bo.json");
+        let record = json!({
+            "schema_version": "cfbo-v1",
+            "capabilities_schema_version": "macOS_codex_v1",
+            "stack": {"os": "Darwin"},
+            "probe": {
+                "id": "probe",
+                "version": "1",
+                "primary_capability_id": "cap_fs_read_workspace_tree",
+                "secondary_capability_ids": []
+            },
+            "run": {"mode": "baseline", "workspace_root": "/tmp", "command": "true"},
+            "operation": {"category": "fs", "verb": "read", "target": "/tmp", "args": {}},
+            "result": {"observed_result": "success", "raw_exit_code": 0, "errno": null, "message": null, "error_detail": null},
+            "payload": {"stdout_snippet": null, "stderr_snippet": null, "raw": {}},
+            "capability_context": {"primary": {"id": "cap_fs_read_workspace_tree", "category": "filesystem", "layer": "os_sandbox"}, "secondary": []},
+            "custom_extension": {"extra_capability_id": "cap_missing"}
+        });
+        std::fs::write(&bo_path, serde_json::to_string(&record).unwrap()).unwrap();
+
+        let rules = vec![CapabilityExtractionRule {
+            label: "custom_extension.extra_capability_id".to_string(),
+            path: "$.custom_extension.extra_capability_id".to_string(),
+        }];
+
+        let errors = validate_boundary_objects(&index, &[dir.path().to_path_buf()], Some(&rules))
+            .expect("validation should run");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("custom_extension.extra_capability_id"));
+        assert!(errors[0].contains("cap_missing"));
+    }
+
+    #[test]
+    fn load_capability_extraction_rules_parses_config_file() {
+        let mut file = NamedTempFile::new().expect("temp file");
+        serde_json::to_writer(
+            &mut file,
+            &json!([
+                {"label": "probe.primary_capability_id", "path": "$.probe.primary_capability_id"},
+            ]),
+        )
+        .unwrap();
+
+        let rules = load_capability_extraction_rules(file.path()).expect("rules load");
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].label, "probe.primary_capability_id");
+    }
+
+    #[test]
+    fn validate_schema_compatibility_accepts_matching_catalog() {
+        let index = sample_index().expect("sample index loads");
+        let dir = tempfile::tempdir().expect("temp dir");
+        std::fs::write(
+            dir.path().join("bo.json"),
+            serde_json::to_string(&json!({"capabilities_schema_version": "macOS_codex_v1"}))
+                .unwrap(),
+        )
+        .unwrap();
+
+        let errors = validate_schema_compatibility(&index, &[dir.path().to_path_buf()], None)
+            .expect("validation should run");
+        assert!(errors.is_empty(), "expected no errors, got {errors:?}");
+    }
+
+    #[test]
+    fn validate_schema_compatibility_accepts_negotiated_equivalent_version() {
+        // Differs from the catalog key as a string ("v01" vs "v1") but parses
+        // to the same family/major via `CatalogVersion`, so negotiation
+        // should accept it without needing a `compatibility` table entry.
+        let index = sample_index().expect("sample index loads");
+        let dir = tempfile::tempdir().expect("temp dir");
+        std::fs::write(
+            dir.path().join("bo.json"),
+            serde_json::to_string(&json!({"capabilities_schema_version": "macOS_codex_v01"}))
+                .unwrap(),
+        )
+        .unwrap();
+
+        let errors = validate_schema_compatibility(&index, &[dir.path().to_path_buf()], None)
+            .expect("validation should run");
+        assert!(
+            errors.is_empty(),
+            "expected negotiated version to be accepted, got {errors:?}"
+        );
+    }
+
+    #[test]
+    fn validate_schema_compatibility_flags_unknown_forward_schema() {
+        let index = sample_index().expect("sample index loads");
+        let dir = tempfile::tempdir().expect("temp dir");
+        std::fs::write(
+            dir.path().join("bo.json"),
+            serde_json::to_string(&json!({"capabilities_schema_version": "macOS_codex_v2"}))
+                .unwrap(),
+        )
+        .unwrap();
+
+        let errors = validate_schema_compatibility(&index, &[dir.path().to_path_buf()], None)
+            .expect("validation should run");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("unknown/forward schema"));
+    }
+
+    #[test]
+    fn validate_schema_compatibility_honors_compatibility_table() {
+        let index = sample_index().expect("sample index loads");
+        let dir = tempfile::tempdir().expect("temp dir");
+        std::fs::write(
+            dir.path().join("bo.json"),
+            serde_json::to_string(&json!({"capabilities_schema_version": "macOS_codex_v0"}))
+                .unwrap(),
+        )
+        .unwrap();
+
+        let mut table = BTreeMap::new();
+        table.insert(
+            "macOS_codex_v0".to_string(),
+            BTreeSet::from(["macOS_codex_v1".to_string()]),
+        );
+
+        let errors =
+            validate_schema_compatibility(&index, &[dir.path().to_path_buf()], Some(&table))
+                .expect("validation should run");
+        assert!(
+            errors.is_empty(),
+            "expected compatibility table to allow the older version, got {errors:?}"
+        );
+    }
+
     fn sample_index() -> Result<CapabilityIndex> {
         let mut file = NamedTempFile::new()?;
         serde_json::to_writer(
@@ -7,10 +7,14 @@
 
 use crate::catalog::CapabilityId;
 use anyhow::{Context, Result};
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+const DYNAMIC_CAPABILITY_MESSAGE: &str = "dynamic capability id cannot be resolved statically";
+const MISSING_PRIMARY_MESSAGE: &str = "missing required primary_capability_id";
+
 #[derive(Debug, Clone)]
 /// Partial probe metadata scraped from a shell script.
 ///
@@ -22,6 +26,89 @@ pub struct ProbeMetadata {
     pub probe_version: Option<String>,
     pub primary_capability: Option<CapabilityId>,
     pub secondary_capabilities: Vec<CapabilityId>,
+    /// Ordered `problem_matcher_pattern=` regex declarations; see
+    /// [`crate::problem_matcher`] for how these are compiled and applied.
+    pub problem_matchers: Vec<String>,
+    /// An optional `platform_cfg="cfg(...)"` declaration restricting which
+    /// hosts this probe is eligible to run on; see
+    /// [`crate::connectors::eval_cfg_predicate`] for the predicate grammar.
+    /// Probe discovery skips probes whose predicate doesn't hold on the
+    /// current host.
+    pub platform_cfg: Option<String>,
+    /// A default `expected_result="..."` declaration (e.g. `"denied"`,
+    /// `"success"`), used to assert the probe's observed result against a
+    /// declared expectation instead of only reporting it.
+    pub expected_result: Option<String>,
+    /// Per-mode overrides parsed from `expected_result_<mode>=` lines (e.g.
+    /// `expected_result_baseline=`, `expected_result_hardened=`), keyed by
+    /// mode name. Takes precedence over `expected_result` for that mode; see
+    /// [`ProbeMetadata::expected_result_for_mode`].
+    pub expected_result_by_mode: BTreeMap<String, String>,
+    /// True when a `primary_capability_id=`/`secondary_capability_id(s)=`
+    /// assignment contains a `$`-substitution that [`parse_token`] declined to
+    /// resolve into a [`CapabilityId`] (e.g. `secondary_capability_id="cap_$MODE"`).
+    /// Coverage accounting surfaces these scripts separately since their
+    /// capability ids can't be statically cross-referenced against the
+    /// catalog.
+    pub has_dynamic_capability_reference: bool,
+    /// Caret-annotated diagnostics collected while scraping capability ids,
+    /// e.g. a `$`-substituted id or a missing `primary_capability_id=`
+    /// declaration. Empty when parsing found nothing to flag. Render with
+    /// [`ProbeDiagnostic::render`] for a human-facing message; coverage
+    /// tooling can instead match on [`ProbeDiagnostic::message`].
+    pub diagnostics: Vec<ProbeDiagnostic>,
+}
+
+/// A single caret-annotated problem found while scraping a probe script,
+/// pointing at the 1-indexed line/column of the offending text the way a
+/// compiler error would, rather than just surfacing a bare missing field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProbeDiagnostic {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+    pub snippet: String,
+}
+
+impl ProbeDiagnostic {
+    fn new(line: usize, column: usize, snippet: &str, message: &str) -> Self {
+        Self {
+            line,
+            column,
+            message: message.to_string(),
+            snippet: snippet.to_string(),
+        }
+    }
+
+    /// Render as an `annotate-snippets`-style block: the message, a `-->`
+    /// location line, and the source line with a caret underline pointing at
+    /// `column`, e.g.:
+    ///
+    /// ```text
+    /// error: dynamic capability id cannot be resolved statically
+    ///    --> probes/foo.sh:12:30
+    ///    |
+    /// 12 | secondary_capability_id="cap_$MODE"
+    ///    |                              ^
+    /// ```
+    pub fn render(&self, script: &Path) -> String {
+        let label = self.line.to_string();
+        let gutter = " ".repeat(label.len());
+        let caret = " ".repeat(self.column.saturating_sub(1));
+        let mut out = String::new();
+        let _ = writeln!(out, "error: {}", self.message);
+        let _ = writeln!(
+            out,
+            "{gutter} --> {}:{}:{}",
+            script.display(),
+            self.line,
+            self.column
+        );
+        let _ = writeln!(out, "{gutter} |");
+        let _ = writeln!(out, "{label} | {}", self.snippet);
+        let _ = writeln!(out, "{gutter} | {caret}^");
+        out
+    }
 }
 
 impl ProbeMetadata {
@@ -32,15 +119,40 @@ impl ProbeMetadata {
     pub fn from_script(path: &Path) -> Result<Self> {
         let contents =
             fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        let (primary_capability, mut diagnostics) = parse_primary_capability(&contents);
+        let (secondary_capabilities, secondary_diagnostics) =
+            parse_secondary_capabilities(&contents);
+        diagnostics.extend(secondary_diagnostics);
+        let has_dynamic_capability_reference = diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.message == DYNAMIC_CAPABILITY_MESSAGE);
+
         Ok(Self {
             script: fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()),
             probe_name: parse_assignment(&contents, "probe_name"),
             probe_version: parse_assignment(&contents, "probe_version"),
-            primary_capability: parse_assignment(&contents, "primary_capability_id")
-                .map(CapabilityId),
-            secondary_capabilities: parse_secondary_capabilities(&contents),
+            primary_capability,
+            secondary_capabilities,
+            problem_matchers: parse_repeated_assignments(&contents, "problem_matcher_pattern"),
+            platform_cfg: parse_assignment(&contents, "platform_cfg"),
+            expected_result: parse_assignment(&contents, "expected_result"),
+            expected_result_by_mode: parse_mode_assignments(&contents, "expected_result_"),
+            has_dynamic_capability_reference,
+            diagnostics,
         })
     }
+
+    /// The declared expectation for `mode`, preferring a per-mode override
+    /// (`expected_result_<mode>=`) and falling back to the probe's default
+    /// `expected_result=`. Returns `None` when neither is declared, meaning
+    /// the probe carries no assertion for this run and callers should treat
+    /// it as unasserted rather than failing.
+    pub fn expected_result_for_mode(&self, mode: &str) -> Option<&str> {
+        self.expected_result_by_mode
+            .get(mode)
+            .or(self.expected_result.as_ref())
+            .map(String::as_str)
+    }
 }
 
 /// Collect every `.sh` script under the provided roots.
@@ -72,7 +184,17 @@ fn collect_from_dir(root: &Path, acc: &mut Vec<PathBuf>) -> Result<()> {
 }
 
 fn parse_assignment(contents: &str, var: &str) -> Option<String> {
+    parse_repeated_assignments(contents, var).into_iter().next()
+}
+
+/// Like [`parse_assignment`], but collects every occurrence of `var = ...` in
+/// script order instead of stopping at the first. Used for declarations where
+/// later lines augment earlier ones rather than overriding them, such as
+/// `problem_matcher_pattern=` (see [`crate::problem_matcher`]), where
+/// declaration order sets match priority.
+fn parse_repeated_assignments(contents: &str, var: &str) -> Vec<String> {
     let prefix = var;
+    let mut values = Vec::new();
     for line in contents.lines() {
         let trimmed = line.trim_start();
         if trimmed.starts_with('#') {
@@ -82,43 +204,118 @@ fn parse_assignment(contents: &str, var: &str) -> Option<String> {
             continue;
         };
         let rest = rest.trim_start();
-        if !rest.starts_with('=') {
+        let Some(rest) = rest.strip_prefix('=') else {
             continue;
+        };
+        if let Some(value) = parse_value(rest) {
+            values.push(value);
         }
-        let mut value = rest[1..].trim_start();
-        if value.is_empty() {
+    }
+    values
+}
+
+/// Scan for lines shaped `<prefix><mode>=<value>` (e.g.
+/// `expected_result_baseline="denied"`), keyed by the `<mode>` suffix. Used
+/// for per-mode overrides of an otherwise single-valued assignment; a bare
+/// `<prefix>=...` line (no mode suffix) never matches, since `strip_prefix`
+/// requires the character right after `prefix` to start an identifier rather
+/// than `=`.
+fn parse_mode_assignments(contents: &str, prefix: &str) -> BTreeMap<String, String> {
+    let mut values = BTreeMap::new();
+    for line in contents.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with('#') {
             continue;
         }
-        if value.starts_with('"') {
-            value = &value[1..];
-            if let Some(end) = value.find('"') {
-                return Some(value[..end].to_string());
-            }
-        } else if value.starts_with('\'') {
-            value = &value[1..];
-            if let Some(end) = value.find('\'') {
-                return Some(value[..end].to_string());
-            }
-        } else {
-            let token = value.split_whitespace().next().unwrap_or("").trim();
-            if !token.is_empty() {
-                return Some(token.to_string());
-            }
+        let Some(rest) = trimmed.strip_prefix(prefix) else {
+            continue;
+        };
+        let Some(eq_pos) = rest.find('=') else {
+            continue;
+        };
+        let mode = rest[..eq_pos].trim();
+        if mode.is_empty() || !mode.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            continue;
         }
+        if let Some(value) = parse_value(&rest[eq_pos + 1..]) {
+            values.insert(mode.to_string(), value);
+        }
+    }
+    values
+}
+
+/// Extract a quoted (`"..."`/`'...'`) or bare-token value from the text
+/// immediately following an assignment's `=`, the way `probe_name="x"` or
+/// `probe_name=x` both resolve to `"x"`.
+fn parse_value(rest: &str) -> Option<String> {
+    let mut value = rest.trim_start();
+    if value.is_empty() {
+        return None;
+    }
+    if let Some(stripped) = value.strip_prefix('"') {
+        value = stripped;
+        return value.find('"').map(|end| value[..end].to_string());
+    }
+    if let Some(stripped) = value.strip_prefix('\'') {
+        value = stripped;
+        return value.find('\'').map(|end| value[..end].to_string());
+    }
+    let token = value.split_whitespace().next().unwrap_or("").trim();
+    if token.is_empty() {
+        None
+    } else {
+        Some(token.to_string())
+    }
+}
+
+/// Scan for a `primary_capability_id=` declaration, the way
+/// [`parse_assignment`] would, but track enough position information to
+/// diagnose the two ways this required field can go wrong: the value is a
+/// `$`-substitution [`parse_token`] can't resolve, or the declaration is
+/// missing entirely.
+fn parse_primary_capability(contents: &str) -> (Option<CapabilityId>, Vec<ProbeDiagnostic>) {
+    for (idx, raw_line) in contents.lines().enumerate() {
+        let trimmed = raw_line.trim_start();
+        if trimmed.starts_with('#') {
+            continue;
+        }
+        let Some(rest) = trimmed.strip_prefix("primary_capability_id") else {
+            continue;
+        };
+        let rest = rest.trim_start();
+        let Some(rest) = rest.strip_prefix('=') else {
+            continue;
+        };
+        let Some(value) = parse_value(rest) else {
+            continue;
+        };
+
+        return match parse_token(&value) {
+            Some(id) => (Some(id), Vec::new()),
+            None if value.contains('$') => (None, vec![dynamic_diagnostic(raw_line, idx + 1)]),
+            None => (None, Vec::new()),
+        };
     }
-    None
+
+    let snippet = contents.lines().next().unwrap_or("");
+    (
+        None,
+        vec![ProbeDiagnostic::new(1, 1, snippet, MISSING_PRIMARY_MESSAGE)],
+    )
 }
 
-fn parse_secondary_capabilities(contents: &str) -> Vec<CapabilityId> {
+fn parse_secondary_capabilities(contents: &str) -> (Vec<CapabilityId>, Vec<ProbeDiagnostic>) {
     let mut ids = BTreeSet::new();
+    let mut diagnostics = Vec::new();
     let mut array_open = false;
-    for raw_line in contents.lines() {
+    for (idx, raw_line) in contents.lines().enumerate() {
+        let line_no = idx + 1;
         let line = raw_line.split('#').next().unwrap_or("");
         let trimmed = line.trim_start();
 
         if array_open {
             let (segment, closed) = array_segment(trimmed);
-            push_tokens(segment, &mut ids);
+            push_tokens(segment, raw_line, line_no, &mut ids, &mut diagnostics);
             if closed {
                 array_open = false;
             }
@@ -126,50 +323,70 @@ fn parse_secondary_capabilities(contents: &str) -> Vec<CapabilityId> {
         }
 
         if let Some(value) = trimmed.strip_prefix("secondary_capability_id=") {
-            if let Some(id) = parse_token(value.trim()) {
-                ids.insert(id);
-            }
+            push_token(value.trim(), raw_line, line_no, &mut ids, &mut diagnostics);
             continue;
         }
 
         if let Some(rest) = trimmed.strip_prefix("secondary_capability_ids=(") {
             let (segment, closed) = array_segment(rest);
-            push_tokens(segment, &mut ids);
+            push_tokens(segment, raw_line, line_no, &mut ids, &mut diagnostics);
             array_open = !closed;
             continue;
         }
 
         if trimmed.contains("--secondary-capability-id") {
-            push_from_flags(trimmed, &mut ids);
+            push_from_flags(trimmed, raw_line, line_no, &mut ids, &mut diagnostics);
         }
     }
 
-    ids.into_iter().collect()
+    (ids.into_iter().collect(), diagnostics)
 }
 
-fn push_tokens(text: &str, acc: &mut BTreeSet<CapabilityId>) {
-    for token in text.split_whitespace() {
-        if let Some(id) = parse_token(token) {
+fn push_token(
+    token: &str,
+    raw_line: &str,
+    line_no: usize,
+    acc: &mut BTreeSet<CapabilityId>,
+    diagnostics: &mut Vec<ProbeDiagnostic>,
+) {
+    match parse_token(token) {
+        Some(id) => {
             acc.insert(id);
         }
+        None if token.contains('$') => diagnostics.push(dynamic_diagnostic(raw_line, line_no)),
+        None => {}
+    }
+}
+
+fn push_tokens(
+    text: &str,
+    raw_line: &str,
+    line_no: usize,
+    acc: &mut BTreeSet<CapabilityId>,
+    diagnostics: &mut Vec<ProbeDiagnostic>,
+) {
+    for token in text.split_whitespace() {
+        push_token(token, raw_line, line_no, acc, diagnostics);
     }
 }
 
-fn push_from_flags(text: &str, acc: &mut BTreeSet<CapabilityId>) {
+fn push_from_flags(
+    text: &str,
+    raw_line: &str,
+    line_no: usize,
+    acc: &mut BTreeSet<CapabilityId>,
+    diagnostics: &mut Vec<ProbeDiagnostic>,
+) {
     let mut parts = text.split_whitespace().peekable();
     while let Some(part) = parts.next() {
         if let Some(rest) = part.strip_prefix("--secondary-capability-id=") {
-            if let Some(id) = parse_token(rest) {
-                acc.insert(id);
-            }
+            push_token(rest, raw_line, line_no, acc, diagnostics);
             continue;
         }
 
         if part == "--secondary-capability-id" {
             if let Some(next) = parts.next() {
-                if let Some(id) = parse_token(next) {
-                    acc.insert(id);
-                }
+                push_token(next, raw_line, line_no, acc, diagnostics);
             }
         }
     }
@@ -183,6 +400,14 @@ fn array_segment(text: &str) -> (&str, bool) {
     }
 }
 
+/// Build a [`DYNAMIC_CAPABILITY_MESSAGE`] diagnostic pointing at the first
+/// `$` on `raw_line`, which is close enough for a single-substitution line
+/// (the common case) without attempting a full shell parse.
+fn dynamic_diagnostic(raw_line: &str, line_no: usize) -> ProbeDiagnostic {
+    let column = raw_line.find('$').map(|byte| byte + 1).unwrap_or(1);
+    ProbeDiagnostic::new(line_no, column, raw_line, DYNAMIC_CAPABILITY_MESSAGE)
+}
+
 fn parse_token(raw: &str) -> Option<CapabilityId> {
     let trimmed = raw.trim().trim_matches(|c| c == '"' || c == '\'');
     // Ignore empty tokens and anything containing shell substitution to avoid
@@ -205,7 +430,7 @@ secondary_capability_id=cap_a
 secondary_capability_ids=(cap_b "cap_c")
 some_command --secondary-capability-id cap_d --secondary-capability-id=cap_e
         "#;
-        let parsed = parse_secondary_capabilities(contents);
+        let (parsed, diagnostics) = parse_secondary_capabilities(contents);
         assert_eq!(
             parsed,
             vec![
@@ -216,6 +441,110 @@ some_command --secondary-capability-id cap_d --secondary-capability-id=cap_e
                 CapabilityId("cap_e".to_string())
             ]
         );
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn expected_result_for_mode_prefers_per_mode_override_over_default() {
+        let temp = TempDir::new().expect("temp dir");
+        let script = temp.path().join("probe.sh");
+        std::fs::write(
+            &script,
+            r#"#!/bin/sh
+expected_result="denied"
+expected_result_hardened="success"
+"#,
+        )
+        .unwrap();
+
+        let metadata = ProbeMetadata::from_script(&script).expect("parse metadata");
+        assert_eq!(metadata.expected_result.as_deref(), Some("denied"));
+        assert_eq!(
+            metadata.expected_result_for_mode("baseline"),
+            Some("denied")
+        );
+        assert_eq!(
+            metadata.expected_result_for_mode("hardened"),
+            Some("success")
+        );
+    }
+
+    #[test]
+    fn expected_result_for_mode_is_none_without_any_declaration() {
+        let temp = TempDir::new().expect("temp dir");
+        let script = temp.path().join("probe.sh");
+        std::fs::write(&script, "#!/bin/sh\necho hi\n").unwrap();
+
+        let metadata = ProbeMetadata::from_script(&script).expect("parse metadata");
+        assert_eq!(metadata.expected_result, None);
+        assert!(metadata.expected_result_by_mode.is_empty());
+        assert_eq!(metadata.expected_result_for_mode("baseline"), None);
+    }
+
+    #[test]
+    fn has_dynamic_capability_reference_detects_dollar_substituted_secondary_id() {
+        let temp = TempDir::new().expect("temp dir");
+        let script = temp.path().join("probe.sh");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\nprimary_capability_id=\"cap_fs_read\"\nsecondary_capability_id=\"cap_$MODE\"\n",
+        )
+        .unwrap();
+
+        let metadata = ProbeMetadata::from_script(&script).expect("parse metadata");
+        assert!(metadata.has_dynamic_capability_reference);
+        assert!(metadata.secondary_capabilities.is_empty());
+        assert_eq!(metadata.diagnostics.len(), 1);
+        let diagnostic = &metadata.diagnostics[0];
+        assert_eq!(diagnostic.line, 3);
+        assert_eq!(diagnostic.column, 30);
+        assert_eq!(diagnostic.message, DYNAMIC_CAPABILITY_MESSAGE);
+    }
+
+    #[test]
+    fn has_dynamic_capability_reference_is_false_for_static_ids() {
+        let temp = TempDir::new().expect("temp dir");
+        let script = temp.path().join("probe.sh");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\nprimary_capability_id=\"cap_fs_read\"\n",
+        )
+        .unwrap();
+
+        let metadata = ProbeMetadata::from_script(&script).expect("parse metadata");
+        assert!(!metadata.has_dynamic_capability_reference);
+        assert!(metadata.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn missing_primary_capability_id_produces_a_caret_diagnostic_at_line_one() {
+        let temp = TempDir::new().expect("temp dir");
+        let script = temp.path().join("probe.sh");
+        std::fs::write(&script, "#!/bin/sh\necho hi\n").unwrap();
+
+        let metadata = ProbeMetadata::from_script(&script).expect("parse metadata");
+        assert_eq!(metadata.primary_capability, None);
+        assert_eq!(metadata.diagnostics.len(), 1);
+        let diagnostic = &metadata.diagnostics[0];
+        assert_eq!(diagnostic.line, 1);
+        assert_eq!(diagnostic.column, 1);
+        assert_eq!(diagnostic.message, MISSING_PRIMARY_MESSAGE);
+    }
+
+    #[test]
+    fn dynamic_primary_capability_id_renders_a_caret_pointing_at_the_dollar_sign() {
+        let temp = TempDir::new().expect("temp dir");
+        let script = temp.path().join("probe.sh");
+        std::fs::write(&script, "primary_capability_id=\"cap_$MODE\"\n").unwrap();
+
+        let metadata = ProbeMetadata::from_script(&script).expect("parse metadata");
+        assert_eq!(metadata.primary_capability, None);
+        assert_eq!(metadata.diagnostics.len(), 1);
+        let rendered = metadata.diagnostics[0].render(Path::new("probes/foo.sh"));
+        assert!(rendered.contains(DYNAMIC_CAPABILITY_MESSAGE));
+        assert!(rendered.contains("probes/foo.sh:1:28"));
+        assert!(rendered.contains("primary_capability_id=\"cap_$MODE\""));
+        assert!(rendered.contains("^"));
     }
 
     #[test]
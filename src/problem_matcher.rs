@@ -0,0 +1,150 @@
+//! Regex "problem matcher" layer for salvaging boundary objects out of
+//! non-JSON probe output.
+//!
+//! Some probes wrap legacy tools that print human diagnostics instead of
+//! calling `emit-record` themselves. A probe may declare an ordered list of
+//! `problem_matcher_pattern=` regex patterns (see
+//! [`crate::probe_metadata::ProbeMetadata::problem_matchers`]); each pattern
+//! must name the `category`, `verb`, `target`, and `status` capture groups
+//! (`errno` and `message` are optional), so a match can stand in for the
+//! boundary fields a well-behaved probe would have reported itself. Patterns
+//! run, in declared order, over a probe's captured output line-by-line when
+//! the probe didn't emit a valid boundary object, and the first match wins.
+
+use anyhow::{Context, Result, bail};
+use regex::Regex;
+
+const REQUIRED_GROUPS: [&str; 4] = ["category", "verb", "target", "status"];
+
+#[derive(Debug, Clone)]
+pub struct ProblemMatcher {
+    pattern: String,
+    regex: Regex,
+}
+
+impl ProblemMatcher {
+    /// Compile a declared pattern, rejecting it up front if it is missing any
+    /// of the required named capture groups rather than failing silently
+    /// later, line after line, with nothing to match against.
+    pub fn compile(pattern: &str) -> Result<Self> {
+        let regex = Regex::new(pattern)
+            .with_context(|| format!("invalid problem matcher pattern: {pattern}"))?;
+        for group in REQUIRED_GROUPS {
+            if regex.capture_names().flatten().all(|name| name != group) {
+                bail!(
+                    "problem matcher pattern is missing required named capture group '{group}': {pattern}"
+                );
+            }
+        }
+        Ok(Self {
+            pattern: pattern.to_string(),
+            regex,
+        })
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+}
+
+/// Fields recovered from a matched line, ready to fill in a synthesized
+/// boundary object's `operation`/`result` sections.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchedFields {
+    pub category: String,
+    pub verb: String,
+    pub target: String,
+    pub status: String,
+    pub errno: Option<String>,
+    pub message: Option<String>,
+}
+
+/// Run `matchers` line-by-line over `text`, trying each pattern against every
+/// line (in declared order) before moving to the next line, and return the
+/// first match found.
+pub fn first_match(matchers: &[ProblemMatcher], text: &str) -> Option<MatchedFields> {
+    for line in text.lines() {
+        for matcher in matchers {
+            let Some(captures) = matcher.regex.captures(line) else {
+                continue;
+            };
+            let field = |name: &str| captures.name(name).map(|m| m.as_str().to_string());
+            let (Some(category), Some(verb), Some(target), Some(status)) = (
+                field("category"),
+                field("verb"),
+                field("target"),
+                field("status"),
+            ) else {
+                continue;
+            };
+            return Some(MatchedFields {
+                category,
+                verb,
+                target,
+                status,
+                errno: field("errno"),
+                message: field("message"),
+            });
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compile_rejects_pattern_missing_a_required_group() {
+        let err = ProblemMatcher::compile(r"^(?P<category>\w+) (?P<verb>\w+)$").unwrap_err();
+        assert!(err.to_string().contains("'target'"));
+    }
+
+    #[test]
+    fn first_match_returns_none_when_no_pattern_fires() {
+        let matchers = vec![ProblemMatcher::compile(
+            r"^DENIED (?P<category>\w+) (?P<verb>\w+) (?P<target>\S+) (?P<status>denied)$",
+        )
+        .unwrap()];
+        assert!(first_match(&matchers, "not-json\n").is_none());
+    }
+
+    #[test]
+    fn first_match_prefers_earlier_declared_pattern_on_the_same_line() {
+        let specific = ProblemMatcher::compile(
+            r"^legacy-tool: (?P<verb>\w+) (?P<target>\S+): (?P<status>denied) \((?P<category>fs)\)$",
+        )
+        .unwrap();
+        let generic = ProblemMatcher::compile(
+            r"^legacy-tool: (?P<verb>\w+) (?P<target>\S+): (?P<status>\w+) \((?P<category>\w+)\)$",
+        )
+        .unwrap();
+        let matchers = vec![specific, generic];
+
+        let matched =
+            first_match(&matchers, "legacy-tool: open /etc/shadow: denied (fs)\n").unwrap();
+        assert_eq!(matched.category, "fs");
+        assert_eq!(matched.verb, "open");
+        assert_eq!(matched.target, "/etc/shadow");
+        assert_eq!(matched.status, "denied");
+        assert_eq!(matched.errno, None);
+        assert_eq!(matched.message, None);
+    }
+
+    #[test]
+    fn first_match_captures_optional_errno_and_message() {
+        let matchers = vec![
+            ProblemMatcher::compile(
+                r"^legacy-tool: (?P<verb>\w+) (?P<target>\S+): (?P<status>\w+) \((?P<category>\w+)\) errno=(?P<errno>\w+) msg=(?P<message>.+)$",
+            )
+            .unwrap(),
+        ];
+        let matched = first_match(
+            &matchers,
+            "legacy-tool: read /etc/shadow: denied (fs) errno=EACCES msg=permission denied\n",
+        )
+        .unwrap();
+        assert_eq!(matched.errno.as_deref(), Some("EACCES"));
+        assert_eq!(matched.message.as_deref(), Some("permission denied"));
+    }
+}
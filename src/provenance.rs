@@ -0,0 +1,420 @@
+//! Provenance/lineage graph over collected boundary objects.
+//!
+//! [`crate::coverage`] tallies which probes touched which capabilities but
+//! discards the chain that produced each observation. [`build_provenance_graph`]
+//! keeps that chain: nodes are probes, operations (category/verb/target),
+//! capabilities, and observed results, linked `probe -> operation ->
+//! capability -> result`. The same probe's records across run modes merge
+//! onto the same probe/operation/capability nodes, so [`ProvenanceGraph::capability_mode_results`]
+//! lets a caller diff a single capability's outcome across modes directly
+//! (e.g. a capability that succeeds under `baseline` but is denied under a
+//! sandboxed mode).
+//!
+//! This module works over raw [`Value`] records rather than the strongly
+//! typed [`crate::BoundaryObject`]: `capability_context.primary` is a
+//! required field on that struct, so a record missing it would never survive
+//! strict deserialization. Operating on `Value` lets a legacy or
+//! hand-crafted record that omits it still produce a graph, with its
+//! operation treated as an orphan linked directly to the probe rather than
+//! silently dropped.
+
+use crate::metadata_validation::find_json_files;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+
+/// A node in the provenance graph, tagged by kind for JSON serialization.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ProvenanceNode {
+    Probe {
+        id: String,
+        versions: BTreeSet<String>,
+    },
+    Operation {
+        category: String,
+        verb: String,
+        target: String,
+    },
+    Capability {
+        id: String,
+    },
+    Result {
+        mode: String,
+        observed_result: String,
+        errno: Option<String>,
+    },
+}
+
+/// A directed edge between two [`ProvenanceNode`] ids.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct ProvenanceEdge {
+    pub from: String,
+    pub to: String,
+}
+
+/// Adjacency-structure lineage graph built from boundary objects.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProvenanceGraph {
+    pub nodes: BTreeMap<String, ProvenanceNode>,
+    pub edges: Vec<ProvenanceEdge>,
+    /// `capability id -> run mode -> observed_result`, merged across every
+    /// probe record that exercised the capability. Diffing this per
+    /// capability id is how callers compute coverage deltas across modes.
+    pub capability_mode_results: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+/// Build a [`ProvenanceGraph`] from a set of boundary-object JSON records.
+///
+/// Operation nodes are deduped by `(category, verb, canonicalized target)`;
+/// records whose `capability_context.primary` is absent become orphan
+/// operations linked directly to the probe (`probe -> operation -> result`)
+/// instead of being dropped.
+pub fn build_provenance_graph(records: &[Value]) -> Result<ProvenanceGraph> {
+    let mut graph = ProvenanceGraph::default();
+    let mut edges: BTreeSet<(String, String)> = BTreeSet::new();
+
+    for record in records {
+        let probe_id = record
+            .pointer("/probe/id")
+            .and_then(Value::as_str)
+            .context("boundary object missing probe.id")?;
+        let probe_version = record
+            .pointer("/probe/version")
+            .and_then(Value::as_str)
+            .context("boundary object missing probe.version")?;
+        let mode = record
+            .pointer("/run/mode")
+            .and_then(Value::as_str)
+            .context("boundary object missing run.mode")?;
+        let category = record
+            .pointer("/operation/category")
+            .and_then(Value::as_str)
+            .context("boundary object missing operation.category")?;
+        let verb = record
+            .pointer("/operation/verb")
+            .and_then(Value::as_str)
+            .context("boundary object missing operation.verb")?;
+        let target = record
+            .pointer("/operation/target")
+            .and_then(Value::as_str)
+            .context("boundary object missing operation.target")?;
+        let observed_result = record
+            .pointer("/result/observed_result")
+            .and_then(Value::as_str)
+            .context("boundary object missing result.observed_result")?;
+        let errno = record
+            .pointer("/result/errno")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let capability_id = record
+            .pointer("/capability_context/primary/id")
+            .and_then(Value::as_str);
+
+        let probe_node_id = format!("probe:{probe_id}");
+        match graph.nodes.get_mut(&probe_node_id) {
+            Some(ProvenanceNode::Probe { versions, .. }) => {
+                versions.insert(probe_version.to_string());
+            }
+            _ => {
+                graph.nodes.insert(
+                    probe_node_id.clone(),
+                    ProvenanceNode::Probe {
+                        id: probe_id.to_string(),
+                        versions: BTreeSet::from([probe_version.to_string()]),
+                    },
+                );
+            }
+        }
+
+        let canonical_target = canonicalize_target(target);
+        let operation_node_id = format!("operation:{category}|{verb}|{canonical_target}");
+        graph
+            .nodes
+            .entry(operation_node_id.clone())
+            .or_insert_with(|| ProvenanceNode::Operation {
+                category: category.to_string(),
+                verb: verb.to_string(),
+                target: canonical_target.clone(),
+            });
+        edges.insert((probe_node_id.clone(), operation_node_id.clone()));
+
+        match capability_id {
+            Some(capability_id) => {
+                let capability_node_id = format!("capability:{capability_id}");
+                graph
+                    .nodes
+                    .entry(capability_node_id.clone())
+                    .or_insert_with(|| ProvenanceNode::Capability {
+                        id: capability_id.to_string(),
+                    });
+                edges.insert((operation_node_id, capability_node_id.clone()));
+
+                let result_node_id = format!("result:{probe_id}|{capability_id}|{mode}");
+                graph
+                    .nodes
+                    .entry(result_node_id.clone())
+                    .or_insert_with(|| ProvenanceNode::Result {
+                        mode: mode.to_string(),
+                        observed_result: observed_result.to_string(),
+                        errno,
+                    });
+                edges.insert((capability_node_id, result_node_id));
+
+                graph
+                    .capability_mode_results
+                    .entry(capability_id.to_string())
+                    .or_default()
+                    .insert(mode.to_string(), observed_result.to_string());
+            }
+            None => {
+                let result_node_id = format!("result:{probe_id}|{operation_node_id}|{mode}");
+                graph
+                    .nodes
+                    .entry(result_node_id.clone())
+                    .or_insert_with(|| ProvenanceNode::Result {
+                        mode: mode.to_string(),
+                        observed_result: observed_result.to_string(),
+                        errno,
+                    });
+                edges.insert((operation_node_id, result_node_id));
+            }
+        }
+    }
+
+    graph.edges = edges
+        .into_iter()
+        .map(|(from, to)| ProvenanceEdge { from, to })
+        .collect();
+    Ok(graph)
+}
+
+/// Read every boundary object under `dirs` and build a [`ProvenanceGraph`]
+/// over them, mirroring [`crate::coverage::capability_coverage`]'s
+/// directory-scanning entry point.
+pub fn provenance_graph_from_dirs(dirs: &[PathBuf]) -> Result<ProvenanceGraph> {
+    let mut records = Vec::new();
+    for json_file in find_json_files(dirs)? {
+        let data = fs::read_to_string(&json_file)
+            .with_context(|| format!("reading {}", json_file.display()))?;
+        let value: Value = serde_json::from_str(&data)
+            .with_context(|| format!("parsing {}", json_file.display()))?;
+        records.push(value);
+    }
+    build_provenance_graph(&records)
+}
+
+/// Strip trailing slashes from a filesystem-style target (a lone `/` is kept)
+/// so `/tmp/workspace` and `/tmp/workspace/` dedupe onto the same operation node.
+fn canonicalize_target(target: &str) -> String {
+    let trimmed = target.trim();
+    if trimmed.len() > 1 && trimmed.ends_with('/') {
+        trimmed.trim_end_matches('/').to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Render a [`ProvenanceGraph`] as Graphviz DOT.
+pub fn to_dot(graph: &ProvenanceGraph) -> String {
+    let mut out = String::from("digraph provenance {\n");
+    for (id, node) in &graph.nodes {
+        let label = match node {
+            ProvenanceNode::Probe { id, versions } => {
+                format!("probe {id}\\nversions: {}", versions.len())
+            }
+            ProvenanceNode::Operation {
+                category,
+                verb,
+                target,
+            } => format!("{category} {verb}\\n{target}"),
+            ProvenanceNode::Capability { id } => format!("capability {id}"),
+            ProvenanceNode::Result {
+                mode,
+                observed_result,
+                errno,
+            } => match errno {
+                Some(errno) => format!("{mode}: {observed_result} ({errno})"),
+                None => format!("{mode}: {observed_result}"),
+            },
+        };
+        out.push_str(&format!(
+            "  \"{id}\" [label=\"{}\"];\n",
+            escape_dot_label(&label)
+        ));
+    }
+    for edge in &graph.edges {
+        out.push_str(&format!("  \"{}\" -> \"{}\";\n", edge.from, edge.to));
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn escape_dot_label(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(
+        probe_id: &str,
+        mode: &str,
+        target: &str,
+        observed_result: &str,
+        capability_id: Option<&str>,
+    ) -> Value {
+        let mut value = json!({
+            "probe": {"id": probe_id, "version": "1"},
+            "run": {"mode": mode},
+            "operation": {"category": "filesystem", "verb": "read", "target": target},
+            "result": {"observed_result": observed_result, "errno": null},
+        });
+        if let Some(capability_id) = capability_id {
+            value["capability_context"] = json!({"primary": {"id": capability_id}});
+        }
+        value
+    }
+
+    #[test]
+    fn build_provenance_graph_links_probe_operation_capability_result() {
+        let records = vec![record(
+            "probe-a",
+            "baseline",
+            "/tmp/workspace",
+            "success",
+            Some("cap_fs_read_workspace_tree"),
+        )];
+
+        let graph = build_provenance_graph(&records).expect("graph builds");
+
+        assert!(graph.nodes.contains_key("probe:probe-a"));
+        assert!(graph
+            .nodes
+            .contains_key("operation:filesystem|read|/tmp/workspace"));
+        assert!(graph
+            .nodes
+            .contains_key("capability:cap_fs_read_workspace_tree"));
+        assert_eq!(graph.edges.len(), 3);
+        assert_eq!(
+            graph.capability_mode_results["cap_fs_read_workspace_tree"]["baseline"],
+            "success"
+        );
+    }
+
+    #[test]
+    fn build_provenance_graph_merges_same_capability_across_modes() {
+        let records = vec![
+            record(
+                "probe-a",
+                "baseline",
+                "/tmp/workspace",
+                "success",
+                Some("cap_fs_read_workspace_tree"),
+            ),
+            record(
+                "probe-a",
+                "sandboxed",
+                "/tmp/workspace",
+                "denied",
+                Some("cap_fs_read_workspace_tree"),
+            ),
+        ];
+
+        let graph = build_provenance_graph(&records).expect("graph builds");
+
+        let per_mode = &graph.capability_mode_results["cap_fs_read_workspace_tree"];
+        assert_eq!(per_mode["baseline"], "success");
+        assert_eq!(per_mode["sandboxed"], "denied");
+        assert_eq!(
+            graph
+                .nodes
+                .values()
+                .filter(|node| matches!(node, ProvenanceNode::Capability { .. }))
+                .count(),
+            1,
+            "both records should share one capability node"
+        );
+    }
+
+    #[test]
+    fn build_provenance_graph_dedupes_operations_by_canonical_target() {
+        let records = vec![
+            record(
+                "probe-a",
+                "baseline",
+                "/tmp/workspace",
+                "success",
+                Some("cap_fs_read_workspace_tree"),
+            ),
+            record(
+                "probe-b",
+                "baseline",
+                "/tmp/workspace/",
+                "success",
+                Some("cap_fs_read_workspace_tree"),
+            ),
+        ];
+
+        let graph = build_provenance_graph(&records).expect("graph builds");
+
+        let operation_nodes = graph
+            .nodes
+            .values()
+            .filter(|node| matches!(node, ProvenanceNode::Operation { .. }))
+            .count();
+        assert_eq!(
+            operation_nodes, 1,
+            "trailing slash should dedupe onto the same operation node"
+        );
+    }
+
+    #[test]
+    fn build_provenance_graph_treats_missing_capability_as_orphan() {
+        let records = vec![record(
+            "probe-a",
+            "baseline",
+            "/tmp/workspace",
+            "success",
+            None,
+        )];
+
+        let graph = build_provenance_graph(&records).expect("graph builds");
+
+        assert!(
+            graph
+                .nodes
+                .values()
+                .all(|node| !matches!(node, ProvenanceNode::Capability { .. })),
+            "no capability should be recorded for an orphan operation"
+        );
+        assert!(graph.capability_mode_results.is_empty());
+        assert_eq!(
+            graph.edges.len(),
+            2,
+            "probe -> operation -> result, skipping capability"
+        );
+    }
+
+    #[test]
+    fn to_dot_renders_nodes_and_edges() {
+        let records = vec![record(
+            "probe-a",
+            "baseline",
+            "/tmp/workspace",
+            "success",
+            Some("cap_fs_read_workspace_tree"),
+        )];
+        let graph = build_provenance_graph(&records).expect("graph builds");
+
+        let dot = to_dot(&graph);
+        assert!(dot.starts_with("digraph provenance {\n"));
+        assert!(dot.contains("\"probe:probe-a\""));
+        assert!(dot.contains("\"probe:probe-a\" -> \"operation:filesystem|read|/tmp/workspace\";"));
+    }
+}
@@ -0,0 +1,262 @@
+//! Shared output layer for probe binaries.
+//!
+//! `probe-target` and `probe-matrix` used to hard-code NDJSON to stdout and
+//! ad-hoc human text to stderr, each reinventing its own formatting and
+//! chatter rules. [`OutputFormat`] gives every binary the same four result
+//! shapes (`jsonl` is today's default, `json` wraps everything in one
+//! envelope, `human` renders an aligned table, `quiet` suppresses result
+//! output entirely), and [`Verbosity`] gates diagnostics (warnings,
+//! malformed-record notices) independently of which format was chosen.
+
+use anyhow::{Result, bail};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+/// How successfully produced boundary objects are rendered to stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// One compact JSON object per line (today's default behavior).
+    Jsonl,
+    /// A single JSON object: `{"records": [...], "summary": {...}}`.
+    Json,
+    /// An aligned text table (probe id, mode, category/verb/target, observed
+    /// result) plus a trailing counts line.
+    Human,
+    /// No result output at all; only a non-zero exit and error text on stderr.
+    Quiet,
+}
+
+impl OutputFormat {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "jsonl" => Ok(OutputFormat::Jsonl),
+            "json" => Ok(OutputFormat::Json),
+            "human" => Ok(OutputFormat::Human),
+            "quiet" => Ok(OutputFormat::Quiet),
+            other => bail!("unknown format '{other}' (expected jsonl, json, human, or quiet)"),
+        }
+    }
+}
+
+/// How chatty stderr diagnostics (version-negotiation warnings, malformed-
+/// record notices, etc.) should be, independent of [`OutputFormat`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    Quiet,
+    Normal,
+    Verbose,
+}
+
+impl Verbosity {
+    /// Resolve `--quiet`/`--verbose` CLI flags, rejecting both at once.
+    pub fn from_flags(quiet: bool, verbose: bool) -> Result<Self> {
+        match (quiet, verbose) {
+            (true, true) => bail!("--quiet and --verbose cannot both be set"),
+            (true, false) => Ok(Verbosity::Quiet),
+            (false, true) => Ok(Verbosity::Verbose),
+            (false, false) => Ok(Verbosity::Normal),
+        }
+    }
+}
+
+/// Print `message` to stderr unless `verbosity` is [`Verbosity::Quiet`].
+pub fn diagnostic(verbosity: Verbosity, message: &str) {
+    if verbosity != Verbosity::Quiet {
+        eprintln!("{message}");
+    }
+}
+
+/// Print `message` to stderr only under [`Verbosity::Verbose`].
+pub fn verbose_diagnostic(verbosity: Verbosity, message: &str) {
+    if verbosity == Verbosity::Verbose {
+        eprintln!("{message}");
+    }
+}
+
+/// Render already matrix-ordered `records` as a single JSON object: the
+/// boundary objects plus a `summary` of per-result and per-mode counts.
+pub fn render_json_records(records: &[Value]) -> Result<String> {
+    let envelope = serde_json::json!({
+        "records": records,
+        "summary": summarize(records),
+    });
+    Ok(serde_json::to_string_pretty(&envelope)?)
+}
+
+/// Render already matrix-ordered `records` as an aligned text table (probe
+/// id, mode, category/verb/target, observed result) plus a trailing counts
+/// line.
+pub fn render_human_records(records: &[Value]) -> String {
+    let headers = ["PROBE", "MODE", "CATEGORY/VERB/TARGET", "RESULT"];
+    let rows: Vec<[String; 4]> = records
+        .iter()
+        .map(|record| {
+            [
+                string_field(record, "/probe/id"),
+                string_field(record, "/run/mode"),
+                format!(
+                    "{}/{}/{}",
+                    string_field(record, "/operation/category"),
+                    string_field(record, "/operation/verb"),
+                    string_field(record, "/operation/target"),
+                ),
+                string_field(record, "/result/observed_result"),
+            ]
+        })
+        .collect();
+
+    let mut widths: [usize; 4] = headers.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let mut out = String::new();
+    write_row(&mut out, &headers.map(str::to_string), &widths);
+    for row in &rows {
+        write_row(&mut out, row, &widths);
+    }
+
+    let summary = summarize(records);
+    let counts = summary["results"]
+        .as_object()
+        .map(|map| {
+            map.iter()
+                .map(|(key, value)| format!("{key}={value}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .filter(|counts| !counts.is_empty())
+        .unwrap_or_else(|| "none".to_string());
+    let _ = writeln!(out, "\n{} probe(s); results: {counts}", records.len());
+    out
+}
+
+fn write_row(out: &mut String, cells: &[String], widths: &[usize]) {
+    let rendered: Vec<String> = cells
+        .iter()
+        .zip(widths)
+        .map(|(cell, width)| format!("{cell:<width$}"))
+        .collect();
+    let _ = writeln!(out, "{}", rendered.join("  ").trim_end());
+}
+
+fn string_field(record: &Value, pointer: &str) -> String {
+    record
+        .pointer(pointer)
+        .and_then(Value::as_str)
+        .unwrap_or("-")
+        .to_string()
+}
+
+/// Render a `key=value` count map as a comma-joined string (e.g.
+/// `"success=3, denied=1"`), or `empty_label` when the map is empty. Shared by
+/// binaries that tally their own maps (probe results, capability references,
+/// etc.) outside the boundary-record summary this module otherwise builds.
+pub fn format_counts(map: &BTreeMap<String, usize>, empty_label: &str) -> String {
+    if map.is_empty() {
+        return empty_label.to_string();
+    }
+    map.iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn summarize(records: &[Value]) -> Value {
+    let mut results: BTreeMap<String, usize> = BTreeMap::new();
+    let mut modes: BTreeMap<String, usize> = BTreeMap::new();
+    for record in records {
+        *results
+            .entry(string_field(record, "/result/observed_result"))
+            .or_insert(0) += 1;
+        *modes.entry(string_field(record, "/run/mode")).or_insert(0) += 1;
+    }
+    serde_json::json!({
+        "total": records.len(),
+        "results": results,
+        "modes": modes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(probe_id: &str, mode: &str, observed_result: &str) -> Value {
+        json!({
+            "probe": {"id": probe_id},
+            "run": {"mode": mode},
+            "operation": {"category": "fs", "verb": "read", "target": "/tmp"},
+            "result": {"observed_result": observed_result},
+        })
+    }
+
+    #[test]
+    fn output_format_parses_known_values_and_rejects_others() {
+        assert_eq!(OutputFormat::parse("jsonl").unwrap(), OutputFormat::Jsonl);
+        assert_eq!(OutputFormat::parse("json").unwrap(), OutputFormat::Json);
+        assert_eq!(OutputFormat::parse("human").unwrap(), OutputFormat::Human);
+        assert_eq!(OutputFormat::parse("quiet").unwrap(), OutputFormat::Quiet);
+        assert!(OutputFormat::parse("yaml").is_err());
+    }
+
+    #[test]
+    fn verbosity_from_flags_rejects_quiet_and_verbose_together() {
+        assert_eq!(
+            Verbosity::from_flags(false, false).unwrap(),
+            Verbosity::Normal
+        );
+        assert_eq!(
+            Verbosity::from_flags(true, false).unwrap(),
+            Verbosity::Quiet
+        );
+        assert_eq!(
+            Verbosity::from_flags(false, true).unwrap(),
+            Verbosity::Verbose
+        );
+        assert!(Verbosity::from_flags(true, true).is_err());
+    }
+
+    #[test]
+    fn render_json_records_wraps_records_with_a_summary() {
+        let records = vec![
+            record("probe-a", "baseline", "success"),
+            record("probe-b", "baseline", "denied"),
+        ];
+
+        let rendered = render_json_records(&records).expect("renders");
+        let parsed: Value = serde_json::from_str(&rendered).expect("valid JSON output");
+        assert_eq!(parsed["records"].as_array().unwrap().len(), 2);
+        assert_eq!(parsed["summary"]["total"], 2);
+        assert_eq!(parsed["summary"]["results"]["success"], 1);
+        assert_eq!(parsed["summary"]["results"]["denied"], 1);
+    }
+
+    #[test]
+    fn render_human_records_includes_header_rows_and_counts_line() {
+        let records = vec![record("probe-a", "baseline", "success")];
+        let rendered = render_human_records(&records);
+        assert!(rendered.contains("PROBE"));
+        assert!(rendered.contains("probe-a"));
+        assert!(rendered.contains("1 probe(s); results: success=1"));
+    }
+
+    #[test]
+    fn render_human_records_reports_none_for_empty_input() {
+        let rendered = render_human_records(&[]);
+        assert!(rendered.contains("0 probe(s); results: none"));
+    }
+
+    #[test]
+    fn format_counts_joins_entries_and_reports_empty_label() {
+        let mut counts = BTreeMap::new();
+        counts.insert("denied".to_string(), 2);
+        counts.insert("success".to_string(), 1);
+        assert_eq!(format_counts(&counts, "none"), "denied=2, success=1");
+        assert_eq!(format_counts(&BTreeMap::new(), "none"), "none");
+    }
+}
@@ -2,9 +2,16 @@
 //!
 //! Centralizes executable detection, PATH resolution, and helper search order
 //! so CLIs subscribe to the same behavior instead of re-implementing it.
+//!
+//! Also centralizes the `-v`/`--verbose`/`FENCE_LOG` command-tracing log (see
+//! [`Verbosity`] and [`CommandLogSpan`]) so `probe-exec`, `fence-bang`, and
+//! `fence-run` report what they spawned the same way instead of each binary
+//! growing its own ad hoc debug prints.
 
 use std::env;
+use std::ffi::{OsStr, OsString};
 use std::path::{Path, PathBuf};
+use std::time::Instant;
 
 /// Returns true when a file exists and has any execute bit set.
 pub fn helper_is_executable(path: &Path) -> bool {
@@ -78,3 +85,171 @@ pub fn find_on_path(name: &str) -> Option<PathBuf> {
     }
     None
 }
+
+/// How much detail the command-tracing log prints. Ordered so comparisons
+/// (`verbosity >= Verbosity::Verbose`) read naturally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Default: no per-command noise.
+    Quiet,
+    /// `-v` / `FENCE_LOG=1`: one line per spawned command describing what
+    /// was planned, and one line on completion with elapsed time/exit code.
+    Verbose,
+    /// `-vv` / `FENCE_LOG=2`: everything `Verbose` prints, plus the child's
+    /// captured stdout/stderr, as if it had been inherited.
+    Debug,
+}
+
+impl Verbosity {
+    /// Resolve from a CLI `-v` occurrence count (each binary's own arg parser
+    /// tallies repeats, including a `-vv` shorthand as two) and `FENCE_LOG`,
+    /// taking whichever of the two requests more detail.
+    pub fn resolve(cli_count: u32) -> Self {
+        let from_env = env::var("FENCE_LOG")
+            .ok()
+            .map(|raw| Self::from_level_str(&raw))
+            .unwrap_or(Verbosity::Quiet);
+        Self::from_cli_count(cli_count).max(from_env)
+    }
+
+    fn from_cli_count(cli_count: u32) -> Self {
+        match cli_count {
+            0 => Verbosity::Quiet,
+            1 => Verbosity::Verbose,
+            _ => Verbosity::Debug,
+        }
+    }
+
+    fn from_level_str(raw: &str) -> Self {
+        match raw.trim() {
+            "2" | "debug" | "vv" => Verbosity::Debug,
+            "1" | "verbose" | "v" => Verbosity::Verbose,
+            _ => Verbosity::Quiet,
+        }
+    }
+}
+
+/// `FENCE_*`/catalog env entries worth calling out in the command log:
+/// sandbox/run-mode wiring that differs per invocation, as opposed to
+/// incidental `PATH`/`HOME` passthrough that every child inherits anyway.
+const LOGGED_ENV_KEYS: &[&str] = &[
+    "FENCE_RUN_MODE",
+    "FENCE_SANDBOX_MODE",
+    "FENCE_WORKSPACE_ROOT",
+    "CATALOG_PATH",
+    "BOUNDARY_PATH",
+    "TMPDIR",
+];
+
+/// A single spawned command's place in the `-v`/`FENCE_LOG` trace: the
+/// "about to run" line (at construction) paired with the "it finished" line
+/// (via [`CommandLogSpan::finish`]), so the two always report the same
+/// elapsed-time window regardless of how the caller's own control flow
+/// branches in between.
+pub struct CommandLogSpan {
+    verbosity: Verbosity,
+    started_at: Instant,
+}
+
+impl CommandLogSpan {
+    /// Emit the command-planning line (when `verbosity` is at least
+    /// [`Verbosity::Verbose`]) and start timing the command. `fence_env` is
+    /// the full environment list a caller is about to pass to the child;
+    /// only [`LOGGED_ENV_KEYS`] entries are shown.
+    pub fn start(
+        verbosity: Verbosity,
+        program: &OsStr,
+        args: &[OsString],
+        cwd: &Path,
+        run_mode: &str,
+        fence_env: &[(OsString, OsString)],
+    ) -> Self {
+        if verbosity >= Verbosity::Verbose {
+            let argv = args
+                .iter()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+                .join(" ");
+            eprintln!(
+                "[fence-log] plan mode={run_mode} program={} argv=[{argv}] cwd={} env={{{}}}",
+                program.to_string_lossy(),
+                cwd.display(),
+                logged_env_display(fence_env),
+            );
+        }
+        Self {
+            verbosity,
+            started_at: Instant::now(),
+        }
+    }
+
+    /// Emit the completion line (elapsed time and exit code). At
+    /// [`Verbosity::Debug`], also print the child's captured stdout/stderr,
+    /// standing in for the `-vv` "inherited" behavior without having to
+    /// actually reconfigure the child's `Stdio` (callers still need to
+    /// capture output themselves for JSON parsing/expectation checks).
+    pub fn finish(self, exit_code: Option<i32>, stdout: &[u8], stderr: &[u8]) {
+        if self.verbosity < Verbosity::Verbose {
+            return;
+        }
+        let elapsed_ms = self.started_at.elapsed().as_millis();
+        let exit_display = exit_code
+            .map(|code| code.to_string())
+            .unwrap_or_else(|| "signal".to_string());
+        eprintln!("[fence-log] done elapsed_ms={elapsed_ms} exit_code={exit_display}");
+
+        if self.verbosity >= Verbosity::Debug {
+            if !stdout.is_empty() {
+                eprintln!("[fence-log] stdout:\n{}", String::from_utf8_lossy(stdout));
+            }
+            if !stderr.is_empty() {
+                eprintln!("[fence-log] stderr:\n{}", String::from_utf8_lossy(stderr));
+            }
+        }
+    }
+}
+
+fn logged_env_display(fence_env: &[(OsString, OsString)]) -> String {
+    fence_env
+        .iter()
+        .filter(|(key, _)| LOGGED_ENV_KEYS.contains(&key.to_string_lossy().as_ref()))
+        .map(|(key, value)| format!("{}={}", key.to_string_lossy(), value.to_string_lossy()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verbosity_from_cli_count_escalates() {
+        assert_eq!(Verbosity::from_cli_count(0), Verbosity::Quiet);
+        assert_eq!(Verbosity::from_cli_count(1), Verbosity::Verbose);
+        assert_eq!(Verbosity::from_cli_count(2), Verbosity::Debug);
+        assert_eq!(Verbosity::from_cli_count(5), Verbosity::Debug);
+    }
+
+    #[test]
+    fn verbosity_from_level_str_accepts_numeric_and_named_levels() {
+        assert_eq!(Verbosity::from_level_str("0"), Verbosity::Quiet);
+        assert_eq!(Verbosity::from_level_str("1"), Verbosity::Verbose);
+        assert_eq!(Verbosity::from_level_str("verbose"), Verbosity::Verbose);
+        assert_eq!(Verbosity::from_level_str("2"), Verbosity::Debug);
+        assert_eq!(Verbosity::from_level_str("debug"), Verbosity::Debug);
+        assert_eq!(Verbosity::from_level_str("garbage"), Verbosity::Quiet);
+    }
+
+    #[test]
+    fn logged_env_display_filters_to_known_keys() {
+        let env = vec![
+            (OsString::from("FENCE_RUN_MODE"), OsString::from("baseline")),
+            (OsString::from("PATH"), OsString::from("/usr/bin")),
+            (OsString::from("TMPDIR"), OsString::from("/tmp/ws")),
+        ];
+        let rendered = logged_env_display(&env);
+        assert!(rendered.contains("FENCE_RUN_MODE=baseline"));
+        assert!(rendered.contains("TMPDIR=/tmp/ws"));
+        assert!(!rendered.contains("PATH"));
+    }
+}
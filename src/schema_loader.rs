@@ -7,8 +7,10 @@
 
 use anyhow::{Context, Result, anyhow, bail};
 use jsonschema::JSONSchema;
+use semver::{Version, VersionReq};
 use serde_json::Value;
 use std::collections::BTreeSet;
+use std::cmp::Ordering;
 use std::fs::File;
 use std::path::Path;
 use std::sync::Arc;
@@ -44,6 +46,12 @@ pub(crate) struct SchemaLoadOptions<'a> {
     /// Patch the schema_version const in the schema payload to match
     /// `expected_version` (or the extracted version when no override is set).
     pub patch_schema_version_const: bool,
+    /// When true, strip `null`-valued object keys from both the canonical
+    /// schema and the loaded schema before comparing them, so a schema that
+    /// differs only by explicit `null`s (rather than omitting the key
+    /// entirely) still matches canonical. Defaults to false to preserve
+    /// today's strict structural-equality behavior.
+    pub normalize_before_canonical: bool,
 }
 
 impl<'a> Default for SchemaLoadOptions<'a> {
@@ -57,6 +65,7 @@ impl<'a> Default for SchemaLoadOptions<'a> {
             allowed_versions: None,
             allow_plain_schema: true,
             patch_schema_version_const: false,
+            normalize_before_canonical: false,
         }
     }
 }
@@ -192,13 +201,7 @@ pub(crate) fn load_json_schema(
     };
 
     if let Some(allowed) = options.allowed_versions {
-        if !allowed.contains(&schema_version) {
-            bail!(
-                "schema_version '{}' not in allowed set {:?}",
-                schema_version,
-                allowed
-            );
-        }
+        check_schema_version_allowed(&schema_version, allowed)?;
     }
 
     if let Some(canonical_path) = options.canonical_schema_path {
@@ -210,11 +213,20 @@ pub(crate) fn load_json_schema(
                 .with_context(|| {
                     format!("parsing canonical schema {}", canonical_path.display())
                 })?;
-            if canonical_value != original_schema {
+            let (comparable_canonical, comparable_schema) = if options.normalize_before_canonical {
+                (strip_nulls(&canonical_value), strip_nulls(&original_schema))
+            } else {
+                (canonical_value.clone(), original_schema.clone())
+            };
+
+            if comparable_canonical != comparable_schema {
+                let pointer = first_diff_pointer(&comparable_canonical, &comparable_schema, "")
+                    .unwrap_or_else(|| "/".to_string());
                 bail!(
-                    "schema {} does not match canonical schema {}",
+                    "schema {} does not match canonical schema {} (first differing field: {})",
                     path.display(),
-                    canonical_path.display()
+                    canonical_path.display(),
+                    pointer
                 );
             }
         }
@@ -245,6 +257,69 @@ pub(crate) fn load_json_schema(
     })
 }
 
+/// Recursively drops `null`-valued object keys so a schema that omits an
+/// unset option entirely compares equal to one that serializes it as an
+/// explicit `null`. Array elements are normalized in place; object key
+/// ordering is already stabilized by `serde_json`'s map.
+fn strip_nulls(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut normalized = serde_json::Map::new();
+            for (key, entry) in map {
+                if entry.is_null() {
+                    continue;
+                }
+                normalized.insert(key.clone(), strip_nulls(entry));
+            }
+            Value::Object(normalized)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(strip_nulls).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Finds the JSON pointer of the first field where `left` and `right` (both
+/// assumed already normalized) diverge, for a short diagnostic on canonical
+/// schema mismatch. `base` is the pointer prefix accumulated so far.
+fn first_diff_pointer(left: &Value, right: &Value, base: &str) -> Option<String> {
+    if left == right {
+        return None;
+    }
+    match (left, right) {
+        (Value::Object(left_map), Value::Object(right_map)) => {
+            let mut keys: BTreeSet<&String> = left_map.keys().collect();
+            keys.extend(right_map.keys());
+            for key in keys {
+                let child = format!("{base}/{key}");
+                match (left_map.get(key), right_map.get(key)) {
+                    (Some(left_value), Some(right_value)) => {
+                        if let Some(diff) = first_diff_pointer(left_value, right_value, &child) {
+                            return Some(diff);
+                        }
+                    }
+                    _ => return Some(child),
+                }
+            }
+            None
+        }
+        (Value::Array(left_items), Value::Array(right_items)) => {
+            for (index, (left_item, right_item)) in
+                left_items.iter().zip(right_items.iter()).enumerate()
+            {
+                let child = format!("{base}/{index}");
+                if let Some(diff) = first_diff_pointer(left_item, right_item, &child) {
+                    return Some(diff);
+                }
+            }
+            if left_items.len() != right_items.len() {
+                return Some(base.to_string());
+            }
+            None
+        }
+        _ => Some(base.to_string()),
+    }
+}
+
 fn extract_schema_version(schema: &Value, pointer: &str) -> Option<String> {
     let version = schema.pointer(pointer).and_then(Value::as_str)?;
     if version
@@ -256,3 +331,148 @@ fn extract_schema_version(schema: &Value, pointer: &str) -> Option<String> {
         None
     }
 }
+
+/// Leading operators that mark an `allowed_versions` entry as a semver
+/// requirement range (e.g. `^2`, `~1.3`, `>=2,<4`) rather than a literal
+/// `schema_version` string to match exactly.
+const RANGE_OPERATOR_PREFIXES: &[&str] = &["^", "~", ">=", ">", "<=", "<", "=", "*"];
+
+fn is_range_entry(entry: &str) -> bool {
+    RANGE_OPERATOR_PREFIXES
+        .iter()
+        .any(|operator| entry.starts_with(operator))
+}
+
+/// Parses `version` as a semver [`Version`], coercing a partial version
+/// (`"1"` -> `"1.0.0"`, `"2.3"` -> `"2.3.0"`) to full `major.minor.patch`
+/// first. Returns `None` rather than erroring when `version` isn't
+/// semver-shaped at all (e.g. `"sandbox_catalog_v1"`), so callers fall back
+/// to exact string comparison for those.
+fn coerce_semver_version(version: &str) -> Option<Version> {
+    if let Ok(parsed) = Version::parse(version) {
+        return Some(parsed);
+    }
+    let parts: Vec<&str> = version.split('.').collect();
+    if parts.is_empty() || parts.len() > 3 || parts.iter().any(|part| part.is_empty()) {
+        return None;
+    }
+    let mut padded: Vec<&str> = parts;
+    while padded.len() < 3 {
+        padded.push("0");
+    }
+    Version::parse(&padded.join(".")).ok()
+}
+
+/// A parsed `allowed_versions` range entry, wrapped the way `cargo-vet`
+/// wraps its own parsed `VersionReq` to give it ordering: `VersionReq` has no
+/// `Ord` impl, since there's no canonical ordering over requirement strings,
+/// so this orders by the requirement's own source text instead, just enough
+/// to collect ranges into a [`BTreeSet`] for a stable error message.
+#[derive(Debug, Clone)]
+struct SchemaVersionRange {
+    source: String,
+    req: VersionReq,
+}
+
+impl PartialEq for SchemaVersionRange {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for SchemaVersionRange {}
+
+impl PartialOrd for SchemaVersionRange {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SchemaVersionRange {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.source.cmp(&other.source)
+    }
+}
+
+/// Checks `schema_version` against `allowed`: an exact string match always
+/// wins (so non-semver literals like `"sandbox_catalog_v1"` keep working
+/// unchanged); otherwise each range-operator-prefixed entry (see
+/// [`is_range_entry`]) is parsed as a [`VersionReq`] and `schema_version`,
+/// coerced to a full [`Version`] by [`coerce_semver_version`], is matched
+/// against it. A malformed range entry never panics: it surfaces as a load
+/// error naming the offending string. The final failure message lists both
+/// the literal and range entries that were checked.
+fn check_schema_version_allowed(schema_version: &str, allowed: &BTreeSet<String>) -> Result<()> {
+    if allowed.contains(schema_version) {
+        return Ok(());
+    }
+
+    let mut ranges: BTreeSet<SchemaVersionRange> = BTreeSet::new();
+    for entry in allowed {
+        if !is_range_entry(entry) {
+            continue;
+        }
+        let req = VersionReq::parse(entry).map_err(|err| {
+            anyhow!("invalid schema_version range '{entry}' in allowed set: {err}")
+        })?;
+        ranges.insert(SchemaVersionRange {
+            source: entry.clone(),
+            req,
+        });
+    }
+
+    if let Some(version) = coerce_semver_version(schema_version) {
+        if ranges.iter().any(|range| range.req.matches(&version)) {
+            return Ok(());
+        }
+    }
+
+    let literals: Vec<&str> = allowed
+        .iter()
+        .filter(|entry| !is_range_entry(entry))
+        .map(String::as_str)
+        .collect();
+    let range_sources: Vec<&str> = ranges.iter().map(|range| range.source.as_str()).collect();
+    bail!(
+        "schema_version '{schema_version}' not in allowed set (literal: {literals:?}, range: {range_sources:?})"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allowed(entries: &[&str]) -> BTreeSet<String> {
+        entries.iter().map(|entry| entry.to_string()).collect()
+    }
+
+    #[test]
+    fn exact_match_wins_even_when_a_range_entry_would_also_match() {
+        // "^1" would also match "1.0.0" via coercion; the exact literal entry
+        // must still take precedence so non-semver literals are never forced
+        // through range parsing unnecessarily.
+        let allowed = allowed(&["1.0.0", "^1"]);
+        check_schema_version_allowed("1.0.0", &allowed).expect("exact literal should match");
+    }
+
+    #[test]
+    fn coerces_multi_segment_versions_against_a_range() {
+        let allowed = allowed(&["^1"]);
+        check_schema_version_allowed("1", &allowed).expect("\"1\" should coerce to 1.0.0");
+        check_schema_version_allowed("2.3", &allowed)
+            .expect_err("2.3 coerces to 2.3.0, outside ^1");
+
+        let allowed = allowed(&["^2.3"]);
+        check_schema_version_allowed("2.3", &allowed).expect("\"2.3\" should coerce to 2.3.0");
+    }
+
+    #[test]
+    fn rejected_version_names_both_literal_and_range_entries_checked() {
+        let allowed = allowed(&["sandbox_catalog_v1", "^2"]);
+        let err = check_schema_version_allowed("1.0.0", &allowed)
+            .expect_err("1.0.0 matches neither the literal nor the range");
+        let message = err.to_string();
+        assert!(message.contains("sandbox_catalog_v1"));
+        assert!(message.contains("^2"));
+    }
+}
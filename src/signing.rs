@@ -0,0 +1,153 @@
+//! Optional detached ed25519 signing for emitted boundary records.
+//!
+//! Signing is opt-in: `emit-record --signing-key-file PATH` attaches a
+//! top-level `signature` object computed over the canonical (sorted-key) JSON
+//! encoding of the record, excluding the `signature` field itself. Consumers
+//! verify with [`verify_record`], which recomputes the same canonical form.
+
+use anyhow::{Context, Result, bail};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde_json::{Map, Value, json};
+use std::fs;
+use std::path::Path;
+
+const SIGNATURE_FIELD: &str = "signature";
+const SIGNATURE_ALG: &str = "ed25519";
+
+/// Load a raw 32-byte ed25519 seed from disk and sign `record` in place.
+///
+/// Must be called after schema validation so the signed body matches exactly
+/// what was validated; the `signature` field is appended afterward and is
+/// excluded from the signed bytes.
+pub fn sign_record(record: &mut Value, key_file: &Path, key_id: Option<&str>) -> Result<()> {
+    let signing_key = load_signing_key(key_file)?;
+    let canonical = canonical_bytes(record);
+    let signature: Signature = signing_key.sign(&canonical);
+
+    let object = record
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("cannot sign a non-object boundary record"))?;
+    object.insert(
+        SIGNATURE_FIELD.to_string(),
+        json!({
+            "alg": SIGNATURE_ALG,
+            "key_id": key_id,
+            "value": BASE64.encode(signature.to_bytes()),
+        }),
+    );
+    Ok(())
+}
+
+/// Verify a boundary record's `signature` field against a raw 32-byte ed25519
+/// public key, recomputing the canonical form the same way `sign_record` does.
+pub fn verify_record(record: &Value, public_key: &[u8]) -> Result<()> {
+    let mut body = record.clone();
+    let object = body
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("boundary record is not a JSON object"))?;
+    let signature_value = object
+        .remove(SIGNATURE_FIELD)
+        .ok_or_else(|| anyhow::anyhow!("boundary record has no signature field"))?;
+
+    let alg = signature_value
+        .get("alg")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow::anyhow!("signature missing 'alg'"))?;
+    if alg != SIGNATURE_ALG {
+        bail!("unsupported signature algorithm '{alg}'");
+    }
+    let encoded = signature_value
+        .get("value")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow::anyhow!("signature missing 'value'"))?;
+    let signature_bytes = BASE64
+        .decode(encoded)
+        .context("signature value is not valid base64")?;
+    let signature = Signature::from_slice(&signature_bytes)
+        .context("signature value is not a valid ed25519 signature")?;
+
+    let verifying_key = VerifyingKey::try_from(public_key)
+        .context("public key is not a valid ed25519 key")?;
+
+    let canonical = canonical_bytes(&body);
+    verifying_key
+        .verify(&canonical, &signature)
+        .context("boundary record signature verification failed")
+}
+
+fn load_signing_key(path: &Path) -> Result<SigningKey> {
+    let bytes = fs::read(path).with_context(|| format!("reading signing key {}", path.display()))?;
+    let seed: [u8; 32] = bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("signing key {} must be exactly 32 bytes", path.display()))?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+/// Recursively sort object keys so two semantically-equal records canonicalize
+/// to the same bytes regardless of construction order.
+fn canonicalize_json(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut sorted = Map::new();
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            for key in keys {
+                sorted.insert(key.clone(), canonicalize_json(&map[key]));
+            }
+            Value::Object(sorted)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(canonicalize_json).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Canonical JSON bytes for `value` with the `signature` field removed, used
+/// as the signing/verification input.
+pub fn canonical_bytes(value: &Value) -> Vec<u8> {
+    let mut without_signature = value.clone();
+    if let Some(object) = without_signature.as_object_mut() {
+        object.remove(SIGNATURE_FIELD);
+    }
+    serde_json::to_vec(&canonicalize_json(&without_signature))
+        .expect("canonicalized JSON always serializes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn canonical_bytes_ignore_key_order_and_signature_field() {
+        let a = json!({"b": 1, "a": 2, "signature": {"alg": "ed25519"}});
+        let b = json!({"a": 2, "b": 1});
+        assert_eq!(canonical_bytes(&a), canonical_bytes(&b));
+    }
+
+    #[test]
+    fn sign_and_verify_round_trip() {
+        let seed = [7u8; 32];
+        let signing_key = SigningKey::from_bytes(&seed);
+        let verifying_key = signing_key.verifying_key();
+
+        let dir = tempfile::tempdir().expect("temp dir");
+        let key_path = dir.path().join("signing.key");
+        fs::write(&key_path, seed).expect("write key");
+
+        let mut record = json!({"probe": {"id": "probe"}, "run": {"mode": "baseline"}});
+        sign_record(&mut record, &key_path, Some("key-1")).expect("signing succeeds");
+        assert_eq!(
+            record.pointer("/signature/key_id").and_then(Value::as_str),
+            Some("key-1")
+        );
+
+        verify_record(&record, verifying_key.as_bytes()).expect("verification succeeds");
+
+        let mut tampered = record.clone();
+        tampered["probe"]["id"] = json!("tampered");
+        assert!(verify_record(&tampered, verifying_key.as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,961 @@
+//! Golden-snapshot diffing for emitted boundary objects.
+//!
+//! Complements [`crate::metadata_validation::validate_boundary_objects`]'s
+//! capability-id checks with structural regression detection: probes are
+//! matched to an approved golden record by `probe.id`/`probe.version`, both
+//! sides have volatile fields redacted, and anything that still differs is
+//! reported as a unified-style diff.
+
+use crate::metadata_validation::find_json_files;
+use anyhow::{Context, Result, anyhow};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Default JSON Pointers redacted before comparison: the boundary-object
+/// fields expected to vary between otherwise-identical runs (workspace
+/// placement, captured output). Callers can extend this list to cover
+/// additional volatile fields (e.g. a future timestamp) without code changes.
+pub fn default_redactions() -> Vec<String> {
+    vec![
+        "/run/workspace_root".to_string(),
+        "/payload/stdout_snippet".to_string(),
+        "/payload/stderr_snippet".to_string(),
+    ]
+}
+
+/// Compare every emitted record under `emitted_dirs` against its golden
+/// counterpart under `golden_dirs`, matched by `probe.id`/`probe.version`.
+///
+/// Returns one diagnostic string per record that is missing a golden match or
+/// whose redacted JSON differs from it; callers treat this the same way as
+/// [`crate::metadata_validation::validate_boundary_objects`]'s error vector.
+pub fn diff_against_golden(
+    emitted_dirs: &[PathBuf],
+    golden_dirs: &[PathBuf],
+    redactions: &[String],
+) -> Result<Vec<String>> {
+    let golden_index = index_by_probe(golden_dirs)?;
+    let mut errors = Vec::new();
+
+    for emitted_path in find_json_files(emitted_dirs)? {
+        let mut emitted_value = read_json(&emitted_path)?;
+        let Some(key) = probe_key(&emitted_value) else {
+            errors.push(format!(
+                "{}: missing probe.id/probe.version, cannot match against a golden record",
+                emitted_path.display()
+            ));
+            continue;
+        };
+
+        let Some(golden_path) = golden_index.get(&key) else {
+            errors.push(format!(
+                "{}: no golden record for probe '{}' version '{}'",
+                emitted_path.display(),
+                key.0,
+                key.1
+            ));
+            continue;
+        };
+
+        let mut golden_value = read_json(golden_path)?;
+        redact(&mut emitted_value, redactions);
+        redact(&mut golden_value, redactions);
+
+        if emitted_value == golden_value {
+            continue;
+        }
+
+        let diff = diff_lines(
+            &to_pretty_lines(&golden_value),
+            &to_pretty_lines(&emitted_value),
+        );
+        errors.push(format!(
+            "{}: drifted from golden record {}\n{}",
+            emitted_path.display(),
+            golden_path.display(),
+            diff.join("\n")
+        ));
+    }
+
+    Ok(errors)
+}
+
+/// Overwrite golden records with the current emitted records they match by
+/// `probe.id`/`probe.version`, creating new golden files under `golden_dir`
+/// for emitted records that have none yet.
+///
+/// The raw (unredacted) emitted content is written; redaction only applies
+/// transiently when diffing, so future comparisons still ignore volatile
+/// fields.
+pub fn accept_golden_snapshots(
+    emitted_dirs: &[PathBuf],
+    golden_dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    let mut written = Vec::new();
+    for emitted_path in find_json_files(emitted_dirs)? {
+        let value = read_json(&emitted_path)?;
+        let Some((id, version)) = probe_key(&value) else {
+            continue;
+        };
+
+        let golden_path = golden_dir.join(golden_file_name(&id, &version));
+        let data = fs::read_to_string(&emitted_path)
+            .with_context(|| format!("reading {}", emitted_path.display()))?;
+        fs::write(&golden_path, data)
+            .with_context(|| format!("writing {}", golden_path.display()))?;
+        written.push(golden_path);
+    }
+    Ok(written)
+}
+
+/// One leaf JSON Pointer path that differs between an actual and an expected
+/// record, for structured assertions (see [`diff_records`]). `expected`/
+/// `actual` are `None` when the path is absent on that side entirely, rather
+/// than merely holding a different value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDiff {
+    pub pointer: String,
+    pub expected: Option<Value>,
+    pub actual: Option<Value>,
+}
+
+/// Compare `actual` against `expected` leaf-by-leaf, after masking
+/// `masked_pointers` (JSON Pointers, same convention as
+/// [`default_redactions`]) on both sides, and return one [`FieldDiff`] per
+/// path that still differs.
+///
+/// Unlike [`diff_against_golden`]'s unified-line diff (meant for a file full
+/// of JSON), this targets a single already-parsed record and reports exactly
+/// which fields drifted, so a caller (e.g. a test assertion) can point
+/// straight at the offending path instead of re-reading a text blob.
+pub fn diff_records(
+    actual: &Value,
+    expected: &Value,
+    masked_pointers: &[String],
+) -> Vec<FieldDiff> {
+    let mut actual = actual.clone();
+    let mut expected = expected.clone();
+    redact(&mut actual, masked_pointers);
+    redact(&mut expected, masked_pointers);
+
+    let mut actual_leaves = BTreeMap::new();
+    let mut expected_leaves = BTreeMap::new();
+    flatten_leaves(&actual, String::new(), &mut actual_leaves);
+    flatten_leaves(&expected, String::new(), &mut expected_leaves);
+
+    let pointers: BTreeSet<&String> = actual_leaves.keys().chain(expected_leaves.keys()).collect();
+    pointers
+        .into_iter()
+        .filter_map(|pointer| {
+            let actual_value = actual_leaves.get(pointer);
+            let expected_value = expected_leaves.get(pointer);
+            if actual_value == expected_value {
+                return None;
+            }
+            Some(FieldDiff {
+                pointer: pointer.clone(),
+                expected: expected_value.cloned(),
+                actual: actual_value.cloned(),
+            })
+        })
+        .collect()
+}
+
+/// Assert that `actual` matches the golden fixture at `expected_path`, after
+/// redacting `redactions` (JSON Pointers, same convention as
+/// [`default_redactions`]) and rewriting any absolute-path string leaf
+/// relative to the repo root on both sides, so a temp checkout's absolute
+/// prefix doesn't cause a spurious mismatch against a fixture authored
+/// elsewhere.
+///
+/// Unlike [`diff_records`], which hands back every [`FieldDiff`] for the
+/// caller to inspect, this is meant for a probe author's own test: it
+/// collapses the mismatch into a single `Err` carrying a unified line diff
+/// (`@@ line N @@` hunks) of the two key-sorted records, so
+/// `compare_boundary(...)?` is enough to lock a record's shape down without
+/// hand-asserting each field.
+pub fn compare_boundary(expected_path: &Path, actual: &Value, redactions: &[&str]) -> Result<()> {
+    let expected = read_json(expected_path)?;
+    let pointers: Vec<String> = redactions.iter().map(|s| s.to_string()).collect();
+
+    let (mut actual, mut expected) = match crate::find_repo_root() {
+        Ok(repo_root) => (
+            relativize_absolute_paths(actual.clone(), &repo_root),
+            relativize_absolute_paths(expected.clone(), &repo_root),
+        ),
+        Err(_) => (actual.clone(), expected.clone()),
+    };
+    redact(&mut actual, &pointers);
+    redact(&mut expected, &pointers);
+
+    if actual == expected {
+        return Ok(());
+    }
+
+    let diff = render_unified_diff(
+        &to_sorted_pretty_lines(&expected),
+        &to_sorted_pretty_lines(&actual),
+    );
+    Err(anyhow!(
+        "boundary object does not match golden fixture {}:\n{diff}",
+        expected_path.display(),
+    ))
+}
+
+/// Recursively sort every object's keys so two semantically-equal `Value`s
+/// pretty-print to byte-identical text regardless of field insertion order,
+/// making [`render_unified_diff`]'s line diff deterministic across runs.
+fn sort_keys(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let sorted: BTreeMap<&String, &Value> = map.iter().collect();
+            Value::Object(
+                sorted
+                    .into_iter()
+                    .map(|(key, item)| (key.clone(), sort_keys(item)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(sort_keys).collect()),
+        other => other.clone(),
+    }
+}
+
+fn to_sorted_pretty_lines(value: &Value) -> Vec<String> {
+    let sorted = sort_keys(value);
+    serde_json::to_string_pretty(&sorted)
+        .unwrap_or_else(|_| sorted.to_string())
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UnifiedDiffOp {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Lines of context kept on either side of a hunk in [`render_unified_diff`].
+const UNIFIED_DIFF_CONTEXT: usize = 3;
+
+/// Render a unified diff (`@@` hunks with surrounding context, `-`/`+`
+/// markers) between `expected` and `actual` pretty-printed lines, via the
+/// same textbook LCS dynamic-programming table used elsewhere in this
+/// module, so callers get exactly the nested field that changed instead of
+/// a flat "not equal".
+fn render_unified_diff(expected: &[String], actual: &[String]) -> String {
+    let (n, m) = (expected.len(), actual.len());
+    let mut lengths = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[i][j] = if expected[i] == actual[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops: Vec<(UnifiedDiffOp, &String)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if expected[i] == actual[j] {
+            ops.push((UnifiedDiffOp::Equal, &expected[i]));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            ops.push((UnifiedDiffOp::Delete, &expected[i]));
+            i += 1;
+        } else {
+            ops.push((UnifiedDiffOp::Insert, &actual[j]));
+            j += 1;
+        }
+    }
+    while i < n {
+        ops.push((UnifiedDiffOp::Delete, &expected[i]));
+        i += 1;
+    }
+    while j < m {
+        ops.push((UnifiedDiffOp::Insert, &actual[j]));
+        j += 1;
+    }
+
+    let interesting: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, (op, _))| *op != UnifiedDiffOp::Equal)
+        .map(|(index, _)| index)
+        .collect();
+    if interesting.is_empty() {
+        return String::new();
+    }
+
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for index in interesting {
+        let start = index.saturating_sub(UNIFIED_DIFF_CONTEXT);
+        let end = (index + UNIFIED_DIFF_CONTEXT + 1).min(ops.len());
+        match ranges.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    let mut rendered = String::new();
+    for (start, end) in ranges {
+        let _ = writeln!(rendered, "@@ line {} @@", start + 1);
+        for (op, line) in &ops[start..end] {
+            let prefix = match op {
+                UnifiedDiffOp::Equal => ' ',
+                UnifiedDiffOp::Delete => '-',
+                UnifiedDiffOp::Insert => '+',
+            };
+            rendered.push(prefix);
+            rendered.push_str(line);
+            rendered.push('\n');
+        }
+    }
+    rendered
+}
+
+/// Rewrite every absolute-path-shaped string leaf in `value` relative to
+/// `repo_root`: a leaf already under `repo_root` is stripped down to its
+/// relative tail, and a leaf that merely contains `repo_root`'s directory
+/// name (e.g. a fixture authored against a differently-placed checkout) is
+/// cut at that name instead. Anything else is left untouched.
+fn relativize_absolute_paths(value: Value, repo_root: &Path) -> Value {
+    match value {
+        Value::String(text) => Value::String(relativize_absolute_path(&text, repo_root)),
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| relativize_absolute_paths(item, repo_root))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, item)| (key, relativize_absolute_paths(item, repo_root)))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+fn relativize_absolute_path(text: &str, repo_root: &Path) -> String {
+    if !text.starts_with('/') {
+        return text.to_string();
+    }
+    if let Ok(rel) = Path::new(text).strip_prefix(repo_root) {
+        return rel.display().to_string();
+    }
+    if let Some(name) = repo_root.file_name().and_then(|n| n.to_str()) {
+        let marker = format!("/{name}/");
+        if let Some(index) = text.find(&marker) {
+            return text[index + marker.len()..].to_string();
+        }
+    }
+    text.to_string()
+}
+
+/// Render [`FieldDiff`]s as one `+`/`-`/`~` line per leaf path: `-` for a path
+/// only present in `expected`, `+` for one only present in `actual`, `~` for
+/// one present on both sides with a different value.
+pub fn render_field_diffs(diffs: &[FieldDiff]) -> String {
+    diffs
+        .iter()
+        .map(|diff| match (&diff.expected, &diff.actual) {
+            (Some(expected), None) => format!("- {}: {expected}", diff.pointer),
+            (None, Some(actual)) => format!("+ {}: {actual}", diff.pointer),
+            (Some(expected), Some(actual)) => {
+                format!("~ {}: {expected} -> {actual}", diff.pointer)
+            }
+            (None, None) => {
+                unreachable!("diff_records never emits a FieldDiff with both sides absent")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Compare `actual` against `template`, leaf-by-leaf, honoring match-any
+/// placeholders in `template`'s string leaves: a bare `[..]` matches any
+/// value at that path, and a named placeholder like `[ERRNO]`/`[PATH]` (any
+/// all-caps/underscore spelling in brackets) does the same while documenting
+/// what the field holds. Returns one [`FieldDiff`] per leaf that still
+/// differs, addressed by JSON Pointer.
+///
+/// This is [`diff_records`]'s sibling for hand-authored golden files: where
+/// `diff_records` blanks out volatile fields by path before comparing two
+/// concrete records, this lets the golden template itself mark which leaves
+/// are volatile, so a probe's contract can ship a golden file that reads
+/// `"errno": "[ERRNO]"` instead of maintaining a separate list of paths to
+/// redact.
+pub fn diff_against_template(actual: &Value, template: &Value) -> Vec<FieldDiff> {
+    let mut actual_leaves = BTreeMap::new();
+    let mut template_leaves = BTreeMap::new();
+    flatten_leaves(actual, String::new(), &mut actual_leaves);
+    flatten_leaves(template, String::new(), &mut template_leaves);
+
+    let pointers: BTreeSet<&String> = actual_leaves.keys().chain(template_leaves.keys()).collect();
+    pointers
+        .into_iter()
+        .filter_map(|pointer| {
+            let actual_value = actual_leaves.get(pointer);
+            let template_value = template_leaves.get(pointer);
+            if let Some(Value::String(token)) = template_value {
+                if is_wildcard_token(token) && actual_value.is_some() {
+                    return None;
+                }
+            }
+            if actual_value == template_value {
+                return None;
+            }
+            Some(FieldDiff {
+                pointer: pointer.clone(),
+                expected: template_value.cloned(),
+                actual: actual_value.cloned(),
+            })
+        })
+        .collect()
+}
+
+/// Load an emitted boundary object and its golden template from disk and
+/// diff them with [`diff_against_template`]. Returns an empty vector when
+/// the emitted record satisfies the template; a future probe contract gate
+/// can fail a probe's run on the first non-empty result, reporting
+/// [`render_field_diffs`] for a readable diff instead of only checking that
+/// `emit-record` ran at all.
+pub fn check_boundary_object_against_template(
+    emitted_path: &Path,
+    template_path: &Path,
+) -> Result<Vec<FieldDiff>> {
+    let actual = read_json(emitted_path)?;
+    let template = read_json(template_path)?;
+    Ok(diff_against_template(&actual, &template))
+}
+
+/// Whether `token` is a match-any placeholder: the bare wildcard `[..]`, or a
+/// named redaction such as `[ERRNO]`/`[PATH]` (any non-empty all-caps/
+/// underscore spelling in brackets).
+fn is_wildcard_token(token: &str) -> bool {
+    let Some(inner) = token.strip_prefix('[').and_then(|s| s.strip_suffix(']')) else {
+        return false;
+    };
+    inner == ".."
+        || (!inner.is_empty() && inner.chars().all(|c| c.is_ascii_uppercase() || c == '_'))
+}
+
+/// Recursively collect JSON Pointer paths to every leaf value (a scalar, or
+/// an empty object/array, which has no children to descend into).
+fn flatten_leaves(value: &Value, pointer: String, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                flatten_leaves(child, format!("{pointer}/{key}"), out);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for (index, child) in items.iter().enumerate() {
+                flatten_leaves(child, format!("{pointer}/{index}"), out);
+            }
+        }
+        leaf => {
+            out.insert(pointer, leaf.clone());
+        }
+    }
+}
+
+fn golden_file_name(id: &str, version: &str) -> String {
+    format!("{id}@{version}.json")
+}
+
+fn index_by_probe(dirs: &[PathBuf]) -> Result<BTreeMap<(String, String), PathBuf>> {
+    let mut index = BTreeMap::new();
+    for path in find_json_files(dirs)? {
+        let value = read_json(&path)?;
+        if let Some(key) = probe_key(&value) {
+            index.insert(key, path);
+        }
+    }
+    Ok(index)
+}
+
+fn probe_key(value: &Value) -> Option<(String, String)> {
+    let id = value.pointer("/probe/id")?.as_str()?.to_string();
+    let version = value.pointer("/probe/version")?.as_str()?.to_string();
+    Some((id, version))
+}
+
+fn read_json(path: &Path) -> Result<Value> {
+    let data = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    serde_json::from_str(&data).with_context(|| format!("parsing {}", path.display()))
+}
+
+fn redact(value: &mut Value, redactions: &[String]) {
+    for pointer in redactions {
+        if let Some(target) = value.pointer_mut(pointer) {
+            *target = Value::String("<redacted>".to_string());
+        }
+    }
+}
+
+fn to_pretty_lines(value: &Value) -> Vec<String> {
+    serde_json::to_string_pretty(value)
+        .unwrap_or_else(|_| value.to_string())
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+/// Unified-style line diff: unchanged lines keep a blank prefix, removed
+/// lines (golden-only) get `-`, added lines (emitted-only) get `+`.
+fn diff_lines(left: &[String], right: &[String]) -> Vec<String> {
+    let matches = longest_common_subsequence(left, right);
+    let mut output = Vec::new();
+    let (mut li, mut ri) = (0, 0);
+
+    for (match_li, match_ri) in matches {
+        while li < match_li {
+            output.push(format!("-{}", left[li]));
+            li += 1;
+        }
+        while ri < match_ri {
+            output.push(format!("+{}", right[ri]));
+            ri += 1;
+        }
+        output.push(format!(" {}", left[li]));
+        li += 1;
+        ri += 1;
+    }
+    while li < left.len() {
+        output.push(format!("-{}", left[li]));
+        li += 1;
+    }
+    while ri < right.len() {
+        output.push(format!("+{}", right[ri]));
+        ri += 1;
+    }
+    output
+}
+
+/// Indices (into `left`, `right`) of a longest common subsequence, computed
+/// with the textbook O(n*m) dynamic-programming table. Boundary objects are
+/// small enough that this is comfortably fast.
+fn longest_common_subsequence(left: &[String], right: &[String]) -> Vec<(usize, usize)> {
+    let (n, m) = (left.len(), right.len());
+    let mut lengths = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[i][j] = if left[i] == right[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if left[i] == right[j] {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(id: &str, version: &str, workspace_root: &str, allow_extra: bool) -> Value {
+        json!({
+            "schema_version": "cfbo-v1",
+            "capabilities_schema_version": "macOS_codex_v1",
+            "stack": {"os": "Darwin"},
+            "probe": {
+                "id": id,
+                "version": version,
+                "primary_capability_id": "cap_fs_read_workspace_tree",
+                "secondary_capability_ids": []
+            },
+            "run": {"mode": "baseline", "workspace_root": workspace_root, "command": "true"},
+            "operation": {"category": "fs", "verb": "read", "target": "/tmp", "args": {}},
+            "result": {
+                "observed_result": if allow_extra { "denied" } else { "success" },
+                "raw_exit_code": 0,
+                "errno": null,
+                "message": null,
+                "error_detail": null
+            },
+            "payload": {"stdout_snippet": "run-specific output", "stderr_snippet": null, "raw": {}},
+            "capability_context": {
+                "primary": {"id": "cap_fs_read_workspace_tree", "category": "filesystem", "layer": "os_sandbox"},
+                "secondary": []
+            }
+        })
+    }
+
+    fn write_record(dir: &Path, name: &str, value: &Value) {
+        fs::write(dir.join(name), serde_json::to_string(value).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn diff_against_golden_ignores_redacted_fields() {
+        let emitted_dir = tempfile::tempdir().expect("temp dir");
+        let golden_dir = tempfile::tempdir().expect("temp dir");
+        write_record(
+            emitted_dir.path(),
+            "record.json",
+            &record("probe", "1", "/tmp/run-a", false),
+        );
+        write_record(
+            golden_dir.path(),
+            "probe@1.json",
+            &record("probe", "1", "/tmp/run-b", false),
+        );
+
+        let errors = diff_against_golden(
+            &[emitted_dir.path().to_path_buf()],
+            &[golden_dir.path().to_path_buf()],
+            &default_redactions(),
+        )
+        .expect("diff should run");
+
+        assert!(
+            errors.is_empty(),
+            "expected redacted fields to be ignored, got {errors:?}"
+        );
+    }
+
+    #[test]
+    fn diff_against_golden_reports_drift_in_unredacted_fields() {
+        let emitted_dir = tempfile::tempdir().expect("temp dir");
+        let golden_dir = tempfile::tempdir().expect("temp dir");
+        write_record(
+            emitted_dir.path(),
+            "record.json",
+            &record("probe", "1", "/tmp/run-a", true),
+        );
+        write_record(
+            golden_dir.path(),
+            "probe@1.json",
+            &record("probe", "1", "/tmp/run-b", false),
+        );
+
+        let errors = diff_against_golden(
+            &[emitted_dir.path().to_path_buf()],
+            &[golden_dir.path().to_path_buf()],
+            &default_redactions(),
+        )
+        .expect("diff should run");
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("drifted from golden record"));
+        assert!(errors[0].contains("-    \"observed_result\": \"success\","));
+        assert!(errors[0].contains("+    \"observed_result\": \"denied\","));
+    }
+
+    #[test]
+    fn diff_against_golden_reports_missing_golden_record() {
+        let emitted_dir = tempfile::tempdir().expect("temp dir");
+        let golden_dir = tempfile::tempdir().expect("temp dir");
+        write_record(
+            emitted_dir.path(),
+            "record.json",
+            &record("probe", "1", "/tmp/run-a", false),
+        );
+
+        let errors = diff_against_golden(
+            &[emitted_dir.path().to_path_buf()],
+            &[golden_dir.path().to_path_buf()],
+            &default_redactions(),
+        )
+        .expect("diff should run");
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("no golden record"));
+    }
+
+    #[test]
+    fn accept_golden_snapshots_writes_matching_file() {
+        let emitted_dir = tempfile::tempdir().expect("temp dir");
+        let golden_dir = tempfile::tempdir().expect("temp dir");
+        write_record(
+            emitted_dir.path(),
+            "record.json",
+            &record("probe", "1", "/tmp/run-a", true),
+        );
+
+        let written =
+            accept_golden_snapshots(&[emitted_dir.path().to_path_buf()], golden_dir.path())
+                .expect("accept should run");
+
+        assert_eq!(written, vec![golden_dir.path().join("probe@1.json")]);
+
+        let errors = diff_against_golden(
+            &[emitted_dir.path().to_path_buf()],
+            &[golden_dir.path().to_path_buf()],
+            &default_redactions(),
+        )
+        .expect("diff should run");
+        assert!(errors.is_empty(), "accepted snapshot should now match");
+    }
+
+    #[test]
+    fn diff_records_reports_changed_missing_and_extra_leaves() {
+        let expected = record("probe", "1", "/tmp/run-a", false);
+        let mut actual = record("probe", "1", "/tmp/run-b", true);
+        actual["payload"]["raw"] = json!({"added_field": "new"});
+
+        let diffs = diff_records(&actual, &expected, &default_redactions());
+
+        let changed = diffs
+            .iter()
+            .find(|d| d.pointer == "/result/observed_result")
+            .expect("observed_result should be reported as changed");
+        assert_eq!(
+            changed.expected.as_ref().and_then(Value::as_str),
+            Some("success")
+        );
+        assert_eq!(
+            changed.actual.as_ref().and_then(Value::as_str),
+            Some("denied")
+        );
+
+        let added = diffs
+            .iter()
+            .find(|d| d.pointer == "/payload/raw/added_field")
+            .expect("added_field should be reported as an added leaf");
+        assert_eq!(added.expected, None);
+        assert_eq!(added.actual.as_ref().and_then(Value::as_str), Some("new"));
+
+        assert!(
+            diffs.iter().all(|d| d.pointer != "/run/workspace_root"),
+            "redacted workspace_root should not appear in the diff"
+        );
+    }
+
+    #[test]
+    fn render_field_diffs_formats_added_removed_and_changed_lines() {
+        let diffs = vec![
+            FieldDiff {
+                pointer: "/a".to_string(),
+                expected: Some(json!("old")),
+                actual: Some(json!("new")),
+            },
+            FieldDiff {
+                pointer: "/b".to_string(),
+                expected: Some(json!("gone")),
+                actual: None,
+            },
+            FieldDiff {
+                pointer: "/c".to_string(),
+                expected: None,
+                actual: Some(json!("fresh")),
+            },
+        ];
+
+        let rendered = render_field_diffs(&diffs);
+        assert_eq!(
+            rendered,
+            "~ /a: \"old\" -> \"new\"\n- /b: \"gone\"\n+ /c: \"fresh\""
+        );
+    }
+
+    #[test]
+    fn diff_against_template_ignores_wildcard_and_named_tokens() {
+        let template = record("probe", "1", "[PATH]", false);
+        let mut actual = record("probe", "1", "/tmp/run-a", false);
+        actual["result"]["errno"] = json!("EPERM");
+
+        let mut template = template;
+        template["result"]["errno"] = json!("[ERRNO]");
+        template["payload"]["stdout_snippet"] = json!("[..]");
+
+        let diffs = diff_against_template(&actual, &template);
+        assert!(
+            diffs.is_empty(),
+            "wildcard and named tokens should match any value, got {diffs:?}"
+        );
+    }
+
+    #[test]
+    fn diff_against_template_reports_mismatched_leaves() {
+        let template = record("probe", "1", "/tmp/run-a", false);
+        let actual = record("probe", "1", "/tmp/run-a", true);
+
+        let diffs = diff_against_template(&actual, &template);
+        let changed = diffs
+            .iter()
+            .find(|d| d.pointer == "/result/observed_result")
+            .expect("observed_result should be reported as changed");
+        assert_eq!(
+            changed.expected.as_ref().and_then(Value::as_str),
+            Some("success")
+        );
+        assert_eq!(
+            changed.actual.as_ref().and_then(Value::as_str),
+            Some("denied")
+        );
+    }
+
+    #[test]
+    fn diff_against_template_reports_missing_and_extra_leaves() {
+        let mut template = record("probe", "1", "/tmp/run-a", false);
+        let mut actual = record("probe", "1", "/tmp/run-a", false);
+        template["payload"]["raw"] = json!({"only_in_template": "x"});
+        actual["payload"]["raw"] = json!({"only_in_actual": "y"});
+
+        let diffs = diff_against_template(&actual, &template);
+        assert!(diffs
+            .iter()
+            .any(|d| d.pointer == "/payload/raw/only_in_template" && d.actual.is_none()));
+        assert!(diffs
+            .iter()
+            .any(|d| d.pointer == "/payload/raw/only_in_actual" && d.expected.is_none()));
+    }
+
+    #[test]
+    fn check_boundary_object_against_template_reads_files() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let mut template = record("probe", "1", "[PATH]", false);
+        template["result"]["errno"] = json!("[ERRNO]");
+        let actual = record("probe", "1", "/tmp/run-a", false);
+
+        let emitted_path = dir.path().join("actual.json");
+        let template_path = dir.path().join("template.json");
+        write_record(dir.path(), "actual.json", &actual);
+        write_record(dir.path(), "template.json", &template);
+
+        let diffs = check_boundary_object_against_template(&emitted_path, &template_path)
+            .expect("check should run");
+        assert!(
+            diffs.is_empty(),
+            "expected template to match, got {diffs:?}"
+        );
+    }
+
+    #[test]
+    fn compare_boundary_ok_when_only_redacted_fields_differ() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let expected_path = dir.path().join("golden.json");
+        write_record(
+            dir.path(),
+            "golden.json",
+            &record("probe", "1", "/tmp/run-a", false),
+        );
+        let actual = record("probe", "1", "/tmp/run-b", false);
+
+        compare_boundary(&expected_path, &actual, &["/run/workspace_root"])
+            .expect("redacted workspace_root should not fail comparison");
+    }
+
+    #[test]
+    fn compare_boundary_reports_unredacted_drift() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let expected_path = dir.path().join("golden.json");
+        write_record(
+            dir.path(),
+            "golden.json",
+            &record("probe", "1", "/tmp/run-a", false),
+        );
+        let actual = record("probe", "1", "/tmp/run-a", true);
+
+        let err = compare_boundary(&expected_path, &actual, &["/run/workspace_root"])
+            .expect_err("observed_result drift should fail comparison");
+        let message = err.to_string();
+        assert!(message.contains("does not match golden fixture"));
+        assert!(message.contains("@@ line"));
+        assert!(message.contains("-    \"observed_result\": \"success\","));
+        assert!(message.contains("+    \"observed_result\": \"denied\","));
+    }
+
+    #[test]
+    fn render_unified_diff_emits_hunks_with_context() {
+        let expected: Vec<String> = ["a", "b", "c", "d", "e"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let actual: Vec<String> = ["a", "b", "X", "d", "e"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let diff = render_unified_diff(&expected, &actual);
+        assert!(diff.contains("@@ line"));
+        assert!(diff.contains("-c"));
+        assert!(diff.contains("+X"));
+        assert!(diff.contains(" a"));
+        assert!(diff.contains(" e"));
+    }
+
+    #[test]
+    fn render_unified_diff_is_empty_for_identical_input() {
+        let lines: Vec<String> = ["a", "b"].iter().map(|s| s.to_string()).collect();
+        assert!(render_unified_diff(&lines, &lines).is_empty());
+    }
+
+    #[test]
+    fn sort_keys_produces_identical_pretty_printed_output_regardless_of_insertion_order() {
+        let first = json!({"b": 1, "a": 2});
+        let second = json!({"a": 2, "b": 1});
+        assert_eq!(
+            to_sorted_pretty_lines(&first),
+            to_sorted_pretty_lines(&second)
+        );
+    }
+
+    #[test]
+    fn relativize_absolute_path_strips_repo_root_prefix() {
+        let repo_root = Path::new("/repo/checkout");
+        assert_eq!(
+            relativize_absolute_path("/repo/checkout/probes/fs/read.sh", repo_root),
+            "probes/fs/read.sh"
+        );
+    }
+
+    #[test]
+    fn relativize_absolute_path_strips_at_repo_basename_when_not_a_direct_prefix() {
+        let repo_root = Path::new("/home/dev/fencerunner");
+        assert_eq!(
+            relativize_absolute_path("/Users/other/fencerunner/probes/fs/read.sh", repo_root),
+            "probes/fs/read.sh"
+        );
+    }
+
+    #[test]
+    fn relativize_absolute_path_leaves_unrelated_strings_untouched() {
+        let repo_root = Path::new("/repo/checkout");
+        assert_eq!(
+            relativize_absolute_path("cap_fs_read", repo_root),
+            "cap_fs_read"
+        );
+        assert_eq!(
+            relativize_absolute_path("/tmp/scratch/x", repo_root),
+            "/tmp/scratch/x"
+        );
+    }
+
+    #[test]
+    fn is_wildcard_token_accepts_dotdot_and_shout_case_only() {
+        assert!(is_wildcard_token("[..]"));
+        assert!(is_wildcard_token("[ERRNO]"));
+        assert!(is_wildcard_token("[PATH]"));
+        assert!(!is_wildcard_token("[..] extra"));
+        assert!(!is_wildcard_token("plain string"));
+        assert!(!is_wildcard_token("[lower]"));
+        assert!(!is_wildcard_token("[]"));
+    }
+}
@@ -5,20 +5,22 @@
 mod support;
 
 use anyhow::{Context, Result, bail};
+use base64::Engine;
 use fencerunner::emit_support::{
-    JsonObjectBuilder, PayloadArgs, TextSource, normalize_secondary_ids, validate_status,
+    JsonObjectBuilder, PayloadArgs, TextSource, did_you_mean, levenshtein, normalize_secondary_ids,
+    parse_defaults_file, prune_null_fields, suggest_closest, validate_status,
 };
 use fencerunner::fence_run_support::{
-    WorkspaceOverride, canonicalize_path, resolve_probe_metadata, workspace_plan_from_override,
-    workspace_tmpdir_plan,
+    ContainmentPolicy, WorkspaceOverride, WorkspacePlan, canonicalize_path, resolve_probe_metadata,
+    workspace_plan_from_override, workspace_tmpdir_plan,
 };
 use fencerunner::{
     self, BoundaryObject, BoundarySchema, CANONICAL_BOUNDARY_SCHEMA_PATH, CapabilityCategory,
     CapabilityContext, CapabilityId, CapabilityIndex, CapabilityLayer, CapabilitySnapshot,
     CatalogKey, CatalogRepository, OperationInfo, Payload, Probe, ProbeInfo, ProbeMetadata,
     ResultInfo, RunInfo, StackInfo, default_boundary_descriptor_path, default_catalog_path,
-    list_probes, load_catalog_from_path, resolve_boundary_schema_path, resolve_helper_binary,
-    resolve_probe,
+    default_redactions, list_probes, load_catalog_from_path, resolve_boundary_schema_path,
+    resolve_helper_binary, resolve_probe,
 };
 use jsonschema::JSONSchema;
 use serde_json::{Value, json};
@@ -26,12 +28,15 @@ use std::collections::{BTreeMap, BTreeSet};
 use std::env;
 use std::fs::{self, File};
 use std::io::Write;
+use std::net::{TcpListener, TcpStream};
 use std::os::unix::fs::{PermissionsExt, symlink};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Mutex, MutexGuard, OnceLock};
-use support::{helper_binary, make_executable, repo_root, run_command};
+use std::thread;
+use std::time::{Duration, Instant};
+use support::{assert_matches_snapshot, helper_binary, make_executable, repo_root, run_command};
 use tempfile::{NamedTempFile, TempDir};
 
 // Ensures boundary objects emitted via emit-record satisfy the boundary schema and
@@ -315,6 +320,87 @@ fn harness_smoke_probe_fixture() -> Result<()> {
     Ok(())
 }
 
+// Characterizes the network boundary the way harness_smoke_probe_fixture
+// characterizes the filesystem one: a probe that attempts a loopback
+// connection should observe `success` under baseline (probe-exec, the
+// current-gen connector) and `denied` once it's wrapped in codex's sandbox
+// (fence-run, which is the only connector that currently implements
+// codex-sandbox; see connectors.rs). Skipped unless FENCE_NET_TESTS=1, Docker
+// is reachable, and (for the sandbox half) codex is on PATH.
+#[test]
+fn cap_net_probes_observe_baseline_success_and_sandbox_denial() -> Result<()> {
+    let repo_root = repo_root();
+    let _guard = repo_guard();
+
+    let Some(service) = ContainerFixture::launch("nginx:alpine", "fence-net-http", 80)? else {
+        eprintln!(
+            "skipping cap_net fixture test: set FENCE_NET_TESTS=1 with Docker available to run it"
+        );
+        return Ok(());
+    };
+
+    let contents = format!(
+        r#"#!/usr/bin/env bash
+set -euo pipefail
+repo_root=$(cd "$(dirname "${{BASH_SOURCE[0]}}")/.." >/dev/null 2>&1 && pwd)
+emit_record_bin="${{repo_root}}/bin/emit-record"
+probe_name="tests_cap_net_connect_loopback"
+primary_capability_id="cap_net_connect_loopback"
+target="{address}"
+host="${{target%%:*}}"
+port="${{target##*:}}"
+if timeout 2 bash -c "exec 3<>/dev/tcp/${{host}}/${{port}}" 2>/dev/null; then
+  status="success"
+  message="connected to ${{target}}"
+else
+  status="denied"
+  message="connection to ${{target}} refused or blocked"
+fi
+"${{emit_record_bin}}" \
+  --run-mode "${{FENCE_RUN_MODE:-baseline}}" \
+  --probe-name "${{probe_name}}" \
+  --probe-version "1" \
+  --primary-capability-id "${{primary_capability_id}}" \
+  --command "connect ${{target}}" \
+  --category "net" \
+  --verb "connect" \
+  --target "${{target}}" \
+  --status "${{status}}" \
+  --errno "" \
+  --message "${{message}}" \
+  --raw-exit-code "0" \
+  --payload-file /dev/null \
+  --operation-args "{{}}"
+"#,
+        address = service.address()
+    );
+    let fixture = FixtureProbe::install_from_contents(
+        &repo_root,
+        "tests_cap_net_connect_loopback",
+        &contents,
+    )?;
+
+    let mut baseline_cmd = Command::new(helper_binary(&repo_root, "probe-exec"));
+    baseline_cmd.arg("baseline").arg(fixture.probe_id());
+    let baseline_output = run_command(baseline_cmd)?;
+    let (baseline_record, _) = parse_boundary_object(&baseline_output.stdout)?;
+    assert_eq!(baseline_record.operation.category, "net");
+    assert_eq!(baseline_record.result.observed_result, "success");
+
+    if !codex_available() {
+        eprintln!("skipping codex-sandbox half of cap_net fixture test: codex not on PATH");
+        return Ok(());
+    }
+
+    let mut sandbox_cmd = Command::new(helper_binary(&repo_root, "fence-run"));
+    sandbox_cmd.arg("codex-sandbox").arg(fixture.probe_id());
+    let sandbox_output = run_command(sandbox_cmd)?;
+    let (sandbox_record, _) = parse_boundary_object(&sandbox_output.stdout)?;
+    assert_eq!(sandbox_record.result.observed_result, "denied");
+
+    Ok(())
+}
+
 // Checks that workspace_root falls back to the caller's cwd when the env hint
 // is blank, matching legacy agent expectations.
 #[test]
@@ -1127,6 +1213,32 @@ fn boundary_object_round_trips_structs() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn diff_against_reports_drift_and_ignores_redacted_fields() -> Result<()> {
+    let expected = serde_json::to_value(sample_boundary_object())?;
+
+    let mut bo = sample_boundary_object();
+    bo.result.observed_result = "denied".to_string();
+    bo.run.workspace_root = Some("/tmp/other-run".to_string());
+
+    let diffs = bo.diff_against(&expected, &default_redactions())?;
+    assert_eq!(diffs.len(), 1);
+    assert_eq!(diffs[0].pointer, "/result/observed_result");
+    assert!(
+        diffs.iter().all(|d| d.pointer != "/run/workspace_root"),
+        "redacted workspace_root should not appear in the diff"
+    );
+    Ok(())
+}
+
+#[test]
+fn assert_matches_snapshot_passes_for_identical_records() -> Result<()> {
+    let expected = serde_json::to_value(sample_boundary_object())?;
+    let bo = sample_boundary_object();
+    assert_matches_snapshot(&bo, &expected, &default_redactions());
+    Ok(())
+}
+
 #[test]
 fn capabilities_schema_version_serializes_in_json() -> Result<()> {
     let mut bo = sample_boundary_object();
@@ -1175,6 +1287,7 @@ fn capability_snapshot_serializes_to_expected_shape() -> Result<()> {
     let ctx = CapabilityContext {
         primary: snapshot.clone(),
         secondary: vec![snapshot.clone()],
+        resolved_grant: None,
     };
     let value = serde_json::to_value(&ctx)?;
     assert_eq!(
@@ -1561,6 +1674,369 @@ fn payload_builder_accepts_inline_snippets() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn emit_record_signing_key_file_attaches_verifiable_signature() -> Result<()> {
+    use ed25519_dalek::SigningKey;
+
+    let repo_root = repo_root();
+    let emit_record = helper_binary(&repo_root, "emit-record");
+
+    let seed = [9u8; 32];
+    let signing_key = SigningKey::from_bytes(&seed);
+    let mut key_file = NamedTempFile::new().context("failed to allocate signing key file")?;
+    key_file.write_all(&seed)?;
+
+    let output = Command::new(&emit_record)
+        .arg("--run-mode")
+        .arg("baseline")
+        .arg("--probe-name")
+        .arg("tests_signing")
+        .arg("--probe-version")
+        .arg("1")
+        .arg("--primary-capability-id")
+        .arg("cap_fs_read_workspace_tree")
+        .arg("--command")
+        .arg("true")
+        .arg("--category")
+        .arg("fs")
+        .arg("--verb")
+        .arg("read")
+        .arg("--target")
+        .arg("/tmp")
+        .arg("--status")
+        .arg("success")
+        .arg("--operation-args")
+        .arg("{}")
+        .arg("--signing-key-file")
+        .arg(key_file.path())
+        .arg("--signing-key-id")
+        .arg("tests-key")
+        .output()
+        .context("failed to execute emit-record with --signing-key-file")?;
+    assert!(output.status.success(), "emit-record should succeed");
+
+    let value: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        value.pointer("/signature/key_id").and_then(Value::as_str),
+        Some("tests-key")
+    );
+    fencerunner::verify_record(&value, signing_key.verifying_key().as_bytes())
+        .context("signature should verify")?;
+
+    let mut tampered = value.clone();
+    tampered["result"]["observed_result"] = json!("denied");
+    assert!(
+        fencerunner::verify_record(&tampered, signing_key.verifying_key().as_bytes()).is_err(),
+        "tampered record should fail verification"
+    );
+    Ok(())
+}
+
+#[test]
+fn emit_record_version_reports_compatibility_surface() -> Result<()> {
+    let repo_root = repo_root();
+    let emit_record = helper_binary(&repo_root, "emit-record");
+
+    let output = Command::new(&emit_record)
+        .arg("version")
+        .output()
+        .context("failed to execute emit-record version")?;
+    assert!(output.status.success(), "emit-record version should succeed");
+
+    let value: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        value.get("schema_version").and_then(Value::as_str),
+        Some(boundary_schema_version().as_str())
+    );
+    assert_eq!(
+        value.get("schema_key").and_then(Value::as_str),
+        boundary_schema_key().as_deref()
+    );
+    assert!(value.get("capabilities_schema_version").is_some());
+    assert!(
+        value
+            .get("catalog_path")
+            .and_then(Value::as_str)
+            .map(|p| Path::new(p).is_file())
+            .unwrap_or(false)
+    );
+    assert!(
+        value
+            .get("detect_stack_path")
+            .and_then(Value::as_str)
+            .map(|p| Path::new(p).is_file())
+            .unwrap_or(false)
+    );
+    assert!(value.get("stack").and_then(|s| s.get("os")).is_some());
+    Ok(())
+}
+
+#[test]
+fn prune_null_fields_drops_nulls_but_keeps_empty_values() {
+    let mut value = json!({
+        "errno": null,
+        "message": "",
+        "raw": {},
+        "nested": {
+            "error_detail": null,
+            "kept": "value"
+        },
+        "items": [{"a": null, "b": 1}]
+    });
+    prune_null_fields(&mut value);
+    assert!(value.get("errno").is_none());
+    assert_eq!(value.get("message").and_then(Value::as_str), Some(""));
+    assert_eq!(value.get("raw"), Some(&json!({})));
+    let nested = value.get("nested").and_then(Value::as_object).unwrap();
+    assert!(!nested.contains_key("error_detail"));
+    assert_eq!(nested.get("kept").and_then(Value::as_str), Some("value"));
+    let item = value.get("items").and_then(Value::as_array).unwrap()[0]
+        .as_object()
+        .unwrap();
+    assert!(!item.contains_key("a"));
+    assert_eq!(item.get("b").and_then(Value::as_i64), Some(1));
+}
+
+#[test]
+fn emit_record_omit_empty_prunes_null_fields() -> Result<()> {
+    let repo_root = repo_root();
+    let emit_record = helper_binary(&repo_root, "emit-record");
+
+    let output = Command::new(&emit_record)
+        .arg("--run-mode")
+        .arg("baseline")
+        .arg("--probe-name")
+        .arg("tests_omit_empty")
+        .arg("--probe-version")
+        .arg("1")
+        .arg("--primary-capability-id")
+        .arg("cap_fs_read_workspace_tree")
+        .arg("--command")
+        .arg("true")
+        .arg("--category")
+        .arg("fs")
+        .arg("--verb")
+        .arg("read")
+        .arg("--target")
+        .arg("/tmp")
+        .arg("--status")
+        .arg("success")
+        .arg("--operation-args")
+        .arg("{}")
+        .arg("--omit-empty")
+        .output()
+        .context("failed to execute emit-record with --omit-empty")?;
+    assert!(output.status.success(), "emit-record should succeed");
+
+    let value: Value = serde_json::from_slice(&output.stdout)?;
+    assert!(value.pointer("/result/errno").is_none());
+    assert!(value.pointer("/result/message").is_none());
+    assert!(value.pointer("/result/error_detail").is_none());
+    assert!(value.pointer("/run/workspace_root").is_some());
+    Ok(())
+}
+
+#[test]
+fn payload_builder_base64_encodes_non_utf8_file_snippets() -> Result<()> {
+    let binary_bytes: &[u8] = &[0xFF, 0xFE, 0x00, 0x10, 0x80];
+    let mut file = NamedTempFile::new().context("failed to allocate snippet file")?;
+    file.write_all(binary_bytes)?;
+
+    let mut payload = PayloadArgs::default();
+    payload.set_stdout(TextSource::File(file.path().to_path_buf()))?;
+    let built = payload.build()?;
+
+    assert_eq!(
+        built.pointer("/stdout_snippet/encoding").and_then(Value::as_str),
+        Some("base64")
+    );
+    let data = built
+        .pointer("/stdout_snippet/data")
+        .and_then(Value::as_str)
+        .expect("base64 data present");
+    let decoded = base64::engine::general_purpose::STANDARD.decode(data)?;
+    assert_eq!(decoded, binary_bytes);
+    Ok(())
+}
+
+#[test]
+fn payload_builder_forces_base64_for_binary_flag_even_with_valid_utf8() -> Result<()> {
+    let mut file = NamedTempFile::new().context("failed to allocate snippet file")?;
+    file.write_all(b"plain text")?;
+
+    let mut payload = PayloadArgs::default();
+    payload.set_stderr(TextSource::BinaryFile(file.path().to_path_buf()))?;
+    let built = payload.build()?;
+
+    assert_eq!(
+        built.pointer("/stderr_snippet/encoding").and_then(Value::as_str),
+        Some("base64")
+    );
+    let data = built
+        .pointer("/stderr_snippet/data")
+        .and_then(Value::as_str)
+        .expect("base64 data present");
+    let decoded = base64::engine::general_purpose::STANDARD.decode(data)?;
+    assert_eq!(decoded, b"plain text");
+    Ok(())
+}
+
+#[test]
+fn payload_builder_without_merge_rejects_file_plus_inline_fields() -> Result<()> {
+    let mut file = NamedTempFile::new().context("failed to allocate payload file")?;
+    write!(file, r#"{{"stdout_snippet": "from file"}}"#)?;
+
+    let mut payload = PayloadArgs::default();
+    payload.set_payload_file(file.path().to_path_buf())?;
+    payload.set_stderr(TextSource::Inline("inline stderr".to_string()))?;
+
+    let err = payload.build().expect_err("strict mode should reject the combination");
+    assert!(err.to_string().contains("--payload-merge-file"));
+    Ok(())
+}
+
+#[test]
+fn payload_builder_merge_mode_layers_inline_fields_over_file_base() -> Result<()> {
+    let mut file = NamedTempFile::new().context("failed to allocate payload file")?;
+    write!(
+        file,
+        r#"{{"stdout_snippet": "from file", "raw": {{"nested": {{"a": 1, "b": 2}}, "untouched": true}}}}"#
+    )?;
+
+    let mut payload = PayloadArgs::default();
+    payload.set_payload_file(file.path().to_path_buf())?;
+    payload.enable_merge_with_file();
+    payload
+        .raw_mut()
+        .insert_json_value("nested".to_string(), r#"{"b": 99}"#.to_string(), "raw")?;
+
+    let built = payload.build()?;
+    assert_eq!(
+        built.pointer("/stdout_snippet").and_then(Value::as_str),
+        Some("from file"),
+        "untouched leaf from the file base should survive"
+    );
+    assert_eq!(built.pointer("/raw/nested/a").and_then(Value::as_i64), Some(1));
+    assert_eq!(
+        built.pointer("/raw/nested/b").and_then(Value::as_i64),
+        Some(99),
+        "inline override should win at the leaf"
+    );
+    assert_eq!(
+        built.pointer("/raw/untouched").and_then(Value::as_bool),
+        Some(true)
+    );
+    Ok(())
+}
+
+#[test]
+fn parse_defaults_file_skips_comments_and_unquotes_values() -> Result<()> {
+    let mut file = NamedTempFile::new().context("failed to allocate defaults file")?;
+    writeln!(
+        file,
+        "# a comment\n\nRUN_MODE=baseline\nPROBE_NAME=\"quoted name\"\nMESSAGE='single quoted'\n"
+    )?;
+
+    let values = parse_defaults_file(file.path())?;
+    assert_eq!(values.get("RUN_MODE").map(String::as_str), Some("baseline"));
+    assert_eq!(
+        values.get("PROBE_NAME").map(String::as_str),
+        Some("quoted name")
+    );
+    assert_eq!(
+        values.get("MESSAGE").map(String::as_str),
+        Some("single quoted")
+    );
+    Ok(())
+}
+
+#[test]
+fn emit_record_defaults_file_seeds_flags_that_cli_can_override() -> Result<()> {
+    let repo_root = repo_root();
+    let emit_record = helper_binary(&repo_root, "emit-record");
+
+    let mut defaults_file = NamedTempFile::new().context("failed to allocate defaults file")?;
+    writeln!(
+        defaults_file,
+        "RUN_MODE=baseline\nPROBE_NAME=tests_defaults_file\nPROBE_VERSION=1\nPRIMARY_CAPABILITY_ID=cap_fs_read_workspace_tree\nCOMMAND=true\nCATEGORY=fs\nVERB=read\nTARGET=/tmp\nSTATUS=denied\n"
+    )?;
+
+    let output = Command::new(&emit_record)
+        .arg("--defaults-file")
+        .arg(defaults_file.path())
+        .arg("--operation-args")
+        .arg("{}")
+        .arg("--status")
+        .arg("success")
+        .output()
+        .context("failed to execute emit-record with --defaults-file")?;
+    assert!(output.status.success(), "emit-record should succeed");
+
+    let (record, _) = parse_boundary_object(&output.stdout)?;
+    assert_eq!(record.probe.id, "tests_defaults_file");
+    assert_eq!(record.result.observed_result, "success");
+    Ok(())
+}
+
+#[test]
+fn levenshtein_matches_known_distances() {
+    assert_eq!(levenshtein("kitten", "sitting"), 3);
+    assert_eq!(levenshtein("same", "same"), 0);
+    assert_eq!(levenshtein("", "abc"), 3);
+}
+
+#[test]
+fn suggest_closest_only_surfaces_plausible_typos() {
+    let candidates = ["cap_fs_read_workspace_tree", "cap_network_connect"];
+    assert_eq!(
+        suggest_closest("cap_fs_read_workspace_tre", candidates),
+        Some("cap_fs_read_workspace_tree")
+    );
+    assert_eq!(suggest_closest("completely_unrelated_xyz", candidates), None);
+    assert_eq!(
+        did_you_mean("cap_fs_read_wokspace_tree", candidates),
+        " Did you mean 'cap_fs_read_workspace_tree'?"
+    );
+}
+
+#[test]
+fn emit_record_unknown_capability_suggests_closest_match() -> Result<()> {
+    let repo_root = repo_root();
+    let emit_record = helper_binary(&repo_root, "emit-record");
+
+    let output = Command::new(&emit_record)
+        .arg("--run-mode")
+        .arg("baseline")
+        .arg("--probe-name")
+        .arg("tests_typo_cap")
+        .arg("--probe-version")
+        .arg("1")
+        .arg("--primary-capability-id")
+        .arg("cap_fs_read_workspace_tre")
+        .arg("--command")
+        .arg("true")
+        .arg("--category")
+        .arg("fs")
+        .arg("--verb")
+        .arg("read")
+        .arg("--target")
+        .arg("/tmp")
+        .arg("--status")
+        .arg("success")
+        .arg("--operation-args")
+        .arg("{}")
+        .output()
+        .context("failed to execute emit-record with a typo'd capability")?;
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Did you mean 'cap_fs_read_workspace_tree'?"),
+        "stderr should suggest the closest capability id; got: {stderr}"
+    );
+    Ok(())
+}
+
 // === probe-exec workspace helpers ===
 
 #[test]
@@ -1578,16 +2054,20 @@ fn resolve_probe_prefers_probes_dir() -> Result<()> {
 
 #[test]
 fn workspace_override_skip_export() {
-    let plan = workspace_plan_from_override(WorkspaceOverride::SkipExport);
+    let workspace = TempWorkspace::new();
+    let policy = ContainmentPolicy::repo_root(&workspace.root);
+    let plan = workspace_plan_from_override(WorkspaceOverride::SkipExport, &policy);
     assert!(plan.export_value.is_none());
 }
 
 #[test]
 fn workspace_override_canonicalizes_path() -> Result<()> {
     let workspace = TempWorkspace::new();
-    let plan = workspace_plan_from_override(WorkspaceOverride::UsePath(
-        workspace.root.join("probes").into_os_string(),
-    ));
+    let policy = ContainmentPolicy::repo_root(&workspace.root);
+    let plan = workspace_plan_from_override(
+        WorkspaceOverride::UsePath(workspace.root.join("probes").into_os_string()),
+        &policy,
+    );
     assert!(
         plan.export_value
             .unwrap()
@@ -1597,14 +2077,46 @@ fn workspace_override_canonicalizes_path() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn workspace_override_rejects_path_outside_containment_policy() -> Result<()> {
+    let workspace = TempWorkspace::new();
+    let outside = TempWorkspace::new();
+    let policy = ContainmentPolicy::repo_root(&workspace.root);
+    let plan = workspace_plan_from_override(
+        WorkspaceOverride::UsePath(outside.root.clone().into_os_string()),
+        &policy,
+    );
+    assert!(plan.export_value.is_none());
+    let violation = plan.containment_error.expect("missing containment error");
+    assert_eq!(violation.path, canonicalize_path(&outside.root));
+    assert_eq!(violation.allowed_roots, policy.allowed_roots);
+    Ok(())
+}
+
+#[test]
+fn workspace_override_trusted_path_bypasses_containment_policy() -> Result<()> {
+    let workspace = TempWorkspace::new();
+    let outside = TempWorkspace::new();
+    let policy = ContainmentPolicy::repo_root(&workspace.root);
+    let plan = workspace_plan_from_override(
+        WorkspaceOverride::TrustedPath(outside.root.clone().into_os_string()),
+        &policy,
+    );
+    assert!(plan.containment_error.is_none());
+    assert!(plan.export_value.is_some());
+    Ok(())
+}
+
 #[test]
 fn workspace_tmpdir_prefers_workspace_tree() -> Result<()> {
     let workspace = TempWorkspace::new();
     let canonical_root = canonicalize_path(&workspace.root);
-    let plan = workspace_plan_from_override(WorkspaceOverride::UsePath(
-        canonical_root.clone().into_os_string(),
-    ));
-    let tmpdir_plan = workspace_tmpdir_plan(&plan, &canonical_root);
+    let policy = ContainmentPolicy::repo_root(&canonical_root);
+    let plan = workspace_plan_from_override(
+        WorkspaceOverride::UsePath(canonical_root.clone().into_os_string()),
+        &policy,
+    );
+    let tmpdir_plan = workspace_tmpdir_plan(&plan, &canonical_root, &policy);
     let tmpdir = tmpdir_plan.path.expect("tmpdir");
     assert!(tmpdir.starts_with(&canonical_root));
     assert!(tmpdir.ends_with("tmp"));
@@ -1617,10 +2129,12 @@ fn workspace_tmpdir_uses_override_when_present() -> Result<()> {
     let workspace = TempWorkspace::new();
     let override_root = workspace.root.join("custom_workspace");
     fs::create_dir_all(&override_root)?;
-    let plan = workspace_plan_from_override(WorkspaceOverride::UsePath(
-        override_root.clone().into_os_string(),
-    ));
-    let tmpdir_plan = workspace_tmpdir_plan(&plan, &workspace.root);
+    let policy = ContainmentPolicy::repo_root(&workspace.root);
+    let plan = workspace_plan_from_override(
+        WorkspaceOverride::UsePath(override_root.clone().into_os_string()),
+        &policy,
+    );
+    let tmpdir_plan = workspace_tmpdir_plan(&plan, &workspace.root, &policy);
     let tmpdir = tmpdir_plan.path.expect("tmpdir");
     let override_canonical = canonicalize_path(&override_root);
     assert!(tmpdir.starts_with(&override_canonical));
@@ -1632,9 +2146,12 @@ fn workspace_tmpdir_records_error_when_all_candidates_fail() -> Result<()> {
     let workspace = TempWorkspace::new();
     let override_file = workspace.root.join("override_marker");
     fs::write(&override_file, "marker")?;
-    let plan =
-        workspace_plan_from_override(WorkspaceOverride::UsePath(override_file.into_os_string()));
-    let tmpdir_plan = workspace_tmpdir_plan(&plan, &workspace.root);
+    let policy = ContainmentPolicy::repo_root(&workspace.root);
+    let plan = workspace_plan_from_override(
+        WorkspaceOverride::UsePath(override_file.into_os_string()),
+        &policy,
+    );
+    let tmpdir_plan = workspace_tmpdir_plan(&plan, &workspace.root, &policy);
     assert!(tmpdir_plan.path.is_none());
     let (attempted, message) = tmpdir_plan.last_error.expect("missing error");
     assert!(!message.is_empty());
@@ -1642,6 +2159,26 @@ fn workspace_tmpdir_records_error_when_all_candidates_fail() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn workspace_tmpdir_records_containment_violation_for_escaping_symlink() -> Result<()> {
+    let workspace = TempWorkspace::new();
+    let outside = TempWorkspace::new();
+    let tmp_link = workspace.root.join("tmp");
+    symlink(&outside.root, &tmp_link)?;
+    let policy = ContainmentPolicy::repo_root(&workspace.root);
+    let plan = WorkspacePlan {
+        export_value: None,
+        containment_error: None,
+    };
+    let tmpdir_plan = workspace_tmpdir_plan(&plan, &workspace.root, &policy);
+    assert!(tmpdir_plan.path.is_none());
+    let violation = tmpdir_plan
+        .containment_error
+        .expect("missing containment error");
+    assert_eq!(violation.path, canonicalize_path(&outside.root));
+    Ok(())
+}
+
 #[test]
 fn resolve_probe_metadata_prefers_script_values() -> Result<()> {
     let workspace = TempWorkspace::new();
@@ -1958,6 +2495,102 @@ impl Drop for FixtureProbe {
     }
 }
 
+// Launches an ephemeral Docker-backed service so `cap_net_*` probes have
+// something to connect to, the way `FixtureProbe` gives `cap_fs_*` probes a
+// script to exercise. Modeled on cargo-test-support's container harness
+// (its `containers/sshd`/`containers/apache` fixtures): a random local port
+// is reserved, the image is published on it, and the container is torn down
+// on `Drop`.
+struct ContainerFixture {
+    container_name: String,
+    port: u16,
+}
+
+impl ContainerFixture {
+    /// Launch `image`, publishing its `internal_port` on a free local port.
+    /// Returns `Ok(None)` instead of erroring when network fixture tests
+    /// aren't opted into (`FENCE_NET_TESTS` unset) or Docker isn't reachable,
+    /// so callers can skip the test cleanly.
+    fn launch(image: &str, name_prefix: &str, internal_port: u16) -> Result<Option<Self>> {
+        if env::var_os("FENCE_NET_TESTS").is_none() || !docker_available() {
+            return Ok(None);
+        }
+
+        let port = reserve_local_port()?;
+        let container_name = format!("{name_prefix}-{}-{port}", std::process::id());
+        let status = Command::new("docker")
+            .args(["run", "--rm", "-d", "--name", &container_name, "-p"])
+            .arg(format!("127.0.0.1:{port}:{internal_port}"))
+            .arg(image)
+            .status()
+            .with_context(|| format!("failed to launch container fixture {container_name}"))?;
+        if !status.success() {
+            bail!("docker run failed for container fixture {container_name}");
+        }
+
+        let fixture = Self {
+            container_name,
+            port,
+        };
+        fixture.wait_for_port()?;
+        Ok(Some(fixture))
+    }
+
+    fn address(&self) -> String {
+        format!("127.0.0.1:{}", self.port)
+    }
+
+    fn wait_for_port(&self) -> Result<()> {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            if TcpStream::connect(self.address()).is_ok() {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                bail!(
+                    "container fixture {} never opened {}",
+                    self.container_name,
+                    self.address()
+                );
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+}
+
+impl Drop for ContainerFixture {
+    fn drop(&mut self) {
+        let _ = Command::new("docker")
+            .args(["rm", "-f", &self.container_name])
+            .status();
+    }
+}
+
+fn docker_available() -> bool {
+    Command::new("docker")
+        .arg("info")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+fn codex_available() -> bool {
+    Command::new("codex")
+        .arg("--version")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+fn reserve_local_port() -> Result<u16> {
+    let listener = TcpListener::bind("127.0.0.1:0").context("reserving local port")?;
+    Ok(listener.local_addr()?.port())
+}
+
 // Removes the referenced file on drop so tests can create temporary symlinks.
 struct FileGuard {
     path: PathBuf,
@@ -2140,6 +2773,7 @@ fn sample_boundary_object() -> BoundaryObject {
         capabilities_schema_version: Some(default_catalog_key()),
         stack: StackInfo {
             sandbox_mode: Some("workspace-write".to_string()),
+            container_image: None,
             os: "Darwin".to_string(),
         },
         probe: ProbeInfo {
@@ -2178,6 +2812,7 @@ fn sample_boundary_object() -> BoundaryObject {
                 layer: CapabilityLayer::Other("layer".to_string()),
             },
             secondary: Vec::new(),
+            resolved_grant: None,
         },
     }
 }
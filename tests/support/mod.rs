@@ -1,5 +1,6 @@
 use anyhow::{Context, Result, bail};
-use codex_fence::find_repo_root;
+use codex_fence::{BoundaryObject, find_repo_root, render_field_diffs};
+use serde_json::Value;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Output};
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -44,6 +45,25 @@ pub fn run_command(mut cmd: Command) -> Result<Output> {
     }
 }
 
+/// Assert that `actual` matches the golden `expected` record, masking
+/// `masked_pointers` on both sides first (pass [`codex_fence::default_redactions`]
+/// for the usual volatile fields). Replaces hand-rolled field-by-field
+/// assertions with one call that points straight at whichever leaf drifted.
+pub fn assert_matches_snapshot(
+    actual: &BoundaryObject,
+    expected: &Value,
+    masked_pointers: &[String],
+) {
+    let diffs = actual
+        .diff_against(expected, masked_pointers)
+        .expect("diff boundary object against snapshot");
+    assert!(
+        diffs.is_empty(),
+        "boundary object drifted from snapshot:\n{}",
+        render_field_diffs(&diffs)
+    );
+}
+
 pub fn make_executable(path: &Path) -> Result<()> {
     #[cfg(unix)]
     {